@@ -1,7 +1,7 @@
 use bevy_ecs::{
     component::Component,
-    prelude::{ParallelSystemDescriptorCoercion, Res, Resource, RunCriteriaDescriptorCoercion},
-    schedule::{ShouldRun, Stage, SystemStage},
+    prelude::{Res, Resource, RunCriteriaDescriptorCoercion},
+    schedule::{ParallelSystemDescriptorCoercion, ShouldRun, Stage, SystemStage},
     system::Query,
     world::World,
 };
@@ -24,4 +24,5 @@ criterion_group!(
     query_get_component_simple,
     query_get_component,
     query_get,
+    query_get_many_sorted,
 );
@@ -402,3 +402,98 @@ pub fn query_get(criterion: &mut Criterion) {
 
     group.finish();
 }
+
+/// Spawns `entity_count` entities with a `Table` component, spread over 8 archetypes.
+fn setup_fragmented(entity_count: u32) -> (World, Vec<Entity>) {
+    let mut world = World::default();
+    let mut entities = Vec::with_capacity(entity_count as usize);
+    for i in 0..entity_count {
+        let mut entity = world.spawn();
+        entity.insert(Table::default());
+        if i & 1 != 0 {
+            entity.insert(WideTable::<0>::default());
+        }
+        if i & 2 != 0 {
+            entity.insert(WideTable::<1>::default());
+        }
+        if i & 4 != 0 {
+            entity.insert(WideTable::<2>::default());
+        }
+        entities.push(entity.id());
+    }
+    entities.shuffle(&mut deterministic_rand());
+    (world, entities)
+}
+
+pub fn query_get_many_sorted(criterion: &mut Criterion) {
+    let mut group = criterion.benchmark_group("query_get_many_sorted");
+    group.warm_up_time(std::time::Duration::from_millis(500));
+    group.measurement_time(std::time::Duration::from_secs(4));
+
+    for entity_count in RANGE.map(|i| i * 10_000) {
+        group.bench_function(format!("{}_entities_get", entity_count), |bencher| {
+            let (mut world, entities) = setup_fragmented(entity_count);
+            let mut query = SystemState::<Query<&Table>>::new(&mut world);
+            let query = query.get(&world);
+
+            bencher.iter(|| {
+                let mut count = 0;
+                for comp in entities.iter().flat_map(|&e| query.get(e)) {
+                    black_box(comp);
+                    count += 1;
+                }
+                assert_eq!(black_box(count), entity_count);
+            });
+        });
+        group.bench_function(format!("{}_entities_iter_many", entity_count), |bencher| {
+            let (mut world, entities) = setup_fragmented(entity_count);
+            let mut query = SystemState::<Query<&Table>>::new(&mut world);
+            let query = query.get(&world);
+
+            bencher.iter(|| {
+                let mut count = 0;
+                for comp in query.iter_many(&entities) {
+                    black_box(comp);
+                    count += 1;
+                }
+                assert_eq!(black_box(count), entity_count);
+            });
+        });
+        group.bench_function(
+            format!("{}_entities_iter_many_sorted", entity_count),
+            |bencher| {
+                let (mut world, entities) = setup_fragmented(entity_count);
+                let mut query = SystemState::<Query<&Table>>::new(&mut world);
+                let query = query.get(&world);
+
+                bencher.iter(|| {
+                    let mut count = 0;
+                    for comp in query.iter_many_sorted(&entities) {
+                        black_box(comp);
+                        count += 1;
+                    }
+                    assert_eq!(black_box(count), entity_count);
+                });
+            },
+        );
+        group.bench_function(
+            format!("{}_entities_get_many_dynamic", entity_count),
+            |bencher| {
+                let (mut world, entities) = setup_fragmented(entity_count);
+                let mut query = SystemState::<Query<&Table>>::new(&mut world);
+                let query = query.get(&world);
+
+                bencher.iter(|| {
+                    let mut count = 0;
+                    for comp in query.get_many_dynamic(&entities).into_iter().flatten() {
+                        black_box(comp);
+                        count += 1;
+                    }
+                    assert_eq!(black_box(count), entity_count);
+                });
+            },
+        );
+    }
+
+    group.finish();
+}
@@ -1,18 +1,26 @@
-use crate::{CoreStage, Plugin, PluginGroup, PluginGroupBuilder, StartupSchedule, StartupStage};
+use crate::{
+    CoreSet, CoreStage, Plugin, PluginGroup, PluginGroupBuilder, StartupSchedule, StartupStage,
+};
 pub use bevy_derive::AppLabel;
 use bevy_derive::{Deref, DerefMut};
 use bevy_ecs::{
-    event::{Event, Events},
+    error::{BevyError, SystemErrorContext, SystemErrorHandler},
+    event::{Event, EventStoragePolicy, Events},
     prelude::{FromWorld, IntoExclusiveSystem},
     schedule::{
-        IntoSystemDescriptor, Schedule, ShouldRun, Stage, StageLabel, State, StateData, SystemSet,
-        SystemStage,
+        ExclusiveSystemDescriptorCoercion, IntoSystemDescriptor, Schedule, ShouldRun, Stage,
+        StageLabel, State as LegacyState, StateData, SystemSet as LegacySystemSet, SystemStage,
+    },
+    schedule_v3::{
+        self, apply_state_transition, run_enter_schedule, IntoSystemConfig, IntoSystemConfigs,
+        IntoSystemSetConfig, IntoSystemSetConfigs, NextState, ScheduleLabel, Schedules, State,
+        States,
     },
-    system::Resource,
+    system::{apply_command_buffers, CommandBuffers, Resource},
     world::World,
 };
-use bevy_utils::{tracing::debug, HashMap};
-use std::fmt::Debug;
+use bevy_utils::{tracing::debug, HashMap, HashSet};
+use std::{any::TypeId, fmt::Debug};
 
 #[cfg(feature = "trace")]
 use bevy_utils::tracing::info_span;
@@ -68,6 +76,8 @@ pub struct App {
     /// A container of [`Stage`]s set to be run in a linear order.
     pub schedule: Schedule,
     sub_apps: HashMap<AppLabelId, SubApp>,
+    plugin_registry: Vec<Box<dyn Plugin>>,
+    plugin_types: HashSet<TypeId>,
 }
 
 /// Each `SubApp` has its own [`Schedule`] and [`World`], enabling a separation of concerns.
@@ -84,6 +94,11 @@ impl Default for App {
 
         app.add_default_stages()
             .add_event::<AppExit>()
+            .init_resource::<CommandBuffers>()
+            .add_system_to_stage(
+                CoreStage::First,
+                apply_command_buffers.exclusive_system().at_start(),
+            )
             .add_system_to_stage(CoreStage::Last, World::clear_trackers.exclusive_system());
 
         #[cfg(feature = "bevy_ci_testing")]
@@ -111,6 +126,8 @@ impl App {
             schedule: Default::default(),
             runner: Box::new(run_once),
             sub_apps: HashMap::default(),
+            plugin_registry: Vec::new(),
+            plugin_types: HashSet::default(),
         }
     }
 
@@ -132,13 +149,34 @@ impl App {
     ///
     /// Finalizes the [`App`] configuration. For general usage, see the example on the item
     /// level documentation.
+    ///
+    /// Before the runner is called, this waits until all the plugins are [ready](Self::ready),
+    /// then [finishes](Self::finish) and [cleans them up](Self::cleanup). While waiting, the
+    /// tasks spawned on the main thread by the global task pools are run.
+    ///
+    /// If the [`ScheduleGraphPlugin`](crate::ScheduleGraphPlugin) was added, the schedule graph
+    /// is written before the runner is called.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a [dependency](Plugin::dependencies) of one of the added plugins is missing.
+    ///
+    /// On wasm, the app cannot wait for its plugins, and this panics if they are not all ready.
     pub fn run(&mut self) {
         #[cfg(feature = "trace")]
         let _bevy_app_run_span = info_span!("bevy_app").entered();
 
+        self.check_plugin_dependencies();
+
+        self.finish_plugins();
+
         let mut app = std::mem::replace(self, App::empty());
+        crate::schedule_graph::write_schedule_graph(&mut app);
         let runner = std::mem::replace(&mut app.runner, Box::new(run_once));
         (runner)(app);
+
+        #[cfg(feature = "bevy_ci_testing")]
+        crate::ci_testing::exit_on_failure();
     }
 
     /// Adds a [`Stage`] with the given `label` to the last position of the app's
@@ -295,7 +333,7 @@ impl App {
     ///
     /// # Examples
     ///
-    /// Here the closure is used to add a system to the update stage:
+    /// Here the closure is used to add a system to the post-update stage:
     ///
     /// ```
     /// # use bevy_app::prelude::*;
@@ -304,7 +342,7 @@ impl App {
     /// # let mut app = App::new();
     /// # fn my_system() {}
     /// #
-    /// app.stage(CoreStage::Update, |stage: &mut SystemStage| {
+    /// app.stage(CoreStage::PostUpdate, |stage: &mut SystemStage| {
     ///     stage.add_system(my_system)
     /// });
     /// ```
@@ -317,7 +355,8 @@ impl App {
         self
     }
 
-    /// Adds a system to the [update stage](Self::add_default_stages) of the app's [`Schedule`].
+    /// Adds a system to [`CoreSet::Update`] in the [update schedule](Self::add_default_stages)
+    /// of the app.
     ///
     /// Refer to the [system module documentation](bevy_ecs::system) to see how a system
     /// can be defined.
@@ -333,11 +372,14 @@ impl App {
     /// #
     /// app.add_system(my_system);
     /// ```
-    pub fn add_system<Params>(&mut self, system: impl IntoSystemDescriptor<Params>) -> &mut Self {
-        self.add_system_to_stage(CoreStage::Update, system)
+    pub fn add_system<P>(&mut self, system: impl IntoSystemConfig<P>) -> &mut Self {
+        self.update_schedule()
+            .add_system(system.in_set(CoreSet::Update));
+        self
     }
 
-    /// Adds a [`SystemSet`] to the [update stage](Self::add_default_stages).
+    /// Adds a collection of systems to [`CoreSet::Update`] in the
+    /// [update schedule](Self::add_default_stages) of the app.
     ///
     /// # Examples
     ///
@@ -350,15 +392,100 @@ impl App {
     /// # fn system_b() {}
     /// # fn system_c() {}
     /// #
-    /// app.add_system_set(
-    ///     SystemSet::new()
-    ///         .with_system(system_a)
-    ///         .with_system(system_b)
-    ///         .with_system(system_c),
-    /// );
+    /// app.add_systems((system_a, system_b, system_c).chain());
+    /// ```
+    pub fn add_systems<P>(&mut self, systems: impl IntoSystemConfigs<P>) -> &mut Self {
+        self.update_schedule()
+            .add_systems(systems.in_set(CoreSet::Update));
+        self
+    }
+
+    /// Configures a [`SystemSet`](bevy_ecs::schedule_v3::SystemSet) in the
+    /// [update schedule](Self::add_default_stages) of the app.
+    ///
+    /// # Examples
+    ///
     /// ```
-    pub fn add_system_set(&mut self, system_set: SystemSet) -> &mut Self {
-        self.add_system_set_to_stage(CoreStage::Update, system_set)
+    /// # use bevy_app::prelude::*;
+    /// # use bevy_ecs::prelude::*;
+    /// #
+    /// # let mut app = App::new();
+    /// #[derive(SystemSet, Clone, Debug, PartialEq, Eq, Hash)]
+    /// struct Physics;
+    ///
+    /// #[derive(Resource)]
+    /// struct Paused;
+    ///
+    /// app.configure_set(Physics.in_set(CoreSet::Update).run_if(not(resource_exists::<Paused>())));
+    /// ```
+    pub fn configure_set(&mut self, set: impl IntoSystemSetConfig) -> &mut Self {
+        self.update_schedule().configure_set(set);
+        self
+    }
+
+    /// Configures a collection of [`SystemSet`](bevy_ecs::schedule_v3::SystemSet)s in the
+    /// [update schedule](Self::add_default_stages) of the app.
+    pub fn configure_sets(&mut self, sets: impl IntoSystemSetConfigs) -> &mut Self {
+        self.update_schedule().configure_sets(sets);
+        self
+    }
+
+    /// Adds a system to the [`Schedule`](schedule_v3::Schedule) identified by `label` in the
+    /// [`Schedules`] resource, such as the [`OnEnter`](bevy_ecs::schedule_v3::OnEnter) schedule
+    /// of a state. The schedule is created if needed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bevy_app::prelude::*;
+    /// # use bevy_ecs::prelude::*;
+    /// #
+    /// #[derive(States, Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+    /// enum AppState {
+    ///     #[default]
+    ///     Menu,
+    ///     InGame,
+    /// }
+    ///
+    /// # fn setup_menu() {}
+    /// # let mut app = App::new();
+    /// app.add_state::<AppState>()
+    ///     .add_system_to_schedule(OnEnter(AppState::Menu), setup_menu);
+    /// ```
+    pub fn add_system_to_schedule<P>(
+        &mut self,
+        label: impl ScheduleLabel,
+        system: impl IntoSystemConfig<P>,
+    ) -> &mut Self {
+        let mut schedules = self.world.get_resource_or_insert_with(Schedules::default);
+        if let Some(schedule) = schedules.get_mut(&label) {
+            schedule.add_system(system);
+        } else {
+            let mut schedule = schedule_v3::Schedule::new();
+            schedule.add_system(system);
+            schedules.insert(label, schedule);
+        }
+        self
+    }
+
+    /// Adds a [`Schedule`](schedule_v3::Schedule) to the [`Schedules`] resource under `label`.
+    ///
+    /// The schedule can then be run with
+    /// [`World::run_schedule`](bevy_ecs::world::World::run_schedule).
+    pub fn add_schedule(
+        &mut self,
+        label: impl ScheduleLabel,
+        schedule: schedule_v3::Schedule,
+    ) -> &mut Self {
+        self.world.add_schedule(label, schedule);
+        self
+    }
+
+    /// Returns the [`Schedule`](schedule_v3::Schedule) of [`CoreStage::Update`].
+    fn update_schedule(&mut self) -> &mut schedule_v3::Schedule {
+        self.schedule
+            .get_stage_mut::<schedule_v3::Schedule>(CoreStage::Update)
+            .expect("the update stage should be a `bevy_ecs::schedule_v3::Schedule`, see `App::add_default_stages`")
     }
 
     /// Adds a system to the [`Stage`] identified by `stage_label`.
@@ -374,6 +501,14 @@ impl App {
     /// #
     /// app.add_system_to_stage(CoreStage::PostUpdate, my_system);
     /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `stage_label` is [`CoreStage::Update`], which is a
+    /// [`Schedule`](schedule_v3::Schedule) rather than a [`Stage`]: use
+    /// [`add_system`](Self::add_system) instead, with
+    /// [run conditions](bevy_ecs::schedule_v3::IntoSystemConfig::run_if) in place of run
+    /// criteria.
     pub fn add_system_to_stage<Params>(
         &mut self,
         stage_label: impl StageLabel,
@@ -384,17 +519,23 @@ impl App {
             stage_label.type_id() != TypeId::of::<StartupStage>(),
             "add systems to a startup stage using App::add_startup_system_to_stage"
         );
+        assert!(
+            stage_label.as_label() != CoreStage::Update.as_label(),
+            "CoreStage::Update only takes systems added with App::add_system, \
+            use `.run_if(..)` in place of run criteria and `.in_set(..)` in place of labels"
+        );
         self.schedule.add_system_to_stage(stage_label, system);
         self
     }
 
-    /// Adds a [`SystemSet`] to the [`Stage`] identified by `stage_label`.
+    /// Adds a [`SystemSet`](bevy_ecs::schedule::SystemSet) to the [`Stage`] identified by `stage_label`.
     ///
     /// # Examples
     ///
     /// ```
     /// # use bevy_app::prelude::*;
     /// # use bevy_ecs::prelude::*;
+    /// # use bevy_ecs::schedule::SystemSet;
     /// #
     /// # let mut app = App::new();
     /// # fn system_a() {}
@@ -409,16 +550,26 @@ impl App {
     ///         .with_system(system_c),
     /// );
     /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `stage_label` is [`CoreStage::Update`], see
+    /// [`add_system_to_stage`](Self::add_system_to_stage).
     pub fn add_system_set_to_stage(
         &mut self,
         stage_label: impl StageLabel,
-        system_set: SystemSet,
+        system_set: LegacySystemSet,
     ) -> &mut Self {
         use std::any::TypeId;
         assert!(
             stage_label.type_id() != TypeId::of::<StartupStage>(),
             "add system sets to a startup stage using App::add_startup_system_set_to_stage"
         );
+        assert!(
+            stage_label.as_label() != CoreStage::Update.as_label(),
+            "CoreStage::Update only takes systems added with App::add_systems, \
+            use `App::configure_set` to give them shared run conditions and ordering"
+        );
         self.schedule
             .add_system_set_to_stage(stage_label, system_set);
         self
@@ -449,13 +600,14 @@ impl App {
         self.add_startup_system_to_stage(StartupStage::Startup, system)
     }
 
-    /// Adds a [`SystemSet`] to the [startup stage](Self::add_default_stages).
+    /// Adds a [`SystemSet`](bevy_ecs::schedule::SystemSet) to the [startup stage](Self::add_default_stages).
     ///
     /// # Examples
     ///
     /// ```
     /// # use bevy_app::prelude::*;
     /// # use bevy_ecs::prelude::*;
+    /// # use bevy_ecs::schedule::SystemSet;
     /// #
     /// # let mut app = App::new();
     /// # fn startup_system_a() {}
@@ -469,7 +621,7 @@ impl App {
     ///         .with_system(startup_system_c),
     /// );
     /// ```
-    pub fn add_startup_system_set(&mut self, system_set: SystemSet) -> &mut Self {
+    pub fn add_startup_system_set(&mut self, system_set: LegacySystemSet) -> &mut Self {
         self.add_startup_system_set_to_stage(StartupStage::Startup, system_set)
     }
 
@@ -501,7 +653,7 @@ impl App {
         self
     }
 
-    /// Adds a [`SystemSet`] to the [startup schedule](Self::add_default_stages), in the stage
+    /// Adds a [`SystemSet`](bevy_ecs::schedule::SystemSet) to the [startup schedule](Self::add_default_stages), in the stage
     /// identified by `stage_label`.
     ///
     /// `stage_label` must refer to a stage inside the startup schedule.
@@ -511,6 +663,7 @@ impl App {
     /// ```
     /// # use bevy_app::prelude::*;
     /// # use bevy_ecs::prelude::*;
+    /// # use bevy_ecs::schedule::SystemSet;
     /// #
     /// # let mut app = App::new();
     /// # fn startup_system_a() {}
@@ -528,7 +681,7 @@ impl App {
     pub fn add_startup_system_set_to_stage(
         &mut self,
         stage_label: impl StageLabel,
-        system_set: SystemSet,
+        system_set: LegacySystemSet,
     ) -> &mut Self {
         self.schedule
             .stage(StartupSchedule, |schedule: &mut Schedule| {
@@ -537,29 +690,37 @@ impl App {
         self
     }
 
-    /// Adds a new [`State`] with the given `initial` value.
-    /// This inserts a new `State<T>` resource and adds a new "driver" to [`CoreStage::Update`].
-    /// Each stage that uses `State<T>` for system run criteria needs a driver. If you need to use
-    /// your state in a different stage, consider using [`Self::add_state_to_stage`] or manually
-    /// adding [`State::get_driver`] to additional stages you need it in.
-    pub fn add_state<T>(&mut self, initial: T) -> &mut Self
-    where
-        T: StateData,
-    {
-        self.add_state_to_stage(CoreStage::Update, initial)
+    /// Adds [`State<S>`] and [`NextState<S>`] resources, starting in the [`Default`] state, and
+    /// applies the transitions queued in [`NextState<S>`] in [`CoreSet::StateTransitions`].
+    ///
+    /// The [`OnEnter`](bevy_ecs::schedule_v3::OnEnter) schedule of the initial state runs in
+    /// [`StartupStage::PreStartup`], and the [`OnExit`](bevy_ecs::schedule_v3::OnExit) and
+    /// [`OnEnter`](bevy_ecs::schedule_v3::OnEnter) schedules run on each transition. Systems
+    /// can be added to them with [`add_system_to_schedule`](Self::add_system_to_schedule).
+    pub fn add_state<S: States>(&mut self) -> &mut Self {
+        self.init_resource::<State<S>>()
+            .init_resource::<NextState<S>>()
+            .add_startup_system_to_stage(
+                StartupStage::PreStartup,
+                run_enter_schedule::<S>.exclusive_system(),
+            );
+        self.update_schedule()
+            .add_system(apply_state_transition::<S>.in_set(CoreSet::StateTransitions));
+        self
     }
 
-    /// Adds a new [`State`] with the given `initial` value.
+    /// Adds a new stage-based [`State`](bevy_ecs::schedule::State) with the given `initial`
+    /// value.
     /// This inserts a new `State<T>` resource and adds a new "driver" to the given stage.
     /// Each stage that uses `State<T>` for system run criteria needs a driver. If you need to use
-    /// your state in more than one stage, consider manually adding [`State::get_driver`] to the
-    /// stages you need it in.
+    /// your state in more than one stage, consider manually adding
+    /// [`State::get_driver`](bevy_ecs::schedule::State::get_driver) to the stages you need it in.
     pub fn add_state_to_stage<T>(&mut self, stage: impl StageLabel, initial: T) -> &mut Self
     where
         T: StateData,
     {
-        self.insert_resource(State::new(initial))
-            .add_system_set_to_stage(stage, State::<T>::get_driver())
+        self.insert_resource(LegacyState::new(initial))
+            .add_system_set_to_stage(stage, LegacyState::<T>::get_driver())
     }
 
     /// Adds utility stages to the [`Schedule`], giving it a standardized structure.
@@ -584,6 +745,13 @@ impl App {
     /// - **Pre-update:** Often used by plugins to prepare their internal state before the
     ///   update stage begins.
     /// - **Update:** Intended for user defined logic. Systems are added here by default.
+    ///   Unlike the other stages, this is a [`Schedule`](schedule_v3::Schedule) organized in
+    ///   [`CoreSet`]s, where state transitions run before the rest of the systems.
+    ///
+    /// Only the update stage has been migrated to [`schedule_v3`] so far: the other stages are
+    /// still [`SystemStage`]s, which take the stage-based
+    /// [`SystemSet`](bevy_ecs::schedule::SystemSet) rather than the
+    /// [`SystemSet`](bevy_ecs::schedule_v3::SystemSet) of the prelude.
     /// - **Post-update:** Often used by plugins to finalize their internal state after the
     ///   world changes that happened during the update stage.
     /// - **Last:** Runs right before the end of the schedule execution cycle.
@@ -608,7 +776,11 @@ impl App {
                     .with_stage(StartupStage::PostStartup, SystemStage::parallel()),
             )
             .add_stage(CoreStage::PreUpdate, SystemStage::parallel())
-            .add_stage(CoreStage::Update, SystemStage::parallel())
+            .add_stage(CoreStage::Update, {
+                let mut update = schedule_v3::Schedule::new();
+                update.configure_set(CoreSet::StateTransitions.before(CoreSet::Update));
+                update
+            })
             .add_stage(CoreStage::PostUpdate, SystemStage::parallel())
             .add_stage(CoreStage::Last, SystemStage::parallel())
     }
@@ -618,7 +790,8 @@ impl App {
     /// This is done by adding a [`Resource`] of type [`Events::<T>`],
     /// and inserting an [`update_system`](Events::update_system) into [`CoreStage::First`].
     ///
-    /// See [`Events`] for defining events.
+    /// See [`Events`] for defining events. If the events were already added, this does nothing,
+    /// keeping the [`EventStoragePolicy`] they were added with.
     ///
     /// # Examples
     ///
@@ -636,12 +809,68 @@ impl App {
         T: Event,
     {
         if !self.world.contains_resource::<Events<T>>() {
-            self.init_resource::<Events<T>>()
+            self.add_event_with_policy::<T>(EventStoragePolicy::default());
+        }
+        self
+    }
+
+    /// Setup the application to manage events of type `T`, stored according to `policy`.
+    ///
+    /// Like [`add_event`](Self::add_event), but lets readers that do not run every frame
+    /// keep events around until they have read them. If the events were already added,
+    /// only the policy of the existing [`Events::<T>`] resource is changed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bevy_app::prelude::*;
+    /// # use bevy_ecs::{prelude::*, event::EventStoragePolicy};
+    /// #
+    /// # struct MyEvent;
+    /// # let mut app = App::new();
+    /// #
+    /// app.add_event_with_policy::<MyEvent>(EventStoragePolicy::RetainUntilConsumed);
+    /// ```
+    pub fn add_event_with_policy<T>(&mut self, policy: EventStoragePolicy) -> &mut Self
+    where
+        T: Event,
+    {
+        if let Some(mut events) = self.world.get_resource_mut::<Events<T>>() {
+            events.set_policy(policy);
+        } else {
+            self.insert_resource(Events::<T>::with_policy(policy))
                 .add_system_to_stage(CoreStage::First, Events::<T>::update_system);
         }
         self
     }
 
+    /// Sets the handler called with the errors returned by systems of this [`App`].
+    ///
+    /// Systems returning `Result<(), E>` pass their errors, together with the name of the
+    /// failing system, to the [`SystemErrorHandler`] resource. By default, errors cause a panic.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bevy_app::prelude::*;
+    /// # use bevy_ecs::{error, prelude::*};
+    /// #
+    /// fn load_config() -> Result<(), BevyError> {
+    ///     let _contents = std::fs::read_to_string("config.ron")?;
+    ///     Ok(())
+    /// }
+    ///
+    /// App::new()
+    ///     .set_system_error_handler(error::warn)
+    ///     .add_system(load_config);
+    /// ```
+    pub fn set_system_error_handler(
+        &mut self,
+        handler: fn(BevyError, SystemErrorContext),
+    ) -> &mut Self {
+        self.insert_resource(SystemErrorHandler(handler))
+    }
+
     /// Inserts a [`Resource`] to the current [`App`] and overwrites any [`Resource`] previously added of the same type.
     ///
     /// A [`Resource`] in Bevy represents globally unique data. [`Resource`]s must be added to Bevy apps
@@ -789,15 +1018,142 @@ impl App {
     /// # }
     /// App::new().add_plugin(bevy_log::LogPlugin::default());
     /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the plugin [is unique](Plugin::is_unique) and was already added.
     pub fn add_plugin<T>(&mut self, plugin: T) -> &mut Self
     where
         T: Plugin,
     {
+        self.add_boxed_plugin(Box::new(plugin))
+    }
+
+    /// Adds a boxed [`Plugin`], e.g. one that was loaded dynamically.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the plugin [is unique](Plugin::is_unique) and was already added.
+    pub fn add_boxed_plugin(&mut self, plugin: Box<dyn Plugin>) -> &mut Self {
         debug!("added plugin: {}", plugin.name());
+        // dispatched through the vtable of `dyn Plugin`, this is the type of the plugin itself
+        let type_id = (*plugin).type_id();
+        if !self.plugin_types.insert(type_id) && plugin.is_unique() {
+            panic!(
+                "Plugin {} was already added to the app. Return `false` from `Plugin::is_unique` to allow adding it several times.",
+                plugin.name()
+            );
+        }
         plugin.build(self);
+        self.plugin_registry.push(plugin);
         self
     }
 
+    /// Returns `true` if a plugin of type `T` was added to the app.
+    ///
+    /// ```
+    /// # use bevy_app::prelude::*;
+    /// # struct MyPlugin;
+    /// # impl Plugin for MyPlugin {
+    /// #     fn build(&self, app: &mut App) {}
+    /// # }
+    /// let mut app = App::new();
+    /// assert!(!app.is_plugin_added::<MyPlugin>());
+    /// app.add_plugin(MyPlugin);
+    /// assert!(app.is_plugin_added::<MyPlugin>());
+    /// ```
+    pub fn is_plugin_added<T: Plugin>(&self) -> bool {
+        self.plugin_types.contains(&TypeId::of::<T>())
+    }
+
+    /// Returns `true` if all the plugins of the app and of its sub apps are
+    /// [ready](Plugin::ready).
+    pub fn ready(&self) -> bool {
+        self.plugin_registry.iter().all(|plugin| plugin.ready(self))
+            && self.sub_apps.values().all(|sub_app| sub_app.app.ready())
+    }
+
+    /// Calls [`Plugin::finish`] on all the plugins of the app and of its sub apps, in the order
+    /// they were added.
+    ///
+    /// This is called by [`run`](Self::run), and only needs to be called manually if the app is
+    /// updated without calling [`run`](Self::run).
+    pub fn finish(&mut self) {
+        let plugin_registry = std::mem::take(&mut self.plugin_registry);
+        for plugin in &plugin_registry {
+            plugin.finish(self);
+        }
+        self.restore_plugin_registry(plugin_registry);
+        for sub_app in self.sub_apps.values_mut() {
+            sub_app.app.finish();
+        }
+    }
+
+    /// Calls [`Plugin::cleanup`] on all the plugins of the app and of its sub apps, in the order
+    /// they were added.
+    ///
+    /// This is called by [`run`](Self::run), and only needs to be called manually if the app is
+    /// updated without calling [`run`](Self::run).
+    pub fn cleanup(&mut self) {
+        let plugin_registry = std::mem::take(&mut self.plugin_registry);
+        for plugin in &plugin_registry {
+            plugin.cleanup(self);
+        }
+        self.restore_plugin_registry(plugin_registry);
+        for sub_app in self.sub_apps.values_mut() {
+            sub_app.app.cleanup();
+        }
+    }
+
+    /// Waits until all the plugins are [ready](Self::ready), then [finishes](Self::finish) and
+    /// [cleans them up](Self::cleanup).
+    pub(crate) fn finish_plugins(&mut self) {
+        // Plugins usually wait for tasks, keep the ones running on the main thread going.
+        #[cfg(not(target_arch = "wasm32"))]
+        while !self.ready() {
+            bevy_tasks::tick_global_task_pools_on_main_thread();
+        }
+        // Tasks only make progress once control is given back to the browser, so waiting here
+        // would never end.
+        #[cfg(target_arch = "wasm32")]
+        assert!(
+            self.ready(),
+            "plugins must be ready when the app is run on wasm, where it cannot wait for them"
+        );
+        self.finish();
+        self.cleanup();
+    }
+
+    /// Puts back the plugins taken out of the registry, before the ones added in the meantime.
+    fn restore_plugin_registry(&mut self, mut plugin_registry: Vec<Box<dyn Plugin>>) {
+        plugin_registry.append(&mut self.plugin_registry);
+        self.plugin_registry = plugin_registry;
+    }
+
+    /// Panics with the list of missing plugins if a [dependency](Plugin::dependencies) of one of
+    /// the added plugins was not added.
+    fn check_plugin_dependencies(&self) {
+        let missing: Vec<_> = self
+            .plugin_registry
+            .iter()
+            .flat_map(|plugin| {
+                plugin
+                    .dependencies()
+                    .into_iter()
+                    .filter(|dependency| !self.plugin_types.contains(&dependency.type_id()))
+                    .map(move |dependency| {
+                        format!("- {} requires {}", plugin.name(), dependency.name())
+                    })
+            })
+            .collect();
+        if !missing.is_empty() {
+            panic!(
+                "Some plugins are missing from the app:\n{}",
+                missing.join("\n")
+            );
+        }
+    }
+
     /// Adds a group of [`Plugin`]s.
     ///
     /// [`Plugin`]s can be grouped into a set by using a [`PluginGroup`].
@@ -1000,3 +1356,259 @@ fn run_once(mut app: App) {
 /// frame is over.
 #[derive(Debug, Clone, Default)]
 pub struct AppExit;
+
+#[cfg(test)]
+mod tests {
+    use crate as bevy_app;
+    use crate::{
+        App, AppLabel, CoreStage, Plugin, PluginDependency, PluginGroup, PluginGroupBuilder,
+    };
+    use bevy_ecs::{
+        component::Component,
+        event::{EventStoragePolicy, Events},
+        schedule::{
+            AmbiguityReportLevel, ReportExecutionOrderAmbiguities, SystemSet as LegacySystemSet,
+        },
+        schedule_v3::{IntoSystemConfig, SystemSet},
+        system::{CommandBuffer, CommandBuffers, ResMut, Resource},
+    };
+
+    struct PluginA;
+    impl Plugin for PluginA {
+        fn build(&self, _app: &mut App) {}
+    }
+
+    struct PluginB;
+    impl Plugin for PluginB {
+        fn build(&self, _app: &mut App) {}
+
+        fn dependencies(&self) -> Vec<PluginDependency> {
+            vec![PluginDependency::of::<PluginA>()]
+        }
+    }
+
+    struct PluginC;
+    impl Plugin for PluginC {
+        fn build(&self, _app: &mut App) {}
+
+        fn is_unique(&self) -> bool {
+            false
+        }
+    }
+
+    struct Group;
+    impl PluginGroup for Group {
+        fn build(&mut self, group: &mut PluginGroupBuilder) {
+            group.add(PluginB).add(PluginA);
+        }
+    }
+
+    #[test]
+    fn plugins_are_registered() {
+        let mut app = App::new();
+        app.add_plugin(PluginC).add_plugin(PluginC);
+        assert!(app.is_plugin_added::<PluginC>());
+        assert!(!app.is_plugin_added::<PluginA>());
+
+        app.add_plugins(Group);
+        assert!(app.is_plugin_added::<PluginA>());
+        assert!(app.is_plugin_added::<PluginB>());
+        app.run();
+    }
+
+    #[derive(Resource, Default)]
+    struct Lifecycle(Vec<&'static str>);
+
+    struct LifecyclePlugin;
+    impl Plugin for LifecyclePlugin {
+        fn build(&self, app: &mut App) {
+            app.init_resource::<Lifecycle>();
+            app.world.resource_mut::<Lifecycle>().0.push("build");
+        }
+
+        fn ready(&self, app: &App) -> bool {
+            app.world.contains_resource::<Ready>()
+        }
+
+        fn finish(&self, app: &mut App) {
+            app.world.resource_mut::<Lifecycle>().0.push("finish");
+        }
+
+        fn cleanup(&self, app: &mut App) {
+            app.world.remove_resource::<Ready>();
+            app.world.resource_mut::<Lifecycle>().0.push("cleanup");
+        }
+    }
+
+    #[derive(Resource)]
+    struct Ready;
+
+    #[derive(AppLabel, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+    struct SubAppLabel;
+
+    #[test]
+    fn plugin_lifecycle() {
+        let mut sub_app = App::empty();
+        sub_app.add_plugin(LifecyclePlugin);
+
+        let mut app = App::new();
+        app.add_plugin(LifecyclePlugin)
+            .add_sub_app(SubAppLabel, sub_app, |_, _| {});
+        assert!(!app.ready());
+
+        app.insert_resource(Ready);
+        assert!(!app.ready());
+        app.sub_app_mut(SubAppLabel).insert_resource(Ready);
+        assert!(app.ready());
+
+        app.finish();
+        app.cleanup();
+        for world in [&app.world, &app.sub_app(SubAppLabel).world] {
+            assert_eq!(
+                world.resource::<Lifecycle>().0,
+                vec!["build", "finish", "cleanup"]
+            );
+            assert!(!world.contains_resource::<Ready>());
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "CoreStage::Update only takes systems added with App::add_system")]
+    fn stage_based_update_systems_panic() {
+        App::new().add_system_to_stage(CoreStage::Update, || {});
+    }
+
+    #[test]
+    #[should_panic(expected = "CoreStage::Update only takes systems added with App::add_systems")]
+    fn stage_based_update_system_sets_panic() {
+        App::new()
+            .add_system_set_to_stage(CoreStage::Update, LegacySystemSet::new().with_system(|| {}));
+    }
+
+    #[test]
+    #[should_panic(expected = "Execution order ambiguities detected")]
+    fn update_ambiguities_error_level() {
+        #[derive(Resource, Default)]
+        struct Counter(u32);
+
+        fn increment(mut counter: ResMut<Counter>) {
+            counter.0 += 1;
+        }
+
+        fn reset(mut counter: ResMut<Counter>) {
+            counter.0 = 0;
+        }
+
+        let mut app = App::new();
+        app.init_resource::<Counter>()
+            .insert_resource(ReportExecutionOrderAmbiguities::new(
+                AmbiguityReportLevel::Error,
+            ))
+            .add_system(increment)
+            .add_system(reset);
+        app.update();
+    }
+
+    #[test]
+    fn update_ambiguities_allow_list() {
+        #[derive(Resource, Default)]
+        struct Counter(u32);
+
+        #[derive(SystemSet, Clone, Debug, PartialEq, Eq, Hash)]
+        struct Cosmetic;
+
+        fn increment(mut counter: ResMut<Counter>) {
+            counter.0 += 1;
+        }
+
+        fn reset(mut counter: ResMut<Counter>) {
+            counter.0 = 0;
+        }
+
+        let mut app = App::new();
+        app.init_resource::<Counter>()
+            .insert_resource(
+                ReportExecutionOrderAmbiguities::new(AmbiguityReportLevel::Error)
+                    .ambiguous_with_set(Cosmetic),
+            )
+            .add_system(increment.in_set(Cosmetic))
+            .add_system(reset);
+        app.update();
+    }
+
+    #[test]
+    #[should_panic(expected = "Execution order ambiguities detected")]
+    fn stage_ambiguities_error_level() {
+        #[derive(Resource, Default)]
+        struct Counter(u32);
+
+        fn increment(mut counter: ResMut<Counter>) {
+            counter.0 += 1;
+        }
+
+        fn reset(mut counter: ResMut<Counter>) {
+            counter.0 = 0;
+        }
+
+        let mut app = App::new();
+        app.init_resource::<Counter>()
+            .insert_resource(ReportExecutionOrderAmbiguities::new(
+                AmbiguityReportLevel::Error,
+            ))
+            .add_system_to_stage(CoreStage::PostUpdate, increment)
+            .add_system_to_stage(CoreStage::PostUpdate, reset);
+        app.update();
+    }
+
+    #[test]
+    fn command_buffers_are_applied_on_update() {
+        #[derive(Component)]
+        struct Spawned;
+
+        let mut app = App::new();
+        let sender = app.world.resource::<CommandBuffers>().sender();
+        let mut buffer = CommandBuffer::new();
+        buffer.reserve_entities(&app.world, 1);
+        let entity = std::thread::spawn(move || {
+            let entity = buffer.spawn((Spawned,));
+            sender.submit(buffer);
+            entity
+        })
+        .join()
+        .unwrap();
+
+        app.update();
+        assert!(app.world.get::<Spawned>(entity).is_some());
+    }
+
+    #[test]
+    fn add_event_keeps_policy() {
+        struct MyEvent;
+
+        let mut app = App::new();
+        app.add_event_with_policy::<MyEvent>(EventStoragePolicy::Manual)
+            .add_event::<MyEvent>();
+        assert_eq!(
+            app.world.resource::<Events<MyEvent>>().policy(),
+            EventStoragePolicy::Manual
+        );
+
+        app.add_event_with_policy::<MyEvent>(EventStoragePolicy::RetainUntilConsumed);
+        assert_eq!(
+            app.world.resource::<Events<MyEvent>>().policy(),
+            EventStoragePolicy::RetainUntilConsumed
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "was already added")]
+    fn unique_plugin_added_twice() {
+        App::new().add_plugin(PluginA).add_plugin(PluginA);
+    }
+
+    #[test]
+    #[should_panic(expected = "PluginB requires bevy_app::app::tests::PluginA")]
+    fn missing_plugin_dependency() {
+        App::new().add_plugin(PluginB).run();
+    }
+}
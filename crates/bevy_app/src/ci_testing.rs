@@ -1,24 +1,141 @@
-use crate::{app::AppExit, App};
-use serde::Deserialize;
+//! Automated testing on CI, enabled with the `bevy_ci_testing` feature.
 
-use bevy_ecs::prelude::Resource;
-use bevy_utils::tracing::info;
+use crate::{app::AppExit, App, AppTypeRegistry, CoreStage};
+use serde::{de::DeserializeSeed, Deserialize};
+
+use bevy_ecs::{
+    event::{Events, ManualEventReader},
+    prelude::{
+        EventWriter, ExclusiveSystemDescriptorCoercion, IntoExclusiveSystem, Local, Res, Resource,
+    },
+    reflect::ReflectResource,
+    world::World,
+};
+use bevy_reflect::{serde::ReflectDeserializer, GetPath, Reflect};
+use bevy_utils::tracing::{error, info};
+use std::{
+    fmt::Display,
+    sync::atomic::{AtomicBool, Ordering},
+};
 
 /// A configuration struct for automated CI testing.
 ///
 /// It gets used when the `bevy_ci_testing` feature is enabled to automatically
 /// exit a Bevy app when run through the CI. This is needed because otherwise
 /// Bevy apps would be stuck in the game loop and wouldn't allow the CI to progress.
+///
+/// It can also script the app with a timeline of [`CiTestingEvent`]s:
+///
+/// ```ron
+/// (
+///     exit_after: Some(100),
+///     events: [
+///         (10, KeyPress("Space")),
+///         (11, KeyRelease("Space")),
+///         (20, SetResource(
+///             resource: "my_game::Level",
+///             path: "index",
+///             value: { "type": "usize", "value": 2 },
+///         )),
+///         (50, AssertResource(
+///             resource: "my_game::Score",
+///             path: "points",
+///             value: { "type": "u32", "value": 100 },
+///         )),
+///         (90, CompareScene("tests/golden/level_2.scn.ron")),
+///     ],
+/// )
+/// ```
 #[derive(Deserialize, Resource)]
 pub struct CiTestingConfig {
     /// The number of frames after which Bevy should exit.
     pub exit_after: Option<u32>,
+    /// The events to send, with the frame to send them on.
+    #[serde(default)]
+    pub events: Vec<(u32, CiTestingEvent)>,
+}
+
+/// A scripted event of a [`CiTestingConfig`].
+///
+/// The events are sent as regular events on the frame they are scheduled for, in
+/// [`CoreStage::First`]. The events concerning other crates are handled by them when their
+/// `bevy_ci_testing` feature is enabled.
+#[derive(Deserialize, Debug, Clone)]
+pub enum CiTestingEvent {
+    /// Presses the keyboard key with the given `KeyCode` name. Handled by `bevy_input`.
+    KeyPress(String),
+    /// Releases the keyboard key with the given `KeyCode` name. Handled by `bevy_input`.
+    KeyRelease(String),
+    /// Presses the mouse button with the given `MouseButton` name. Handled by `bevy_input`.
+    MouseButtonPress(String),
+    /// Releases the mouse button with the given `MouseButton` name. Handled by `bevy_input`.
+    MouseButtonRelease(String),
+    /// Sets the field at `path` of a reflected resource, before [`CoreStage::Update`].
+    SetResource {
+        /// The type name of the resource, which must be registered with `#[reflect(Resource)]`.
+        resource: String,
+        /// The [path](bevy_reflect::GetPath) of the field, empty for the whole resource.
+        path: String,
+        /// The new value, in the reflection format used by scene files.
+        value: ron::Value,
+    },
+    /// Fails the test unless the field at `path` of a reflected resource equals `value`, at the
+    /// end of the frame.
+    AssertResource {
+        /// The type name of the resource, which must be registered with `#[reflect(Resource)]`.
+        resource: String,
+        /// The [path](bevy_reflect::GetPath) of the field, empty for the whole resource.
+        path: String,
+        /// The expected value, in the reflection format used by scene files.
+        value: ron::Value,
+    },
+    /// Writes the world as a `DynamicScene` to the given file, at the end of the frame. Handled by
+    /// `bevy_scene`.
+    DumpScene(String),
+    /// Fails the test unless the world, as a `DynamicScene`, matches the given golden file at the
+    /// end of the frame. Handled by `bevy_scene`.
+    CompareScene(String),
+}
+
+static FAILED: AtomicBool = AtomicBool::new(false);
+
+/// Fails the CI test: logs `message` and exits the app.
+///
+/// Once the runner of the app returns, the process exits with a non-zero status.
+pub fn fail(world: &mut World, message: impl Display) {
+    error!("CI test failed: {}", message);
+    FAILED.store(true, Ordering::Relaxed);
+    world.send_event(AppExit);
+}
+
+/// Exits the process with a non-zero status if the CI test failed.
+pub(crate) fn exit_on_failure() {
+    #[cfg(not(target_arch = "wasm32"))]
+    if FAILED.load(Ordering::Relaxed) {
+        std::process::exit(1);
+    }
+}
+
+/// Returns an exclusive system calling `handler` for each [`CiTestingEvent`].
+pub fn ci_testing_event_handler(
+    mut handler: impl FnMut(&mut World, &CiTestingEvent) + Send + Sync + 'static,
+) -> impl FnMut(&mut World) + Send + Sync + 'static {
+    let mut reader = ManualEventReader::<CiTestingEvent>::default();
+    move |world: &mut World| {
+        let events: Vec<_> = reader
+            .iter(world.resource::<Events<CiTestingEvent>>())
+            .cloned()
+            .collect();
+        for event in &events {
+            handler(world, event);
+        }
+    }
 }
 
 fn ci_testing_exit_after(
-    mut current_frame: bevy_ecs::prelude::Local<u32>,
-    ci_testing_config: bevy_ecs::prelude::Res<CiTestingConfig>,
-    mut app_exit_events: bevy_ecs::event::EventWriter<AppExit>,
+    mut current_frame: Local<u32>,
+    ci_testing_config: Res<CiTestingConfig>,
+    mut app_exit_events: EventWriter<AppExit>,
 ) {
     if let Some(exit_after) = ci_testing_config.exit_after {
         if *current_frame > exit_after {
@@ -29,6 +146,98 @@ fn ci_testing_exit_after(
     *current_frame += 1;
 }
 
+fn ci_testing_send_events(
+    mut current_frame: Local<u32>,
+    ci_testing_config: Res<CiTestingConfig>,
+    mut events: EventWriter<CiTestingEvent>,
+) {
+    events.send_batch(
+        ci_testing_config
+            .events
+            .iter()
+            .filter(|(frame, _)| *frame == *current_frame)
+            .map(|(_, event)| event.clone()),
+    );
+    *current_frame += 1;
+}
+
+fn handle_set_resource(world: &mut World, event: &CiTestingEvent) {
+    if let CiTestingEvent::SetResource {
+        resource,
+        path,
+        value,
+    } = event
+    {
+        if let Err(message) = with_resource_field(world, resource, path, value, |field, value| {
+            field.apply(value);
+            Ok(())
+        }) {
+            fail(world, message);
+        }
+    }
+}
+
+fn handle_assert_resource(world: &mut World, event: &CiTestingEvent) {
+    if let CiTestingEvent::AssertResource {
+        resource,
+        path,
+        value,
+    } = event
+    {
+        if let Err(message) = with_resource_field(world, resource, path, value, |field, value| {
+            if field.reflect_partial_eq(value) == Some(true) {
+                Ok(())
+            } else {
+                Err(format!("expected {:?}, found {:?}", value, field))
+            }
+        }) {
+            fail(world, message);
+        }
+    }
+}
+
+/// Deserializes `value` and passes it to `f` together with the field at `path` of `resource`.
+fn with_resource_field(
+    world: &mut World,
+    resource: &str,
+    path: &str,
+    value: &ron::Value,
+    f: impl FnOnce(&mut dyn Reflect, &dyn Reflect) -> Result<(), String>,
+) -> Result<(), String> {
+    let registry = world.resource::<AppTypeRegistry>().clone();
+    let registry = registry.read();
+    let registration = registry
+        .get_with_name(resource)
+        .ok_or_else(|| format!("resource {} is not registered", resource))?;
+    let reflect_resource = registration
+        .data::<ReflectResource>()
+        .ok_or_else(|| format!("resource {} does not reflect Resource", resource))?;
+    let value = ReflectDeserializer::new(&registry)
+        .deserialize(value.clone())
+        .map_err(|err| format!("invalid value for {}.{}: {}", resource, path, err))?;
+
+    let mut resource_value = reflect_resource
+        .reflect_mut(world)
+        .ok_or_else(|| format!("resource {} does not exist", resource))?;
+    let field = if path.is_empty() {
+        &mut *resource_value
+    } else {
+        resource_value
+            .path_mut(path)
+            .map_err(|err| format!("invalid path {}.{}: {:?}", resource, path, err))?
+    };
+    if field.type_name() != value.type_name() {
+        return Err(format!(
+            "{}.{} is a {}, not a {}",
+            resource,
+            path,
+            field.type_name(),
+            value.type_name()
+        ));
+    }
+    f(field, &*value).map_err(|err| format!("{}.{}: {}", resource, path, err))
+}
+
 pub(crate) fn setup_app(app: &mut App) -> &mut App {
     #[cfg(not(target_arch = "wasm32"))]
     let config: CiTestingConfig = {
@@ -47,7 +256,73 @@ pub(crate) fn setup_app(app: &mut App) -> &mut App {
     };
 
     app.insert_resource(config)
-        .add_system(ci_testing_exit_after);
+        .add_event::<CiTestingEvent>()
+        .add_system(ci_testing_exit_after)
+        .add_system_to_stage(CoreStage::First, ci_testing_send_events)
+        .add_system_to_stage(
+            CoreStage::PreUpdate,
+            ci_testing_event_handler(handle_set_resource)
+                .exclusive_system()
+                .at_start(),
+        )
+        .add_system_to_stage(
+            CoreStage::Last,
+            ci_testing_event_handler(handle_assert_resource)
+                .exclusive_system()
+                .at_end(),
+        );
 
     app
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Resource, Reflect, Default)]
+    #[reflect(Resource)]
+    struct Score {
+        points: u32,
+    }
+
+    fn event(ron: &str) -> CiTestingEvent {
+        ron::from_str(ron).unwrap()
+    }
+
+    #[test]
+    fn set_and_assert_resource() {
+        let mut world = World::new();
+        world.init_resource::<Events<AppExit>>();
+        world.init_resource::<AppTypeRegistry>();
+        world
+            .resource::<AppTypeRegistry>()
+            .write()
+            .register::<Score>();
+        world.init_resource::<Score>();
+
+        handle_set_resource(
+            &mut world,
+            &event(
+                r#"SetResource(resource: "bevy_app::ci_testing::tests::Score", path: "points", value: { "type": "u32", "value": 42 })"#,
+            ),
+        );
+        assert_eq!(world.resource::<Score>().points, 42);
+
+        handle_assert_resource(
+            &mut world,
+            &event(
+                r#"AssertResource(resource: "bevy_app::ci_testing::tests::Score", path: "points", value: { "type": "u32", "value": 42 })"#,
+            ),
+        );
+        assert!(world.resource::<Events<AppExit>>().is_empty());
+
+        handle_assert_resource(
+            &mut world,
+            &event(
+                r#"AssertResource(resource: "bevy_app::ci_testing::tests::Score", path: "points", value: { "type": "u32", "value": 7 })"#,
+            ),
+        );
+        assert!(!world.resource::<Events<AppExit>>().is_empty());
+        FAILED.store(false, Ordering::Relaxed);
+    }
+}
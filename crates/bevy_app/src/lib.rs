@@ -5,15 +5,19 @@
 mod app;
 mod plugin;
 mod plugin_group;
+mod schedule_graph;
 mod schedule_runner;
 
+pub mod testing;
+
 #[cfg(feature = "bevy_ci_testing")]
-mod ci_testing;
+pub mod ci_testing;
 
 pub use app::*;
 pub use bevy_derive::DynamicPlugin;
 pub use plugin::*;
 pub use plugin_group::*;
+pub use schedule_graph::*;
 pub use schedule_runner::*;
 
 #[allow(missing_docs)]
@@ -23,11 +27,12 @@ pub mod prelude {
     pub use crate::AppTypeRegistry;
     #[doc(hidden)]
     pub use crate::{
-        app::App, CoreStage, DynamicPlugin, Plugin, PluginGroup, StartupSchedule, StartupStage,
+        app::App, CoreSet, CoreStage, DynamicPlugin, Plugin, PluginGroup, StartupSchedule,
+        StartupStage,
     };
 }
 
-use bevy_ecs::schedule::StageLabel;
+use bevy_ecs::{schedule::StageLabel, schedule_v3::SystemSet};
 
 /// The names of the default [`App`] stages.
 ///
@@ -38,7 +43,11 @@ pub enum CoreStage {
     First,
     /// The [`Stage`](bevy_ecs::schedule::Stage) that runs before [`CoreStage::Update`].
     PreUpdate,
-    /// The [`Stage`](bevy_ecs::schedule::Stage) responsible for doing most app logic. Systems should be registered here by default.
+    /// The [`Schedule`](bevy_ecs::schedule_v3::Schedule) responsible for doing most app logic. Systems should be registered here by default.
+    ///
+    /// Its systems are organized in [`CoreSet`]s. This is the only stage migrated to
+    /// [`schedule_v3`](bevy_ecs::schedule_v3) so far, so systems are added to it with
+    /// [`App::add_system`] rather than [`App::add_system_to_stage`].
     Update,
     /// The [`Stage`](bevy_ecs::schedule::Stage) that runs after [`CoreStage::Update`].
     PostUpdate,
@@ -64,3 +73,15 @@ pub enum StartupStage {
     /// The [`Stage`](bevy_ecs::schedule::Stage) that runs once after [`StartupStage::Startup`].
     PostStartup,
 }
+
+/// The [`SystemSet`]s of the [`CoreStage::Update`] schedule.
+///
+/// Systems added with [`App::add_system`] are placed in [`CoreSet::Update`].
+#[derive(Debug, Hash, PartialEq, Eq, Clone, SystemSet)]
+pub enum CoreSet {
+    /// Applies the state transitions of the states added with [`App::add_state`].
+    /// Runs before [`CoreSet::Update`].
+    StateTransitions,
+    /// Runs the app logic. Systems are added here by default.
+    Update,
+}
@@ -1,5 +1,5 @@
 use crate::App;
-use std::any::Any;
+use std::any::{Any, TypeId};
 
 /// A collection of Bevy app logic and configuration.
 ///
@@ -8,10 +8,85 @@ use std::any::Any;
 pub trait Plugin: Any + Send + Sync {
     /// Configures the [`App`] to which this plugin is added.
     fn build(&self, app: &mut App);
+    /// Returns `true` once the plugin is ready to [finish](Plugin::finish) configuring the
+    /// [`App`], e.g. when a resource initialized asynchronously is available.
+    ///
+    /// [`App::run`] waits until all the plugins are ready before finishing them. On wasm, where
+    /// it cannot wait, plugins must be ready by the time the app is run.
+    fn ready(&self, _app: &App) -> bool {
+        true
+    }
+    /// Finishes configuring the [`App`], once all the plugins were [built](Plugin::build) and are
+    /// [ready](Plugin::ready).
+    ///
+    /// This is called by [`App::run`] before the runner of the app is called.
+    fn finish(&self, _app: &mut App) {}
+    /// Runs after all the plugins were [finished](Plugin::finish), e.g. to remove the
+    /// resources only needed to configure the [`App`].
+    ///
+    /// This is called by [`App::run`] before the runner of the app is called.
+    fn cleanup(&self, _app: &mut App) {}
     /// Configures a name for the [`Plugin`] which is primarily used for debugging.
     fn name(&self) -> &str {
         std::any::type_name::<Self>()
     }
+    /// The other [`Plugin`]s that must be added to the [`App`] for this plugin to work.
+    ///
+    /// They can be added before or after this plugin, but [`App::run`] panics if any of them is
+    /// missing.
+    ///
+    /// ```
+    /// # use bevy_app::{prelude::*, PluginDependency};
+    /// # struct InputPlugin;
+    /// # impl Plugin for InputPlugin {
+    /// #     fn build(&self, app: &mut App) {}
+    /// # }
+    /// struct PlayerControllerPlugin;
+    ///
+    /// impl Plugin for PlayerControllerPlugin {
+    ///     fn build(&self, app: &mut App) {}
+    ///
+    ///     fn dependencies(&self) -> Vec<PluginDependency> {
+    ///         vec![PluginDependency::of::<InputPlugin>()]
+    ///     }
+    /// }
+    /// ```
+    fn dependencies(&self) -> Vec<PluginDependency> {
+        Vec::new()
+    }
+    /// If the plugin can only be added once to an [`App`].
+    ///
+    /// If this returns `true`, adding the plugin a second time panics.
+    fn is_unique(&self) -> bool {
+        true
+    }
+}
+
+/// A [`Plugin`] type that another plugin requires, see [`Plugin::dependencies`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PluginDependency {
+    type_id: TypeId,
+    name: &'static str,
+}
+
+impl PluginDependency {
+    /// Returns the dependency on the plugin of type `T`.
+    pub fn of<T: Plugin>() -> Self {
+        Self {
+            type_id: TypeId::of::<T>(),
+            name: std::any::type_name::<T>(),
+        }
+    }
+
+    /// Returns the [`TypeId`] of the required plugin.
+    pub fn type_id(&self) -> TypeId {
+        self.type_id
+    }
+
+    /// Returns the type name of the required plugin.
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
 }
 
 /// A type representing an unsafe function that returns a mutable pointer to a [`Plugin`].
@@ -0,0 +1,165 @@
+use crate::{app::App, plugin::Plugin};
+use bevy_ecs::{prelude::Resource, schedule::ScheduleGraph, schedule_v3::Schedules};
+use bevy_utils::tracing::{error, info};
+use std::path::PathBuf;
+
+/// The configuration information for the [`ScheduleGraphPlugin`].
+///
+/// It can be added as a [`Resource`](bevy_ecs::system::Resource) before the
+/// [`ScheduleGraphPlugin`] to choose where the graph is written.
+#[derive(Resource, Clone, Debug)]
+pub struct ScheduleGraphSettings {
+    /// The file the graph is written to in the Graphviz DOT format, if any.
+    pub dot_path: Option<PathBuf>,
+    /// The file the graph is written to in the JSON format, if any.
+    pub json_path: Option<PathBuf>,
+}
+
+impl Default for ScheduleGraphSettings {
+    fn default() -> Self {
+        ScheduleGraphSettings {
+            dot_path: Some(PathBuf::from("schedule_graph.dot")),
+            json_path: Some(PathBuf::from("schedule_graph.json")),
+        }
+    }
+}
+
+/// Writes the resolved dependency graph of the [`App`]'s schedule to disk when the app starts,
+/// to help debug the execution order of systems.
+///
+/// The graph is written by [`App::run`], before the runner starts, according to the
+/// [`ScheduleGraphSettings`]. See [`App::schedule_graph`] for its contents.
+#[derive(Default)]
+pub struct ScheduleGraphPlugin;
+
+impl Plugin for ScheduleGraphPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ScheduleGraphSettings>();
+    }
+}
+
+impl App {
+    /// Returns the resolved dependency graph of every stage of the [`App`]'s schedule, in
+    /// execution order, followed by the graphs of the schedules stored in the [`Schedules`]
+    /// resource.
+    ///
+    /// Systems that have not run yet are initialized first. See
+    /// [`Schedule::graph`](bevy_ecs::schedule::Schedule::graph) for details.
+    pub fn schedule_graph(&mut self) -> ScheduleGraph {
+        let mut graph = self.schedule.graph(&mut self.world);
+        if let Some(mut schedules) = self.world.remove_resource::<Schedules>() {
+            for (label, schedule) in schedules.iter_mut() {
+                let stage = schedule.stage_graph(&mut self.world);
+                graph.stages.push((format!("{:?}", label), stage));
+            }
+            self.world.insert_resource(schedules);
+        }
+        graph
+    }
+}
+
+/// Writes the schedule graph according to the [`ScheduleGraphSettings`], if they are present.
+pub(crate) fn write_schedule_graph(app: &mut App) {
+    let settings = match app.world.get_resource::<ScheduleGraphSettings>() {
+        Some(settings) => settings.clone(),
+        None => return,
+    };
+
+    let graph = app.schedule_graph();
+    let outputs = [
+        (settings.dot_path, graph.to_dot()),
+        (settings.json_path, graph.to_json()),
+    ];
+    for (path, contents) in outputs {
+        if let Some(path) = path {
+            match std::fs::write(&path, contents) {
+                Ok(()) => info!("Wrote the schedule graph to {}", path.display()),
+                Err(err) => error!(
+                    "Failed to write the schedule graph to {}: {}",
+                    path.display(),
+                    err
+                ),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn my_system() {}
+
+    fn other_system() {}
+
+    #[test]
+    fn schedule_graph_includes_app_systems() {
+        let mut app = App::new();
+        app.add_system(my_system);
+        let graph = app.schedule_graph();
+
+        let update = graph.get("CoreStage::Update").unwrap();
+        assert!(update
+            .systems
+            .iter()
+            .any(|system| system.name.ends_with("my_system")));
+        assert!(graph
+            .stages
+            .iter()
+            .any(|(label, _)| label.starts_with("StartupSchedule/")));
+        assert_eq!(
+            graph.stages.last().map(|(label, _)| label.as_str()),
+            Some("CoreStage::Last")
+        );
+    }
+
+    #[test]
+    fn schedule_graph_includes_update_edges() {
+        use bevy_ecs::schedule_v3::IntoSystemConfig;
+
+        let mut app = App::new();
+        app.add_system(my_system)
+            .add_system(other_system.after(my_system));
+        let graph = app.schedule_graph();
+
+        let update = graph.get("CoreStage::Update").unwrap();
+        let index = |name: &str| {
+            update
+                .systems
+                .iter()
+                .position(|system| system.name.ends_with(name))
+                .unwrap()
+        };
+        let (my_system, other_system) = (index("my_system"), index("other_system"));
+        assert_eq!(update.systems[other_system].dependencies, vec![my_system]);
+        assert!(update.systems[other_system]
+            .after
+            .iter()
+            .any(|name| name.contains("my_system")));
+    }
+
+    #[test]
+    fn plugin_writes_graph() {
+        let dir = std::env::temp_dir().join(format!("bevy_schedule_graph_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let dot_path = dir.join("graph.dot");
+        let json_path = dir.join("graph.json");
+
+        let mut app = App::new();
+        app.insert_resource(ScheduleGraphSettings {
+            dot_path: Some(dot_path.clone()),
+            json_path: Some(json_path.clone()),
+        })
+        .add_plugin(ScheduleGraphPlugin)
+        .add_system(my_system);
+        app.run();
+
+        let dot = std::fs::read_to_string(&dot_path).unwrap();
+        let json = std::fs::read_to_string(&json_path).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert!(dot.starts_with("digraph schedule {"));
+        assert!(dot.contains("my_system"));
+        assert!(json.starts_with("{\"stages\":["));
+        assert!(json.contains("my_system"));
+    }
+}
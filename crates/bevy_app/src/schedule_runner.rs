@@ -22,10 +22,56 @@ pub enum RunMode {
         /// has completed before repeating. A value of [`None`] will not wait.
         wait: Option<Duration>,
     },
+    /// Indicates that the [`App`]'s schedule should run repeatedly at a fixed tick rate.
+    ///
+    /// Unlike [`RunMode::Loop`], the time spent updating the [`App`] is accounted for: the ticks
+    /// are scheduled every `period` from the first one, so that they do not drift. The timing of
+    /// the ticks is recorded in the [`FramePacingStats`] resource.
+    Paced {
+        /// The [`Duration`] between the start of two ticks.
+        period: Duration,
+        /// What to do with the ticks that were missed because an update took too long.
+        overrun: OverrunPolicy,
+    },
     /// Indicates that the [`App`]'s schedule should run only once.
     Once,
 }
 
+/// Determines how a [`RunMode::Paced`] runner recovers from the ticks it missed because an update
+/// took longer than the tick period.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum OverrunPolicy {
+    /// Runs up to `max_ticks` of the late ticks back to back to catch up, and skips the others.
+    CatchUp {
+        /// The maximum number of late ticks run without waiting.
+        max_ticks: u32,
+    },
+    /// Runs the next tick immediately, and skips the other late ticks.
+    #[default]
+    Skip,
+}
+
+/// Statistics about the ticks of a [`RunMode::Paced`] runner.
+///
+/// It is inserted and updated by the [`ScheduleRunnerPlugin`] after each update of the [`App`],
+/// so the systems of a tick see the statistics up to the previous tick.
+#[derive(Clone, Debug, Default, Resource)]
+pub struct FramePacingStats {
+    /// The number of ticks run.
+    pub ticks: u64,
+    /// The number of ticks whose update ended more than one period after the tick was scheduled,
+    /// or after it started if it started late. Catch-up ticks that run late only because of an
+    /// earlier overrun are not counted.
+    pub overruns: u64,
+    /// The number of ticks skipped to recover from overruns, see [`OverrunPolicy`].
+    pub skipped_ticks: u64,
+    /// The time taken by the last update.
+    pub last_update: Duration,
+    /// How late the last update ended past the start time of the next tick, or zero if it
+    /// ended in time.
+    pub last_overrun: Duration,
+}
+
 impl Default for RunMode {
     fn default() -> Self {
         RunMode::Loop { wait: None }
@@ -57,10 +103,85 @@ impl ScheduleRunnerSettings {
             },
         }
     }
+
+    /// Runs `ticks_per_second` ticks every second. See [`RunMode::Paced`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `ticks_per_second` is not a finite, strictly positive number.
+    pub fn run_paced(ticks_per_second: f64, overrun: OverrunPolicy) -> Self {
+        assert!(
+            ticks_per_second > 0.0 && ticks_per_second.is_finite(),
+            "ticks_per_second must be finite and greater than zero, got {ticks_per_second}"
+        );
+        ScheduleRunnerSettings {
+            run_mode: RunMode::Paced {
+                period: Duration::from_secs_f64(1.0 / ticks_per_second),
+                overrun,
+            },
+        }
+    }
+}
+
+/// Schedules the ticks of a [`RunMode::Paced`] runner.
+struct FramePacer {
+    period: Duration,
+    overrun: OverrunPolicy,
+    next_tick: Option<Instant>,
+}
+
+impl FramePacer {
+    fn new(period: Duration, overrun: OverrunPolicy) -> Self {
+        Self {
+            period,
+            overrun,
+            next_tick: None,
+        }
+    }
+
+    /// Records the update that ran from `start_time` to `end_time` into `stats`, and returns the
+    /// delay until the next tick.
+    fn after_update(
+        &mut self,
+        start_time: Instant,
+        end_time: Instant,
+        stats: &mut FramePacingStats,
+    ) -> Duration {
+        let scheduled = self.next_tick.unwrap_or(start_time);
+        let mut next_tick = scheduled + self.period;
+        stats.ticks += 1;
+        stats.last_update = end_time - start_time;
+        stats.last_overrun = end_time.saturating_duration_since(next_tick);
+
+        // a catch-up tick starts after it was scheduled, so only its own update can overrun
+        if end_time > scheduled.max(start_time) + self.period {
+            stats.overruns += 1;
+        }
+        if end_time > next_tick {
+            let late_ticks = (stats.last_overrun.as_nanos() / self.period.as_nanos().max(1))
+                .try_into()
+                .unwrap_or(u32::MAX)
+                .saturating_add(1);
+            let max_late_ticks = match self.overrun {
+                OverrunPolicy::CatchUp { max_ticks } => max_ticks.max(1),
+                OverrunPolicy::Skip => 1,
+            };
+            if late_ticks > max_late_ticks {
+                let skipped_ticks = late_ticks - max_late_ticks;
+                stats.skipped_ticks += skipped_ticks as u64;
+                next_tick += self.period * skipped_ticks;
+            }
+        }
+
+        self.next_tick = Some(next_tick);
+        next_tick.saturating_duration_since(end_time)
+    }
 }
 
 /// Configures an [`App`] to run its [`Schedule`](bevy_ecs::schedule::Schedule) according to a given
 /// [`RunMode`].
+///
+/// The [`RunMode`] is read from the [`ScheduleRunnerSettings`] resource when the plugin is added.
 #[derive(Default)]
 pub struct ScheduleRunnerPlugin;
 
@@ -76,10 +197,15 @@ impl Plugin for ScheduleRunnerPlugin {
                 RunMode::Once => {
                     app.update();
                 }
-                RunMode::Loop { wait } => {
-                    let mut tick = move |app: &mut App,
-                                         wait: Option<Duration>|
-                          -> Result<Option<Duration>, AppExit> {
+                RunMode::Loop { .. } | RunMode::Paced { .. } => {
+                    let mut pacer = match settings.run_mode {
+                        RunMode::Paced { period, overrun } => {
+                            app.init_resource::<FramePacingStats>();
+                            Some(FramePacer::new(period, overrun))
+                        }
+                        _ => None,
+                    };
+                    let mut tick = move |app: &mut App| -> Result<Option<Duration>, AppExit> {
                         let start_time = Instant::now();
 
                         if let Some(app_exit_events) =
@@ -104,7 +230,12 @@ impl Plugin for ScheduleRunnerPlugin {
 
                         let end_time = Instant::now();
 
-                        if let Some(wait) = wait {
+                        if let Some(pacer) = &mut pacer {
+                            let mut stats = app.world.resource_mut::<FramePacingStats>();
+                            return Ok(Some(pacer.after_update(start_time, end_time, &mut stats)));
+                        }
+
+                        if let RunMode::Loop { wait: Some(wait) } = settings.run_mode {
                             let exe_time = end_time - start_time;
                             if exe_time < wait {
                                 return Ok(Some(wait - exe_time));
@@ -116,7 +247,7 @@ impl Plugin for ScheduleRunnerPlugin {
 
                     #[cfg(not(target_arch = "wasm32"))]
                     {
-                        while let Ok(delay) = tick(&mut app, wait) {
+                        while let Ok(delay) = tick(&mut app) {
                             if let Some(delay) = delay {
                                 std::thread::sleep(delay);
                             }
@@ -142,7 +273,7 @@ impl Plugin for ScheduleRunnerPlugin {
 
                         let c = move || {
                             let mut app = Rc::get_mut(&mut rc).unwrap();
-                            let delay = tick(&mut app, wait);
+                            let delay = tick(&mut app);
                             match delay {
                                 Ok(delay) => {
                                     set_timeout(f.borrow().as_ref().unwrap(), delay.unwrap_or(asap))
@@ -158,3 +289,78 @@ impl Plugin for ScheduleRunnerPlugin {
         });
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{FramePacer, FramePacingStats, OverrunPolicy, ScheduleRunnerSettings};
+    use bevy_utils::{Duration, Instant};
+
+    const PERIOD: Duration = Duration::from_millis(10);
+
+    fn ms(millis: u64) -> Duration {
+        Duration::from_millis(millis)
+    }
+
+    #[test]
+    fn paced_ticks_do_not_drift() {
+        let mut pacer = FramePacer::new(PERIOD, OverrunPolicy::Skip);
+        let mut stats = FramePacingStats::default();
+        let start = Instant::now();
+
+        assert_eq!(pacer.after_update(start, start + ms(4), &mut stats), ms(6));
+        // the tick started 1ms late, the next one is still on time
+        assert_eq!(
+            pacer.after_update(start + ms(11), start + ms(15), &mut stats),
+            ms(5)
+        );
+        assert_eq!(stats.ticks, 2);
+        assert_eq!(stats.overruns, 0);
+        assert_eq!(stats.last_update, ms(4));
+    }
+
+    #[test]
+    fn overrun_policies() {
+        let start = Instant::now();
+
+        let mut pacer = FramePacer::new(PERIOD, OverrunPolicy::Skip);
+        let mut stats = FramePacingStats::default();
+        // ends 35ms late: the next tick runs immediately, and the 3 ticks after it are skipped
+        assert_eq!(
+            pacer.after_update(start, start + ms(45), &mut stats),
+            Duration::ZERO
+        );
+        assert_eq!(stats.overruns, 1);
+        assert_eq!(stats.skipped_ticks, 3);
+        assert_eq!(stats.last_overrun, ms(35));
+        assert_eq!(
+            pacer.after_update(start + ms(45), start + ms(46), &mut stats),
+            ms(4)
+        );
+
+        let mut pacer = FramePacer::new(PERIOD, OverrunPolicy::CatchUp { max_ticks: 2 });
+        let mut stats = FramePacingStats::default();
+        assert_eq!(
+            pacer.after_update(start, start + ms(45), &mut stats),
+            Duration::ZERO
+        );
+        assert_eq!(stats.skipped_ticks, 2);
+        // the two late ticks run back to back
+        assert_eq!(
+            pacer.after_update(start + ms(45), start + ms(46), &mut stats),
+            Duration::ZERO
+        );
+        assert_eq!(
+            pacer.after_update(start + ms(46), start + ms(47), &mut stats),
+            ms(3)
+        );
+        // the late ticks ran in time from their start, so they are not overruns
+        assert_eq!(stats.overruns, 1);
+        assert_eq!(stats.last_overrun, Duration::ZERO);
+    }
+
+    #[test]
+    #[should_panic(expected = "ticks_per_second must be finite and greater than zero")]
+    fn run_paced_zero_ticks_per_second() {
+        ScheduleRunnerSettings::run_paced(0.0, OverrunPolicy::Skip);
+    }
+}
@@ -0,0 +1,325 @@
+//! Tools for testing [`App`]s headlessly and deterministically.
+
+use crate::app::{App, AppExit};
+use bevy_ecs::{
+    event::{Event, Events, ManualEventReader},
+    world::World,
+};
+use bevy_utils::Duration;
+use std::collections::BTreeMap;
+
+type WorldAction = Box<dyn FnOnce(&mut World)>;
+type Clock = Box<dyn FnMut(&mut World, Duration)>;
+
+/// Runs an [`App`] frame by frame with a fixed virtual frame time, without a runner or a window.
+///
+/// Frames are counted from `0`. Before each frame, the harness:
+/// 1. passes the virtual time elapsed since frame `0` to every clock added with
+///    [`add_clock`](Self::add_clock), e.g. to feed it to `bevy_time` through its `TimeSender`,
+/// 2. runs the actions scheduled with [`run_at`](Self::run_at), like the events injected with
+///    [`send_event_at`](Self::send_event_at).
+///
+/// After the frame, the checks scheduled with [`assert_at`](Self::assert_at) are run.
+///
+/// Since neither the wall-clock time nor the real input devices are involved, running the same
+/// harness twice gives the same results.
+///
+/// # Examples
+///
+/// ```
+/// # use bevy_app::{prelude::*, testing::AppTestHarness};
+/// # use bevy_ecs::prelude::*;
+/// #
+/// struct Jump;
+///
+/// #[derive(Resource, Default)]
+/// struct Jumps(u32);
+///
+/// fn count_jumps(mut events: EventReader<Jump>, mut jumps: ResMut<Jumps>) {
+///     jumps.0 += events.iter().count() as u32;
+/// }
+///
+/// let mut app = App::new();
+/// app.add_event::<Jump>()
+///     .init_resource::<Jumps>()
+///     .add_system(count_jumps);
+///
+/// let mut harness = AppTestHarness::new(app);
+/// harness
+///     .send_event_at(2, Jump)
+///     .send_event_at(5, Jump)
+///     .assert_at(2, |world| assert_eq!(world.resource::<Jumps>().0, 1));
+/// harness.update_frames(10);
+///
+/// assert_eq!(harness.frame(), 10);
+/// assert_eq!(harness.world().resource::<Jumps>().0, 2);
+/// ```
+pub struct AppTestHarness {
+    app: App,
+    frame: u32,
+    frame_delta: Duration,
+    clocks: Vec<Clock>,
+    before_frame: BTreeMap<u32, Vec<WorldAction>>,
+    after_frame: BTreeMap<u32, Vec<WorldAction>>,
+    app_exit_reader: ManualEventReader<AppExit>,
+    exit_requested: bool,
+}
+
+impl AppTestHarness {
+    /// The virtual time between two frames used by default, one sixtieth of a second.
+    pub const DEFAULT_FRAME_DELTA: Duration = Duration::from_nanos(1_000_000_000 / 60);
+
+    /// Creates a harness running `app`, starting at frame `0`.
+    ///
+    /// Like [`App::run`], this waits until the plugins of `app` are [ready](App::ready), then
+    /// [finishes](App::finish) and [cleans them up](App::cleanup).
+    pub fn new(mut app: App) -> Self {
+        app.finish_plugins();
+        Self {
+            app,
+            frame: 0,
+            frame_delta: Self::DEFAULT_FRAME_DELTA,
+            clocks: Vec::new(),
+            before_frame: BTreeMap::new(),
+            after_frame: BTreeMap::new(),
+            app_exit_reader: ManualEventReader::default(),
+            exit_requested: false,
+        }
+    }
+
+    /// Sets the virtual time between two frames.
+    #[must_use]
+    pub fn with_frame_delta(mut self, frame_delta: Duration) -> Self {
+        self.frame_delta = frame_delta;
+        self
+    }
+
+    /// Returns the virtual time between two frames.
+    pub fn frame_delta(&self) -> Duration {
+        self.frame_delta
+    }
+
+    /// Adds a function that is given the virtual time elapsed since frame `0` before each frame.
+    pub fn add_clock(&mut self, clock: impl FnMut(&mut World, Duration) + 'static) -> &mut Self {
+        self.clocks.push(Box::new(clock));
+        self
+    }
+
+    /// Runs `action` on the [`World`] right before `frame` is updated.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `frame` has already been updated.
+    pub fn run_at(&mut self, frame: u32, action: impl FnOnce(&mut World) + 'static) -> &mut Self {
+        self.assert_not_updated(frame);
+        self.before_frame
+            .entry(frame)
+            .or_default()
+            .push(Box::new(action));
+        self
+    }
+
+    /// Sends `event` right before `frame` is updated, so that it can be read during that frame.
+    ///
+    /// This is how input is injected, e.g. `KeyboardInput` or `GamepadEventRaw` events.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `frame` has already been updated.
+    pub fn send_event_at<E: Event>(&mut self, frame: u32, event: E) -> &mut Self {
+        self.run_at(frame, move |world| world.send_event(event))
+    }
+
+    /// Runs `check` on the [`World`] right after `frame` is updated.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `frame` has already been updated.
+    pub fn assert_at(&mut self, frame: u32, check: impl FnOnce(&World) + 'static) -> &mut Self {
+        self.assert_not_updated(frame);
+        self.after_frame
+            .entry(frame)
+            .or_default()
+            .push(Box::new(move |world: &mut World| check(world)));
+        self
+    }
+
+    /// Updates the app for a single frame.
+    pub fn update(&mut self) {
+        let elapsed = self.elapsed();
+        for clock in &mut self.clocks {
+            clock(&mut self.app.world, elapsed);
+        }
+        for action in self.before_frame.remove(&self.frame).into_iter().flatten() {
+            action(&mut self.app.world);
+        }
+
+        self.app.update();
+
+        if let Some(app_exit_events) = self.app.world.get_resource::<Events<AppExit>>() {
+            if self.app_exit_reader.iter(app_exit_events).last().is_some() {
+                self.exit_requested = true;
+            }
+        }
+        for check in self.after_frame.remove(&self.frame).into_iter().flatten() {
+            check(&mut self.app.world);
+        }
+        self.frame += 1;
+    }
+
+    /// Updates the app for `frames` frames.
+    pub fn update_frames(&mut self, frames: u32) {
+        for _ in 0..frames {
+            self.update();
+        }
+    }
+
+    /// Updates the app until an [`AppExit`] event is sent, for at most `max_frames` frames.
+    ///
+    /// Returns `true` if the app requested to exit.
+    pub fn update_until_exit(&mut self, max_frames: u32) -> bool {
+        for _ in 0..max_frames {
+            if self.exit_requested {
+                break;
+            }
+            self.update();
+        }
+        self.exit_requested
+    }
+
+    /// Returns `true` if an [`AppExit`] event has been sent in any of the updated frames.
+    pub fn exit_requested(&self) -> bool {
+        self.exit_requested
+    }
+
+    /// Returns the index of the next frame to be updated, which is the number of frames updated
+    /// so far.
+    pub fn frame(&self) -> u32 {
+        self.frame
+    }
+
+    /// Returns the virtual time elapsed between frame `0` and the next frame to be updated.
+    pub fn elapsed(&self) -> Duration {
+        self.frame_delta * self.frame
+    }
+
+    /// Returns the [`World`] of the app.
+    pub fn world(&self) -> &World {
+        &self.app.world
+    }
+
+    /// Returns the [`World`] of the app mutably.
+    pub fn world_mut(&mut self) -> &mut World {
+        &mut self.app.world
+    }
+
+    /// Returns the app.
+    pub fn app(&self) -> &App {
+        &self.app
+    }
+
+    /// Returns the app mutably.
+    pub fn app_mut(&mut self) -> &mut App {
+        &mut self.app
+    }
+
+    fn assert_not_updated(&self, frame: u32) {
+        assert!(
+            frame >= self.frame,
+            "Frame {} has already been updated, the harness is at frame {}.",
+            frame,
+            self.frame
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::AppTestHarness;
+    use crate::{App, AppExit, Plugin};
+    use bevy_ecs::prelude::*;
+    use bevy_tasks::{ComputeTaskPool, TaskPool};
+    use bevy_utils::Duration;
+    use std::{
+        cell::RefCell,
+        rc::Rc,
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc,
+        },
+    };
+
+    #[test]
+    fn clocks_receive_virtual_time() {
+        let elapsed = Rc::new(RefCell::new(Vec::new()));
+        let mut harness =
+            AppTestHarness::new(App::new()).with_frame_delta(Duration::from_millis(10));
+        let recorded = elapsed.clone();
+        harness.add_clock(move |_, elapsed| recorded.borrow_mut().push(elapsed));
+        harness.update_frames(3);
+
+        assert_eq!(
+            *elapsed.borrow(),
+            vec![
+                Duration::ZERO,
+                Duration::from_millis(10),
+                Duration::from_millis(20)
+            ]
+        );
+        assert_eq!(harness.elapsed(), Duration::from_millis(30));
+    }
+
+    #[test]
+    fn plugins_are_finished() {
+        #[derive(Resource)]
+        struct Finished;
+
+        struct WaitingPlugin(Arc<AtomicBool>);
+
+        impl Plugin for WaitingPlugin {
+            fn build(&self, _app: &mut App) {
+                let loaded = self.0.clone();
+                ComputeTaskPool::init(TaskPool::default)
+                    .spawn_local(async move { loaded.store(true, Ordering::Relaxed) })
+                    .detach();
+            }
+
+            fn ready(&self, _app: &App) -> bool {
+                self.0.load(Ordering::Relaxed)
+            }
+
+            fn finish(&self, app: &mut App) {
+                app.insert_resource(Finished);
+            }
+        }
+
+        let mut app = App::new();
+        app.add_plugin(WaitingPlugin(Arc::default()));
+        let harness = AppTestHarness::new(app);
+        assert!(harness.world().contains_resource::<Finished>());
+    }
+
+    #[test]
+    fn update_until_exit() {
+        let mut app = App::new();
+        app.add_system(|mut frames: Local<u32>, mut exit: EventWriter<AppExit>| {
+            *frames += 1;
+            if *frames == 4 {
+                exit.send(AppExit);
+            }
+        });
+        let mut harness = AppTestHarness::new(app);
+
+        assert!(harness.update_until_exit(10));
+        assert_eq!(harness.frame(), 4);
+        assert!(!AppTestHarness::new(App::new()).update_until_exit(3));
+    }
+
+    #[test]
+    #[should_panic(expected = "already been updated")]
+    fn scheduling_past_frame_panics() {
+        let mut harness = AppTestHarness::new(App::new());
+        harness.update_frames(2);
+        harness.run_at(1, |_| {});
+    }
+}
@@ -0,0 +1,86 @@
+use bevy_app::{App, Plugin};
+use bevy_ecs::{system::ResMut, world::World};
+
+use crate::{Diagnostic, DiagnosticId, Diagnostics};
+
+/// Adds diagnostics about the archetypes, tables and sparse sets of the ECS to an App,
+/// specifically their count, fragmentation and memory usage
+#[derive(Default)]
+pub struct EcsMemoryDiagnosticsPlugin;
+
+impl Plugin for EcsMemoryDiagnosticsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_startup_system(Self::setup_system)
+            .add_system(Self::diagnostic_system);
+    }
+}
+
+impl EcsMemoryDiagnosticsPlugin {
+    pub const ARCHETYPE_COUNT: DiagnosticId =
+        DiagnosticId::from_u128(99832747270774514029293063197963791389);
+    pub const FRAGMENTED_ARCHETYPE_COUNT: DiagnosticId =
+        DiagnosticId::from_u128(95952712930750627326346425417950879716);
+    pub const TABLE_COUNT: DiagnosticId =
+        DiagnosticId::from_u128(111378958269477729779551637383885801852);
+    pub const MEMORY_USED: DiagnosticId =
+        DiagnosticId::from_u128(99046035851812707030296314604882627407);
+    pub const MEMORY_ALLOCATED: DiagnosticId =
+        DiagnosticId::from_u128(9334048445836922433823737219931462383);
+    pub const SPARSE_SET_MEMORY_ALLOCATED: DiagnosticId =
+        DiagnosticId::from_u128(4755831552705444347460646825813169836);
+
+    /// Archetypes with fewer entities than this are counted as fragmented.
+    pub const FRAGMENTED_ARCHETYPE_MIN_ENTITIES: usize = 16;
+
+    pub fn setup_system(mut diagnostics: ResMut<Diagnostics>) {
+        diagnostics.add(Diagnostic::new(
+            Self::ARCHETYPE_COUNT,
+            "ecs_archetype_count",
+            20,
+        ));
+        diagnostics.add(Diagnostic::new(
+            Self::FRAGMENTED_ARCHETYPE_COUNT,
+            "ecs_fragmented_archetype_count",
+            20,
+        ));
+        diagnostics.add(Diagnostic::new(Self::TABLE_COUNT, "ecs_table_count", 20));
+        diagnostics
+            .add(Diagnostic::new(Self::MEMORY_USED, "ecs_memory_used", 20).with_suffix("KiB"));
+        diagnostics.add(
+            Diagnostic::new(Self::MEMORY_ALLOCATED, "ecs_memory_allocated", 20).with_suffix("KiB"),
+        );
+        diagnostics.add(
+            Diagnostic::new(
+                Self::SPARSE_SET_MEMORY_ALLOCATED,
+                "ecs_sparse_set_memory_allocated",
+                20,
+            )
+            .with_suffix("KiB"),
+        );
+    }
+
+    pub fn diagnostic_system(world: &mut World) {
+        let stats = world.memory_stats();
+        let mut diagnostics = world.resource_mut::<Diagnostics>();
+
+        diagnostics.add_measurement(Self::ARCHETYPE_COUNT, || stats.archetypes.len() as f64);
+        diagnostics.add_measurement(Self::FRAGMENTED_ARCHETYPE_COUNT, || {
+            stats
+                .fragmented_archetypes(Self::FRAGMENTED_ARCHETYPE_MIN_ENTITIES)
+                .count() as f64
+        });
+        diagnostics.add_measurement(Self::TABLE_COUNT, || stats.tables.len() as f64);
+        diagnostics.add_measurement(Self::MEMORY_USED, || stats.bytes_used() as f64 / 1024.0);
+        diagnostics.add_measurement(Self::MEMORY_ALLOCATED, || {
+            stats.bytes_allocated() as f64 / 1024.0
+        });
+        diagnostics.add_measurement(Self::SPARSE_SET_MEMORY_ALLOCATED, || {
+            stats
+                .sparse_sets
+                .iter()
+                .map(|set| set.bytes_allocated)
+                .sum::<usize>() as f64
+                / 1024.0
+        });
+    }
+}
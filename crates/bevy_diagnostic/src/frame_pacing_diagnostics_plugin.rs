@@ -0,0 +1,64 @@
+use bevy_app::{App, FramePacingStats, Plugin};
+use bevy_ecs::system::{Res, ResMut};
+
+use crate::{Diagnostic, DiagnosticId, Diagnostics};
+
+/// Adds diagnostics about the ticks of a [`RunMode::Paced`](bevy_app::RunMode::Paced) runner to an
+/// App, specifically the update time, overrun time, overrun count and skipped tick count
+#[derive(Default)]
+pub struct FramePacingDiagnosticsPlugin;
+
+impl Plugin for FramePacingDiagnosticsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_startup_system(Self::setup_system)
+            .add_system(Self::diagnostic_system);
+    }
+}
+
+impl FramePacingDiagnosticsPlugin {
+    pub const UPDATE_TIME: DiagnosticId =
+        DiagnosticId::from_u128(1506170860541567981499762216166610574);
+    pub const OVERRUN_TIME: DiagnosticId =
+        DiagnosticId::from_u128(155267552897475341546538238703316344402);
+    pub const OVERRUN_COUNT: DiagnosticId =
+        DiagnosticId::from_u128(218360073460129803151481269183502921682);
+    pub const SKIPPED_TICK_COUNT: DiagnosticId =
+        DiagnosticId::from_u128(244851184693600881321538331599759520560);
+
+    pub fn setup_system(mut diagnostics: ResMut<Diagnostics>) {
+        diagnostics
+            .add(Diagnostic::new(Self::UPDATE_TIME, "tick_update_time", 20).with_suffix("ms"));
+        diagnostics
+            .add(Diagnostic::new(Self::OVERRUN_TIME, "tick_overrun_time", 20).with_suffix("ms"));
+        diagnostics.add(Diagnostic::new(
+            Self::OVERRUN_COUNT,
+            "tick_overrun_count",
+            1,
+        ));
+        diagnostics.add(Diagnostic::new(
+            Self::SKIPPED_TICK_COUNT,
+            "skipped_tick_count",
+            1,
+        ));
+    }
+
+    /// Does nothing until the runner has recorded its first tick in [`FramePacingStats`].
+    pub fn diagnostic_system(
+        mut diagnostics: ResMut<Diagnostics>,
+        stats: Option<Res<FramePacingStats>>,
+    ) {
+        let stats = match stats {
+            Some(stats) if stats.ticks > 0 => stats,
+            _ => return,
+        };
+
+        diagnostics.add_measurement(Self::UPDATE_TIME, || {
+            stats.last_update.as_secs_f64() * 1000.
+        });
+        diagnostics.add_measurement(Self::OVERRUN_TIME, || {
+            stats.last_overrun.as_secs_f64() * 1000.
+        });
+        diagnostics.add_measurement(Self::OVERRUN_COUNT, || stats.overruns as f64);
+        diagnostics.add_measurement(Self::SKIPPED_TICK_COUNT, || stats.skipped_ticks as f64);
+    }
+}
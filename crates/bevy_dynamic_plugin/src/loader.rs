@@ -28,7 +28,6 @@ impl DynamicPluginExt for App {
     unsafe fn load_plugin(&mut self, path: &str) -> &mut Self {
         let (lib, plugin) = dynamically_load_plugin(path);
         std::mem::forget(lib); // Ensure that the library is not automatically unloaded
-        plugin.build(self);
-        self
+        self.add_boxed_plugin(plugin)
     }
 }
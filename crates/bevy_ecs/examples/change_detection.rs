@@ -1,4 +1,4 @@
-use bevy_ecs::prelude::*;
+use bevy_ecs::{prelude::*, schedule_v3::Schedule};
 use rand::Rng;
 use std::ops::Deref;
 
@@ -18,19 +18,14 @@ fn main() {
 
     // Create a new Schedule, which defines an execution strategy for Systems
     let mut schedule = Schedule::default();
-    // Create a Stage to add to our Schedule. Each Stage in a schedule runs all of its systems
-    // before moving on to the next Stage
-    let mut update = SystemStage::parallel();
 
-    // Add systems to the Stage to execute our app logic
-    // We can label our systems to force a specific run-order between some of them
-    update.add_system(spawn_entities.label(SimulationSystem::Spawn));
-    update.add_system(print_counter_when_changed.after(SimulationSystem::Spawn));
-    update.add_system(age_all_entities.label(SimulationSystem::Age));
-    update.add_system(remove_old_entities.after(SimulationSystem::Age));
-    update.add_system(print_changed_entities.after(SimulationSystem::Age));
-    // Add the Stage with our systems to the Schedule
-    schedule.add_stage("update", update);
+    // Add systems to the Schedule to execute our app logic
+    // We can group our systems into sets to force a specific run-order between some of them
+    schedule.add_system(spawn_entities.in_set(SimulationSet::Spawn));
+    schedule.add_system(print_counter_when_changed.after(SimulationSet::Spawn));
+    schedule.add_system(age_all_entities.in_set(SimulationSet::Age));
+    schedule.add_system(remove_old_entities.after(SimulationSet::Age));
+    schedule.add_system(print_changed_entities.after(SimulationSet::Age));
 
     // Simulate 10 frames in our world
     for iteration in 1..=10 {
@@ -51,9 +46,9 @@ struct Age {
     frames: i32,
 }
 
-// System labels to enforce a run order of our systems
-#[derive(SystemLabel, Debug, Clone, PartialEq, Eq, Hash)]
-enum SimulationSystem {
+// System sets to enforce a run order of our systems
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+enum SimulationSet {
     Spawn,
     Age,
 }
@@ -1,4 +1,4 @@
-use bevy_ecs::{event::Events, prelude::*};
+use bevy_ecs::{event::Events, prelude::*, schedule_v3::Schedule};
 
 // In this example a system sends a custom event with a 50/50 chance during any frame.
 // If an event was send, it will be printed by the console in a receiving system.
@@ -7,25 +7,22 @@ fn main() {
     let mut world = World::new();
     world.insert_resource(Events::<MyEvent>::default());
 
-    // Create a schedule and a stage
+    // Create a schedule to store our systems
     let mut schedule = Schedule::default();
 
-    // Events need to be updated in every frame. This update should happen before we use
-    // the events. To guarantee this, we can let the update run in an earlier stage than our logic.
-    // Here we will use a stage called "first" that will always run it's systems before the Stage
-    // called "second". In "first" we update the events and in "second" we run our systems
-    // sending and receiving events.
-    let mut first = SystemStage::parallel();
-    first.add_system(Events::<MyEvent>::update_system);
-    schedule.add_stage("first", first);
-
-    // Add systems sending and receiving events to a "second" Stage
-    let mut second = SystemStage::parallel();
-    second.add_system(sending_system);
-    second.add_system(receiving_system.after(sending_system));
-
-    // Run the "second" Stage after the "first" Stage, so our Events always get updated before we use them
-    schedule.add_stage_after("first", "second", second);
+    // Events need to be updated in every frame in order to clear our buffers.
+    // This update should happen before we use the events.
+    // Here, we use system sets to control the ordering.
+    #[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+    pub struct FlushEvents;
+
+    schedule.add_system(Events::<MyEvent>::update_system.in_set(FlushEvents));
+
+    // Add systems sending and receiving events after the events are flushed.
+    schedule.add_systems((
+        sending_system.after(FlushEvents),
+        receiving_system.after(sending_system),
+    ));
 
     // Simulate 10 frames of our world
     for iteration in 1..=10 {
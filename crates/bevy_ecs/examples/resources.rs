@@ -1,4 +1,4 @@
-use bevy_ecs::prelude::*;
+use bevy_ecs::{prelude::*, schedule_v3::Schedule};
 use rand::Rng;
 use std::ops::Deref;
 
@@ -11,14 +11,11 @@ fn main() {
     // Add the counter resource
     world.insert_resource(Counter { value: 0 });
 
-    // Create a schedule and a stage
+    // Create a schedule
     let mut schedule = Schedule::default();
-    let mut update = SystemStage::parallel();
 
     // Add systems to increase the counter and to print out the current value
-    update.add_system(increase_counter);
-    update.add_system(print_counter.after(increase_counter));
-    schedule.add_stage("update", update);
+    schedule.add_systems((increase_counter, print_counter).chain());
 
     for iteration in 1..=10 {
         println!("Simulating frame {}/10", iteration);
@@ -3,13 +3,14 @@
 
 use crate::{
     bundle::BundleId,
-    component::{ComponentId, StorageType},
+    component::{ComponentId, Components, StorageType},
     entity::{Entity, EntityLocation},
-    storage::{Column, SparseArray, SparseSet, SparseSetIndex, TableId},
+    storage::{Column, ShrinkPolicy, SparseArray, SparseSet, SparseSetIndex, TableId},
 };
 use std::{
     collections::HashMap,
     hash::Hash,
+    mem::size_of,
     ops::{Index, IndexMut},
 };
 
@@ -133,8 +134,40 @@ pub(crate) struct ArchetypeComponentInfo {
     pub(crate) archetype_component_id: ArchetypeComponentId,
 }
 
+/// Caches which kinds of [`ComponentHooks`](crate::component::ComponentHooks) are registered
+/// for at least one component of an [`Archetype`], so entities without hooks skip them cheaply.
+#[derive(Debug, Default, Clone, Copy)]
+pub(crate) struct ArchetypeHooks {
+    on_add: bool,
+    on_insert: bool,
+    on_remove: bool,
+}
+
+/// Memory usage of an [`Archetype`], as reported by [`Archetypes::stats`].
+///
+/// Component data stored in tables and sparse sets is reported by
+/// [`Tables::stats`](crate::storage::Tables::stats) and
+/// [`SparseSets::stats`](crate::storage::SparseSets::stats) instead.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ArchetypeStats {
+    /// The id of the archetype.
+    pub id: ArchetypeId,
+    /// The id of the table storing the table components of the archetype.
+    pub table_id: TableId,
+    /// The number of entities in the archetype.
+    pub entity_count: usize,
+    /// The number of components of the archetype, across tables and sparse sets.
+    pub component_count: usize,
+    /// The bytes occupied by the entity list, the table rows and any unique components.
+    pub bytes_used: usize,
+    /// The bytes allocated for the entity list, the table rows and any unique components,
+    /// including unused capacity.
+    pub bytes_allocated: usize,
+}
+
 pub struct Archetype {
     id: ArchetypeId,
+    hooks: ArchetypeHooks,
     entities: Vec<Entity>,
     edges: Edges,
     table_info: TableInfo,
@@ -181,6 +214,7 @@ impl Archetype {
         }
         Self {
             id,
+            hooks: Default::default(),
             table_info: TableInfo {
                 id: table_id,
                 entity_rows: Default::default(),
@@ -249,6 +283,24 @@ impl Archetype {
         &mut self.edges
     }
 
+    /// Returns `true` if any component of this archetype has an `on_add` hook.
+    #[inline]
+    pub fn has_add_hook(&self) -> bool {
+        self.hooks.on_add
+    }
+
+    /// Returns `true` if any component of this archetype has an `on_insert` hook.
+    #[inline]
+    pub fn has_insert_hook(&self) -> bool {
+        self.hooks.on_insert
+    }
+
+    /// Returns `true` if any component of this archetype has an `on_remove` hook.
+    #[inline]
+    pub fn has_remove_hook(&self) -> bool {
+        self.hooks.on_remove
+    }
+
     #[inline]
     pub fn entity_table_row(&self, index: usize) -> usize {
         self.table_info.entity_rows[index]
@@ -328,6 +380,14 @@ impl Archetype {
         self.entities.clear();
         self.table_info.entity_rows.clear();
     }
+
+    pub(crate) fn shrink_to_fit(&mut self) {
+        self.entities.shrink_to_fit();
+        self.table_info.entity_rows.shrink_to_fit();
+        for column in self.unique_components.values_mut() {
+            column.shrink_to_fit();
+        }
+    }
 }
 
 /// A generational id that changes every time the set of archetypes changes
@@ -391,7 +451,12 @@ impl Default for Archetypes {
             archetype_ids: Default::default(),
             archetype_component_count: 0,
         };
-        archetypes.get_id_or_insert(TableId::empty(), Vec::new(), Vec::new());
+        archetypes.get_id_or_insert(
+            &Components::default(),
+            TableId::empty(),
+            Vec::new(),
+            Vec::new(),
+        );
 
         // adds the resource archetype. it is "special" in that it is inaccessible via a "hash",
         // which prevents entities from being added to it
@@ -478,6 +543,31 @@ impl Archetypes {
         self.archetypes.iter()
     }
 
+    /// Returns the memory used by each archetype.
+    pub fn stats(&self) -> Vec<ArchetypeStats> {
+        const ROW_SIZE: usize = size_of::<Entity>() + size_of::<usize>();
+        self.archetypes
+            .iter()
+            .map(|archetype| {
+                let unique = archetype.unique_components.values().map(Column::stats);
+                let (unique_used, unique_allocated) = unique
+                    .fold((0, 0), |(used, allocated), c| {
+                        (used + c.bytes_used, allocated + c.bytes_allocated)
+                    });
+                ArchetypeStats {
+                    id: archetype.id(),
+                    table_id: archetype.table_id(),
+                    entity_count: archetype.len(),
+                    component_count: archetype.components.len(),
+                    bytes_used: archetype.len() * ROW_SIZE + unique_used,
+                    bytes_allocated: archetype.entities.capacity() * size_of::<Entity>()
+                        + archetype.table_info.entity_rows.capacity() * size_of::<usize>()
+                        + unique_allocated,
+                }
+            })
+            .collect()
+    }
+
     /// Gets the archetype id matching the given inputs or inserts a new one if it doesn't exist.
     /// `table_components` and `sparse_set_components` must be sorted
     ///
@@ -485,6 +575,7 @@ impl Archetypes {
     /// [`TableId`] must exist in tables
     pub(crate) fn get_id_or_insert(
         &mut self,
+        components: &Components,
         table_id: TableId,
         table_components: Vec<ComponentId>,
         sparse_set_components: Vec<ComponentId>,
@@ -514,14 +605,23 @@ impl Archetypes {
                 let sparse_set_archetype_components = (0..sparse_set_components.len())
                     .map(|_| next_archetype_component_id())
                     .collect();
-                archetypes.push(Archetype::new(
+                let mut archetype = Archetype::new(
                     id,
                     table_id,
                     table_components,
                     sparse_set_components,
                     table_archetype_components,
                     sparse_set_archetype_components,
-                ));
+                );
+                for component_id in archetype.components.indices() {
+                    // SAFETY: archetypes are only created from initialized components
+                    let hooks = unsafe { components.get_info_unchecked(component_id) }.hooks();
+                    let flags = &mut archetype.hooks;
+                    flags.on_add |= hooks.on_add.is_some();
+                    flags.on_insert |= hooks.on_insert.is_some();
+                    flags.on_remove |= hooks.on_remove.is_some();
+                }
+                archetypes.push(archetype);
                 id
             })
     }
@@ -536,6 +636,14 @@ impl Archetypes {
             archetype.clear_entities();
         }
     }
+
+    pub(crate) fn shrink_to_fit(&mut self, policy: ShrinkPolicy) {
+        for archetype in &mut self.archetypes {
+            if policy == ShrinkPolicy::All || archetype.is_empty() {
+                archetype.shrink_to_fit();
+            }
+        }
+    }
 }
 
 impl Index<ArchetypeId> for Archetypes {
@@ -133,6 +133,29 @@ macro_rules! tuple_impl {
 
 all_tuples!(tuple_impl, 0, 15, C);
 
+/// The component values written by a [`BundleInserter`] or [`BundleSpawner`], either from a
+/// [`Bundle`] or from type-erased [`DynamicBundleComponents`].
+pub(crate) trait BundleComponents {
+    /// Calls `func` on each value, in "bundle order".
+    fn get_components(self, func: impl FnMut(OwningPtr<'_>));
+}
+
+impl<T: Bundle> BundleComponents for T {
+    fn get_components(self, func: impl FnMut(OwningPtr<'_>)) {
+        Bundle::get_components(self, func);
+    }
+}
+
+/// The values of a bundle whose components are only known at runtime, in the order of the
+/// [`ComponentId`]s passed to [`Bundles::init_dynamic_info`].
+pub(crate) struct DynamicBundleComponents<'a>(pub(crate) Vec<OwningPtr<'a>>);
+
+impl BundleComponents for DynamicBundleComponents<'_> {
+    fn get_components(self, func: impl FnMut(OwningPtr<'_>)) {
+        self.0.into_iter().for_each(func);
+    }
+}
+
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
 pub struct BundleId(usize);
 
@@ -266,7 +289,7 @@ impl BundleInfo {
     /// `entity`, `bundle` must match this [`BundleInfo`]'s type
     #[inline]
     #[allow(clippy::too_many_arguments)]
-    unsafe fn write_components<T: Bundle>(
+    unsafe fn write_components<T: BundleComponents>(
         &self,
         table: &mut Table,
         sparse_sets: &mut SparseSets,
@@ -377,8 +400,12 @@ impl BundleInfo {
                     new_sparse_set_components
                 };
             };
-            let new_archetype_id =
-                archetypes.get_id_or_insert(table_id, table_components, sparse_set_components);
+            let new_archetype_id = archetypes.get_id_or_insert(
+                components,
+                table_id,
+                table_components,
+                sparse_set_components,
+            );
             // add an edge from the old archetype to the new archetype
             archetypes[archetype_id].edges_mut().insert_add_bundle(
                 self.id,
@@ -417,7 +444,7 @@ impl<'a, 'b> BundleInserter<'a, 'b> {
     /// `entity` must currently exist in the source archetype for this inserter. `archetype_index`
     /// must be `entity`'s location in the archetype. `T` must match this [`BundleInfo`]'s type
     #[inline]
-    pub unsafe fn insert<T: Bundle>(
+    pub unsafe fn insert<T: BundleComponents>(
         &mut self,
         entity: Entity,
         archetype_index: usize,
@@ -581,6 +608,7 @@ impl<'a, 'b> BundleSpawner<'a, 'b> {
 pub struct Bundles {
     bundle_infos: Vec<BundleInfo>,
     bundle_ids: HashMap<TypeId, BundleId>,
+    dynamic_bundle_ids: HashMap<Vec<ComponentId>, BundleId>,
 }
 
 impl Bundles {
@@ -613,6 +641,41 @@ impl Bundles {
         // SAFETY: index either exists, or was initialized
         unsafe { self.bundle_infos.get_unchecked(id.0) }
     }
+
+    /// Initializes the [`BundleInfo`] of a bundle made of the given components, which are only
+    /// known at runtime. The components are in "bundle order".
+    ///
+    /// # Panics
+    ///
+    /// Panics if any of the [`ComponentId`]s is not valid, or if they contain duplicates.
+    pub(crate) fn init_dynamic_info<'a>(
+        &'a mut self,
+        components: &mut Components,
+        component_ids: &[ComponentId],
+    ) -> &'a BundleInfo {
+        let bundle_infos = &mut self.bundle_infos;
+        let id = self
+            .dynamic_bundle_ids
+            .entry(component_ids.to_vec())
+            .or_insert_with(|| {
+                for &component_id in component_ids {
+                    assert!(
+                        components.get_info(component_id).is_some(),
+                        "{:?} is not a valid component id",
+                        component_id
+                    );
+                }
+                let id = BundleId(bundle_infos.len());
+                // SAFETY: the component ids were checked above
+                let bundle_info = unsafe {
+                    initialize_bundle("dynamic bundle", component_ids.to_vec(), id, components)
+                };
+                bundle_infos.push(bundle_info);
+                id
+            });
+        // SAFETY: index either exists, or was initialized
+        unsafe { self.bundle_infos.get_unchecked(id.0) }
+    }
 }
 
 /// # Safety
@@ -647,3 +710,122 @@ unsafe fn initialize_bundle(
         storage_types,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate as bevy_ecs;
+    use crate::prelude::*;
+
+    #[derive(Component)]
+    struct A;
+
+    #[derive(Component)]
+    struct B(usize);
+
+    #[derive(Component)]
+    struct C;
+
+    #[derive(Resource, Default)]
+    struct Log(Vec<&'static str>);
+
+    fn log_hooks<T: Component>(world: &mut World) {
+        world
+            .register_component_hooks::<T>()
+            .on_add(|mut world, _, _| world.resource_mut::<Log>().0.push("add"))
+            .on_insert(|mut world, _, _| world.resource_mut::<Log>().0.push("insert"))
+            .on_remove(|mut world, _, _| world.resource_mut::<Log>().0.push("remove"));
+    }
+
+    #[test]
+    fn component_hook_order() {
+        let mut world = World::new();
+        world.init_resource::<Log>();
+        log_hooks::<A>(&mut world);
+
+        let entity = world.spawn().insert(A).id();
+        world.entity_mut(entity).insert(A);
+        world.entity_mut(entity).remove::<A>();
+        world.entity_mut(entity).insert_bundle((A, B(0)));
+        world.despawn(entity);
+
+        assert_eq!(
+            world.resource::<Log>().0,
+            ["add", "insert", "insert", "remove", "add", "insert", "remove"]
+        );
+    }
+
+    #[test]
+    fn component_hook_on_remove_reads_value() {
+        let mut world = World::new();
+        world.init_resource::<Log>();
+        world
+            .register_component_hooks::<B>()
+            .on_remove(|mut world, entity, _| {
+                let value = world.get::<B>(entity).unwrap().0;
+                world.resource_mut::<Log>().0.push(["zero", "one"][value]);
+            });
+
+        let entity = world.spawn().insert_bundle((A, B(1))).id();
+        world
+            .entity_mut(entity)
+            .remove_bundle_intersection::<(B, C)>();
+        world.entity_mut(entity).insert(B(0));
+        world.despawn(entity);
+
+        assert_eq!(world.resource::<Log>().0, ["one", "zero"]);
+    }
+
+    #[test]
+    fn component_hook_commands_are_applied() {
+        let mut world = World::new();
+        world
+            .register_component_hooks::<A>()
+            .on_add(|mut world, entity, _| {
+                world.commands().entity(entity).insert(B(1));
+            })
+            .on_remove(|mut world, entity, _| {
+                world.commands().entity(entity).remove::<B>();
+            });
+
+        let mut entity = world.spawn();
+        entity.insert(A);
+        assert_eq!(entity.get::<B>().unwrap().0, 1);
+        entity.remove::<A>();
+        assert!(!entity.contains::<B>());
+    }
+
+    #[test]
+    fn component_hooks_run_for_batches() {
+        let mut world = World::new();
+        world.init_resource::<Log>();
+        log_hooks::<A>(&mut world);
+
+        let entities: Vec<_> = world.spawn_batch([(A, B(0)), (A, B(1))]).collect();
+        assert_eq!(
+            world.resource::<Log>().0,
+            ["add", "insert", "add", "insert"]
+        );
+
+        // Hooks run as each entity is spawned, not when the iterator is dropped.
+        world.resource_mut::<Log>().0.clear();
+        let mut batch = world.spawn_batch([(A, B(2)), (A, B(3))]);
+        batch.next();
+        std::mem::forget(batch);
+        assert_eq!(world.resource::<Log>().0, ["add", "insert"]);
+
+        world.resource_mut::<Log>().0.clear();
+        let other = world.spawn().insert(B(2)).id();
+        world
+            .insert_or_spawn_batch([(entities[0], (A,)), (other, (A,))])
+            .unwrap();
+        assert_eq!(world.resource::<Log>().0, ["insert", "add", "insert"]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn component_hooks_after_use_panic() {
+        let mut world = World::new();
+        world.spawn().insert(A);
+        world.register_component_hooks::<A>().on_add(|_, _, _| {});
+    }
+}
@@ -1,8 +1,15 @@
 //! Types that detect when their internal data mutate.
 
-use crate::{component::ComponentTicks, ptr::PtrMut, system::Resource};
+use crate::{
+    component::ComponentTicks,
+    ptr::{Ptr, PtrMut},
+    system::Resource,
+};
 #[cfg(feature = "bevy_reflect")]
-use std::ops::{Deref, DerefMut};
+use std::{
+    ops::{Deref, DerefMut},
+    ptr::NonNull,
+};
 
 /// The (arbitrarily chosen) minimum number of world tick increments between `check_tick` scans.
 ///
@@ -68,8 +75,8 @@ pub trait DetectChanges {
 }
 
 macro_rules! change_detection_impl {
-    ($name:ident < $( $generics:tt ),+ >, $target:ty, $($traits:ident)?) => {
-        impl<$($generics),* $(: $traits)?> DetectChanges for $name<$($generics),*> {
+    ($name:ident < $( $generics:tt ),+ >, $target:ty, $($bounds:tt)*) => {
+        impl<$($generics),*: $($bounds)*> DetectChanges for $name<$($generics),*> {
             #[inline]
             fn is_added(&self) -> bool {
                 self.ticks
@@ -97,7 +104,7 @@ macro_rules! change_detection_impl {
             }
         }
 
-        impl<$($generics),* $(: $traits)?> Deref for $name<$($generics),*> {
+        impl<$($generics),*: $($bounds)*> Deref for $name<$($generics),*> {
             type Target = $target;
 
             #[inline]
@@ -106,7 +113,7 @@ macro_rules! change_detection_impl {
             }
         }
 
-        impl<$($generics),* $(: $traits)?> DerefMut for $name<$($generics),*> {
+        impl<$($generics),*: $($bounds)*> DerefMut for $name<$($generics),*> {
             #[inline]
             fn deref_mut(&mut self) -> &mut Self::Target {
                 self.set_changed();
@@ -114,14 +121,14 @@ macro_rules! change_detection_impl {
             }
         }
 
-        impl<$($generics),* $(: $traits)?> AsRef<$target> for $name<$($generics),*> {
+        impl<$($generics),*: $($bounds)*> AsRef<$target> for $name<$($generics),*> {
             #[inline]
             fn as_ref(&self) -> &$target {
                 self.deref()
             }
         }
 
-        impl<$($generics),* $(: $traits)?> AsMut<$target> for $name<$($generics),*> {
+        impl<$($generics),*: $($bounds)*> AsMut<$target> for $name<$($generics),*> {
             #[inline]
             fn as_mut(&mut self) -> &mut $target {
                 self.deref_mut()
@@ -131,8 +138,8 @@ macro_rules! change_detection_impl {
 }
 
 macro_rules! impl_into_inner {
-    ($name:ident < $( $generics:tt ),+ >, $target:ty, $($traits:ident)?) => {
-        impl<$($generics),* $(: $traits)?> $name<$($generics),*> {
+    ($name:ident < $( $generics:tt ),+ >, $target:ty, $($bounds:tt)*) => {
+        impl<$($generics),*: $($bounds)*> $name<$($generics),*> {
             /// Consume `self` and return a mutable reference to the
             /// contained value while marking `self` as "changed".
             #[inline]
@@ -234,8 +241,9 @@ pub struct Mut<'a, T: ?Sized> {
     pub(crate) ticks: Ticks<'a>,
 }
 
-change_detection_impl!(Mut<'a, T>, T,);
-impl_into_inner!(Mut<'a, T>, T,);
+// `Mut` also wraps unsized values, such as `Mut<dyn Reflect>`.
+change_detection_impl!(Mut<'a, T>, T, ?Sized);
+impl_into_inner!(Mut<'a, T>, T, ?Sized);
 impl_debug!(Mut<'a, T>,);
 
 /// Unique mutable borrow of resources or an entity's component.
@@ -258,6 +266,19 @@ impl<'a> MutUntyped<'a> {
     pub fn into_inner(self) -> PtrMut<'a> {
         self.value
     }
+
+    /// Returns a pointer to the value, without marking it as changed.
+    pub fn as_ref(&self) -> Ptr<'_> {
+        // SAFETY: the shared borrow of `self` prevents mutable access for the returned lifetime
+        unsafe { Ptr::new(NonNull::new_unchecked(self.value.as_ptr())) }
+    }
+
+    /// Returns a mutable pointer to the value, marking it as changed.
+    pub fn as_mut(&mut self) -> PtrMut<'_> {
+        self.set_changed();
+        // SAFETY: the mutable borrow of `self` ensures the returned pointer is unique
+        unsafe { PtrMut::new(NonNull::new_unchecked(self.value.as_ptr())) }
+    }
 }
 
 impl DetectChanges for MutUntyped<'_> {
@@ -295,15 +316,18 @@ impl std::fmt::Debug for MutUntyped<'_> {
 #[cfg(test)]
 mod tests {
     use bevy_ecs_macros::Resource;
+    use std::any::Any;
 
     use crate::{
         self as bevy_ecs,
         change_detection::{
             ComponentTicks, Mut, NonSendMut, ResMut, Ticks, CHECK_TICK_THRESHOLD, MAX_CHANGE_AGE,
         },
-        component::Component,
-        query::ChangeTrackers,
-        system::{IntoSystem, Query, System},
+        component::{Component, Tick},
+        entity::Entity,
+        prelude::DetectChanges,
+        query::{ChangeTrackers, Changed},
+        system::{IntoSystem, Query, System, SystemChangeTick},
         world::World,
     };
 
@@ -370,6 +394,55 @@ mod tests {
         assert!(change_detected_system.run((), &mut world));
     }
 
+    #[test]
+    fn tick_is_newer_than() {
+        let last_change_tick = Tick::new(u32::MAX);
+        let change_tick = Tick::new(5);
+
+        assert!(Tick::new(0).is_newer_than(last_change_tick, change_tick));
+        assert!(Tick::new(5).is_newer_than(last_change_tick, change_tick));
+        assert!(!Tick::new(u32::MAX).is_newer_than(last_change_tick, change_tick));
+        assert!(!Tick::new(u32::MAX - 1).is_newer_than(last_change_tick, change_tick));
+    }
+
+    #[test]
+    fn query_since_tick() {
+        #[derive(Resource)]
+        struct LastSent(Tick);
+
+        fn replicate(
+            mut query: Query<Entity, Changed<C>>,
+            mut last_sent: ResMut<LastSent>,
+            ticks: SystemChangeTick,
+        ) -> usize {
+            let count = query.since(last_sent.0).iter().count();
+            last_sent.0 = ticks.this_run();
+            count
+        }
+
+        let mut world = World::new();
+        world.insert_resource(LastSent(Tick::new(0)));
+        let entity = world.spawn().insert(C).id();
+
+        let mut replicate_system = IntoSystem::into_system(replicate);
+        replicate_system.initialize(&mut world);
+        assert_eq!(replicate_system.run((), &mut world), 1);
+        assert_eq!(replicate_system.run((), &mut world), 0);
+
+        // a stored tick from before the change still sees it
+        world.get_mut::<C>(entity).unwrap().set_changed();
+        let before_change = world.resource::<LastSent>().0;
+        let mut query = world.query_filtered::<Entity, Changed<C>>();
+        assert_eq!(query.iter_since(&world, before_change).count(), 1);
+        assert_eq!(replicate_system.run((), &mut world), 1);
+        assert_eq!(query.iter_since(&world, before_change).count(), 1);
+
+        let ticks = world.entity(entity).get_change_ticks::<C>().unwrap();
+        assert!(ticks
+            .changed_tick()
+            .is_newer_than(before_change, Tick::new(world.read_change_tick())));
+    }
+
     #[test]
     fn change_tick_scan() {
         let mut world = World::new();
@@ -424,6 +497,30 @@ mod tests {
         assert_eq!(4, into_mut.ticks.change_tick);
     }
 
+    #[test]
+    fn mut_unsized() {
+        let mut component_ticks = ComponentTicks {
+            added: 1,
+            changed: 1,
+        };
+        let ticks = Ticks {
+            component_ticks: &mut component_ticks,
+            last_change_tick: 2,
+            change_tick: 3,
+        };
+        let mut res = R {};
+        let mut value: Mut<dyn Any> = Mut {
+            value: &mut res,
+            ticks,
+        };
+
+        assert!(value.is::<R>());
+        assert!(!value.is_changed());
+        assert!(value.downcast_mut::<R>().is_some());
+        assert!(value.is_changed());
+        assert!(value.into_inner().is::<R>());
+    }
+
     #[test]
     fn mut_from_non_send_mut() {
         let mut component_ticks = ComponentTicks {
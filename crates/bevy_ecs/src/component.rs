@@ -2,8 +2,10 @@
 
 use crate::{
     change_detection::MAX_CHANGE_AGE,
+    entity::Entity,
     storage::{SparseSetIndex, Storages},
     system::Resource,
+    world::DeferredWorld,
 };
 pub use bevy_ecs_macros::Component;
 use bevy_ptr::OwningPtr;
@@ -155,10 +157,101 @@ pub enum StorageType {
     SparseSet,
 }
 
+/// A function run by the [`World`](crate::world::World) when a component is added to,
+/// inserted into or removed from an entity. See [`ComponentHooks`].
+pub type ComponentHook = for<'w> fn(DeferredWorld<'w>, Entity, ComponentId);
+
+/// The lifecycle hooks of a component type.
+///
+/// Hooks run synchronously, right after a component is added or inserted and right before it is
+/// removed, so unlike [`Added`](crate::query::Added) or
+/// [`RemovedComponents`](crate::system::RemovedComponents) they observe every change as it
+/// happens, including the value of a component that is about to be removed. They receive a
+/// [`DeferredWorld`], which can read and mutate existing data but defers structural changes
+/// through [`DeferredWorld::commands`]. Those commands are applied before the operation that
+/// triggered the hook returns.
+///
+/// Each hook can only be set once, and only before the component is first added to an entity.
+///
+/// ```
+/// # use bevy_ecs::prelude::*;
+/// #[derive(Component)]
+/// struct Health(u32);
+///
+/// #[derive(Resource, Default)]
+/// struct Alive(u32);
+///
+/// let mut world = World::new();
+/// world.init_resource::<Alive>();
+/// world
+///     .register_component_hooks::<Health>()
+///     .on_add(|mut world, _, _| world.resource_mut::<Alive>().0 += 1)
+///     .on_remove(|mut world, _, _| world.resource_mut::<Alive>().0 -= 1);
+///
+/// let entity = world.spawn().insert(Health(10)).id();
+/// assert_eq!(world.resource::<Alive>().0, 1);
+/// world.despawn(entity);
+/// assert_eq!(world.resource::<Alive>().0, 0);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ComponentHooks {
+    pub(crate) on_add: Option<ComponentHook>,
+    pub(crate) on_insert: Option<ComponentHook>,
+    pub(crate) on_remove: Option<ComponentHook>,
+}
+
+impl ComponentHooks {
+    /// Sets a hook that runs when the component is added to an entity that did not have it.
+    /// It runs before [`ComponentHooks::on_insert`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if an `on_add` hook was already set for this component.
+    pub fn on_add(&mut self, hook: ComponentHook) -> &mut Self {
+        assert!(
+            self.on_add.is_none(),
+            "Component already has an on_add hook"
+        );
+        self.on_add = Some(hook);
+        self
+    }
+
+    /// Sets a hook that runs every time the component is inserted, whether or not the entity
+    /// already had it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an `on_insert` hook was already set for this component.
+    pub fn on_insert(&mut self, hook: ComponentHook) -> &mut Self {
+        assert!(
+            self.on_insert.is_none(),
+            "Component already has an on_insert hook"
+        );
+        self.on_insert = Some(hook);
+        self
+    }
+
+    /// Sets a hook that runs right before the component is removed from an entity, including
+    /// when the entity is despawned. The component can still be read from the hook.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an `on_remove` hook was already set for this component.
+    pub fn on_remove(&mut self, hook: ComponentHook) -> &mut Self {
+        assert!(
+            self.on_remove.is_none(),
+            "Component already has an on_remove hook"
+        );
+        self.on_remove = Some(hook);
+        self
+    }
+}
+
 #[derive(Debug)]
 pub struct ComponentInfo {
     id: ComponentId,
     descriptor: ComponentDescriptor,
+    hooks: ComponentHooks,
 }
 
 impl ComponentInfo {
@@ -203,8 +296,18 @@ impl ComponentInfo {
         self.descriptor.is_send_and_sync
     }
 
+    /// The lifecycle hooks registered for this component.
+    #[inline]
+    pub fn hooks(&self) -> &ComponentHooks {
+        &self.hooks
+    }
+
     fn new(id: ComponentId, descriptor: ComponentDescriptor) -> Self {
-        ComponentInfo { id, descriptor }
+        ComponentInfo {
+            id,
+            descriptor,
+            hooks: Default::default(),
+        }
     }
 }
 
@@ -433,6 +536,11 @@ impl Components {
         self.components.get_unchecked(id.0)
     }
 
+    #[inline]
+    pub(crate) fn get_hooks_mut(&mut self, id: ComponentId) -> Option<&mut ComponentHooks> {
+        self.components.get_mut(id.0).map(|info| &mut info.hooks)
+    }
+
     /// Type-erased equivalent of [`Components::component_id`].
     #[inline]
     pub fn get_id(&self, type_id: TypeId) -> Option<ComponentId> {
@@ -517,6 +625,68 @@ impl Components {
     }
 }
 
+/// A value that tracks when a system ran relative to other systems.
+/// This is used to power change detection.
+///
+/// Ticks can be stored, for example by a replication system that keeps track of the last tick
+/// it sent to each client, and then compared with [`Tick::is_newer_than`] or used as the
+/// baseline of a [`Query`](crate::system::Query) through
+/// [`Query::since`](crate::system::Query::since).
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Tick(u32);
+
+impl Tick {
+    /// Creates a new [`Tick`] wrapping the given value.
+    #[inline]
+    pub const fn new(tick: u32) -> Self {
+        Self(tick)
+    }
+
+    /// Gets the value of this change tick.
+    #[inline]
+    pub const fn get(self) -> u32 {
+        self.0
+    }
+
+    /// Sets the value of this change tick.
+    #[inline]
+    pub fn set(&mut self, tick: u32) {
+        self.0 = tick;
+    }
+
+    /// Returns `true` if this `Tick` occurred since the system's `last_change_tick`.
+    ///
+    /// `change_tick` is the current tick of the system, used as a reference to help deal with
+    /// wraparound.
+    #[inline]
+    pub fn is_newer_than(self, last_change_tick: Tick, change_tick: Tick) -> bool {
+        // This works even with wraparound because the world tick (`change_tick`) is always "newer" than
+        // `last_change_tick` and `self`, and we scan periodically to clamp `ComponentTicks` values
+        // so they never get older than `u32::MAX` (the difference would overflow).
+        //
+        // The clamp here ensures determinism (since scans could differ between app runs).
+        let ticks_since_insert = change_tick.0.wrapping_sub(self.0).min(MAX_CHANGE_AGE);
+        let ticks_since_system = change_tick
+            .0
+            .wrapping_sub(last_change_tick.0)
+            .min(MAX_CHANGE_AGE);
+
+        ticks_since_system > ticks_since_insert
+    }
+}
+
+impl From<u32> for Tick {
+    fn from(tick: u32) -> Self {
+        Self(tick)
+    }
+}
+
+impl From<Tick> for u32 {
+    fn from(tick: Tick) -> Self {
+        tick.0
+    }
+}
+
 /// Records when a component was added and when it was last mutably dereferenced (or added).
 #[derive(Copy, Clone, Debug)]
 pub struct ComponentTicks {
@@ -528,33 +698,27 @@ impl ComponentTicks {
     #[inline]
     /// Returns `true` if the component was added after the system last ran.
     pub fn is_added(&self, last_change_tick: u32, change_tick: u32) -> bool {
-        // This works even with wraparound because the world tick (`change_tick`) is always "newer" than
-        // `last_change_tick` and `self.added`, and we scan periodically to clamp `ComponentTicks` values
-        // so they never get older than `u32::MAX` (the difference would overflow).
-        //
-        // The clamp here ensures determinism (since scans could differ between app runs).
-        let ticks_since_insert = change_tick.wrapping_sub(self.added).min(MAX_CHANGE_AGE);
-        let ticks_since_system = change_tick
-            .wrapping_sub(last_change_tick)
-            .min(MAX_CHANGE_AGE);
-
-        ticks_since_system > ticks_since_insert
+        self.added_tick()
+            .is_newer_than(Tick(last_change_tick), Tick(change_tick))
     }
 
     #[inline]
     /// Returns `true` if the component was added or mutably dereferenced after the system last ran.
     pub fn is_changed(&self, last_change_tick: u32, change_tick: u32) -> bool {
-        // This works even with wraparound because the world tick (`change_tick`) is always "newer" than
-        // `last_change_tick` and `self.changed`, and we scan periodically to clamp `ComponentTicks` values
-        // so they never get older than `u32::MAX` (the difference would overflow).
-        //
-        // The clamp here ensures determinism (since scans could differ between app runs).
-        let ticks_since_change = change_tick.wrapping_sub(self.changed).min(MAX_CHANGE_AGE);
-        let ticks_since_system = change_tick
-            .wrapping_sub(last_change_tick)
-            .min(MAX_CHANGE_AGE);
+        self.changed_tick()
+            .is_newer_than(Tick(last_change_tick), Tick(change_tick))
+    }
 
-        ticks_since_system > ticks_since_change
+    /// Returns the tick at which the component was added.
+    #[inline]
+    pub fn added_tick(&self) -> Tick {
+        Tick(self.added)
+    }
+
+    /// Returns the tick at which the component was last added or mutably dereferenced.
+    #[inline]
+    pub fn changed_tick(&self) -> Tick {
+        Tick(self.changed)
     }
 
     pub(crate) fn new(change_tick: u32) -> Self {
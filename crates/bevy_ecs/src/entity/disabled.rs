@@ -0,0 +1,40 @@
+use crate::{self as bevy_ecs, component::Component};
+
+/// Marker component that hides an entity from queries without despawning it.
+///
+/// Entities with a [`Disabled`] component are skipped by every [`Query`](crate::system::Query)
+/// and [`QueryState`](crate::query::QueryState), unless the query explicitly mentions
+/// [`Disabled`], for example through `With<Disabled>`, `Option<&Disabled>` or the
+/// [`IncludeDisabled`](crate::query::IncludeDisabled) filter. Their components are kept as-is
+/// and can still be accessed directly through the [`World`](crate::world::World), so an entity
+/// is enabled again simply by removing the marker.
+///
+/// This is useful to pool entities, such as projectiles, that are frequently turned off and on.
+///
+/// [`Disabled`] uses table storage, so that queries iterating over tables can skip the tables
+/// of disabled entities as a whole.
+///
+/// ```
+/// # use bevy_ecs::prelude::*;
+/// # use bevy_ecs::entity::Disabled;
+/// # use bevy_ecs::query::IncludeDisabled;
+/// #[derive(Component)]
+/// struct Projectile;
+///
+/// let mut world = World::new();
+/// let projectile = world.spawn().insert_bundle((Projectile, Disabled)).id();
+///
+/// assert_eq!(world.query::<&Projectile>().iter(&world).count(), 0);
+/// assert_eq!(
+///     world
+///         .query_filtered::<&Projectile, IncludeDisabled>()
+///         .iter(&world)
+///         .count(),
+///     1
+/// );
+///
+/// world.entity_mut(projectile).remove::<Disabled>();
+/// assert_eq!(world.query::<&Projectile>().iter(&world).count(), 1);
+/// ```
+#[derive(Component, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Disabled;
@@ -33,10 +33,12 @@
 //! [`EntityMut::insert`]: crate::world::EntityMut::insert
 //! [`EntityMut::insert_bundle`]: crate::world::EntityMut::insert_bundle
 //! [`EntityMut::remove`]: crate::world::EntityMut::remove
+mod disabled;
 mod map_entities;
 mod serde;
 
 pub use self::serde::*;
+pub use disabled::*;
 pub use map_entities::*;
 
 use crate::{archetype::ArchetypeId, storage::SparseSetIndex};
@@ -260,6 +262,22 @@ impl<'a> Iterator for ReserveEntitiesIterator<'a> {
 impl<'a> core::iter::ExactSizeIterator for ReserveEntitiesIterator<'a> {}
 impl<'a> core::iter::FusedIterator for ReserveEntitiesIterator<'a> {}
 
+/// The order in which [`Entities`] reuses the IDs of despawned entities.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EntityOrder {
+    /// The most recently freed ID is reused first.
+    #[default]
+    Unordered,
+    /// Freed IDs are reused lowest first, so the IDs handed out only depend on which IDs are free,
+    /// not on the order in which their entities were despawned.
+    ///
+    /// Together with sorting by [`Entity`], e.g. with
+    /// [`Query::par_for_each_sorted`](crate::system::Query::par_for_each_sorted), this makes
+    /// simulations that despawn entities in a nondeterministic order reproducible. Freeing an
+    /// entity costs `O(n)` in the number of free IDs in this mode.
+    Deterministic,
+}
+
 #[derive(Debug, Default)]
 pub struct Entities {
     pub(crate) meta: Vec<EntityMeta>,
@@ -303,9 +321,49 @@ pub struct Entities {
     free_cursor: AtomicIdCursor,
     /// Stores the number of free entities for [`len`](Entities::len)
     len: u32,
+    /// With [`EntityOrder::Deterministic`], the freelist is kept sorted in descending order, so
+    /// that the lowest free ID is at its end and allocated first.
+    order: EntityOrder,
 }
 
 impl Entities {
+    /// Returns the order in which the IDs of despawned entities are reused.
+    #[inline]
+    pub fn order(&self) -> EntityOrder {
+        self.order
+    }
+
+    /// Sets the order in which the IDs of despawned entities are reused.
+    ///
+    /// Must not be called while reserved entities are awaiting `flush()`.
+    pub fn set_order(&mut self, order: EntityOrder) {
+        self.verify_flushed();
+        self.order = order;
+        self.sort_freelist();
+    }
+
+    /// Restores the freelist order required by [`EntityOrder::Deterministic`].
+    fn sort_freelist(&mut self) {
+        if self.order == EntityOrder::Deterministic {
+            self.pending.sort_unstable_by(|a, b| b.cmp(a));
+        }
+    }
+
+    /// Removes `id` from the freelist, if it is in it.
+    fn remove_from_freelist(&mut self, id: u32) -> bool {
+        match self.pending.iter().position(|item| *item == id) {
+            Some(index) => {
+                if self.order == EntityOrder::Deterministic {
+                    self.pending.remove(index);
+                } else {
+                    self.pending.swap_remove(index);
+                }
+                true
+            }
+            None => false,
+        }
+    }
+
     /// Reserve entity IDs concurrently.
     ///
     /// Storage for entity generation and location is lazily allocated by calling `flush`.
@@ -412,13 +470,13 @@ impl Entities {
 
         let loc = if entity.id as usize >= self.meta.len() {
             self.pending.extend((self.meta.len() as u32)..entity.id);
+            self.sort_freelist();
             let new_free_cursor = self.pending.len() as IdCursor;
             *self.free_cursor.get_mut() = new_free_cursor;
             self.meta.resize(entity.id as usize + 1, EntityMeta::EMPTY);
             self.len += 1;
             None
-        } else if let Some(index) = self.pending.iter().position(|item| *item == entity.id) {
-            self.pending.swap_remove(index);
+        } else if self.remove_from_freelist(entity.id) {
             let new_free_cursor = self.pending.len() as IdCursor;
             *self.free_cursor.get_mut() = new_free_cursor;
             self.len += 1;
@@ -443,13 +501,13 @@ impl Entities {
 
         let result = if entity.id as usize >= self.meta.len() {
             self.pending.extend((self.meta.len() as u32)..entity.id);
+            self.sort_freelist();
             let new_free_cursor = self.pending.len() as IdCursor;
             *self.free_cursor.get_mut() = new_free_cursor;
             self.meta.resize(entity.id as usize + 1, EntityMeta::EMPTY);
             self.len += 1;
             AllocAtWithoutReplacement::DidNotExist
-        } else if let Some(index) = self.pending.iter().position(|item| *item == entity.id) {
-            self.pending.swap_remove(index);
+        } else if self.remove_from_freelist(entity.id) {
             let new_free_cursor = self.pending.len() as IdCursor;
             *self.free_cursor.get_mut() = new_free_cursor;
             self.len += 1;
@@ -483,7 +541,12 @@ impl Entities {
 
         let loc = mem::replace(&mut meta.location, EntityMeta::EMPTY.location);
 
-        self.pending.push(entity.id);
+        if self.order == EntityOrder::Deterministic {
+            let index = self.pending.partition_point(|&id| id > entity.id);
+            self.pending.insert(index, entity.id);
+        } else {
+            self.pending.push(entity.id);
+        }
 
         let new_free_cursor = self.pending.len() as IdCursor;
         *self.free_cursor.get_mut() = new_free_cursor;
@@ -519,6 +582,14 @@ impl Entities {
         self.len = 0;
     }
 
+    /// Shrinks the capacity of the entity metadata and the freelist as much as possible.
+    ///
+    /// Metadata of freed entities is kept so that their generation survives until reuse.
+    pub fn shrink_to_fit(&mut self) {
+        self.meta.shrink_to_fit();
+        self.pending.shrink_to_fit();
+    }
+
     /// Returns `Ok(Location { archetype: Archetype::invalid(), index: undefined })` for pending entities.
     pub fn get(&self, entity: Entity) -> Option<EntityLocation> {
         if (entity.id as usize) < self.meta.len() {
@@ -630,6 +701,65 @@ impl Entities {
     pub fn is_empty(&self) -> bool {
         self.len == 0
     }
+
+    /// Captures the allocation state of these [`Entities`]: the generation of every ID and the
+    /// order in which free IDs will be reused.
+    ///
+    /// Must not be called while reserved entities are awaiting `flush()`.
+    pub fn allocator_snapshot(&self) -> EntitiesSnapshot {
+        assert!(
+            self.free_cursor.load(Ordering::Relaxed) == self.pending.len() as IdCursor,
+            "flush() needs to be called before taking a snapshot of the entity allocator"
+        );
+        EntitiesSnapshot {
+            generations: self.meta.iter().map(|meta| meta.generation).collect(),
+            pending: self.pending.clone(),
+            len: self.len,
+        }
+    }
+
+    /// Restores the allocation state captured by [`Entities::allocator_snapshot`], so that the
+    /// same IDs and generations are allocated afterwards.
+    ///
+    /// The entities that currently exist must be exactly those that existed when the snapshot
+    /// was taken, plus the `preserved` ones, which stay allocated.
+    pub(crate) fn restore_allocator(&mut self, snapshot: &EntitiesSnapshot, preserved: &[Entity]) {
+        self.verify_flushed();
+        debug_assert!(self.meta[snapshot.generations.len().min(self.meta.len())..]
+            .iter()
+            .enumerate()
+            .all(
+                |(index, meta)| meta.location.archetype_id == ArchetypeId::INVALID
+                    || preserved
+                        .iter()
+                        .any(|entity| entity.id as usize == snapshot.generations.len() + index)
+            ));
+        let preserved: Vec<_> = preserved
+            .iter()
+            .map(|&entity| (entity, self.meta[entity.id as usize].location))
+            .collect();
+        self.meta
+            .resize(snapshot.generations.len(), EntityMeta::EMPTY);
+        for (meta, &generation) in self.meta.iter_mut().zip(&snapshot.generations) {
+            meta.generation = generation;
+        }
+        self.pending.clone_from(&snapshot.pending);
+        self.sort_freelist();
+        *self.free_cursor.get_mut() = self.pending.len() as IdCursor;
+        self.len = snapshot.len;
+        for (entity, location) in preserved {
+            self.alloc_at_without_replacement(entity);
+            self.meta[entity.id as usize].location = location;
+        }
+    }
+}
+
+/// The allocation state of [`Entities`], see [`Entities::allocator_snapshot`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EntitiesSnapshot {
+    generations: Vec<u32>,
+    pending: Vec<u32>,
+    len: u32,
 }
 
 #[derive(Copy, Clone, Debug)]
@@ -697,4 +827,20 @@ mod tests {
         assert!(entities.contains(e));
         assert!(entities.get(e).is_none());
     }
+
+    #[test]
+    fn deterministic_order_reuses_lowest_id() {
+        let mut entities = Entities::default();
+        let e: Vec<_> = (0..5).map(|_| entities.alloc()).collect();
+        entities.free(e[4]);
+        entities.set_order(EntityOrder::Deterministic);
+        entities.free(e[1]);
+        entities.free(e[3]);
+
+        assert_eq!(entities.alloc().id(), 1);
+        assert_eq!(entities.reserve_entity().id(), 3);
+        entities.flush_as_invalid();
+        assert_eq!(entities.alloc().id(), 4);
+        assert_eq!(entities.alloc().id(), 5);
+    }
 }
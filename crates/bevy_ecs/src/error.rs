@@ -0,0 +1,124 @@
+//! Error handling for systems that return a [`Result`].
+//!
+//! Systems returning `Result<(), E>` where `E: Into<BevyError>` can be added to a schedule like
+//! any other system. When such a system returns an error, the error is passed to the
+//! [`SystemErrorHandler`] resource together with a [`SystemErrorContext`] describing the system
+//! that failed.
+//!
+//! ```
+//! # use bevy_ecs::prelude::*;
+//! # use bevy_ecs::error::{self, SystemErrorHandler};
+//! #[derive(Component)]
+//! struct Player;
+//!
+//! fn player_exists(query: Query<&Player>) -> Result<(), BevyError> {
+//!     let _player = query.get_single()?;
+//!     Ok(())
+//! }
+//!
+//! let mut world = World::new();
+//! world.insert_resource(SystemErrorHandler(error::warn));
+//!
+//! let mut schedule = bevy_ecs::schedule_v3::Schedule::new();
+//! schedule.add_system(player_exists);
+//! schedule.run(&mut world);
+//! ```
+
+use crate as bevy_ecs;
+use crate::system::Resource;
+use bevy_utils::tracing::{error as log_error, warn as log_warn};
+use std::{borrow::Cow, error::Error, fmt};
+
+/// A type-erased error returned from a system.
+///
+/// Any type implementing [`std::error::Error`] converts into a [`BevyError`], so errors can be
+/// propagated with `?` from systems returning `Result<(), BevyError>`.
+pub struct BevyError {
+    inner: Box<dyn Error + Send + Sync + 'static>,
+}
+
+impl BevyError {
+    /// Returns a reference to the underlying error if it is of type `E`.
+    pub fn downcast_ref<E: Error + 'static>(&self) -> Option<&E> {
+        self.inner.downcast_ref::<E>()
+    }
+
+    /// Returns the underlying error.
+    pub fn into_inner(self) -> Box<dyn Error + Send + Sync + 'static> {
+        self.inner
+    }
+}
+
+impl<E> From<E> for BevyError
+where
+    Box<dyn Error + Send + Sync + 'static>: From<E>,
+{
+    fn from(error: E) -> Self {
+        BevyError {
+            inner: From::from(error),
+        }
+    }
+}
+
+impl fmt::Display for BevyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.inner, f)
+    }
+}
+
+impl fmt::Debug for BevyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.inner, f)
+    }
+}
+
+/// Information about the system that returned a [`BevyError`].
+#[derive(Debug, Clone)]
+pub struct SystemErrorContext {
+    /// The name of the system that failed.
+    pub name: Cow<'static, str>,
+}
+
+/// The handler called with every error returned by a system.
+///
+/// If this resource is not present, [`panic`] is used.
+///
+/// Use one of the handlers provided by this module, or any other function with the same
+/// signature, to customize how errors are reported.
+#[derive(Resource, Debug, Clone, Copy)]
+pub struct SystemErrorHandler(pub fn(BevyError, SystemErrorContext));
+
+impl Default for SystemErrorHandler {
+    fn default() -> Self {
+        SystemErrorHandler(panic)
+    }
+}
+
+/// Panics with the error and the name of the failing system.
+pub fn panic(error: BevyError, context: SystemErrorContext) {
+    panic!(
+        "Encountered an error in system `{}`: {}",
+        context.name, error
+    );
+}
+
+/// Logs the error and the name of the failing system at the `error` level.
+pub fn error(error: BevyError, context: SystemErrorContext) {
+    log_error!(
+        "Encountered an error in system `{}`: {}",
+        context.name,
+        error
+    );
+}
+
+/// Logs the error and the name of the failing system at the `warn` level.
+pub fn warn(error: BevyError, context: SystemErrorContext) {
+    log_warn!(
+        "Encountered an error in system `{}`: {}",
+        context.name,
+        error
+    );
+}
+
+/// Ignores the error.
+pub fn ignore(_error: BevyError, _context: SystemErrorContext) {}
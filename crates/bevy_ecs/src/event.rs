@@ -2,12 +2,16 @@
 
 use crate as bevy_ecs;
 use crate::system::{Local, Res, ResMut, Resource, SystemParam};
-use bevy_utils::tracing::trace;
+use bevy_utils::tracing::{trace, warn};
 use std::ops::{Deref, DerefMut};
 use std::{
     fmt::{self},
     hash::Hash,
     marker::PhantomData,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc, Mutex, Weak,
+    },
 };
 
 /// A type that can be stored in an [`Events<E>`] resource
@@ -57,6 +61,27 @@ struct EventInstance<E: Event> {
     pub event: E,
 }
 
+/// Controls how long an [`Events`] collection keeps events around.
+///
+/// The policy only affects what [`Events::update`] does; [`Events::clear`] and
+/// [`Events::drain`] always remove every stored event.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EventStoragePolicy {
+    /// Events are kept for two [`Events::update`] calls, then dropped whether or not they were read.
+    #[default]
+    DoubleBuffer,
+    /// Events are kept until every registered reader has read them, and never for fewer
+    /// updates than with [`EventStoragePolicy::DoubleBuffer`].
+    ///
+    /// An [`EventReader`] registers itself the first time it reads, and a [`ManualEventReader`]
+    /// can be registered up front with [`Events::register_reader`]. A reader is unregistered when
+    /// it is dropped. Registered readers that stop reading keep events alive indefinitely.
+    RetainUntilConsumed,
+    /// [`Events::update`] does nothing; events are kept until [`Events::clear`] or
+    /// [`Events::drain`] is called.
+    Manual,
+}
+
 /// An event collection that represents the events that occurred within the last two
 /// [`Events::update`] calls.
 /// Events can be written to using an [`EventWriter`]
@@ -78,7 +103,11 @@ struct EventInstance<E: Event> {
 /// Events will persist across a single frame boundary and so ordering of event producers and
 /// consumers is not critical (although poorly-planned ordering may cause accumulating lag).
 /// If events are not handled by the end of the frame after they are updated, they will be
-/// dropped silently.
+/// dropped. The first time this happens to a reader that had not read them yet, a warning is
+/// logged.
+///
+/// This behavior can be changed by choosing a different [`EventStoragePolicy`], for example
+/// when readers run in a schedule that does not run every frame.
 ///
 /// # Example
 /// ```
@@ -136,6 +165,9 @@ pub struct Events<E: Event> {
     /// Holds the newer events.
     events_b: EventSequence<E>,
     event_count: usize,
+    policy: EventStoragePolicy,
+    /// Read positions of the registered readers. Dropped readers are pruned lazily.
+    readers: Mutex<Vec<Weak<ReaderCursor>>>,
 }
 
 // Derived Default impl would incorrectly require E: Default
@@ -145,6 +177,8 @@ impl<E: Event> Default for Events<E> {
             events_a: Default::default(),
             events_b: Default::default(),
             event_count: Default::default(),
+            policy: Default::default(),
+            readers: Default::default(),
         }
     }
 }
@@ -318,9 +352,20 @@ impl<'w, 's, E: Event> EventWriter<'w, 's, E> {
     }
 }
 
+/// The read position of a registered reader, shared with the [`Events`] it reads.
+#[derive(Debug)]
+struct ReaderCursor {
+    last_event_count: AtomicUsize,
+    /// Whether events this reader had not read were dropped already, so that a reader that doesn't
+    /// run every update only causes a single warning.
+    warned_unread: AtomicBool,
+}
+
 #[derive(Debug)]
 pub struct ManualEventReader<E: Event> {
     last_event_count: usize,
+    /// Shared with the [`Events`] this reader is registered with, if any.
+    cursor: Option<Arc<ReaderCursor>>,
     _marker: PhantomData<E>,
 }
 
@@ -328,6 +373,7 @@ impl<E: Event> Default for ManualEventReader<E> {
     fn default() -> Self {
         ManualEventReader {
             last_event_count: 0,
+            cursor: None,
             _marker: Default::default(),
         }
     }
@@ -349,6 +395,10 @@ impl<E: Event> ManualEventReader<E> {
         events: &'a Events<E>,
     ) -> impl DoubleEndedIterator<Item = (&'a E, EventId<E>)>
            + ExactSizeIterator<Item = (&'a E, EventId<E>)> {
+        let cursor = self
+            .cursor
+            .get_or_insert_with(|| events.register_cursor(self.last_event_count))
+            .clone();
         // if the reader has seen some of the events in a buffer, find the proper index offset.
         // otherwise read all events in the buffer
         let a_index = (self.last_event_count).saturating_sub(events.events_a.start_event_count);
@@ -359,12 +409,20 @@ impl<E: Event> ManualEventReader<E> {
         // Ensure `len` is implemented correctly
         debug_assert_eq!(unread_count, self.len(events));
         self.last_event_count = events.event_count - unread_count;
+        cursor
+            .last_event_count
+            .store(self.last_event_count, Ordering::Relaxed);
         // Iterate the oldest first, then the newer events
         let iterator = a.iter().chain(b.iter());
         iterator
             .map(|e| (&e.event, e.event_id))
             .with_exact_size(unread_count)
-            .inspect(move |(_, id)| self.last_event_count = (id.id + 1).max(self.last_event_count))
+            .inspect(move |(_, id)| {
+                self.last_event_count = (id.id + 1).max(self.last_event_count);
+                cursor
+                    .last_event_count
+                    .store(self.last_event_count, Ordering::Relaxed);
+            })
     }
 
     /// See [`EventReader::len`]
@@ -440,6 +498,26 @@ impl<I: Iterator> ExactSizeIterator for ExactSize<I> {
 }
 
 impl<E: Event> Events<E> {
+    /// Creates an empty collection that stores events according to `policy`.
+    pub fn with_policy(policy: EventStoragePolicy) -> Self {
+        Self {
+            policy,
+            ..Default::default()
+        }
+    }
+
+    /// Returns the [`EventStoragePolicy`] of this collection.
+    #[inline]
+    pub fn policy(&self) -> EventStoragePolicy {
+        self.policy
+    }
+
+    /// Changes the [`EventStoragePolicy`] of this collection. Stored events are kept and will be
+    /// dropped according to the new policy on the next [`update`](Events::update).
+    pub fn set_policy(&mut self, policy: EventStoragePolicy) {
+        self.policy = policy;
+    }
+
     /// "Sends" an `event` by writing it to the current event buffer. [`EventReader`]s can then read
     /// the event.
     pub fn send(&mut self, event: E) {
@@ -477,18 +555,98 @@ impl<E: Event> Events<E> {
         }
     }
 
-    /// Swaps the event buffers and clears the oldest event buffer. In general, this should be
-    /// called once per frame/update.
+    /// Gets a new [`ManualEventReader`] that is registered with this collection. This will include
+    /// all events already in the event buffers.
+    ///
+    /// Under [`EventStoragePolicy::RetainUntilConsumed`], events are kept until this reader has
+    /// read them or is dropped.
+    pub fn register_reader(&mut self) -> ManualEventReader<E> {
+        let last_event_count = self.events_a.start_event_count;
+        ManualEventReader {
+            last_event_count,
+            cursor: Some(self.register_cursor(last_event_count)),
+            ..Default::default()
+        }
+    }
+
+    fn register_cursor(&self, last_event_count: usize) -> Arc<ReaderCursor> {
+        let cursor = Arc::new(ReaderCursor {
+            last_event_count: AtomicUsize::new(last_event_count),
+            warned_unread: AtomicBool::new(false),
+        });
+        let mut readers = self.readers.lock().unwrap_or_else(|e| e.into_inner());
+        readers.retain(|reader| reader.strong_count() > 0);
+        readers.push(Arc::downgrade(&cursor));
+        cursor
+    }
+
+    /// Returns the cursors of all registered readers that are still alive.
+    fn reader_cursors(&mut self) -> Vec<Arc<ReaderCursor>> {
+        let readers = self.readers.get_mut().unwrap_or_else(|e| e.into_inner());
+        readers.retain(|reader| reader.strong_count() > 0);
+        readers
+            .iter()
+            .filter_map(|reader| reader.upgrade())
+            .collect()
+    }
+
+    /// Drops the events that are no longer needed according to the [`EventStoragePolicy`] of
+    /// this collection. In general, this should be called once per frame/update.
+    ///
+    /// With [`EventStoragePolicy::DoubleBuffer`] this swaps the event buffers and clears the
+    /// oldest event buffer.
     pub fn update(&mut self) {
-        std::mem::swap(&mut self.events_a, &mut self.events_b);
-        self.events_b.clear();
-        self.events_b.start_event_count = self.event_count;
+        match self.policy {
+            EventStoragePolicy::DoubleBuffer => {
+                self.warn_unread(self.events_b.start_event_count);
+                std::mem::swap(&mut self.events_a, &mut self.events_b);
+                self.events_b.clear();
+                self.events_b.start_event_count = self.event_count;
+            }
+            EventStoragePolicy::RetainUntilConsumed => {
+                // Keep everything a double buffer would, plus whatever a registered reader has not
+                // read yet.
+                let keep_from = self
+                    .reader_cursors()
+                    .iter()
+                    .map(|cursor| cursor.last_event_count.load(Ordering::Relaxed))
+                    .fold(self.events_b.start_event_count, usize::min);
+                let newer = std::mem::take(&mut self.events_b.events);
+                self.events_a.extend(newer);
+                let dropped = keep_from
+                    .saturating_sub(self.events_a.start_event_count)
+                    .min(self.events_a.len());
+                self.events_a.drain(..dropped);
+                self.events_a.start_event_count += dropped;
+                self.events_b.start_event_count = self.event_count;
+            }
+            EventStoragePolicy::Manual => {}
+        }
         debug_assert_eq!(
             self.events_a.start_event_count + self.events_a.len(),
             self.events_b.start_event_count
         );
     }
 
+    /// Logs a warning for each registered reader that has not read the events before
+    /// `drop_until`.
+    ///
+    /// Only the first drop is reported for each reader, later ones are silently ignored.
+    fn warn_unread(&mut self, drop_until: usize) {
+        let start = self.events_a.start_event_count;
+        for cursor in self.reader_cursors() {
+            let last_event_count = cursor.last_event_count.load(Ordering::Relaxed);
+            let unread = drop_until.saturating_sub(last_event_count.max(start));
+            if unread > 0 && !cursor.warned_unread.swap(true, Ordering::Relaxed) {
+                warn!(
+                    "Dropped {} unread event(s) of type {}. Consider a different `EventStoragePolicy` if the reader does not run every update. Further drops for this reader will not be reported.",
+                    unread,
+                    std::any::type_name::<E>()
+                );
+            }
+        }
+    }
+
     /// A system that calls [`Events::update`] once per frame.
     pub fn update_system(mut events: ResMut<Self>) {
         events.update();
@@ -829,6 +987,96 @@ mod tests {
     #[derive(Clone, PartialEq, Debug, Default)]
     struct EmptyTestEvent;
 
+    #[test]
+    fn test_events_retain_until_consumed() {
+        let mut events = Events::<TestEvent>::with_policy(EventStoragePolicy::RetainUntilConsumed);
+        let mut reader = events.register_reader();
+
+        events.send(TestEvent { i: 0 });
+        events.update();
+        events.send(TestEvent { i: 1 });
+        events.update();
+        events.update();
+
+        // a double buffer would have dropped event 0 by now
+        assert_eq!(
+            get_events(&events, &mut reader),
+            vec![TestEvent { i: 0 }, TestEvent { i: 1 }]
+        );
+
+        events.update();
+        events.update();
+        assert!(events.is_empty());
+
+        // dropped readers no longer hold on to events
+        events.send(TestEvent { i: 2 });
+        drop(reader);
+        events.update();
+        events.update();
+        assert!(events.is_empty());
+    }
+
+    #[test]
+    fn test_events_retain_until_consumed_lazy_registration() {
+        let mut events = Events::<TestEvent>::with_policy(EventStoragePolicy::RetainUntilConsumed);
+        let mut reader = events.get_reader();
+        assert!(get_events(&events, &mut reader).is_empty());
+
+        events.send(TestEvent { i: 0 });
+        events.update();
+        events.update();
+        events.update();
+
+        assert_eq!(get_events(&events, &mut reader), vec![TestEvent { i: 0 }]);
+    }
+
+    #[test]
+    fn test_events_manual_policy() {
+        let mut events = Events::<TestEvent>::with_policy(EventStoragePolicy::Manual);
+        let mut reader = events.get_reader();
+
+        events.send(TestEvent { i: 0 });
+        events.update();
+        events.update();
+        events.update();
+        assert_eq!(events.len(), 1);
+        assert_eq!(get_events(&events, &mut reader), vec![TestEvent { i: 0 }]);
+
+        events.clear();
+        assert!(events.is_empty());
+    }
+
+    #[test]
+    fn test_events_warn_unread_once_per_reader() {
+        let warned = |reader: &ManualEventReader<TestEvent>| {
+            reader
+                .cursor
+                .as_ref()
+                .unwrap()
+                .warned_unread
+                .load(Ordering::Relaxed)
+        };
+
+        let mut events = Events::<TestEvent>::default();
+        let mut reader_a = events.register_reader();
+        let mut reader_b = events.register_reader();
+
+        events.send(TestEvent { i: 0 });
+        events.update();
+        assert!(!warned(&reader_a));
+        get_events(&events, &mut reader_b);
+        events.update();
+        assert!(warned(&reader_a));
+        assert!(!warned(&reader_b));
+        assert!(get_events(&events, &mut reader_a).is_empty());
+
+        // A reader that was already warned doesn't keep the others from being warned.
+        events.send(TestEvent { i: 1 });
+        events.update();
+        events.update();
+        assert!(warned(&reader_b));
+    }
+
     #[test]
     fn test_firing_empty_event() {
         let mut events = Events::<EmptyTestEvent>::default();
@@ -9,11 +9,16 @@ pub mod bundle;
 pub mod change_detection;
 pub mod component;
 pub mod entity;
+pub mod error;
 pub mod event;
+pub mod observer;
 pub mod query;
 #[cfg(feature = "bevy_reflect")]
 pub mod reflect;
+pub mod relation;
+pub mod rollback;
 pub mod schedule;
+pub mod schedule_v3;
 pub mod storage;
 pub mod system;
 pub mod world;
@@ -30,13 +35,23 @@ pub mod prelude {
         bundle::Bundle,
         change_detection::DetectChanges,
         component::Component,
-        entity::Entity,
+        entity::{Disabled, Entity},
+        error::BevyError,
         event::{EventReader, EventWriter, Events},
-        query::{Added, AnyOf, ChangeTrackers, Changed, Or, QueryState, With, Without},
+        observer::Trigger,
+        query::{
+            Added, AnyOf, ChangeTrackers, Changed, IncludeDisabled, Or, QueryState, With, Without,
+        },
+        relation::{Relation, RelationKind, RelationSources},
         schedule::{
-            AmbiguitySetLabel, ExclusiveSystemDescriptorCoercion, ParallelSystemDescriptorCoercion,
-            RunCriteria, RunCriteriaDescriptorCoercion, RunCriteriaLabel, Schedule, Stage,
-            StageLabel, State, SystemLabel, SystemSet, SystemStage,
+            AmbiguitySetLabel, ExclusiveSystemDescriptorCoercion, RunCriteria,
+            RunCriteriaDescriptorCoercion, RunCriteriaLabel, Schedule, Stage, StageLabel,
+            SystemLabel, SystemStage,
+        },
+        schedule_v3::{
+            apply_state_transition, apply_system_buffers, common_conditions::*, Condition,
+            IntoSystemConfig, IntoSystemConfigs, IntoSystemSet, IntoSystemSetConfig,
+            IntoSystemSetConfigs, NextState, OnEnter, OnExit, Schedules, State, States, SystemSet,
         },
         system::{
             Commands, In, IntoChainSystem, IntoExclusiveSystem, IntoSystem, Local, NonSend,
@@ -56,7 +71,7 @@ mod tests {
     use crate::{
         bundle::Bundle,
         component::{Component, ComponentId},
-        entity::Entity,
+        entity::{Disabled, Entity, EntityOrder},
         query::{Added, ChangeTrackers, Changed, FilteredAccess, With, Without, WorldQuery},
         system::Resource,
         world::{Mut, World},
@@ -397,6 +412,53 @@ mod tests {
         );
     }
 
+    #[test]
+    fn deterministic_simulation() {
+        // Runs the same simulation, despawning the same entities in a different order.
+        fn simulate(reverse_despawns: bool) -> Vec<(Entity, usize)> {
+            let mut world = World::new();
+            world.set_entity_order(EntityOrder::Deterministic);
+            let entities: Vec<_> = (0..100)
+                .map(|i| {
+                    let mut entity = world.spawn();
+                    entity.insert(A(i));
+                    if i % 3 == 0 {
+                        entity.insert(B(i));
+                    }
+                    entity.id()
+                })
+                .collect();
+            let mut despawned: Vec<_> = entities.iter().copied().step_by(4).collect();
+            if reverse_despawns {
+                despawned.reverse();
+            }
+            for entity in despawned {
+                world.despawn(entity);
+            }
+            for i in 100..120 {
+                world.spawn().insert(A(i));
+            }
+
+            world.query::<(Entity, &mut A)>().par_for_each_sorted_mut(
+                &mut world,
+                8,
+                |(entity, mut a)| {
+                    a.0 += entity.id() as usize;
+                },
+            );
+            world
+                .query::<(Entity, &A)>()
+                .iter_sorted_by_key(&world, |(entity, _)| *entity)
+                .map(|(entity, a)| (entity, a.0))
+                .collect()
+        }
+
+        ComputeTaskPool::init(TaskPool::default);
+        let first = simulate(false);
+        assert_eq!(first.len(), 95);
+        assert_eq!(first, simulate(true));
+    }
+
     #[test]
     fn par_for_each_sparse() {
         ComputeTaskPool::init(TaskPool::default);
@@ -1303,8 +1365,10 @@ mod tests {
         let mut expected = FilteredAccess::<ComponentId>::default();
         let a_id = world.components.get_id(TypeId::of::<A>()).unwrap();
         let b_id = world.components.get_id(TypeId::of::<B>()).unwrap();
+        let disabled_id = world.components.get_id(TypeId::of::<Disabled>()).unwrap();
         expected.add_write(a_id);
         expected.add_read(b_id);
+        expected.add_without(disabled_id);
         assert!(
             query.component_access.eq(&expected),
             "ComponentId access from query fetch and query filter should be combined"
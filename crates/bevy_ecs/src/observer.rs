@@ -0,0 +1,388 @@
+//! Types for reacting to events targeted at a specific [entity](Entity).
+//!
+//! [`Events<E>`](crate::event::Events) are broadcast to every reader and only handled once a
+//! system polls them. Observers are the opposite: calling [`World::trigger`] immediately runs
+//! every observer system registered for the event type `E`, first the ones attached to the
+//! target entity with [`EntityMut::observe`](crate::world::EntityMut::observe) and then the
+//! global ones added with [`World::observe`].
+//!
+//! An observer is a system whose input is a [`Trigger<E>`], giving it access to the event and to
+//! the entity it was triggered on. Any [`Commands`](crate::system::Commands) an observer issues
+//! are applied right after it runs, so they have taken effect by the time
+//! [`World::trigger`] returns.
+//!
+//! Events can also bubble: [`World::trigger_bubbling`] repeats the entity observers for each
+//! entity returned by a [`Traversal`], such as `bevy_hierarchy`'s `Parent`, until the chain ends
+//! or an observer calls [`Trigger::stop_propagation`].
+//!
+//! ```
+//! # use bevy_ecs::prelude::*;
+//! struct Damage(u32);
+//!
+//! #[derive(Component)]
+//! struct Health(u32);
+//!
+//! fn take_damage(In(trigger): In<Trigger<Damage>>, mut query: Query<&mut Health>) {
+//!     let mut health = query.get_mut(trigger.entity()).unwrap();
+//!     health.0 = health.0.saturating_sub(trigger.event().0);
+//! }
+//!
+//! let mut world = World::new();
+//! let unit = world.spawn().insert(Health(10)).observe(take_damage).id();
+//!
+//! world.trigger(Damage(3), unit);
+//! assert_eq!(world.get::<Health>(unit).unwrap().0, 7);
+//! ```
+
+use crate::{
+    component::{Component, SparseStorage},
+    entity::Entity,
+    event::Event,
+    system::{Resource, System},
+    world::World,
+};
+use std::{
+    mem,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+
+/// The input of an observer system: the event being triggered and the entity it targets.
+///
+/// Observer systems take it as `In<Trigger<E>>`. See the
+/// [module level documentation](crate::observer) for an example.
+///
+/// The event is shared by all the observers of a trigger, which is why it cannot be mutated.
+/// A trigger owns its data, so it can be kept after the observer returns, but calling
+/// [`Trigger::stop_propagation`] then has no effect.
+pub struct Trigger<E> {
+    event: Arc<E>,
+    entity: Entity,
+    target: Entity,
+    propagate: Arc<AtomicBool>,
+}
+
+impl<E> Trigger<E> {
+    /// The event that was triggered.
+    #[inline]
+    pub fn event(&self) -> &E {
+        &self.event
+    }
+
+    /// The entity this observer is running for.
+    ///
+    /// While an event bubbles up, this is the entity currently being visited, and differs from
+    /// [`Trigger::target`]. For global observers this is always the original target.
+    #[inline]
+    pub fn entity(&self) -> Entity {
+        self.entity
+    }
+
+    /// The entity the event was originally triggered on.
+    #[inline]
+    pub fn target(&self) -> Entity {
+        self.target
+    }
+
+    /// Stops the event from bubbling further up its [`Traversal`] once the observers of the
+    /// current entity have run. Global observers still run.
+    #[inline]
+    pub fn stop_propagation(&mut self) {
+        self.propagate.store(false, Ordering::Relaxed);
+    }
+}
+
+/// A system that can observe events of type `E`.
+pub type BoxedObserverSystem<E> = Box<dyn System<In = Trigger<E>, Out = ()>>;
+
+/// Describes how an event bubbles from one entity to the next in [`World::trigger_bubbling`].
+pub trait Traversal: Send + Sync + 'static {
+    /// Returns the entity the event should visit after `entity`, if any.
+    fn traverse(world: &World, entity: Entity) -> Option<Entity>;
+}
+
+/// A [`Traversal`] that never bubbles, used by [`World::trigger`].
+impl Traversal for () {
+    #[inline]
+    fn traverse(_world: &World, _entity: Entity) -> Option<Entity> {
+        None
+    }
+}
+
+/// The observer systems for events of type `E`.
+///
+/// Global observers are stored in this type as a resource, and the observers of an entity as a
+/// component on that entity.
+pub struct Observers<E: Event> {
+    systems: Vec<BoxedObserverSystem<E>>,
+}
+
+impl<E: Event> Default for Observers<E> {
+    fn default() -> Self {
+        Self {
+            systems: Vec::new(),
+        }
+    }
+}
+
+impl<E: Event> Component for Observers<E> {
+    type Storage = SparseStorage;
+}
+
+impl<E: Event> Resource for Observers<E> {}
+
+impl<E: Event> Observers<E> {
+    /// Returns the number of observers.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.systems.len()
+    }
+
+    /// Returns `true` if there are no observers.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.systems.is_empty()
+    }
+
+    /// Runs every observer, applying the commands of each one before running the next.
+    fn run(&mut self, world: &mut World, event: &Arc<E>, entity: Entity, target: Entity) -> bool {
+        let propagate = Arc::new(AtomicBool::new(true));
+        for system in &mut self.systems {
+            let trigger = Trigger {
+                event: event.clone(),
+                entity,
+                target,
+                propagate: propagate.clone(),
+            };
+            system.run(trigger, world);
+            system.apply_buffers(world);
+        }
+        propagate.load(Ordering::Relaxed)
+    }
+
+    /// Puts back observers taken out while they were running, keeping the ones added meanwhile.
+    fn restore(&mut self, mut taken: Self) {
+        taken.systems.append(&mut self.systems);
+        *self = taken;
+    }
+}
+
+/// Initializes `system` and adds it as an observer of `entity`, or as a global observer.
+pub(crate) fn observe<E: Event>(
+    world: &mut World,
+    entity: Option<Entity>,
+    mut system: BoxedObserverSystem<E>,
+) {
+    system.initialize(world);
+    match entity {
+        Some(entity) => {
+            let mut entity = world.entity_mut(entity);
+            match entity.get_mut::<Observers<E>>() {
+                Some(mut observers) => observers.systems.push(system),
+                None => {
+                    entity.insert(Observers {
+                        systems: vec![system],
+                    });
+                }
+            }
+        }
+        None => world
+            .get_resource_or_insert_with(Observers::<E>::default)
+            .systems
+            .push(system),
+    }
+}
+
+/// Runs the observers of `target` and of every entity reached from it through `T`, then the
+/// global observers.
+pub(crate) fn trigger<T: Traversal, E: Event>(world: &mut World, event: E, target: Entity) {
+    let event = Arc::new(event);
+    let mut current = Some(target);
+    while let Some(entity) = current {
+        let mut propagate = true;
+        // Observers are taken out of the entity while they run, so they can freely access the
+        // world. This also means an observer is not re-entrant.
+        if let Some(mut observers) = world.get_mut::<Observers<E>>(entity) {
+            let mut taken = mem::take(&mut *observers);
+            propagate = taken.run(world, &event, entity, target);
+            if let Some(mut observers) = world.get_mut::<Observers<E>>(entity) {
+                observers.restore(taken);
+            }
+        }
+        current = if propagate {
+            T::traverse(world, entity)
+        } else {
+            None
+        };
+    }
+
+    if let Some(mut observers) = world.get_resource_mut::<Observers<E>>() {
+        let mut taken = mem::take(&mut *observers);
+        taken.run(world, &event, target, target);
+        match world.get_resource_mut::<Observers<E>>() {
+            Some(mut observers) => observers.restore(taken),
+            None => world.insert_resource(taken),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Traversal;
+    use crate as bevy_ecs;
+    use crate::prelude::*;
+
+    #[derive(Resource, Default)]
+    struct Log(Vec<&'static str>);
+
+    struct Ping;
+
+    #[derive(Component)]
+    struct Up(Entity);
+
+    impl Traversal for Up {
+        fn traverse(world: &World, entity: Entity) -> Option<Entity> {
+            world.get::<Up>(entity).map(|up| up.0)
+        }
+    }
+
+    #[test]
+    fn entity_and_global_observers() {
+        let mut world = World::new();
+        world.init_resource::<Log>();
+        world.observe(|_: In<Trigger<Ping>>, mut log: ResMut<Log>| log.0.push("global"));
+        let a = world
+            .spawn()
+            .observe(|_: In<Trigger<Ping>>, mut log: ResMut<Log>| log.0.push("a"))
+            .id();
+        let b = world.spawn().id();
+
+        world.trigger(Ping, a);
+        world.trigger(Ping, b);
+        assert_eq!(world.resource::<Log>().0, ["a", "global", "global"]);
+    }
+
+    #[test]
+    fn kept_triggers_stay_valid() {
+        #[derive(Resource, Default)]
+        struct Sum(usize);
+
+        let mut world = World::new();
+        world.init_resource::<Sum>();
+        let entity = world
+            .spawn()
+            .observe(
+                |In(trigger): In<Trigger<usize>>,
+                 mut previous: Local<Option<Trigger<usize>>>,
+                 mut sum: ResMut<Sum>| {
+                    if let Some(mut previous) = previous.replace(trigger) {
+                        sum.0 += *previous.event();
+                        previous.stop_propagation();
+                    }
+                },
+            )
+            .id();
+
+        world.trigger(1usize, entity);
+        world.trigger(2usize, entity);
+        world.trigger(3usize, entity);
+        assert_eq!(world.resource::<Sum>().0, 3);
+    }
+
+    #[test]
+    fn observer_commands_are_applied() {
+        #[derive(Component)]
+        struct Hit;
+
+        let mut world = World::new();
+        let entity = world
+            .spawn()
+            .observe(|In(trigger): In<Trigger<Ping>>, mut commands: Commands| {
+                commands.entity(trigger.entity()).insert(Hit);
+            })
+            .id();
+
+        world.trigger(Ping, entity);
+        assert!(world.entity(entity).contains::<Hit>());
+    }
+
+    #[test]
+    fn bubbling() {
+        let mut world = World::new();
+        world.init_resource::<Log>();
+        let root = world
+            .spawn()
+            .observe(|In(trigger): In<Trigger<Ping>>, mut log: ResMut<Log>| {
+                assert_ne!(trigger.entity(), trigger.target());
+                log.0.push("root");
+            })
+            .id();
+        let middle = world
+            .spawn()
+            .insert(Up(root))
+            .observe(|In(mut trigger): In<Trigger<Ping>>, mut log: ResMut<Log>| {
+                log.0.push("middle");
+                if trigger.target() != trigger.entity() {
+                    trigger.stop_propagation();
+                }
+            })
+            .id();
+        let leaf = world.spawn().insert(Up(middle)).id();
+        world.observe(|In(trigger): In<Trigger<Ping>>, mut log: ResMut<Log>| {
+            assert_eq!(trigger.entity(), trigger.target());
+            log.0.push("global");
+        });
+
+        world.trigger_bubbling::<Up>(Ping, middle);
+        assert_eq!(world.resource::<Log>().0, ["middle", "root", "global"]);
+
+        world.resource_mut::<Log>().0.clear();
+        world.trigger_bubbling::<Up>(Ping, leaf);
+        assert_eq!(world.resource::<Log>().0, ["middle", "global"]);
+    }
+
+    #[test]
+    fn nested_triggers() {
+        struct Pong;
+
+        let mut world = World::new();
+        world.init_resource::<Log>();
+        let entity = world
+            .spawn()
+            .observe(|In(trigger): In<Trigger<Ping>>, mut commands: Commands| {
+                commands.trigger(Pong, trigger.entity());
+            })
+            .observe(|_: In<Trigger<Pong>>, mut log: ResMut<Log>| log.0.push("pong"))
+            .id();
+        world.observe(|In(trigger): In<Trigger<Ping>>, mut commands: Commands| {
+            commands
+                .entity(trigger.entity())
+                .observe(|_: In<Trigger<Ping>>, mut log: ResMut<Log>| log.0.push("late"));
+        });
+
+        world.trigger(Ping, entity);
+        assert_eq!(world.resource::<Log>().0, ["pong"]);
+        world.trigger(Ping, entity);
+        assert_eq!(world.resource::<Log>().0, ["pong", "pong", "late"]);
+    }
+
+    #[test]
+    fn despawn_in_observer() {
+        let mut world = World::new();
+        world.init_resource::<Log>();
+        let entity = world
+            .spawn()
+            .observe(|In(trigger): In<Trigger<Ping>>, mut commands: Commands| {
+                commands.entity(trigger.entity()).despawn();
+            })
+            .id();
+        world.observe(|_: In<Trigger<Ping>>, mut log: ResMut<Log>| log.0.push("global"));
+
+        world.trigger(Ping, entity);
+        assert!(world.get_entity(entity).is_none());
+        world.trigger(Ping, entity);
+        assert_eq!(world.resource::<Log>().0, ["global", "global"]);
+    }
+}
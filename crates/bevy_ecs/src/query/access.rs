@@ -227,6 +227,15 @@ impl<T: SparseSetIndex> FilteredAccess<T> {
         self.without.insert(index.sparse_set_index());
     }
 
+    /// Returns `true` if this accesses the element given by `index`, or is filtered on its
+    /// presence or absence.
+    pub fn mentions(&self, index: T) -> bool {
+        let sparse_index = index.sparse_set_index();
+        self.access.has_read(index)
+            || self.with.contains(sparse_index)
+            || self.without.contains(sparse_index)
+    }
+
     pub fn extend_intersect_filter(&mut self, other: &FilteredAccess<T>) {
         self.without.intersect_with(&other.without);
         self.with.intersect_with(&other.with);
@@ -0,0 +1,636 @@
+use crate::{
+    archetype::{Archetype, ArchetypeComponentId},
+    change_detection::{MutUntyped, Ticks},
+    component::{ComponentId, ComponentTicks, StorageType},
+    entity::Entity,
+    query::{
+        debug_checked_unreachable, Access, FilteredAccess, QueryItem, QueryState,
+        ReadOnlyWorldQuery, WorldQuery, WorldQueryGats,
+    },
+    storage::{Column, ComponentSparseSet, Table, Tables},
+    world::World,
+};
+use bevy_ptr::{Ptr, ThinSlicePtr, UnsafeCellDeref};
+use std::cell::UnsafeCell;
+
+/// Builds a [`QueryState`] over components that are only known at runtime, by their
+/// [`ComponentId`].
+///
+/// This is useful when the queried components come from a scripting language, an editor or a
+/// type registry rather than from Rust types. The resulting [`DynamicQuery`] registers the same
+/// [`Access`] as the equivalent static query, so it can be checked for conflicts against other
+/// queries and systems.
+///
+/// **You should prefer to use static [`WorldQuery`] types where possible and only use this in
+/// cases where the actual component types are not known at compile time.**
+///
+/// ```
+/// # use bevy_ecs::prelude::*;
+/// # use bevy_ecs::query::QueryBuilder;
+/// #[derive(Component)]
+/// struct Position(f32);
+/// #[derive(Component)]
+/// struct Velocity(f32);
+/// #[derive(Component)]
+/// struct Frozen;
+///
+/// let mut world = World::new();
+/// world.spawn().insert_bundle((Position(0.0), Velocity(1.0)));
+/// world.spawn().insert_bundle((Position(0.0), Velocity(1.0), Frozen));
+///
+/// let position = world.init_component::<Position>();
+/// let velocity = world.init_component::<Velocity>();
+/// let frozen = world.init_component::<Frozen>();
+///
+/// let mut query = QueryBuilder::new()
+///     .write(position)
+///     .read(velocity)
+///     .without(frozen)
+///     .build(&mut world);
+///
+/// for mut item in query.iter_mut(&mut world) {
+///     // SAFETY: the components were registered for these types.
+///     let velocity = unsafe { item.get(1).unwrap().deref::<Velocity>() }.0;
+///     let position = unsafe { item.get_mut(0).unwrap().as_mut().deref_mut::<Position>() };
+///     position.0 += velocity;
+/// }
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct QueryBuilder {
+    terms: Vec<(ComponentId, bool, bool)>,
+    with: Vec<ComponentId>,
+    without: Vec<ComponentId>,
+}
+
+impl QueryBuilder {
+    /// Creates an empty [`QueryBuilder`], which matches every entity.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Fetches a shared reference to the component with the given `id`, only matching entities
+    /// that have it.
+    #[must_use]
+    pub fn read(mut self, id: ComponentId) -> Self {
+        self.terms.push((id, false, false));
+        self
+    }
+
+    /// Fetches a mutable reference to the component with the given `id`, only matching entities
+    /// that have it.
+    #[must_use]
+    pub fn write(mut self, id: ComponentId) -> Self {
+        self.terms.push((id, true, false));
+        self
+    }
+
+    /// Fetches a shared reference to the component with the given `id` if the entity has it,
+    /// without filtering out entities that do not.
+    #[must_use]
+    pub fn optional_read(mut self, id: ComponentId) -> Self {
+        self.terms.push((id, false, true));
+        self
+    }
+
+    /// Fetches a mutable reference to the component with the given `id` if the entity has it,
+    /// without filtering out entities that do not.
+    #[must_use]
+    pub fn optional_write(mut self, id: ComponentId) -> Self {
+        self.terms.push((id, true, true));
+        self
+    }
+
+    /// Only matches entities that have the component with the given `id`, without fetching it.
+    #[must_use]
+    pub fn with(mut self, id: ComponentId) -> Self {
+        self.with.push(id);
+        self
+    }
+
+    /// Only matches entities that do not have the component with the given `id`.
+    #[must_use]
+    pub fn without(mut self, id: ComponentId) -> Self {
+        self.without.push(id);
+        self
+    }
+
+    /// Creates the [`QueryState`] for the given [`World`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if any of the [`ComponentId`]s does not belong to a component of `world`, or if a
+    /// component is fetched mutably more than once, or both mutably and immutably.
+    pub fn build(self, world: &mut World) -> QueryState<DynamicQuery> {
+        let terms = self
+            .terms
+            .into_iter()
+            .map(|(id, mutable, optional)| {
+                let info = world
+                    .components()
+                    .get_info(id)
+                    .unwrap_or_else(|| panic!("{:?} is not a valid component id", id));
+                DynamicTerm {
+                    id,
+                    storage_type: info.storage_type(),
+                    mutable,
+                    optional,
+                }
+            })
+            .collect();
+        for &id in self.with.iter().chain(&self.without) {
+            if world.components().get_info(id).is_none() {
+                panic!("{:?} is not a valid component id", id);
+            }
+        }
+        let state = DynamicQueryState {
+            terms,
+            with: self.with,
+            without: self.without,
+        };
+        QueryState::new_with_state(world, state, ())
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct DynamicTerm {
+    id: ComponentId,
+    storage_type: StorageType,
+    mutable: bool,
+    optional: bool,
+}
+
+/// The [`WorldQuery::State`] of a [`DynamicQuery`], created by a [`QueryBuilder`].
+#[derive(Debug, Clone, Default)]
+pub struct DynamicQueryState {
+    terms: Vec<DynamicTerm>,
+    with: Vec<ComponentId>,
+    without: Vec<ComponentId>,
+}
+
+impl DynamicQueryState {
+    /// Returns the ids of the fetched components, in the order they were added to the
+    /// [`QueryBuilder`].
+    pub fn component_ids(&self) -> impl Iterator<Item = ComponentId> + '_ {
+        self.terms.iter().map(|term| term.id)
+    }
+}
+
+/// A [`WorldQuery`] over components chosen at runtime, see [`QueryBuilder`].
+///
+/// Initializing this query from a [`World`] directly, e.g. through
+/// [`World::query`](crate::world::World::query), creates an empty query which matches every
+/// entity.
+pub struct DynamicQuery;
+
+/// The read-only variant of [`DynamicQuery`], which fetches every component immutably.
+pub struct ReadOnlyDynamicQuery;
+
+/// A component fetched by a [`DynamicQuery`].
+pub enum DynamicComponent<'w> {
+    /// A component that was fetched immutably.
+    Ref(Ptr<'w>),
+    /// A component that was fetched mutably.
+    Mut(MutUntyped<'w>),
+}
+
+/// The item returned by a [`DynamicQuery`] for each matched entity.
+pub struct DynamicItem<'w> {
+    /// The matched entity.
+    pub entity: Entity,
+    /// The fetched components, in the order they were added to the [`QueryBuilder`].
+    ///
+    /// Optional components that the entity does not have are [`None`].
+    pub components: Vec<Option<DynamicComponent<'w>>>,
+}
+
+impl<'w> DynamicItem<'w> {
+    /// Returns a pointer to the component at `index`, if the entity has it.
+    pub fn get(&self, index: usize) -> Option<Ptr<'_>> {
+        match self.components.get(index)?.as_ref()? {
+            DynamicComponent::Ref(ptr) => Some(*ptr),
+            DynamicComponent::Mut(value) => Some(value.as_ref()),
+        }
+    }
+
+    /// Returns the mutable component at `index`, if the entity has it and it was fetched mutably.
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut MutUntyped<'w>> {
+        match self.components.get_mut(index)?.as_mut()? {
+            DynamicComponent::Ref(_) => None,
+            DynamicComponent::Mut(value) => Some(value),
+        }
+    }
+}
+
+#[doc(hidden)]
+#[derive(Clone)]
+pub struct DynamicFetch<'w> {
+    terms: Vec<DynamicTermFetch<'w>>,
+    entities: Option<ThinSlicePtr<'w, Entity>>,
+    entity_table_rows: Option<ThinSlicePtr<'w, usize>>,
+    last_change_tick: u32,
+    change_tick: u32,
+}
+
+#[derive(Clone)]
+struct DynamicTermFetch<'w> {
+    mutable: bool,
+    matches: bool,
+    column: Option<&'w Column>,
+    sparse_set: Option<&'w ComponentSparseSet>,
+}
+
+impl DynamicQueryState {
+    fn matches_component_set(&self, set_contains_id: &impl Fn(ComponentId) -> bool) -> bool {
+        self.terms
+            .iter()
+            .all(|term| term.optional || set_contains_id(term.id))
+            && self.with.iter().all(|&id| set_contains_id(id))
+            && !self.without.iter().any(|&id| set_contains_id(id))
+    }
+
+    fn init_fetch<'w>(
+        &self,
+        world: &'w World,
+        last_change_tick: u32,
+        change_tick: u32,
+    ) -> DynamicFetch<'w> {
+        DynamicFetch {
+            terms: self
+                .terms
+                .iter()
+                .map(|term| DynamicTermFetch {
+                    mutable: term.mutable,
+                    matches: false,
+                    column: None,
+                    sparse_set: match term.storage_type {
+                        StorageType::Table => None,
+                        StorageType::SparseSet => world.storages().sparse_sets.get(term.id),
+                    },
+                })
+                .collect(),
+            entities: None,
+            entity_table_rows: None,
+            last_change_tick,
+            change_tick,
+        }
+    }
+
+    fn set_table<'w>(&self, fetch: &mut DynamicFetch<'w>, table: &'w Table) {
+        fetch.entities = Some(table.entities().into());
+        fetch.entity_table_rows = None;
+        for (term, term_fetch) in self.terms.iter().zip(&mut fetch.terms) {
+            term_fetch.column = table.get_column(term.id);
+            term_fetch.matches = match term.storage_type {
+                StorageType::Table => term_fetch.column.is_some(),
+                StorageType::SparseSet => false,
+            };
+        }
+    }
+
+    fn set_archetype<'w>(
+        &self,
+        fetch: &mut DynamicFetch<'w>,
+        archetype: &'w Archetype,
+        tables: &'w Tables,
+    ) {
+        fetch.entities = Some(archetype.entities().into());
+        fetch.entity_table_rows = Some(archetype.entity_table_rows().into());
+        let table = &tables[archetype.table_id()];
+        for (term, term_fetch) in self.terms.iter().zip(&mut fetch.terms) {
+            term_fetch.matches = archetype.contains(term.id);
+            term_fetch.column = match term.storage_type {
+                StorageType::Table if term_fetch.matches => table.get_column(term.id),
+                _ => None,
+            };
+        }
+    }
+}
+
+impl<'w> DynamicFetch<'w> {
+    /// # Safety
+    ///
+    /// `index` must be in bounds of the current table or archetype, and the caller must have the
+    /// access registered for the fetched components.
+    unsafe fn fetch(&mut self, index: usize, read_only: bool) -> DynamicItem<'w> {
+        let entity = *self
+            .entities
+            .unwrap_or_else(|| debug_checked_unreachable())
+            .get(index);
+        let table_row = match self.entity_table_rows {
+            Some(entity_table_rows) => *entity_table_rows.get(index),
+            None => index,
+        };
+        let (last_change_tick, change_tick) = (self.last_change_tick, self.change_tick);
+        let components = self
+            .terms
+            .iter()
+            .map(|term| {
+                if !term.matches {
+                    return None;
+                }
+                let (ptr, ticks): (Ptr<'w>, &'w UnsafeCell<ComponentTicks>) =
+                    match (term.column, term.sparse_set) {
+                        (Some(column), _) => (
+                            column.get_data_unchecked(table_row),
+                            column.get_ticks_unchecked(table_row),
+                        ),
+                        (None, Some(sparse_set)) => sparse_set
+                            .get_with_ticks(entity)
+                            .unwrap_or_else(|| debug_checked_unreachable()),
+                        (None, None) => debug_checked_unreachable(),
+                    };
+                Some(if term.mutable && !read_only {
+                    DynamicComponent::Mut(MutUntyped {
+                        value: ptr.assert_unique(),
+                        ticks: Ticks {
+                            component_ticks: ticks.deref_mut(),
+                            last_change_tick,
+                            change_tick,
+                        },
+                    })
+                } else {
+                    DynamicComponent::Ref(ptr)
+                })
+            })
+            .collect();
+        DynamicItem { entity, components }
+    }
+}
+
+macro_rules! impl_dynamic_query {
+    ($query:ty, $read_only:expr) => {
+        // SAFETY: `update_component_access` and `update_archetype_component_access` register the
+        // access of every fetched component, mutable if it is fetched mutably.
+        unsafe impl WorldQuery for $query {
+            type ReadOnly = ReadOnlyDynamicQuery;
+            type State = DynamicQueryState;
+
+            fn shrink<'wlong: 'wshort, 'wshort>(
+                item: QueryItem<'wlong, Self>,
+            ) -> QueryItem<'wshort, Self> {
+                item
+            }
+
+            const IS_DENSE: bool = false;
+
+            const IS_ARCHETYPAL: bool = true;
+
+            unsafe fn init_fetch<'w>(
+                world: &'w World,
+                state: &DynamicQueryState,
+                last_change_tick: u32,
+                change_tick: u32,
+            ) -> DynamicFetch<'w> {
+                state.init_fetch(world, last_change_tick, change_tick)
+            }
+
+            #[inline]
+            unsafe fn set_archetype<'w>(
+                fetch: &mut DynamicFetch<'w>,
+                state: &DynamicQueryState,
+                archetype: &'w Archetype,
+                tables: &'w Tables,
+            ) {
+                state.set_archetype(fetch, archetype, tables);
+            }
+
+            #[inline]
+            unsafe fn set_table<'w>(
+                fetch: &mut DynamicFetch<'w>,
+                state: &DynamicQueryState,
+                table: &'w Table,
+            ) {
+                state.set_table(fetch, table);
+            }
+
+            #[inline]
+            unsafe fn archetype_fetch<'w>(
+                fetch: &mut <Self as WorldQueryGats<'w>>::Fetch,
+                archetype_index: usize,
+            ) -> <Self as WorldQueryGats<'w>>::Item {
+                fetch.fetch(archetype_index, $read_only)
+            }
+
+            #[inline]
+            unsafe fn table_fetch<'w>(
+                fetch: &mut <Self as WorldQueryGats<'w>>::Fetch,
+                table_row: usize,
+            ) -> <Self as WorldQueryGats<'w>>::Item {
+                fetch.fetch(table_row, $read_only)
+            }
+
+            fn update_component_access(
+                state: &DynamicQueryState,
+                access: &mut FilteredAccess<ComponentId>,
+            ) {
+                for term in &state.terms {
+                    let mutable = term.mutable && !$read_only;
+                    if mutable {
+                        assert!(
+                            !access.access().has_read(term.id),
+                            "Mutable access to {:?} conflicts with a previous access in this query. Mutable component access must be unique.",
+                            term.id,
+                        );
+                    } else {
+                        assert!(
+                            !access.access().has_write(term.id),
+                            "Shared access to {:?} conflicts with a previous access in this query. Shared access cannot coincide with exclusive access.",
+                            term.id,
+                        );
+                    }
+                    match (mutable, term.optional) {
+                        (false, false) => access.add_read(term.id),
+                        (true, false) => access.add_write(term.id),
+                        // Optional components must not add a `with` filter, like `Option<T>`.
+                        (false, true) => access.access_mut().add_read(term.id),
+                        (true, true) => access.access_mut().add_write(term.id),
+                    }
+                }
+                for &id in &state.with {
+                    access.add_with(id);
+                }
+                for &id in &state.without {
+                    access.add_without(id);
+                }
+            }
+
+            fn update_archetype_component_access(
+                state: &DynamicQueryState,
+                archetype: &Archetype,
+                access: &mut Access<ArchetypeComponentId>,
+            ) {
+                for term in &state.terms {
+                    if let Some(archetype_component_id) =
+                        archetype.get_archetype_component_id(term.id)
+                    {
+                        if term.mutable && !$read_only {
+                            access.add_write(archetype_component_id);
+                        } else {
+                            access.add_read(archetype_component_id);
+                        }
+                    }
+                }
+            }
+
+            fn init_state(_world: &mut World) -> DynamicQueryState {
+                DynamicQueryState::default()
+            }
+
+            fn matches_component_set(
+                state: &DynamicQueryState,
+                set_contains_id: &impl Fn(ComponentId) -> bool,
+            ) -> bool {
+                state.matches_component_set(set_contains_id)
+            }
+        }
+
+        impl<'w> WorldQueryGats<'w> for $query {
+            type Fetch = DynamicFetch<'w>;
+            type Item = DynamicItem<'w>;
+        }
+    };
+}
+
+impl_dynamic_query!(DynamicQuery, false);
+impl_dynamic_query!(ReadOnlyDynamicQuery, true);
+
+/// SAFETY: every component is fetched immutably
+unsafe impl ReadOnlyWorldQuery for ReadOnlyDynamicQuery {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{self as bevy_ecs, change_detection::DetectChanges, component::Component};
+
+    #[derive(Component, Debug, PartialEq)]
+    struct A(usize);
+    #[derive(Component, Debug, PartialEq)]
+    struct B(usize);
+    #[derive(Component, Debug, PartialEq)]
+    #[component(storage = "SparseSet")]
+    struct C(usize);
+
+    #[test]
+    fn dynamic_query() {
+        let mut world = World::new();
+        let e1 = world.spawn().insert_bundle((A(1), B(1))).id();
+        let e2 = world.spawn().insert_bundle((A(2), C(2))).id();
+        world.spawn().insert_bundle((A(3), B(3), C(3)));
+        world.spawn().insert(B(4));
+        let a = world.init_component::<A>();
+        let b = world.init_component::<B>();
+        let c = world.init_component::<C>();
+
+        let mut query = QueryBuilder::new()
+            .read(a)
+            .optional_read(c)
+            .without(b)
+            .build(&mut world);
+        let items = query
+            .iter(&world)
+            .map(|item| {
+                // SAFETY: the ids belong to `A` and `C`
+                unsafe {
+                    (
+                        item.entity,
+                        item.get(0).unwrap().deref::<A>().0,
+                        item.get(1).map(|ptr| ptr.deref::<C>().0),
+                    )
+                }
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(items, vec![(e2, 2, Some(2))]);
+
+        let mut query = QueryBuilder::new().read(a).with(b).build(&mut world);
+        let entities = query
+            .iter(&world)
+            .map(|item| item.entity)
+            .collect::<Vec<_>>();
+        assert_eq!(entities.len(), 2);
+        assert!(entities.contains(&e1));
+    }
+
+    #[test]
+    fn dynamic_query_mut() {
+        let mut world = World::new();
+        world.spawn().insert_bundle((A(1), C(10)));
+        world.spawn().insert_bundle((A(2), C(20)));
+        let a = world.init_component::<A>();
+        let c = world.init_component::<C>();
+        world.clear_trackers();
+
+        let mut query = QueryBuilder::new().write(a).write(c).build(&mut world);
+        for mut item in query.iter_mut(&mut world) {
+            for index in 0..2 {
+                let value = item.get_mut(index).unwrap();
+                assert!(!value.is_changed());
+                // SAFETY: both `A` and `C` wrap a `usize`
+                unsafe { *value.as_mut().deref_mut::<usize>() += 1 };
+            }
+        }
+
+        let mut values = world
+            .query::<(&A, &C)>()
+            .iter(&world)
+            .map(|(a, c)| (a.0, c.0))
+            .collect::<Vec<_>>();
+        values.sort_unstable();
+        assert_eq!(values, vec![(2, 11), (3, 21)]);
+        assert_eq!(
+            world
+                .query_filtered::<(), (bevy_ecs::query::Changed<A>, bevy_ecs::query::Changed<C>)>()
+                .iter(&world)
+                .count(),
+            2
+        );
+    }
+
+    #[test]
+    fn dynamic_query_access() {
+        let mut world = World::new();
+        let a = world.init_component::<A>();
+        let b = world.init_component::<B>();
+
+        let read = QueryBuilder::new().read(a).build(&mut world);
+        let write = QueryBuilder::new().write(a).build(&mut world);
+        let write_without_b = QueryBuilder::new().write(a).without(b).build(&mut world);
+        let optional_read = QueryBuilder::new()
+            .read(b)
+            .optional_read(a)
+            .build(&mut world);
+        let static_query = world.query_filtered::<&mut A, bevy_ecs::query::With<B>>();
+
+        assert!(read
+            .component_access()
+            .is_compatible(read.component_access()));
+        assert!(!read
+            .component_access()
+            .is_compatible(write.component_access()));
+        assert!(write_without_b
+            .component_access()
+            .is_compatible(static_query.component_access()));
+        assert!(!optional_read
+            .component_access()
+            .is_compatible(static_query.component_access()));
+        assert!(optional_read.component_access().access().has_read(a));
+    }
+
+    #[test]
+    #[should_panic]
+    fn dynamic_query_conflicting_access() {
+        let mut world = World::new();
+        let a = world.init_component::<A>();
+        QueryBuilder::new().read(a).write(a).build(&mut world);
+    }
+
+    #[test]
+    #[should_panic(expected = "is not a valid component id")]
+    fn dynamic_query_invalid_filter_id() {
+        let mut world = World::new();
+        let a = world.init_component::<A>();
+        let mut other_world = World::new();
+        other_world.init_component::<A>();
+        let b = other_world.init_component::<B>();
+        QueryBuilder::new().read(a).without(b).build(&mut world);
+    }
+}
@@ -1,7 +1,7 @@
 use crate::{
     archetype::{Archetype, ArchetypeComponentId},
     component::{Component, ComponentId, ComponentStorage, ComponentTicks, StorageType},
-    entity::Entity,
+    entity::{Disabled, Entity},
     query::{
         debug_checked_unreachable, Access, FilteredAccess, QueryFetch, WorldQuery, WorldQueryGats,
     },
@@ -241,6 +241,117 @@ impl<T: Component> WorldQueryGats<'_> for Without<T> {
 // SAFETY: no component access or archetype component access
 unsafe impl<T: Component> ReadOnlyWorldQuery for Without<T> {}
 
+/// Filter that opts a query into also matching [`Disabled`] entities.
+///
+/// Queries skip entities with the [`Disabled`] marker unless they mention it explicitly. This
+/// filter matches every entity, regardless of whether it is disabled or not.
+///
+/// For scheduling purposes, this filter reads [`Disabled`], like `Option<&Disabled>` would.
+///
+/// # Examples
+///
+/// ```
+/// # use bevy_ecs::component::Component;
+/// # use bevy_ecs::query::IncludeDisabled;
+/// # use bevy_ecs::system::IntoSystem;
+/// # use bevy_ecs::system::Query;
+/// #
+/// # #[derive(Component)]
+/// # struct Projectile;
+/// #
+/// fn count_pooled_projectiles(query: Query<&Projectile, IncludeDisabled>) {
+///     println!("{} projectiles in the pool", query.iter().count());
+/// }
+/// # bevy_ecs::system::assert_is_system(count_pooled_projectiles);
+/// ```
+pub struct IncludeDisabled;
+
+// SAFETY: `ROQueryFetch<Self>` is the same as `QueryFetch<Self>`. The only access is a read of
+// `Disabled`, which is never fetched.
+unsafe impl WorldQuery for IncludeDisabled {
+    type ReadOnly = Self;
+    type State = ComponentId;
+
+    fn shrink<'wlong: 'wshort, 'wshort>(
+        _: <Self as WorldQueryGats<'wlong>>::Item,
+    ) -> <Self as WorldQueryGats<'wshort>>::Item {
+    }
+
+    unsafe fn init_fetch(
+        _world: &World,
+        _state: &ComponentId,
+        _last_change_tick: u32,
+        _change_tick: u32,
+    ) {
+    }
+
+    const IS_DENSE: bool = true;
+
+    const IS_ARCHETYPAL: bool = true;
+
+    #[inline]
+    unsafe fn set_table(_fetch: &mut (), _state: &ComponentId, _table: &Table) {}
+
+    #[inline]
+    unsafe fn set_archetype(
+        _fetch: &mut (),
+        _state: &ComponentId,
+        _archetype: &Archetype,
+        _tables: &Tables,
+    ) {
+    }
+
+    #[inline]
+    unsafe fn archetype_fetch<'w>(
+        _fetch: &mut <Self as WorldQueryGats<'w>>::Fetch,
+        _archetype_index: usize,
+    ) -> <Self as WorldQueryGats<'w>>::Item {
+    }
+
+    #[inline]
+    unsafe fn table_fetch<'w>(
+        _fetch: &mut <Self as WorldQueryGats<'w>>::Fetch,
+        _table_row: usize,
+    ) -> <Self as WorldQueryGats<'w>>::Item {
+    }
+
+    #[inline]
+    fn update_component_access(&id: &ComponentId, access: &mut FilteredAccess<ComponentId>) {
+        // Only touch the unfiltered access, so that disabled and enabled entities both match.
+        access.access_mut().add_read(id);
+    }
+
+    #[inline]
+    fn update_archetype_component_access(
+        &id: &ComponentId,
+        archetype: &Archetype,
+        access: &mut Access<ArchetypeComponentId>,
+    ) {
+        if let Some(archetype_component_id) = archetype.get_archetype_component_id(id) {
+            access.add_read(archetype_component_id);
+        }
+    }
+
+    fn init_state(world: &mut World) -> ComponentId {
+        world.init_component::<Disabled>()
+    }
+
+    fn matches_component_set(
+        _state: &ComponentId,
+        _set_contains_id: &impl Fn(ComponentId) -> bool,
+    ) -> bool {
+        true
+    }
+}
+
+impl WorldQueryGats<'_> for IncludeDisabled {
+    type Fetch = ();
+    type Item = ();
+}
+
+// SAFETY: only reads `Disabled`
+unsafe impl ReadOnlyWorldQuery for IncludeDisabled {}
+
 /// A filter that tests if any of the given filters apply.
 ///
 /// This is useful for example if a system with multiple components in a query only wants to run
@@ -618,7 +729,7 @@ impl_tick_filter!(
 
 impl_tick_filter!(
     /// A filter on a component that only retains results added or mutably dereferenced after the system last ran.
-    ///  
+    ///
     /// A common use for this filter is avoiding redundant work when values have not changed.
     ///
     /// **Note** that simply *mutably dereferencing* a component is considered a change ([`DerefMut`](std::ops::DerefMut)).
@@ -669,6 +780,7 @@ pub trait ArchetypeFilter {}
 
 impl<T> ArchetypeFilter for With<T> {}
 impl<T> ArchetypeFilter for Without<T> {}
+impl ArchetypeFilter for IncludeDisabled {}
 
 macro_rules! impl_archetype_filter_tuple {
     ($($filter: ident),*) => {
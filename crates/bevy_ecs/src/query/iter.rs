@@ -74,7 +74,8 @@ impl<'w, 's, Q: WorldQuery, F: WorldQuery> FusedIterator for QueryIter<'w, 's, Q
 
 /// An [`Iterator`] over [`Query`](crate::system::Query) results of a list of [`Entity`]s.
 ///
-/// This struct is created by the [`Query::iter_many`](crate::system::Query::iter_many) and [`Query::iter_many_mut`](crate::system::Query::iter_many_mut) methods.
+/// This struct is created by the [`Query::iter_many`](crate::system::Query::iter_many), [`Query::iter_many_mut`](crate::system::Query::iter_many_mut),
+/// [`Query::iter_many_sorted`](crate::system::Query::iter_many_sorted) and [`Query::iter_many_sorted_mut`](crate::system::Query::iter_many_sorted_mut) methods.
 pub struct QueryManyIter<'w, 's, Q: WorldQuery, F: WorldQuery, I: Iterator>
 where
     I::Item: Borrow<Entity>,
@@ -86,6 +87,7 @@ where
     fetch: QueryFetch<'w, Q>,
     filter: QueryFetch<'w, F>,
     query_state: &'s QueryState<Q, F>,
+    current_archetype: Option<ArchetypeId>,
 }
 
 impl<'w, 's, Q: WorldQuery, F: WorldQuery, I: Iterator> QueryManyIter<'w, 's, Q, F, I>
@@ -124,6 +126,7 @@ where
             fetch,
             filter,
             entity_iter: entity_list.into_iter(),
+            current_archetype: None,
         }
     }
 
@@ -149,24 +152,29 @@ where
                 continue;
             }
 
-            let archetype = &self.archetypes[location.archetype_id];
-
-            // SAFETY: `archetype` is from the world that `fetch/filter` were created for,
-            // `fetch_state`/`filter_state` are the states that `fetch/filter` were initialized with
-            Q::set_archetype(
-                &mut self.fetch,
-                &self.query_state.fetch_state,
-                archetype,
-                self.tables,
-            );
-            // SAFETY: `table` is from the world that `fetch/filter` were created for,
-            // `fetch_state`/`filter_state` are the states that `fetch/filter` were initialized with
-            F::set_archetype(
-                &mut self.filter,
-                &self.query_state.filter_state,
-                archetype,
-                self.tables,
-            );
+            // Consecutive entities of the same archetype (e.g. from `iter_many_sorted`) share the
+            // fetch state set up for the first of them.
+            if self.current_archetype != Some(location.archetype_id) {
+                let archetype = &self.archetypes[location.archetype_id];
+
+                // SAFETY: `archetype` is from the world that `fetch/filter` were created for,
+                // `fetch_state`/`filter_state` are the states that `fetch/filter` were initialized with
+                Q::set_archetype(
+                    &mut self.fetch,
+                    &self.query_state.fetch_state,
+                    archetype,
+                    self.tables,
+                );
+                // SAFETY: `table` is from the world that `fetch/filter` were created for,
+                // `fetch_state`/`filter_state` are the states that `fetch/filter` were initialized with
+                F::set_archetype(
+                    &mut self.filter,
+                    &self.query_state.filter_state,
+                    archetype,
+                    self.tables,
+                );
+                self.current_archetype = Some(location.archetype_id);
+            }
             // SAFETY: set_archetype was called prior.
             // `location.index` is an archetype index row in range of the current archetype, because if it was not, the match above would have `continue`d
             if F::archetype_filter_fetch(&mut self.filter, location.index) {
@@ -1,10 +1,12 @@
 mod access;
+mod builder;
 mod fetch;
 mod filter;
 mod iter;
 mod state;
 
 pub use access::*;
+pub use builder::*;
 pub use fetch::*;
 pub use filter::*;
 pub use iter::*;
@@ -20,8 +22,9 @@ pub(crate) unsafe fn debug_checked_unreachable() -> ! {
 #[cfg(test)]
 mod tests {
     use super::WorldQuery;
-    use crate::prelude::{AnyOf, Entity, Or, QueryState, With, Without};
-    use crate::query::{ArchetypeFilter, QueryCombinationIter, QueryFetch};
+    use crate::entity::Disabled;
+    use crate::prelude::{AnyOf, Entity, IncludeDisabled, Or, QueryState, With, Without};
+    use crate::query::{ArchetypeFilter, QueryCombinationIter, QueryEntityError, QueryFetch};
     use crate::system::{IntoSystem, Query, System, SystemState};
     use crate::{self as bevy_ecs, component::Component, world::World};
     use std::any::type_name;
@@ -657,6 +660,51 @@ count():       {count}"#
         }
     }
 
+    #[test]
+    fn many_entities_sorted() {
+        let mut world = World::new();
+        let a = world.spawn().insert(A(1)).id();
+        let ab = world.spawn().insert_bundle((A(2), B(0))).id();
+        let b = world.spawn().insert(B(0)).id();
+        let a2 = world.spawn().insert(A(3)).id();
+        let despawned = world.spawn().insert(A(4)).id();
+        world.despawn(despawned);
+
+        let entities = [ab, b, a2, despawned, a, ab];
+        let mut query = world.query::<&A>();
+        let mut sorted: Vec<_> = query
+            .iter_many_sorted(&world, entities)
+            .map(|a| a.0)
+            .collect();
+        // grouped by archetype, duplicates are kept
+        assert_eq!(sorted, vec![1, 3, 2, 2]);
+
+        let results = query.get_many_dynamic(&world, &entities);
+        assert_eq!(
+            results,
+            vec![
+                Ok(&A(2)),
+                Err(QueryEntityError::QueryDoesNotMatch(b)),
+                Ok(&A(3)),
+                Err(QueryEntityError::NoSuchEntity(despawned)),
+                Ok(&A(1)),
+                Ok(&A(2)),
+            ]
+        );
+
+        let mut query = world.query::<&mut A>();
+        let mut iter = query.iter_many_sorted_mut(&mut world, entities);
+        while let Some(mut a) = iter.fetch_next() {
+            a.0 *= 10;
+        }
+        sorted = world
+            .query::<&A>()
+            .iter_many_sorted(&world, entities)
+            .map(|a| a.0)
+            .collect();
+        assert_eq!(sorted, vec![10, 30, 200, 200]);
+    }
+
     #[test]
     fn mut_to_immut_query_methods_have_immut_item() {
         #[derive(Component)]
@@ -671,11 +719,13 @@ count():       {count}"#
         let _: Option<[&Foo; 2]> = q.iter_combinations::<2>(&world).next();
         let _: Option<&Foo> = q.iter_manual(&world).next();
         let _: Option<&Foo> = q.iter_many(&world, [e]).next();
+        let _: Option<&Foo> = q.iter_many_sorted(&world, [e]).next();
         q.for_each(&world, |_: &Foo| ());
 
         let _: Option<&Foo> = q.get(&world, e).ok();
         let _: Option<&Foo> = q.get_manual(&world, e).ok();
         let _: Option<[&Foo; 1]> = q.get_many(&world, [e]).ok();
+        let _: Option<&Foo> = q.get_many_dynamic(&world, &[e]).pop().unwrap().ok();
         let _: Option<&Foo> = q.get_single(&world).ok();
         let _: &Foo = q.single(&world);
 
@@ -685,13 +735,72 @@ count():       {count}"#
         let _: Option<&Foo> = q.iter().next();
         let _: Option<[&Foo; 2]> = q.iter_combinations::<2>().next();
         let _: Option<&Foo> = q.iter_many([e]).next();
+        let _: Option<&Foo> = q.iter_many_sorted([e]).next();
         q.for_each(|_: &Foo| ());
 
         let _: Option<&Foo> = q.get(e).ok();
         let _: Option<&Foo> = q.get_component(e).ok();
         let _: Option<[&Foo; 1]> = q.get_many([e]).ok();
+        let _: Option<&Foo> = q.get_many_dynamic(&[e]).pop().unwrap().ok();
         let _: Option<&Foo> = q.get_single().ok();
         let _: [&Foo; 1] = q.many([e]);
         let _: &Foo = q.single();
     }
+
+    #[test]
+    fn disabled_entities_are_skipped_by_default() {
+        let mut world = World::new();
+        let enabled = world.spawn().insert(A(0)).id();
+        let disabled = world.spawn().insert_bundle((A(1), Disabled)).id();
+
+        let mut query = world.query::<Entity>();
+        assert_eq!(query.iter(&world).collect::<Vec<_>>(), vec![enabled]);
+        assert!(query.get(&world, disabled).is_err());
+
+        let mut query = world.query::<&A>();
+        assert_eq!(query.iter(&world).collect::<Vec<_>>(), vec![&A(0)]);
+
+        // entities disabled after the query was created are skipped as well
+        world.entity_mut(enabled).insert(Disabled);
+        assert_eq!(query.iter(&world).count(), 0);
+
+        world.entity_mut(disabled).remove::<Disabled>();
+        assert_eq!(query.iter(&world).collect::<Vec<_>>(), vec![&A(1)]);
+    }
+
+    #[test]
+    fn disabled_entities_can_be_opted_into() {
+        let mut world = World::new();
+        let enabled = world.spawn().insert(A(0)).id();
+        let disabled = world.spawn().insert_bundle((A(1), Disabled)).id();
+
+        let mut query = world.query_filtered::<Entity, With<Disabled>>();
+        assert_eq!(query.iter(&world).collect::<Vec<_>>(), vec![disabled]);
+
+        let mut query = world.query::<(Entity, Option<&Disabled>)>();
+        assert_eq!(query.iter(&world).count(), 2);
+
+        let mut query = world.query_filtered::<Entity, (With<A>, IncludeDisabled)>();
+        let entities = query.iter(&world).collect::<HashSet<_>>();
+        assert_eq!(entities, [enabled, disabled].into_iter().collect());
+        assert!(query.get(&world, disabled).is_ok());
+    }
+
+    #[test]
+    fn disabled_filter_is_considered_in_access() {
+        let mut world = World::new();
+        let disabled_id = world.init_component::<Disabled>();
+
+        let query = world.query::<&mut A>();
+        let disabled_query = world.query_filtered::<&mut A, With<Disabled>>();
+        let all_query = world.query_filtered::<&mut A, IncludeDisabled>();
+
+        assert!(query.component_access().mentions(disabled_id));
+        assert!(query
+            .component_access()
+            .is_compatible(disabled_query.component_access()));
+        assert!(!query
+            .component_access()
+            .is_compatible(all_query.component_access()));
+    }
 }
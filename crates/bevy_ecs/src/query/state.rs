@@ -1,7 +1,7 @@
 use crate::{
     archetype::{Archetype, ArchetypeComponentId, ArchetypeGeneration, ArchetypeId},
-    component::ComponentId,
-    entity::Entity,
+    component::{ComponentId, Tick},
+    entity::{Disabled, Entity, EntityLocation},
     prelude::FromWorld,
     query::{Access, FilteredAccess, QueryCombinationIter, QueryIter, WorldQuery},
     storage::TableId,
@@ -31,6 +31,9 @@ pub struct QueryState<Q: WorldQuery, F: WorldQuery = ()> {
     pub(crate) matched_table_ids: Vec<TableId>,
     // NOTE: we maintain both a ArchetypeId bitset and a vec because iterating the vec is faster
     pub(crate) matched_archetype_ids: Vec<ArchetypeId>,
+    // NOTE: set to the id of `Disabled` if archetypes containing it are skipped, which is the case
+    // unless the query mentions `Disabled` explicitly
+    pub(crate) skipped_disabled: Option<ComponentId>,
     pub(crate) fetch_state: Q::State,
     pub(crate) filter_state: F::State,
 }
@@ -84,7 +87,15 @@ impl<Q: WorldQuery, F: WorldQuery> QueryState<Q, F> {
     pub fn new(world: &mut World) -> Self {
         let fetch_state = Q::init_state(world);
         let filter_state = F::init_state(world);
+        Self::new_with_state(world, fetch_state, filter_state)
+    }
 
+    /// Creates a new [`QueryState`] from already initialized fetch and filter states.
+    pub(crate) fn new_with_state(
+        world: &mut World,
+        fetch_state: Q::State,
+        filter_state: F::State,
+    ) -> Self {
         let mut component_access = FilteredAccess::default();
         Q::update_component_access(&fetch_state, &mut component_access);
 
@@ -98,6 +109,15 @@ impl<Q: WorldQuery, F: WorldQuery> QueryState<Q, F> {
         // properly considered in a global "cross-query" context (both within systems and across systems).
         component_access.extend(&filter_component_access);
 
+        // Disabled entities are hidden from queries which do not explicitly opt into seeing them.
+        let disabled_id = world.init_component::<Disabled>();
+        let skipped_disabled = if component_access.mentions(disabled_id) {
+            None
+        } else {
+            component_access.add_without(disabled_id);
+            Some(disabled_id)
+        };
+
         let mut state = Self {
             world_id: world.id(),
             archetype_generation: ArchetypeGeneration::initial(),
@@ -109,11 +129,25 @@ impl<Q: WorldQuery, F: WorldQuery> QueryState<Q, F> {
             matched_tables: Default::default(),
             matched_archetypes: Default::default(),
             archetype_component_access: Default::default(),
+            skipped_disabled,
         };
         state.update_archetypes(world);
         state
     }
 
+    /// Returns the components and resources accessed by this query, along with its filters.
+    ///
+    /// This is used by the scheduler to determine which queries can run in parallel.
+    pub fn component_access(&self) -> &FilteredAccess<ComponentId> {
+        &self.component_access
+    }
+
+    /// Returns the archetype components accessed by this query, for the archetypes matched so
+    /// far.
+    pub fn archetype_component_access(&self) -> &Access<ArchetypeComponentId> {
+        &self.archetype_component_access
+    }
+
     /// Checks if the query is empty for the given [`World`], where the last change and current tick are given.
     #[inline]
     pub fn is_empty(&self, world: &World, last_change_tick: u32, change_tick: u32) -> bool {
@@ -155,7 +189,11 @@ impl<Q: WorldQuery, F: WorldQuery> QueryState<Q, F> {
 
     /// Creates a new [`Archetype`].
     pub fn new_archetype(&mut self, archetype: &Archetype) {
-        if Q::matches_component_set(&self.fetch_state, &|id| archetype.contains(id))
+        if self
+            .skipped_disabled
+            .filter(|&id| archetype.contains(id))
+            .is_none()
+            && Q::matches_component_set(&self.fetch_state, &|id| archetype.contains(id))
             && F::matches_component_set(&self.filter_state, &|id| archetype.contains(id))
         {
             Q::update_archetype_component_access(
@@ -525,6 +563,42 @@ impl<Q: WorldQuery, F: WorldQuery> QueryState<Q, F> {
         }
     }
 
+    /// Returns an [`Iterator`] over the query results for the given [`World`], where change
+    /// detection filters such as [`Changed`](crate::query::Changed) and
+    /// [`Added`](crate::query::Added) consider changes made since `tick`, instead of since the
+    /// last time the world's change trackers were cleared.
+    ///
+    /// This can only be called for read-only queries, see [`Self::iter_mut_since`] for
+    /// write-queries.
+    #[inline]
+    pub fn iter_since<'w, 's>(
+        &'s mut self,
+        world: &'w World,
+        tick: Tick,
+    ) -> QueryIter<'w, 's, Q::ReadOnly, F::ReadOnly> {
+        // SAFETY: query is read only
+        unsafe {
+            self.update_archetypes(world);
+            self.as_readonly()
+                .iter_unchecked_manual(world, tick.get(), world.read_change_tick())
+        }
+    }
+
+    /// Returns an [`Iterator`] over the query results for the given [`World`], where change
+    /// detection filters consider changes made since `tick`, see [`Self::iter_since`].
+    #[inline]
+    pub fn iter_mut_since<'w, 's>(
+        &'s mut self,
+        world: &'w mut World,
+        tick: Tick,
+    ) -> QueryIter<'w, 's, Q, F> {
+        // SAFETY: query has unique world access
+        unsafe {
+            self.update_archetypes(world);
+            self.iter_unchecked_manual(world, tick.get(), world.read_change_tick())
+        }
+    }
+
     /// Returns an [`Iterator`] over the query results for the given [`World`] without updating the query's archetypes.
     /// Archetypes must be manually updated before by using [`Self::update_archetypes`].
     ///
@@ -642,6 +716,241 @@ impl<Q: WorldQuery, F: WorldQuery> QueryState<Q, F> {
         }
     }
 
+    /// Returns an [`Iterator`] over the query results of a list of [`Entity`]'s, visiting the
+    /// entities grouped by the table and archetype they are stored in instead of in the given order.
+    ///
+    /// Entities that do not exist or do not match the query are skipped. Fetching entities that are
+    /// scattered across the world this way is more cache-friendly than calling [`Self::get`] for each.
+    ///
+    /// This can only return immutable data (mutable data will be cast to an immutable form).
+    /// See [`Self::iter_many_sorted_mut`] for queries that contain at least one mutable component.
+    #[inline]
+    pub fn iter_many_sorted<'w, 's, EntityList: IntoIterator>(
+        &'s mut self,
+        world: &'w World,
+        entities: EntityList,
+    ) -> QueryManyIter<'w, 's, Q::ReadOnly, F::ReadOnly, std::vec::IntoIter<Entity>>
+    where
+        EntityList::Item: Borrow<Entity>,
+    {
+        self.update_archetypes(world);
+        let entities = self.sort_by_storage(world, entities);
+        // SAFETY: query is read only
+        unsafe {
+            self.as_readonly().iter_many_unchecked_manual(
+                entities,
+                world,
+                world.last_change_tick(),
+                world.read_change_tick(),
+            )
+        }
+    }
+
+    /// Returns an iterator over the query results of a list of [`Entity`]'s, visiting the
+    /// entities grouped by the table and archetype they are stored in instead of in the given order.
+    ///
+    /// See [`Self::iter_many_sorted`].
+    #[inline]
+    pub fn iter_many_sorted_mut<'w, 's, EntityList: IntoIterator>(
+        &'s mut self,
+        world: &'w mut World,
+        entities: EntityList,
+    ) -> QueryManyIter<'w, 's, Q, F, std::vec::IntoIter<Entity>>
+    where
+        EntityList::Item: Borrow<Entity>,
+    {
+        self.update_archetypes(world);
+        let entities = self.sort_by_storage(world, entities);
+        // SAFETY: Query has unique world access.
+        unsafe {
+            self.iter_many_unchecked_manual(
+                entities,
+                world,
+                world.last_change_tick(),
+                world.read_change_tick(),
+            )
+        }
+    }
+
+    /// Returns an [`Iterator`] over the query results for the given [`World`], sorted by the key
+    /// extracted by `key`.
+    ///
+    /// Unlike [`Self::iter`], whose order depends on the history of archetype creation and table
+    /// moves, the order of this iterator only depends on the keys. For the order to be fully
+    /// deterministic, the keys must be unique, e.g. by including the [`Entity`] of each item.
+    ///
+    /// This can only be called for read-only queries, see [`Self::iter_sorted_by_key_mut`] for
+    /// write-queries.
+    #[inline]
+    pub fn iter_sorted_by_key<'w, K: Ord>(
+        &mut self,
+        world: &'w World,
+        key: impl FnMut(&ROQueryItem<'w, Q>) -> K,
+    ) -> std::vec::IntoIter<ROQueryItem<'w, Q>> {
+        let mut items: Vec<_> = self.iter(world).collect();
+        items.sort_by_key(key);
+        items.into_iter()
+    }
+
+    /// Returns an [`Iterator`] over the query results for the given [`World`], sorted by the key
+    /// extracted by `key`.
+    ///
+    /// See [`Self::iter_sorted_by_key`].
+    #[inline]
+    pub fn iter_sorted_by_key_mut<'w, K: Ord>(
+        &mut self,
+        world: &'w mut World,
+        key: impl FnMut(&QueryItem<'w, Q>) -> K,
+    ) -> std::vec::IntoIter<QueryItem<'w, Q>> {
+        let mut items: Vec<_> = self.iter_mut(world).collect();
+        items.sort_by_key(key);
+        items.into_iter()
+    }
+
+    /// Returns the read-only query results for the given slice of [`Entity`], in the same order.
+    ///
+    /// The entities are fetched grouped by the table and archetype they are stored in, which is
+    /// more cache-friendly than calling [`Self::get`] for each of them. Unlike
+    /// [`Self::get_many`], the number of entities does not need to be known at compile time, and
+    /// each entity that does not exist or does not match the query gets its own
+    /// [`QueryEntityError`].
+    ///
+    /// ```rust
+    /// use bevy_ecs::prelude::*;
+    /// use bevy_ecs::query::QueryEntityError;
+    ///
+    /// #[derive(Component, PartialEq, Debug)]
+    /// struct A(usize);
+    ///
+    /// #[derive(Component)]
+    /// struct B;
+    ///
+    /// let mut world = World::new();
+    /// let a0 = world.spawn().insert(A(0)).id();
+    /// let a1 = world.spawn().insert_bundle((A(1), B)).id();
+    /// let b = world.spawn().insert(B).id();
+    ///
+    /// let mut query_state = world.query::<&A>();
+    /// let results = query_state.get_many_dynamic(&world, &[a1, b, a0]);
+    ///
+    /// assert_eq!(results, vec![Ok(&A(1)), Err(QueryEntityError::QueryDoesNotMatch(b)), Ok(&A(0))]);
+    /// ```
+    #[inline]
+    pub fn get_many_dynamic<'w>(
+        &mut self,
+        world: &'w World,
+        entities: &[Entity],
+    ) -> Vec<Result<ROQueryItem<'w, Q>, QueryEntityError>> {
+        self.update_archetypes(world);
+        // SAFETY: query is read only and world is validated
+        unsafe {
+            self.as_readonly().get_many_dynamic_unchecked_manual(
+                world,
+                entities,
+                world.last_change_tick(),
+                world.read_change_tick(),
+            )
+        }
+    }
+
+    /// Gets the query results for the given [`World`] and slice of [`Entity`], where the last
+    /// change and the current change tick are given. The results are in the order of `entities`.
+    ///
+    /// # Safety
+    ///
+    /// This does not check for mutable query correctness. To be safe, make sure mutable queries
+    /// have unique access to the components they query.
+    /// This does not check for entity uniqueness.
+    ///
+    /// This must be called on the same `World` that the `Query` was generated from:
+    /// use `QueryState::validate_world` to verify this.
+    pub(crate) unsafe fn get_many_dynamic_unchecked_manual<'w>(
+        &self,
+        world: &'w World,
+        entities: &[Entity],
+        last_change_tick: u32,
+        change_tick: u32,
+    ) -> Vec<Result<QueryItem<'w, Q>, QueryEntityError>> {
+        let mut results = Vec::with_capacity(entities.len());
+        let mut order = Vec::with_capacity(entities.len());
+        for (i, &entity) in entities.iter().enumerate() {
+            match world.entities.get(entity) {
+                Some(location) => {
+                    results.push(Err(QueryEntityError::QueryDoesNotMatch(entity)));
+                    if self
+                        .matched_archetypes
+                        .contains(location.archetype_id.index())
+                    {
+                        order.push((Self::storage_key(world, location), location, i));
+                    }
+                }
+                None => results.push(Err(QueryEntityError::NoSuchEntity(entity))),
+            }
+        }
+        order.sort_unstable_by_key(|(key, _, _)| *key);
+
+        let mut fetch = Q::init_fetch(world, &self.fetch_state, last_change_tick, change_tick);
+        let mut filter = F::init_fetch(world, &self.filter_state, last_change_tick, change_tick);
+        let mut current_archetype = None;
+        for (_, location, i) in order {
+            if current_archetype != Some(location.archetype_id) {
+                let archetype = &world.archetypes[location.archetype_id];
+                Q::set_archetype(
+                    &mut fetch,
+                    &self.fetch_state,
+                    archetype,
+                    &world.storages().tables,
+                );
+                F::set_archetype(
+                    &mut filter,
+                    &self.filter_state,
+                    archetype,
+                    &world.storages().tables,
+                );
+                current_archetype = Some(location.archetype_id);
+            }
+            if F::archetype_filter_fetch(&mut filter, location.index) {
+                results[i] = Ok(Q::archetype_fetch(&mut fetch, location.index));
+            }
+        }
+        results
+    }
+
+    /// Sorts `entities` by the table and archetype they are stored in, and then by their row in
+    /// the table. Entities that do not exist or whose archetype does not match the query are dropped.
+    pub(crate) fn sort_by_storage<EntityList: IntoIterator>(
+        &self,
+        world: &World,
+        entities: EntityList,
+    ) -> Vec<Entity>
+    where
+        EntityList::Item: Borrow<Entity>,
+    {
+        let mut keyed: Vec<_> = entities
+            .into_iter()
+            .filter_map(|entity| {
+                let entity = *entity.borrow();
+                let location = world.entities.get(entity)?;
+                self.matched_archetypes
+                    .contains(location.archetype_id.index())
+                    .then(|| (Self::storage_key(world, location), entity))
+            })
+            .collect();
+        keyed.sort_unstable_by_key(|(key, _)| *key);
+        keyed.into_iter().map(|(_, entity)| entity).collect()
+    }
+
+    /// The order in which [`Self::sort_by_storage`] and [`Self::get_many_dynamic`] visit entities.
+    #[inline]
+    fn storage_key(world: &World, location: EntityLocation) -> (usize, usize, usize) {
+        let archetype = &world.archetypes[location.archetype_id];
+        (
+            archetype.table_id().index(),
+            location.archetype_id.index(),
+            archetype.entity_table_row(location.index),
+        )
+    }
+
     /// Returns an [`Iterator`] over the query results for the given [`World`].
     ///
     /// # Safety
@@ -856,6 +1165,64 @@ impl<Q: WorldQuery, F: WorldQuery> QueryState<Q, F> {
         }
     }
 
+    /// Runs `func` on each query result in parallel, in batches of entities sorted by [`Entity`]
+    /// ID.
+    ///
+    /// Which items end up in a batch, and the order of the items within it, only depends on the
+    /// IDs of the matching entities, see [`EntityOrder::Deterministic`](crate::entity::EntityOrder::Deterministic).
+    ///
+    /// This can only be called for read-only queries, see [`Self::par_for_each_sorted_mut`] for
+    /// write-queries.
+    ///
+    /// # Panics
+    /// The [`ComputeTaskPool`] is not initialized. If using this from a query that is being
+    /// initialized and run from the ECS scheduler, this should never panic.
+    #[inline]
+    pub fn par_for_each_sorted<'w, FN: Fn(ROQueryItem<'w, Q>) + Send + Sync + Clone>(
+        &mut self,
+        world: &'w World,
+        batch_size: usize,
+        func: FN,
+    ) {
+        // SAFETY: query is read only
+        unsafe {
+            self.update_archetypes(world);
+            self.as_readonly().par_for_each_sorted_unchecked_manual(
+                world,
+                batch_size,
+                func,
+                world.last_change_tick(),
+                world.read_change_tick(),
+            );
+        }
+    }
+
+    /// Runs `func` on each query result in parallel, in batches of entities sorted by [`Entity`]
+    /// ID. See [`Self::par_for_each_sorted`].
+    ///
+    /// # Panics
+    /// The [`ComputeTaskPool`] is not initialized. If using this from a query that is being
+    /// initialized and run from the ECS scheduler, this should never panic.
+    #[inline]
+    pub fn par_for_each_sorted_mut<'w, FN: Fn(QueryItem<'w, Q>) + Send + Sync + Clone>(
+        &mut self,
+        world: &'w mut World,
+        batch_size: usize,
+        func: FN,
+    ) {
+        // SAFETY: query has unique world access
+        unsafe {
+            self.update_archetypes(world);
+            self.par_for_each_sorted_unchecked_manual(
+                world,
+                batch_size,
+                func,
+                world.last_change_tick(),
+                world.read_change_tick(),
+            );
+        }
+    }
+
     /// Runs `func` on each query result in parallel.
     ///
     /// This can only be called for read-only queries.
@@ -1066,6 +1433,67 @@ impl<Q: WorldQuery, F: WorldQuery> QueryState<Q, F> {
         });
     }
 
+    /// Runs `func` on each query result in parallel for the given [`World`], in batches of
+    /// entities sorted by [`Entity`] ID, where the last change and the current change tick are
+    /// given.
+    ///
+    /// # Panics
+    /// The [`ComputeTaskPool`] is not initialized. If using this from a query that is being
+    /// initialized and run from the ECS scheduler, this should never panic.
+    ///
+    /// # Safety
+    ///
+    /// This does not check for mutable query correctness. To be safe, make sure mutable queries
+    /// have unique access to the components they query.
+    /// This does not validate that `world.id()` matches `self.world_id`. Calling this on a `world`
+    /// with a mismatched [`WorldId`] is unsound.
+    pub(crate) unsafe fn par_for_each_sorted_unchecked_manual<
+        'w,
+        FN: Fn(QueryItem<'w, Q>) + Send + Sync + Clone,
+    >(
+        &self,
+        world: &'w World,
+        batch_size: usize,
+        func: FN,
+        last_change_tick: u32,
+        change_tick: u32,
+    ) {
+        let mut entities: Vec<Entity> = self
+            .matched_archetype_ids
+            .iter()
+            .flat_map(|id| world.archetypes[*id].entities().iter().copied())
+            .collect();
+        entities.sort_unstable_by_key(|entity| entity.id());
+
+        ComputeTaskPool::get().scope(|scope| {
+            for batch in entities.chunks(batch_size) {
+                let func = func.clone();
+                let task = async move {
+                    for &entity in batch {
+                        // Entities rejected by the filter, e.g. `Changed<T>`, are skipped.
+                        if let Ok(item) =
+                            self.get_unchecked_manual(world, entity, last_change_tick, change_tick)
+                        {
+                            func(item);
+                        }
+                    }
+                };
+
+                #[cfg(feature = "trace")]
+                let span = bevy_utils::tracing::info_span!(
+                    "par_for_each_sorted",
+                    query = std::any::type_name::<Q>(),
+                    filter = std::any::type_name::<F>(),
+                    count = batch.len(),
+                );
+                #[cfg(feature = "trace")]
+                let task = task.instrument(span);
+
+                scope.spawn(task);
+            }
+        });
+    }
+
     /// Returns a single immutable query result when there is exactly one entity matching
     /// the query.
     ///
@@ -0,0 +1,568 @@
+//! Types for declaring typed relations between [entities](Entity).
+//!
+//! A relation is a directed edge from a *source* entity to a *target* entity, tagged with a
+//! [`RelationKind`]. Edges are stored as regular components on both endpoints: the source holds
+//! a [`Relation<K>`] listing its targets, and the target holds a [`RelationSources<K>`] listing
+//! the entities that relate to it. This means relations can be queried from either side and
+//! combined with the usual [`Query`](crate::system::Query) filters, such as
+//! `With<Relation<K>>` or `Changed<RelationSources<K>>`.
+//!
+//! Both sides are kept in sync by [`World::relate`] and [`World::unrelate`] (and the matching
+//! [`EntityMut`](crate::world::EntityMut) and [`EntityCommands`](crate::system::EntityCommands)
+//! methods), and by [component hooks](crate::component::ComponentHooks) when either component is
+//! removed directly. When an entity is despawned, every edge it takes part in is cleaned up
+//! according to [`RelationKind::DESPAWN_POLICY`], so a relation never points at a despawned entity.
+//!
+//! ```
+//! # use bevy_ecs::prelude::*;
+//! # use bevy_ecs::relation::DespawnPolicy;
+//! struct InInventory;
+//!
+//! impl RelationKind for InInventory {
+//!     // Items are destroyed along with the container holding them.
+//!     const DESPAWN_POLICY: DespawnPolicy = DespawnPolicy::DespawnSources;
+//! }
+//!
+//! let mut world = World::new();
+//! let chest = world.spawn().id();
+//! let sword = world.spawn().relate::<InInventory>(chest).id();
+//!
+//! assert_eq!(world.get::<Relation<InInventory>>(sword).unwrap().targets(), &[chest]);
+//! assert_eq!(world.get::<RelationSources<InInventory>>(chest).unwrap().sources(), &[sword]);
+//!
+//! world.despawn(chest);
+//! assert!(world.get_entity(sword).is_none());
+//! ```
+
+use crate::{
+    component::{Component, ComponentId, TableStorage},
+    entity::{Entity, EntityMap, MapEntities, MapEntitiesError},
+    query::IncludeDisabled,
+    storage::SparseSet,
+    world::{DeferredWorld, World},
+};
+use bevy_utils::HashSet;
+use std::{fmt, marker::PhantomData};
+
+/// A marker type describing a kind of relation between entities.
+///
+/// See the [module level documentation](crate::relation) for how relations are stored.
+pub trait RelationKind: Send + Sync + 'static {
+    /// Decides what happens to the sources of an edge of this kind when its target is despawned.
+    ///
+    /// Despawning the source of an edge always just removes the edge.
+    const DESPAWN_POLICY: DespawnPolicy = DespawnPolicy::RemoveEdge;
+}
+
+/// What to do with the sources of a relation when its target is despawned.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub enum DespawnPolicy {
+    /// Remove the edge from each source, leaving the sources otherwise untouched.
+    #[default]
+    RemoveEdge,
+    /// Despawn every source of the relation. This cascades through the sources' own relations.
+    DespawnSources,
+    /// Panic if the target still has any sources when it is despawned.
+    Panic,
+}
+
+/// The outgoing edges of a [`RelationKind`] `K`, stored on the source entity.
+///
+/// This component can only be created and modified through [`World::relate`] and
+/// [`World::unrelate`], which keep it in sync with the [`RelationSources<K>`] of each target.
+/// Removing it directly removes all the outgoing edges of the entity.
+pub struct Relation<K: RelationKind> {
+    targets: Vec<Entity>,
+    marker: PhantomData<K>,
+}
+
+impl<K: RelationKind> Component for Relation<K> {
+    type Storage = TableStorage;
+}
+
+impl<K: RelationKind> Relation<K> {
+    /// The entities this entity relates to, in the order the edges were added.
+    #[inline]
+    pub fn targets(&self) -> &[Entity] {
+        &self.targets
+    }
+
+    /// Returns `true` if there is an edge from this entity to `target`.
+    #[inline]
+    pub fn contains(&self, target: Entity) -> bool {
+        self.targets.contains(&target)
+    }
+}
+
+impl<K: RelationKind> fmt::Debug for Relation<K> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple(&format!("Relation<{}>", std::any::type_name::<K>()))
+            .field(&self.targets)
+            .finish()
+    }
+}
+
+impl<K: RelationKind> MapEntities for Relation<K> {
+    fn map_entities(&mut self, entity_map: &EntityMap) -> Result<(), MapEntitiesError> {
+        for target in &mut self.targets {
+            *target = entity_map.get(*target)?;
+        }
+        Ok(())
+    }
+}
+
+/// The incoming edges of a [`RelationKind`] `K`, stored on the target entity.
+///
+/// This is the inverse of [`Relation<K>`] and is maintained automatically alongside it.
+/// Removing it directly removes all the incoming edges of the entity.
+pub struct RelationSources<K: RelationKind> {
+    sources: Vec<Entity>,
+    marker: PhantomData<K>,
+}
+
+impl<K: RelationKind> Component for RelationSources<K> {
+    type Storage = TableStorage;
+}
+
+impl<K: RelationKind> RelationSources<K> {
+    /// The entities relating to this entity, in the order the edges were added.
+    #[inline]
+    pub fn sources(&self) -> &[Entity] {
+        &self.sources
+    }
+
+    /// Returns `true` if there is an edge from `source` to this entity.
+    #[inline]
+    pub fn contains(&self, source: Entity) -> bool {
+        self.sources.contains(&source)
+    }
+}
+
+impl<K: RelationKind> fmt::Debug for RelationSources<K> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple(&format!("RelationSources<{}>", std::any::type_name::<K>()))
+            .field(&self.sources)
+            .finish()
+    }
+}
+
+impl<K: RelationKind> MapEntities for RelationSources<K> {
+    fn map_entities(&mut self, entity_map: &EntityMap) -> Result<(), MapEntitiesError> {
+        for source in &mut self.sources {
+            *source = entity_map.get(*source)?;
+        }
+        Ok(())
+    }
+}
+
+/// Applies the [`DespawnPolicy`] of a relation kind to an entity about to be despawned.
+type DespawnHandler = fn(&mut World, Entity);
+
+/// Removes the edges of a relation kind pointing at entities that were freed without running
+/// their `on_remove` hooks.
+type FreedHandler = fn(&mut World, &HashSet<Entity>);
+
+/// Stores the despawn cleanup for every relation component registered in a [`World`].
+#[derive(Default)]
+pub struct Relations {
+    despawn_handlers: SparseSet<ComponentId, Option<DespawnHandler>>,
+    freed_handlers: Vec<FreedHandler>,
+}
+
+impl Relations {
+    /// Returns `true` if no relation kinds have been registered yet.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.despawn_handlers.is_empty()
+    }
+
+    /// Returns `true` if `component_id` is a [`Relation`] or [`RelationSources`] component.
+    #[inline]
+    pub fn contains(&self, component_id: ComponentId) -> bool {
+        self.despawn_handlers.contains(component_id)
+    }
+
+    #[inline]
+    pub(crate) fn despawn_handler(&self, component_id: ComponentId) -> Option<DespawnHandler> {
+        self.despawn_handlers.get(component_id).copied().flatten()
+    }
+}
+
+/// The edges stored in a [`Relation`] or a [`RelationSources`].
+trait Edges: Component {
+    fn edges(&self) -> &Vec<Entity>;
+    fn edges_mut(&mut self) -> &mut Vec<Entity>;
+}
+
+impl<K: RelationKind> Edges for Relation<K> {
+    fn edges(&self) -> &Vec<Entity> {
+        &self.targets
+    }
+
+    fn edges_mut(&mut self) -> &mut Vec<Entity> {
+        &mut self.targets
+    }
+}
+
+impl<K: RelationKind> Edges for RelationSources<K> {
+    fn edges(&self) -> &Vec<Entity> {
+        &self.sources
+    }
+
+    fn edges_mut(&mut self) -> &mut Vec<Entity> {
+        &mut self.sources
+    }
+}
+
+/// Registers the components of the relation kind `K` in `world`.
+///
+/// The `on_remove` hooks of both components keep the other side of the edges in sync, whether
+/// the component is removed directly, through [`World::unrelate`] or by despawning its entity.
+pub(crate) fn init_relation<K: RelationKind>(world: &mut World) {
+    let relation_id = world.init_component::<Relation<K>>();
+    if world.relations.contains(relation_id) {
+        return;
+    }
+    let sources_id = world.init_component::<RelationSources<K>>();
+    world
+        .register_component_hooks::<Relation<K>>()
+        .on_remove(remove_opposite_edges::<Relation<K>, RelationSources<K>>);
+    world
+        .register_component_hooks::<RelationSources<K>>()
+        .on_remove(remove_opposite_edges::<RelationSources<K>, Relation<K>>);
+    let handlers = &mut world.relations.despawn_handlers;
+    handlers.insert(relation_id, None);
+    handlers.insert(sources_id, Some(despawn_target::<K>));
+    world.relations.freed_handlers.push(remove_freed_edges::<K>);
+}
+
+/// Adds an edge of kind `K` from `source` to `target`. Returns `false` if it already existed.
+///
+/// # Panics
+///
+/// Panics if either entity does not exist.
+pub(crate) fn relate<K: RelationKind>(world: &mut World, source: Entity, target: Entity) -> bool {
+    assert!(
+        world.get_entity(target).is_some(),
+        "Could not relate {:?} to {:?} because the target doesn't exist in this World.",
+        source,
+        target
+    );
+    init_relation::<K>(world);
+
+    let mut source_mut = world.entity_mut(source);
+    if let Some(mut relation) = source_mut.get_mut::<Relation<K>>() {
+        if relation.contains(target) {
+            return false;
+        }
+        relation.targets.push(target);
+    } else {
+        source_mut.insert(Relation::<K> {
+            targets: vec![target],
+            marker: PhantomData,
+        });
+    }
+
+    let mut target_mut = world.entity_mut(target);
+    if let Some(mut sources) = target_mut.get_mut::<RelationSources<K>>() {
+        sources.sources.push(source);
+    } else {
+        target_mut.insert(RelationSources::<K> {
+            sources: vec![source],
+            marker: PhantomData,
+        });
+    }
+    true
+}
+
+/// Removes the edge of kind `K` from `source` to `target`. Returns `false` if there was none.
+pub(crate) fn unrelate<K: RelationKind>(world: &mut World, source: Entity, target: Entity) -> bool {
+    let removed = remove_edge::<Relation<K>>(world, source, target);
+    remove_edge::<RelationSources<K>>(world, target, source);
+    removed
+}
+
+/// Removes `other` from the edges `C` of `entity`, dropping the component once empty.
+fn remove_edge<C: Edges>(world: &mut World, entity: Entity, other: Entity) -> bool {
+    let mut entity_mut = match world.get_entity_mut(entity) {
+        Some(entity_mut) => entity_mut,
+        None => return false,
+    };
+    let now_empty = match entity_mut.get_mut::<C>() {
+        Some(mut edges) => match edges.edges().iter().position(|&e| e == other) {
+            Some(index) => {
+                edges.edges_mut().remove(index);
+                edges.edges().is_empty()
+            }
+            None => return false,
+        },
+        None => return false,
+    };
+    if now_empty {
+        entity_mut.remove::<C>();
+    }
+    true
+}
+
+/// The `on_remove` hook of the edges `C`, which removes `entity` from the opposite edges `O` of
+/// each entity it is connected to.
+fn remove_opposite_edges<C: Edges, O: Edges>(
+    mut world: DeferredWorld,
+    entity: Entity,
+    _: ComponentId,
+) {
+    let others = world.get::<C>(entity).unwrap().edges().clone();
+    for other in others {
+        let mut edges = match world.get_mut::<O>(other) {
+            Some(edges) => edges,
+            None => continue,
+        };
+        if let Some(index) = edges.edges().iter().position(|&e| e == entity) {
+            edges.edges_mut().remove(index);
+            if edges.edges().is_empty() {
+                world.commands().add(move |world: &mut World| {
+                    // An edge may have been added back before the command was applied
+                    if let Some(mut other_mut) = world.get_entity_mut(other) {
+                        if matches!(other_mut.get::<O>(), Some(edges) if edges.edges().is_empty()) {
+                            other_mut.remove::<O>();
+                        }
+                    }
+                });
+            }
+        }
+    }
+}
+
+/// Removes every edge pointing at one of the `freed` entities, which were freed without running
+/// the `on_remove` hooks that keep both sides of the edges in sync, such as when rolling back to a
+/// [`WorldSnapshot`](crate::rollback::WorldSnapshot).
+pub(crate) fn remove_edges_to(world: &mut World, freed: &HashSet<Entity>) {
+    for handler in world.relations.freed_handlers.clone() {
+        handler(world, freed);
+    }
+}
+
+fn remove_freed_edges<K: RelationKind>(world: &mut World, freed: &HashSet<Entity>) {
+    retain_edges::<Relation<K>>(world, freed);
+    retain_edges::<RelationSources<K>>(world, freed);
+}
+
+/// Removes the `freed` entities from the edges `C` of every entity, dropping the component of
+/// the entities left without edges.
+fn retain_edges<C: Edges>(world: &mut World, freed: &HashSet<Entity>) {
+    let mut emptied = Vec::new();
+    let mut query = world.query_filtered::<(Entity, &mut C), IncludeDisabled>();
+    for (entity, mut edges) in query.iter_mut(world) {
+        if edges.edges().iter().any(|other| freed.contains(other)) {
+            edges.edges_mut().retain(|other| !freed.contains(other));
+            if edges.edges().is_empty() {
+                emptied.push(entity);
+            }
+        }
+    }
+    for entity in emptied {
+        world.entity_mut(entity).remove::<C>();
+    }
+}
+
+/// Called when an entity holding a [`RelationSources<K>`] is despawned, to apply
+/// [`RelationKind::DESPAWN_POLICY`]. The edges themselves are removed by the `on_remove` hooks.
+fn despawn_target<K: RelationKind>(world: &mut World, entity: Entity) {
+    let sources: Vec<_> = match world.get::<RelationSources<K>>(entity) {
+        Some(sources) => sources
+            .sources
+            .iter()
+            .copied()
+            // Self-edges are removed along with the entity.
+            .filter(|&source| source != entity)
+            .collect(),
+        None => return,
+    };
+    if sources.is_empty() {
+        return;
+    }
+    match K::DESPAWN_POLICY {
+        DespawnPolicy::RemoveEdge => {}
+        DespawnPolicy::Panic => panic!(
+            "Entity {:?} was despawned while it was still the target of {:?}.",
+            entity,
+            world.get::<RelationSources<K>>(entity).unwrap()
+        ),
+        DespawnPolicy::DespawnSources => {
+            // Removing the edges first keeps a cycle of edges from despawning this entity again.
+            world.entity_mut(entity).remove::<RelationSources<K>>();
+            for source in sources {
+                world.despawn(source);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DespawnPolicy, Relation, RelationKind, RelationSources};
+    use crate::{self as bevy_ecs, component::Component, query::With, world::World};
+
+    struct Targeting;
+    impl RelationKind for Targeting {}
+
+    struct ChildOf;
+    impl RelationKind for ChildOf {
+        const DESPAWN_POLICY: DespawnPolicy = DespawnPolicy::DespawnSources;
+    }
+
+    struct Anchored;
+    impl RelationKind for Anchored {
+        const DESPAWN_POLICY: DespawnPolicy = DespawnPolicy::Panic;
+    }
+
+    #[derive(Component)]
+    struct A;
+
+    #[test]
+    fn relate_is_visible_from_both_sides() {
+        let mut world = World::new();
+        let target = world.spawn().id();
+        let a = world.spawn().insert(A).relate::<Targeting>(target).id();
+        let b = world.spawn().relate::<Targeting>(target).id();
+
+        assert!(!world.relate::<Targeting>(a, target));
+        assert_eq!(
+            world.get::<Relation<Targeting>>(a).unwrap().targets(),
+            &[target]
+        );
+        assert_eq!(
+            world
+                .get::<RelationSources<Targeting>>(target)
+                .unwrap()
+                .sources(),
+            &[a, b]
+        );
+
+        let mut query = world.query_filtered::<&Relation<Targeting>, With<A>>();
+        assert_eq!(query.iter(&world).count(), 1);
+    }
+
+    #[test]
+    fn unrelate_removes_empty_components() {
+        let mut world = World::new();
+        let target = world.spawn().id();
+        let source = world.spawn().relate::<Targeting>(target).id();
+
+        assert!(world.unrelate::<Targeting>(source, target));
+        assert!(!world.unrelate::<Targeting>(source, target));
+        assert!(world.get::<Relation<Targeting>>(source).is_none());
+        assert!(world.get::<RelationSources<Targeting>>(target).is_none());
+    }
+
+    #[test]
+    fn remove_component_removes_edges() {
+        let mut world = World::new();
+        let target = world.spawn().id();
+        let other = world.spawn().id();
+        let source = world
+            .spawn()
+            .relate::<Targeting>(target)
+            .relate::<Targeting>(other)
+            .id();
+        let other_source = world.spawn().relate::<Targeting>(target).id();
+
+        world.entity_mut(source).remove::<Relation<Targeting>>();
+        assert_eq!(
+            world
+                .get::<RelationSources<Targeting>>(target)
+                .unwrap()
+                .sources(),
+            &[other_source]
+        );
+        assert!(world.get::<RelationSources<Targeting>>(other).is_none());
+
+        world
+            .entity_mut(target)
+            .remove::<RelationSources<Targeting>>();
+        assert!(world.get::<Relation<Targeting>>(other_source).is_none());
+        assert!(world.get_entity(other_source).is_some());
+    }
+
+    #[test]
+    fn despawn_source_removes_edge() {
+        let mut world = World::new();
+        let target = world.spawn().id();
+        let source = world.spawn().relate::<Targeting>(target).id();
+
+        world.despawn(source);
+        assert!(world.get::<RelationSources<Targeting>>(target).is_none());
+    }
+
+    #[test]
+    fn despawn_target_removes_edge() {
+        let mut world = World::new();
+        let target = world.spawn().id();
+        let other = world.spawn().id();
+        let source = world
+            .spawn()
+            .relate::<Targeting>(target)
+            .relate::<Targeting>(other)
+            .id();
+
+        world.despawn(target);
+        assert_eq!(
+            world.get::<Relation<Targeting>>(source).unwrap().targets(),
+            &[other]
+        );
+
+        world.despawn(other);
+        assert!(world.get_entity(source).is_some());
+        assert!(world.get::<Relation<Targeting>>(source).is_none());
+    }
+
+    #[test]
+    fn despawn_target_despawns_sources_recursively() {
+        let mut world = World::new();
+        let root = world.spawn().id();
+        let child = world.spawn().relate::<ChildOf>(root).id();
+        let grandchild = world.spawn().relate::<ChildOf>(child).id();
+        let unrelated = world.spawn().relate::<Targeting>(child).id();
+
+        world.despawn(root);
+        assert!(world.get_entity(child).is_none());
+        assert!(world.get_entity(grandchild).is_none());
+        assert!(world.get::<Relation<Targeting>>(unrelated).is_none());
+    }
+
+    #[test]
+    fn despawn_handles_cycles() {
+        let mut world = World::new();
+        let a = world.spawn().id();
+        let b = world.spawn().relate::<ChildOf>(a).id();
+        world.relate::<ChildOf>(a, b);
+        world.relate::<ChildOf>(a, a);
+
+        assert!(world.despawn(a));
+        assert!(world.get_entity(a).is_none());
+        assert!(world.get_entity(b).is_none());
+        assert_eq!(world.entities().len(), 0);
+    }
+
+    #[test]
+    fn clear_entities_skips_despawn_policies() {
+        let mut world = World::new();
+        let target = world.spawn().id();
+        world.spawn().relate::<Anchored>(target);
+
+        world.clear_entities();
+        assert_eq!(world.entities().len(), 0);
+        let entity = world.spawn().id();
+        assert!(world.get::<RelationSources<Anchored>>(entity).is_none());
+    }
+
+    #[test]
+    #[should_panic]
+    fn despawn_target_panics() {
+        let mut world = World::new();
+        let target = world.spawn().id();
+        world.spawn().relate::<Anchored>(target);
+
+        world.despawn(target);
+    }
+}
@@ -0,0 +1,626 @@
+//! Types for saving the state of a [`World`] and rolling it back later.
+//!
+//! A [`WorldSnapshot`] captures every entity that exists, the allocation state of
+//! [`Entities`](crate::entity::Entities), and clones of the components and resources registered in
+//! the [`RollbackRegistry`]. Restoring it despawns entities spawned since, respawns those
+//! despawned since with the same [`Entity`] IDs, and writes back the registered components and
+//! resources, so that entities allocated afterwards get the same IDs as they did the first time.
+//!
+//! Entities spawned since the snapshot are freed without running `on_remove`
+//! [component hooks](crate::component::ComponentHooks) or the
+//! [`DespawnPolicy`](crate::relation::DespawnPolicy) of relations, since the snapshot already
+//! describes what the rest of the world should look like. The [relation](crate::relation) edges
+//! pointing at them are removed afterwards, and so are the references held by other components
+//! through the cleanups added with [`RollbackRegistry::register_cleanup`]. Internal entities,
+//! such as those of [registered systems](World::register_system), are neither captured nor
+//! restored.
+//!
+//! A [`SnapshotBuffer`] keeps the last few snapshots in a ring buffer, indexed by frame, which is
+//! what rollback netcode needs to re-simulate frames once late inputs arrive.
+//!
+//! ```
+//! # use bevy_ecs::prelude::*;
+//! # use bevy_ecs::rollback::{RollbackRegistry, SnapshotBuffer};
+//! #[derive(Component, Clone)]
+//! struct Position(i32);
+//!
+//! let mut world = World::new();
+//! world.init_resource::<RollbackRegistry>();
+//! world.resource_mut::<RollbackRegistry>().register_component::<Position>();
+//! world.insert_resource(SnapshotBuffer::new(8));
+//!
+//! let player = world.spawn().insert(Position(0)).id();
+//! world.save_snapshot(0);
+//!
+//! world.get_mut::<Position>(player).unwrap().0 = 5;
+//! let projectile = world.spawn().insert(Position(1)).id();
+//!
+//! world.rollback_to(0);
+//! assert_eq!(world.get::<Position>(player).unwrap().0, 0);
+//! assert!(world.get_entity(projectile).is_none());
+//! // Re-simulating spawns the projectile with the same id.
+//! assert_eq!(world.spawn().id(), projectile);
+//! ```
+
+use crate::{
+    self as bevy_ecs,
+    archetype::Archetype,
+    component::Component,
+    entity::{EntitiesSnapshot, Entity},
+    query::{IncludeDisabled, With},
+    relation,
+    system::{RegisteredSystem, Resource},
+    world::World,
+};
+use bevy_utils::HashSet;
+use std::{any::TypeId, collections::VecDeque};
+
+/// The components and resources that are captured by a [`WorldSnapshot`].
+///
+/// Only types registered here are saved and restored; every other component and resource is left
+/// untouched by a rollback, except that entities spawned after the snapshot are despawned and
+/// entities despawned after the snapshot are respawned with the registered components only.
+#[derive(Resource, Default)]
+pub struct RollbackRegistry {
+    components: Vec<(TypeId, SnapshotFn)>,
+    resources: Vec<(TypeId, SnapshotFn)>,
+    cleanups: Vec<CleanupFn>,
+}
+
+type SnapshotFn = fn(&mut World) -> Box<dyn SnapshotData>;
+type CleanupFn = fn(&mut World, &HashSet<Entity>);
+
+impl RollbackRegistry {
+    /// Registers the [`Component`] `T` to be captured by snapshots.
+    pub fn register_component<T: Component + Clone>(&mut self) -> &mut Self {
+        if !self
+            .components
+            .iter()
+            .any(|(id, _)| *id == TypeId::of::<T>())
+        {
+            self.components
+                .push((TypeId::of::<T>(), snapshot_component::<T>));
+        }
+        self
+    }
+
+    /// Registers the [`Resource`] `T` to be captured by snapshots.
+    pub fn register_resource<T: Resource + Clone>(&mut self) -> &mut Self {
+        if !self
+            .resources
+            .iter()
+            .any(|(id, _)| *id == TypeId::of::<T>())
+        {
+            self.resources
+                .push((TypeId::of::<T>(), snapshot_resource::<T>));
+        }
+        self
+    }
+
+    /// Registers a `cleanup` that runs at the end of each rollback with the entities it freed.
+    ///
+    /// Those entities are freed without running their `on_remove`
+    /// [component hooks](crate::component::ComponentHooks), so components that are not captured
+    /// by snapshots but refer to other entities should be cleaned up here, the way relations are.
+    pub fn register_cleanup(&mut self, cleanup: fn(&mut World, &HashSet<Entity>)) -> &mut Self {
+        self.cleanups.push(cleanup);
+        self
+    }
+}
+
+/// The saved values of one registered type.
+trait SnapshotData: Send + Sync {
+    fn restore(&self, world: &mut World);
+}
+
+struct ComponentSnapshot<T>(Vec<(Entity, T)>);
+
+fn snapshot_component<T: Component + Clone>(world: &mut World) -> Box<dyn SnapshotData> {
+    let values = world
+        .query_filtered::<(Entity, &T), IncludeDisabled>()
+        .iter(world)
+        .map(|(entity, value)| (entity, value.clone()))
+        .collect();
+    Box::new(ComponentSnapshot(values))
+}
+
+impl<T: Component + Clone> SnapshotData for ComponentSnapshot<T> {
+    fn restore(&self, world: &mut World) {
+        let saved = self
+            .0
+            .iter()
+            .map(|(entity, _)| *entity)
+            .collect::<HashSet<_>>();
+        let added = world
+            .query_filtered::<Entity, (With<T>, IncludeDisabled)>()
+            .iter(world)
+            .filter(|entity| !saved.contains(entity))
+            .collect::<Vec<_>>();
+        for entity in added {
+            world.entity_mut(entity).remove::<T>();
+        }
+        for (entity, value) in &self.0 {
+            let mut entity = world.entity_mut(*entity);
+            match entity.get_mut::<T>() {
+                Some(mut current) => *current = value.clone(),
+                None => {
+                    entity.insert(value.clone());
+                }
+            }
+        }
+    }
+}
+
+struct ResourceSnapshot<T>(Option<T>);
+
+fn snapshot_resource<T: Resource + Clone>(world: &mut World) -> Box<dyn SnapshotData> {
+    Box::new(ResourceSnapshot(world.get_resource::<T>().cloned()))
+}
+
+impl<T: Resource + Clone> SnapshotData for ResourceSnapshot<T> {
+    fn restore(&self, world: &mut World) {
+        match (&self.0, world.get_resource_mut::<T>()) {
+            (Some(value), Some(mut current)) => *current = value.clone(),
+            (Some(value), None) => world.insert_resource(value.clone()),
+            (None, Some(_)) => {
+                world.remove_resource::<T>();
+            }
+            (None, None) => {}
+        }
+    }
+}
+
+/// The state of a [`World`] at some point in time, see the [module docs](crate::rollback).
+pub struct WorldSnapshot {
+    entities: Vec<Entity>,
+    allocator: EntitiesSnapshot,
+    data: Vec<Box<dyn SnapshotData>>,
+}
+
+impl WorldSnapshot {
+    /// Captures the entities of `world`, except internal ones, and the components and resources
+    /// registered in its [`RollbackRegistry`], if any.
+    pub fn take(world: &mut World) -> Self {
+        world.flush();
+        let entities = alive_entities(world);
+        let allocator = world.entities().allocator_snapshot();
+        let snapshot_fns = world
+            .get_resource::<RollbackRegistry>()
+            .map(|registry| {
+                registry
+                    .components
+                    .iter()
+                    .chain(&registry.resources)
+                    .map(|(_, snapshot_fn)| *snapshot_fn)
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+        let data = snapshot_fns
+            .into_iter()
+            .map(|snapshot_fn| snapshot_fn(world))
+            .collect();
+        WorldSnapshot {
+            entities,
+            allocator,
+            data,
+        }
+    }
+
+    /// Returns the entities that existed when the snapshot was taken.
+    pub fn entities(&self) -> &[Entity] {
+        &self.entities
+    }
+
+    /// Restores `world` to the state captured by this snapshot.
+    ///
+    /// Restored components and resources are written at the current change tick, so they are
+    /// detected as changed (or added, if they did not exist) by the systems that re-simulate the
+    /// following frames. Removed ones show up in
+    /// [`RemovedComponents`](crate::system::RemovedComponents).
+    pub fn restore(&self, world: &mut World) {
+        world.flush();
+        let saved = self.entities.iter().copied().collect::<HashSet<_>>();
+        let mut freed = despawn_unsaved(world, &saved);
+        for &entity in &self.entities {
+            if world.get_entity(entity).is_none() && world.get_or_spawn(entity).is_none() {
+                panic!(
+                    "{:?} cannot be restored, its id was reused by an internal entity, such as \
+                    a registered system, after the snapshot was taken",
+                    entity
+                );
+            }
+        }
+        for data in &self.data {
+            data.restore(world);
+        }
+        // Component hooks may have spawned entities while restoring.
+        freed.extend(despawn_unsaved(world, &saved));
+        relation::remove_edges_to(world, &freed);
+        let cleanups = world
+            .get_resource::<RollbackRegistry>()
+            .map(|registry| registry.cleanups.clone())
+            .unwrap_or_default();
+        for cleanup in cleanups {
+            cleanup(world, &freed);
+        }
+        world.flush();
+        let internal = entities_where(world, is_internal);
+        world.entities.restore_allocator(&self.allocator, &internal);
+    }
+}
+
+/// Returns `true` if the entities of `archetype` are internal to the world, like the entities of
+/// registered systems, which are left alone by rollbacks.
+fn is_internal(world: &World, archetype: &Archetype) -> bool {
+    matches!(
+        world.components().get_id(TypeId::of::<RegisteredSystem>()),
+        Some(id) if archetype.contains(id)
+    )
+}
+
+fn entities_where(world: &World, filter: impl Fn(&World, &Archetype) -> bool) -> Vec<Entity> {
+    world
+        .archetypes()
+        .iter()
+        .filter(|archetype| filter(world, archetype))
+        .flat_map(|archetype| archetype.entities())
+        .copied()
+        .collect()
+}
+
+fn alive_entities(world: &World) -> Vec<Entity> {
+    entities_where(world, |world, archetype| !is_internal(world, archetype))
+}
+
+/// Frees the entities that are not `saved`, returning them.
+fn despawn_unsaved(world: &mut World, saved: &HashSet<Entity>) -> HashSet<Entity> {
+    let freed = alive_entities(world)
+        .into_iter()
+        .filter(|entity| !saved.contains(entity))
+        .collect::<HashSet<_>>();
+    for &entity in &freed {
+        world.despawn_without_hooks(entity);
+    }
+    freed
+}
+
+/// A ring buffer of the last [`WorldSnapshot`]s, indexed by frame.
+///
+/// When it is full, saving a new snapshot discards the oldest one.
+#[derive(Resource)]
+pub struct SnapshotBuffer {
+    snapshots: VecDeque<(u64, WorldSnapshot)>,
+    capacity: usize,
+}
+
+impl SnapshotBuffer {
+    /// Creates an empty buffer holding up to `capacity` snapshots.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is zero.
+    pub fn new(capacity: usize) -> Self {
+        assert!(
+            capacity > 0,
+            "a SnapshotBuffer must hold at least one snapshot"
+        );
+        SnapshotBuffer {
+            snapshots: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    /// Returns the maximum number of snapshots held by this buffer.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns the number of snapshots held by this buffer.
+    pub fn len(&self) -> usize {
+        self.snapshots.len()
+    }
+
+    /// Returns `true` if this buffer holds no snapshots.
+    pub fn is_empty(&self) -> bool {
+        self.snapshots.is_empty()
+    }
+
+    /// Stores the `snapshot` of the given `frame`, replacing any snapshot of the same or a later
+    /// frame, and discarding the oldest snapshot if the buffer is full.
+    pub fn push(&mut self, frame: u64, snapshot: WorldSnapshot) {
+        self.discard_from(frame);
+        if self.snapshots.len() == self.capacity {
+            self.snapshots.pop_front();
+        }
+        self.snapshots.push_back((frame, snapshot));
+    }
+
+    /// Returns the snapshot of the given `frame`, if it is still held by this buffer.
+    pub fn get(&self, frame: u64) -> Option<&WorldSnapshot> {
+        self.snapshots
+            .iter()
+            .find(|(snapshot_frame, _)| *snapshot_frame == frame)
+            .map(|(_, snapshot)| snapshot)
+    }
+
+    /// Returns the frames of the snapshots held by this buffer, from oldest to newest.
+    pub fn frames(&self) -> impl Iterator<Item = u64> + '_ {
+        self.snapshots.iter().map(|(frame, _)| *frame)
+    }
+
+    /// Discards the snapshots of `frame` and every later frame.
+    pub fn discard_from(&mut self, frame: u64) {
+        while matches!(self.snapshots.back(), Some((last, _)) if *last >= frame) {
+            self.snapshots.pop_back();
+        }
+    }
+}
+
+impl World {
+    /// Takes a [`WorldSnapshot`] of this world and stores it in the [`SnapshotBuffer`] resource
+    /// as the snapshot of `frame`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the [`SnapshotBuffer`] resource does not exist.
+    pub fn save_snapshot(&mut self, frame: u64) {
+        let snapshot = WorldSnapshot::take(self);
+        self.get_resource_mut::<SnapshotBuffer>()
+            .expect("saving a snapshot requires the SnapshotBuffer resource")
+            .push(frame, snapshot);
+    }
+
+    /// Restores this world to the snapshot of `frame` stored in the [`SnapshotBuffer`] resource,
+    /// and discards the snapshots of later frames, which are about to be re-simulated.
+    ///
+    /// Returns `false` if there is no snapshot of `frame`, e.g. because it was discarded to make
+    /// room for newer snapshots.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the [`SnapshotBuffer`] resource does not exist.
+    pub fn rollback_to(&mut self, frame: u64) -> bool {
+        let mut buffer = self
+            .remove_resource::<SnapshotBuffer>()
+            .expect("rolling back requires the SnapshotBuffer resource");
+        let restored = match buffer.get(frame) {
+            Some(snapshot) => {
+                snapshot.restore(self);
+                buffer.discard_from(frame + 1);
+                true
+            }
+            None => false,
+        };
+        self.insert_resource(buffer);
+        restored
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+
+    #[derive(Component, Clone, Debug, PartialEq)]
+    struct Position(i32);
+
+    #[derive(Component, Clone, Debug, PartialEq)]
+    #[component(storage = "SparseSet")]
+    struct Marker;
+
+    #[derive(Component, Debug, PartialEq)]
+    struct NotSaved(i32);
+
+    #[derive(Resource, Clone, Debug, PartialEq)]
+    struct Frame(u64);
+
+    fn world() -> World {
+        let mut world = World::new();
+        world.init_resource::<RollbackRegistry>();
+        world
+            .resource_mut::<RollbackRegistry>()
+            .register_component::<Position>()
+            .register_component::<Marker>()
+            .register_resource::<Frame>();
+        world.insert_resource(SnapshotBuffer::new(2));
+        world
+    }
+
+    #[test]
+    fn restore_components_and_resources() {
+        let mut world = world();
+        world.insert_resource(Frame(0));
+        let a = world.spawn().insert(Position(0)).insert(NotSaved(0)).id();
+        let b = world.spawn().insert_bundle((Position(10), Marker)).id();
+        world.save_snapshot(0);
+
+        world.get_mut::<Position>(a).unwrap().0 = 1;
+        world.get_mut::<NotSaved>(a).unwrap().0 = 1;
+        world.entity_mut(a).insert(Marker);
+        world.entity_mut(b).remove::<Marker>();
+        world.resource_mut::<Frame>().0 = 1;
+        world.clear_trackers();
+
+        assert!(world.rollback_to(0));
+        assert_eq!(world.get::<Position>(a), Some(&Position(0)));
+        assert_eq!(world.get::<NotSaved>(a), Some(&NotSaved(1)));
+        assert!(world.get::<Marker>(a).is_none());
+        assert_eq!(world.get::<Marker>(b), Some(&Marker));
+        assert_eq!(world.resource::<Frame>(), &Frame(0));
+
+        let changed = world
+            .query_filtered::<Entity, Changed<Position>>()
+            .iter(&world)
+            .count();
+        assert_eq!(changed, 2);
+        let added = world
+            .query_filtered::<Entity, Added<Marker>>()
+            .iter(&world)
+            .collect::<Vec<_>>();
+        assert_eq!(added, vec![b]);
+        assert!(world.removed::<Marker>().any(|entity| entity == a));
+    }
+
+    #[test]
+    fn restore_entity_allocation() {
+        let mut world = world();
+        let a = world.spawn().insert(Position(0)).id();
+        let b = world.spawn().insert(Position(1)).id();
+        world.despawn(b);
+        world.save_snapshot(0);
+
+        let c = world.spawn().insert(Position(2)).id();
+        let d = world.spawn().id();
+        world.despawn(a);
+
+        assert!(world.rollback_to(0));
+        assert_eq!(world.get::<Position>(a), Some(&Position(0)));
+        assert!(world.get_entity(c).is_none());
+        assert!(world.get_entity(d).is_none());
+        assert_eq!(world.entities().len(), 1);
+        assert_eq!(world.spawn().id(), c);
+        assert_eq!(world.spawn().id(), d);
+    }
+
+    #[test]
+    fn restore_skips_hooks() {
+        #[derive(Component)]
+        struct Hooked;
+
+        #[derive(Resource, Default)]
+        struct Removed(usize);
+
+        let mut world = world();
+        world.init_resource::<Removed>();
+        world
+            .register_component_hooks::<Hooked>()
+            .on_remove(|mut world, _, _| world.resource_mut::<Removed>().0 += 1);
+        world.save_snapshot(0);
+
+        let a = world.spawn().insert(Hooked).id();
+        assert!(world.rollback_to(0));
+        assert!(world.get_entity(a).is_none());
+        assert_eq!(world.resource::<Removed>().0, 0);
+    }
+
+    #[test]
+    fn restore_preserves_registered_systems() {
+        let mut world = world();
+        world.insert_resource(Frame(0));
+        let increment = |mut frame: ResMut<Frame>| frame.0 += 1;
+        let before = world.register_system(increment);
+        world.spawn().insert(Position(0));
+        world.save_snapshot(0);
+
+        let after = world.register_system(increment);
+        let spawned = world.spawn().insert(Position(1)).id();
+
+        assert!(world.rollback_to(0));
+        assert!(world.get_entity(spawned).is_none());
+        world.run_system(before).unwrap();
+        world.run_system(after).unwrap();
+        assert_eq!(world.resource::<Frame>(), &Frame(2));
+
+        // Entities spawned afterwards don't reuse the ids of registered systems.
+        world.spawn().insert(Position(2));
+        world.spawn().insert(Position(3));
+        world.run_system(before).unwrap();
+        world.run_system(after).unwrap();
+        assert_eq!(world.resource::<Frame>(), &Frame(4));
+    }
+
+    #[test]
+    fn restore_removes_edges_to_freed_entities() {
+        struct Targeting;
+        impl RelationKind for Targeting {}
+
+        struct Ping;
+
+        #[derive(Resource, Default)]
+        struct Pinged(usize);
+
+        let mut world = world();
+        world.init_resource::<Pinged>();
+        let target = world.spawn().insert(Position(0)).id();
+        let source = world.spawn().insert(Position(1)).id();
+        world.save_snapshot(0);
+
+        let spawned = world
+            .spawn()
+            .relate::<Targeting>(target)
+            .observe(|_: In<Trigger<Ping>>, mut pinged: ResMut<Pinged>| pinged.0 += 1)
+            .id();
+        world.relate::<Targeting>(source, spawned);
+        world.relate::<Targeting>(source, target);
+        world.relate::<Targeting>(target, spawned);
+
+        assert!(world.rollback_to(0));
+        assert!(world.get_entity(spawned).is_none());
+        assert_eq!(
+            world
+                .get::<RelationSources<Targeting>>(target)
+                .unwrap()
+                .sources(),
+            &[source]
+        );
+        assert_eq!(
+            world.get::<Relation<Targeting>>(source).unwrap().targets(),
+            &[target]
+        );
+        assert!(world.get::<Relation<Targeting>>(target).is_none());
+
+        // The observers of the freed entity are gone too, even once its id is reused.
+        assert_eq!(world.spawn().id(), spawned);
+        world.trigger(Ping, spawned);
+        assert_eq!(world.resource::<Pinged>().0, 0);
+    }
+
+    #[test]
+    fn restore_runs_cleanups() {
+        #[derive(Component)]
+        struct Follow(Entity);
+
+        fn remove_follows(world: &mut World, freed: &HashSet<Entity>) {
+            let followers = world
+                .query::<(Entity, &Follow)>()
+                .iter(world)
+                .filter(|(_, follow)| freed.contains(&follow.0))
+                .map(|(entity, _)| entity)
+                .collect::<Vec<_>>();
+            for entity in followers {
+                world.entity_mut(entity).remove::<Follow>();
+            }
+        }
+
+        let mut world = world();
+        world
+            .resource_mut::<RollbackRegistry>()
+            .register_cleanup(remove_follows);
+        let follower = world.spawn().insert(Position(0)).id();
+        world.save_snapshot(0);
+
+        let leader = world.spawn().id();
+        world.entity_mut(follower).insert(Follow(leader));
+
+        assert!(world.rollback_to(0));
+        assert!(world.get::<Follow>(follower).is_none());
+    }
+
+    #[test]
+    fn ring_buffer() {
+        let mut world = world();
+        world.insert_resource(Frame(0));
+        for frame in 0..4 {
+            world.resource_mut::<Frame>().0 = frame;
+            world.save_snapshot(frame);
+        }
+        let buffer = world.resource::<SnapshotBuffer>();
+        assert_eq!(buffer.frames().collect::<Vec<_>>(), vec![2, 3]);
+
+        assert!(!world.rollback_to(1));
+        assert!(world.rollback_to(2));
+        assert_eq!(world.resource::<Frame>(), &Frame(2));
+        let buffer = world.resource::<SnapshotBuffer>();
+        assert_eq!(buffer.frames().collect::<Vec<_>>(), vec![2]);
+    }
+}
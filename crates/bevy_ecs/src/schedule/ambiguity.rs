@@ -0,0 +1,246 @@
+use crate::{
+    self as bevy_ecs,
+    component::ComponentId,
+    schedule::{SystemContainer, SystemGraphKind, SystemLabel, SystemLabelId},
+    schedule_v3::{BoxedSystemSet, SystemSet},
+    world::World,
+};
+use bevy_ecs_macros::Resource;
+use bevy_utils::tracing::warn;
+use std::fmt::Write;
+
+/// How a [`SystemStage`](super::SystemStage) or a [`Schedule`](crate::schedule_v3::Schedule)
+/// reacts to execution order ambiguities.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum AmbiguityReportLevel {
+    /// Ambiguities are not reported.
+    Ignore,
+    /// Ambiguities are logged as a warning.
+    #[default]
+    Warn,
+    /// Ambiguities cause the stage or schedule to panic when it is (re)built, which makes
+    /// `App::update` panic.
+    ///
+    /// This is useful to fail CI when an ambiguity is introduced.
+    Error,
+}
+
+/// When this resource is present in the `App`'s `Resources`, each `SystemStage` and
+/// `schedule_v3::Schedule` will report pairs of systems with ambiguous execution order,
+/// according to the configured [`AmbiguityReportLevel`].
+///
+/// Systems that access the same Component or Resource within the same stage
+/// risk an ambiguous order that could result in logic bugs, unless they have an
+/// explicit execution ordering constraint between them.
+///
+/// This occurs because, in the absence of explicit constraints, systems are executed in
+/// an unstable, arbitrary order within each stage that may vary between runs and frames.
+///
+/// Some ambiguities reported by the ambiguity checker may be warranted (to allow two systems to run
+/// without blocking each other) or spurious, as the exact combination of archetypes used may
+/// prevent them from ever conflicting during actual gameplay. You can resolve the warnings produced
+/// by the ambiguity checker by adding `.before` or `.after` to one of the conflicting systems
+/// referencing the other system to force a specific ordering. Ambiguities that are known to be
+/// harmless can be allowed per system, with
+/// [`in_ambiguity_set`](super::ParallelSystemDescriptorCoercion::in_ambiguity_set) in a
+/// `SystemStage`, or with [`ambiguous_with`](crate::schedule_v3::IntoSystemConfig::ambiguous_with)
+/// and [`ambiguous_with_all`](crate::schedule_v3::IntoSystemConfig::ambiguous_with_all) in a
+/// `schedule_v3::Schedule`. Systems can also be allowed to be ambiguous with any other system
+/// here, by label with [`ambiguous_with`](Self::ambiguous_with), by system set with
+/// [`ambiguous_with_set`](Self::ambiguous_with_set), or by crate with
+/// [`ambiguous_with_crate`](Self::ambiguous_with_crate).
+///
+/// The checker may report a system more times than the amount of constraints it would actually need
+/// to have unambiguous order with regards to a group of already-constrained systems.
+///
+/// ```
+/// # use bevy_ecs::prelude::*;
+/// # use bevy_ecs::schedule::{AmbiguityReportLevel, ReportExecutionOrderAmbiguities};
+/// #[derive(Debug, Hash, PartialEq, Eq, Clone, SystemLabel)]
+/// struct Cosmetic;
+///
+/// let mut world = World::new();
+/// world.insert_resource(
+///     ReportExecutionOrderAmbiguities::new(AmbiguityReportLevel::Error)
+///         .ambiguous_with(Cosmetic)
+///         .ambiguous_with_crate("third_party_crate"),
+/// );
+/// ```
+#[derive(Resource, Debug, Clone, Default)]
+pub struct ReportExecutionOrderAmbiguities {
+    /// How ambiguities are reported.
+    pub level: AmbiguityReportLevel,
+    /// Systems with any of these labels are allowed to be ambiguous with any other system in a
+    /// `SystemStage`.
+    pub ambiguous_with_labels: Vec<SystemLabelId>,
+    /// Systems in any of these sets are allowed to be ambiguous with any other system in a
+    /// `schedule_v3::Schedule`.
+    pub ambiguous_with_sets: Vec<BoxedSystemSet>,
+    /// Systems defined in any of these crates are allowed to be ambiguous with any other system.
+    pub ambiguous_with_crates: Vec<String>,
+}
+
+impl ReportExecutionOrderAmbiguities {
+    /// Creates a new configuration that reports ambiguities with the given `level`.
+    pub fn new(level: AmbiguityReportLevel) -> Self {
+        ReportExecutionOrderAmbiguities {
+            level,
+            ..Default::default()
+        }
+    }
+
+    /// Allows systems with the given `label` to be ambiguous with any other system in a
+    /// [`SystemStage`](super::SystemStage).
+    #[must_use]
+    pub fn ambiguous_with(mut self, label: impl SystemLabel) -> Self {
+        self.ambiguous_with_labels.push(label.as_label());
+        self
+    }
+
+    /// Allows systems in the given `set`, directly or through nested sets, to be ambiguous with
+    /// any other system in a [`Schedule`](crate::schedule_v3::Schedule).
+    #[must_use]
+    pub fn ambiguous_with_set(mut self, set: impl SystemSet) -> Self {
+        self.ambiguous_with_sets.push(Box::new(set));
+        self
+    }
+
+    /// Allows systems defined in the crate named `crate_name` to be ambiguous with any other
+    /// system.
+    ///
+    /// The crate of a system is the first segment of its name, e.g. `bevy_render` for
+    /// `bevy_render::view::visibility::check_visibility`.
+    #[must_use]
+    pub fn ambiguous_with_crate(mut self, crate_name: impl Into<String>) -> Self {
+        self.ambiguous_with_crates.push(crate_name.into());
+        self
+    }
+
+    /// Returns `true` if ambiguities involving the system named `name` should not be reported
+    /// because of the crate it is defined in.
+    pub(crate) fn allows_crate_of(&self, name: &str) -> bool {
+        let crate_name = name.split("::").next().unwrap_or_default();
+        self.ambiguous_with_crates
+            .iter()
+            .any(|allowed| allowed == crate_name)
+    }
+
+    /// Returns `true` if ambiguities involving `system` should not be reported.
+    pub(crate) fn allows(&self, system: &impl SystemContainer) -> bool {
+        self.allows_crate_of(&system.name())
+            || system
+                .labels()
+                .iter()
+                .any(|label| self.ambiguous_with_labels.contains(label))
+    }
+}
+
+/// A pair of systems with an ambiguous execution order, as found by a
+/// [`SystemStage`](super::SystemStage) or a [`Schedule`](crate::schedule_v3::Schedule).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SystemOrderAmbiguity {
+    /// How both systems are executed; only systems of the same kind can be ambiguous.
+    pub kind: SystemGraphKind,
+    /// The name of the first system.
+    pub system_a: String,
+    /// The name of the second system.
+    pub system_b: String,
+    /// The components and resources both systems access, where at least one of them requires
+    /// mutable access.
+    ///
+    /// This is empty if either system has exclusive world access.
+    pub conflicts: Vec<ComponentId>,
+}
+
+/// A machine-readable report of the execution order ambiguities of a
+/// [`SystemStage`](super::SystemStage) or a [`Schedule`](crate::schedule_v3::Schedule), see
+/// [`SystemStage::ambiguity_report`](super::SystemStage::ambiguity_report) and
+/// [`Schedule::ambiguity_report`](crate::schedule_v3::Schedule::ambiguity_report).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AmbiguityReport {
+    /// The ambiguous pairs of systems, grouped by [`SystemGraphKind`] in execution order.
+    pub ambiguities: Vec<SystemOrderAmbiguity>,
+}
+
+impl AmbiguityReport {
+    /// Returns `true` if no ambiguities were found.
+    pub fn is_empty(&self) -> bool {
+        self.ambiguities.is_empty()
+    }
+
+    /// Appends the given `ambiguities` between `systems` to the report, skipping those that are
+    /// allowed by `settings`.
+    pub(crate) fn extend(
+        &mut self,
+        systems: &[impl SystemContainer],
+        kind: SystemGraphKind,
+        ambiguities: Vec<(usize, usize, Vec<ComponentId>)>,
+        settings: &ReportExecutionOrderAmbiguities,
+    ) {
+        self.ambiguities.extend(
+            ambiguities
+                .into_iter()
+                .filter(|(index_a, index_b, _)| {
+                    !settings.allows(&systems[*index_a]) && !settings.allows(&systems[*index_b])
+                })
+                .map(|(index_a, index_b, conflicts)| SystemOrderAmbiguity {
+                    kind,
+                    system_a: systems[index_a].name().into_owned(),
+                    system_b: systems[index_b].name().into_owned(),
+                    conflicts,
+                }),
+        );
+    }
+
+    /// Logs or panics with the description of the report, depending on `level`. Does nothing if
+    /// the report is empty.
+    pub(crate) fn report(&self, level: AmbiguityReportLevel, world: &World) {
+        if self.is_empty() {
+            return;
+        }
+        match level {
+            AmbiguityReportLevel::Ignore => {}
+            AmbiguityReportLevel::Warn => warn!("{}", self.describe(world)),
+            AmbiguityReportLevel::Error => panic!("{}", self.describe(world)),
+        }
+    }
+
+    /// Returns a human-readable description of the report, using `world` to name the conflicting
+    /// components and resources.
+    pub fn describe(&self, world: &World) -> String {
+        let mut string = "Execution order ambiguities detected, you might want to \
+                add an explicit dependency relation between some of these systems:\n"
+            .to_owned();
+        let mut kind = None;
+        for ambiguity in &self.ambiguities {
+            if kind != Some(ambiguity.kind) {
+                kind = Some(ambiguity.kind);
+                let header = match ambiguity.kind {
+                    SystemGraphKind::Parallel => "Parallel systems",
+                    SystemGraphKind::ExclusiveAtStart => "Exclusive systems at start of stage",
+                    SystemGraphKind::ExclusiveBeforeCommands => {
+                        "Exclusive systems before commands of stage"
+                    }
+                    SystemGraphKind::ExclusiveAtEnd => "Exclusive systems at end of stage",
+                    SystemGraphKind::Exclusive => "Exclusive systems",
+                };
+                writeln!(string, " * {}:", header).unwrap();
+            }
+            writeln!(
+                string,
+                " -- {:?} and {:?}",
+                ambiguity.system_a, ambiguity.system_b
+            )
+            .unwrap();
+            if !ambiguity.conflicts.is_empty() {
+                let names = ambiguity
+                    .conflicts
+                    .iter()
+                    .map(|id| world.components().get_info(*id).unwrap().name())
+                    .collect::<Vec<_>>();
+                writeln!(string, "    conflicts: {:?}", names).unwrap();
+            }
+        }
+        string
+    }
+}
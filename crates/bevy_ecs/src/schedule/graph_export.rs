@@ -0,0 +1,517 @@
+//! Snapshots of resolved schedules, for debugging system ordering.
+//!
+//! See [`SystemStage::graph`](super::SystemStage::graph) and
+//! [`Schedule::graph`](super::Schedule::graph).
+
+use std::fmt::Write;
+
+/// How a system in a [`StageGraph`] is executed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SystemGraphKind {
+    /// A parallel system.
+    Parallel,
+    /// An exclusive system that runs at the start of the stage.
+    ExclusiveAtStart,
+    /// An exclusive system that runs after parallel systems, but before their command buffers are
+    /// applied.
+    ExclusiveBeforeCommands,
+    /// An exclusive system that runs at the end of the stage.
+    ExclusiveAtEnd,
+    /// An exclusive system of a [`schedule_v3::Schedule`](crate::schedule_v3::Schedule), which
+    /// runs at its position in the dependency graph.
+    Exclusive,
+}
+
+impl SystemGraphKind {
+    /// Returns the `snake_case` name of this kind, as used in the exported formats.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SystemGraphKind::Parallel => "parallel",
+            SystemGraphKind::ExclusiveAtStart => "exclusive_at_start",
+            SystemGraphKind::ExclusiveBeforeCommands => "exclusive_before_commands",
+            SystemGraphKind::ExclusiveAtEnd => "exclusive_at_end",
+            SystemGraphKind::Exclusive => "exclusive",
+        }
+    }
+
+    /// Returns `true` if the system has exclusive world access.
+    pub fn is_exclusive(&self) -> bool {
+        !matches!(self, SystemGraphKind::Parallel)
+    }
+}
+
+/// A system in a [`StageGraph`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SystemGraphNode {
+    /// The name of the system.
+    pub name: String,
+    /// How the system is executed.
+    pub kind: SystemGraphKind,
+    /// The labels (or sets) of the system.
+    pub labels: Vec<String>,
+    /// The labels this system was declared to run before.
+    pub before: Vec<String>,
+    /// The labels this system was declared to run after.
+    pub after: Vec<String>,
+    /// Indices into [`StageGraph::run_criteria`] of everything that decides whether this system
+    /// runs.
+    pub run_criteria: Vec<usize>,
+    /// Indices into [`StageGraph::systems`] of the systems that must complete before this one
+    /// starts.
+    pub dependencies: Vec<usize>,
+}
+
+/// A run criteria (or run condition) in a [`StageGraph`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RunCriteriaGraphNode {
+    /// The name of the run criteria system.
+    pub name: String,
+    /// The label of the run criteria, if any.
+    pub label: Option<String>,
+}
+
+/// A pair of systems in a [`StageGraph`] with an ambiguous execution order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AmbiguityGraphEdge {
+    /// Index into [`StageGraph::systems`] of the first system.
+    pub system_a: usize,
+    /// Index into [`StageGraph::systems`] of the second system.
+    pub system_b: usize,
+    /// The names of the components and resources both systems access, where at least one of them
+    /// has mutable access.
+    ///
+    /// Empty for exclusive systems, which conflict with everything.
+    pub conflicts: Vec<String>,
+}
+
+/// The resolved dependency graph of a single stage.
+///
+/// Systems are listed in the order they were sorted in, so that every dependency of a system
+/// comes before it.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StageGraph {
+    /// The systems of the stage.
+    pub systems: Vec<SystemGraphNode>,
+    /// The run criteria used by the systems of the stage.
+    pub run_criteria: Vec<RunCriteriaGraphNode>,
+    /// The pairs of systems reported by the ambiguity checker.
+    pub ambiguities: Vec<AmbiguityGraphEdge>,
+}
+
+impl StageGraph {
+    /// Renders this graph in the Graphviz DOT format.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::new();
+        writeln!(dot, "digraph stage {{").unwrap();
+        write_dot_stage(&mut dot, "", self, "    ");
+        writeln!(dot, "}}").unwrap();
+        dot
+    }
+
+    /// Renders this graph as JSON.
+    pub fn to_json(&self) -> String {
+        let mut json = String::new();
+        write_json_stage(&mut json, self);
+        json
+    }
+}
+
+/// The resolved dependency graphs of every stage of a [`Schedule`](super::Schedule), in
+/// execution order.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ScheduleGraph {
+    /// The label of each stage, along with its graph.
+    ///
+    /// Stages of nested schedules are flattened, and their labels are prefixed with the label of
+    /// the stage containing them, e.g. `"StartupSchedule/StartupStage::PostStartup"`.
+    pub stages: Vec<(String, StageGraph)>,
+}
+
+impl ScheduleGraph {
+    /// Returns the graph of the stage with the given (possibly prefixed) label.
+    pub fn get(&self, label: &str) -> Option<&StageGraph> {
+        self.stages
+            .iter()
+            .find(|(stage_label, _)| stage_label == label)
+            .map(|(_, graph)| graph)
+    }
+
+    /// Renders this graph in the Graphviz DOT format, with one cluster per stage.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::new();
+        writeln!(dot, "digraph schedule {{").unwrap();
+        writeln!(dot, "    compound=true;").unwrap();
+        for (index, (label, stage)) in self.stages.iter().enumerate() {
+            writeln!(dot, "    subgraph \"cluster_{index}\" {{").unwrap();
+            writeln!(dot, "        label={};", quote(label)).unwrap();
+            write_dot_stage(&mut dot, &format!("{index}_"), stage, "        ");
+            writeln!(dot, "    }}").unwrap();
+        }
+        writeln!(dot, "}}").unwrap();
+        dot
+    }
+
+    /// Renders this graph as JSON.
+    pub fn to_json(&self) -> String {
+        let mut json = String::new();
+        json.push_str("{\"stages\":[");
+        for (index, (label, stage)) in self.stages.iter().enumerate() {
+            if index > 0 {
+                json.push(',');
+            }
+            write!(json, "{{\"label\":{},\"graph\":", quote(label)).unwrap();
+            write_json_stage(&mut json, stage);
+            json.push('}');
+        }
+        json.push_str("]}");
+        json
+    }
+}
+
+fn write_dot_stage(dot: &mut String, prefix: &str, stage: &StageGraph, indent: &str) {
+    for (index, system) in stage.systems.iter().enumerate() {
+        let style = if system.kind.is_exclusive() {
+            ", style=bold"
+        } else {
+            ""
+        };
+        writeln!(
+            dot,
+            "{indent}\"{prefix}s{index}\" [label={}, shape=box{style}];",
+            quote(&system.name)
+        )
+        .unwrap();
+    }
+    for (index, criteria) in stage.run_criteria.iter().enumerate() {
+        writeln!(
+            dot,
+            "{indent}\"{prefix}c{index}\" [label={}, shape=diamond];",
+            quote(criteria.label.as_ref().unwrap_or(&criteria.name))
+        )
+        .unwrap();
+    }
+    for (index, system) in stage.systems.iter().enumerate() {
+        for dependency in &system.dependencies {
+            writeln!(
+                dot,
+                "{indent}\"{prefix}s{dependency}\" -> \"{prefix}s{index}\";"
+            )
+            .unwrap();
+        }
+        for criteria in &system.run_criteria {
+            writeln!(
+                dot,
+                "{indent}\"{prefix}c{criteria}\" -> \"{prefix}s{index}\" [style=dashed];"
+            )
+            .unwrap();
+        }
+    }
+    for ambiguity in &stage.ambiguities {
+        writeln!(
+            dot,
+            "{indent}\"{prefix}s{}\" -> \"{prefix}s{}\" [dir=none, style=dotted, color=red, label={}];",
+            ambiguity.system_a,
+            ambiguity.system_b,
+            quote(&ambiguity.conflicts.join(", "))
+        )
+        .unwrap();
+    }
+}
+
+fn write_json_stage(json: &mut String, stage: &StageGraph) {
+    json.push_str("{\"systems\":[");
+    for (index, system) in stage.systems.iter().enumerate() {
+        if index > 0 {
+            json.push(',');
+        }
+        write!(
+            json,
+            "{{\"name\":{},\"kind\":\"{}\",\"labels\":{},\"before\":{},\"after\":{},\
+            \"run_criteria\":{:?},\"dependencies\":{:?}}}",
+            quote(&system.name),
+            system.kind.as_str(),
+            json_strings(&system.labels),
+            json_strings(&system.before),
+            json_strings(&system.after),
+            system.run_criteria,
+            system.dependencies,
+        )
+        .unwrap();
+    }
+    json.push_str("],\"run_criteria\":[");
+    for (index, criteria) in stage.run_criteria.iter().enumerate() {
+        if index > 0 {
+            json.push(',');
+        }
+        let label = criteria
+            .label
+            .as_ref()
+            .map_or_else(|| "null".to_string(), |label| quote(label));
+        write!(
+            json,
+            "{{\"name\":{},\"label\":{label}}}",
+            quote(&criteria.name)
+        )
+        .unwrap();
+    }
+    json.push_str("],\"ambiguities\":[");
+    for (index, ambiguity) in stage.ambiguities.iter().enumerate() {
+        if index > 0 {
+            json.push(',');
+        }
+        write!(
+            json,
+            "{{\"system_a\":{},\"system_b\":{},\"conflicts\":{}}}",
+            ambiguity.system_a,
+            ambiguity.system_b,
+            json_strings(&ambiguity.conflicts)
+        )
+        .unwrap();
+    }
+    json.push_str("]}");
+}
+
+fn json_strings(strings: &[String]) -> String {
+    let strings: Vec<_> = strings.iter().map(|string| quote(string)).collect();
+    format!("[{}]", strings.join(","))
+}
+
+/// Quotes and escapes a string, in a way that is valid for both DOT and JSON.
+fn quote(string: &str) -> String {
+    let mut quoted = String::with_capacity(string.len() + 2);
+    quoted.push('"');
+    for c in string.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c if c.is_control() => write!(quoted, "\\u{:04x}", c as u32).unwrap(),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        self as bevy_ecs,
+        schedule::{
+            ExclusiveSystemDescriptorCoercion, ParallelSystemDescriptorCoercion,
+            RunCriteriaDescriptorCoercion, RunCriteriaLabel, Schedule, ShouldRun, StageLabel,
+            SystemLabel, SystemStage,
+        },
+        schedule_v3::{self, IntoSystemConfigs},
+        system::{IntoExclusiveSystem, ResMut, Resource},
+        world::World,
+    };
+
+    use super::*;
+
+    #[derive(Resource)]
+    struct R;
+
+    #[derive(SystemLabel)]
+    struct First;
+
+    #[derive(RunCriteriaLabel)]
+    struct Always;
+
+    #[derive(StageLabel)]
+    enum TestStage {
+        A,
+        B,
+        C,
+        D,
+    }
+
+    fn first() {}
+    fn writes_a(_: ResMut<R>) {}
+    fn writes_b(_: ResMut<R>) {}
+    fn exclusive(_: &mut World) {}
+    fn always() -> ShouldRun {
+        ShouldRun::Yes
+    }
+    fn yes() -> bool {
+        true
+    }
+
+    fn system_index(graph: &StageGraph, name: &str) -> usize {
+        graph
+            .systems
+            .iter()
+            .position(|system| system.name.ends_with(name))
+            .unwrap()
+    }
+
+    #[test]
+    fn stage_graph_json() {
+        let mut world = World::new();
+        let mut stage = SystemStage::parallel()
+            .with_system(exclusive.exclusive_system().at_end())
+            .with_system(
+                writes_a
+                    .after(First)
+                    .with_run_criteria(always.label(Always)),
+            )
+            .with_system(first.label(First));
+        let json = stage.graph(&mut world).to_json();
+        assert_eq!(
+            json,
+            concat!(
+                r#"{"systems":["#,
+                r#"{"name":"bevy_ecs::schedule::graph_export::tests::first","kind":"parallel","#,
+                r#""labels":["bevy_ecs::schedule::graph_export::tests::first","First"],"#,
+                r#""before":[],"after":[],"run_criteria":[],"dependencies":[]},"#,
+                r#"{"name":"bevy_ecs::schedule::graph_export::tests::writes_a","kind":"parallel","#,
+                r#""labels":["bevy_ecs::schedule::graph_export::tests::writes_a"],"#,
+                r#""before":[],"after":["First"],"run_criteria":[0],"dependencies":[0]},"#,
+                r#"{"name":"bevy_ecs::schedule::graph_export::tests::exclusive","#,
+                r#""kind":"exclusive_at_end","labels":[],"before":[],"after":[],"#,
+                r#""run_criteria":[],"dependencies":[]}],"#,
+                r#""run_criteria":[{"name":"bevy_ecs::schedule::graph_export::tests::always","#,
+                r#""label":"Always"}],"#,
+                r#""ambiguities":[]}"#,
+            )
+        );
+    }
+
+    #[test]
+    fn stage_graph_dot() {
+        let mut world = World::new();
+        let mut stage = SystemStage::parallel()
+            .with_system(exclusive.exclusive_system().at_start())
+            .with_system(
+                writes_a
+                    .after(First)
+                    .with_run_criteria(always.label(Always)),
+            )
+            .with_system(first.label(First));
+        let dot = stage.graph(&mut world).to_dot();
+        assert_eq!(
+            dot,
+            r#"digraph stage {
+    "s0" [label="bevy_ecs::schedule::graph_export::tests::exclusive", shape=box, style=bold];
+    "s1" [label="bevy_ecs::schedule::graph_export::tests::first", shape=box];
+    "s2" [label="bevy_ecs::schedule::graph_export::tests::writes_a", shape=box];
+    "c0" [label="Always", shape=diamond];
+    "s1" -> "s2";
+    "c0" -> "s2" [style=dashed];
+}
+"#
+        );
+    }
+
+    #[test]
+    fn stage_graph_ambiguities() {
+        let mut world = World::new();
+        world.insert_resource(R);
+        let mut stage = SystemStage::parallel()
+            .with_system(writes_a)
+            .with_system(writes_b)
+            .with_system(first.before(writes_a).before(writes_b));
+        let graph = stage.graph(&mut world);
+
+        let a = system_index(&graph, "writes_a");
+        let b = system_index(&graph, "writes_b");
+        assert_eq!(graph.ambiguities.len(), 1);
+        let ambiguity = &graph.ambiguities[0];
+        assert_eq!(
+            (
+                ambiguity.system_a.min(ambiguity.system_b),
+                ambiguity.system_a.max(ambiguity.system_b)
+            ),
+            (a.min(b), a.max(b))
+        );
+        assert_eq!(
+            ambiguity.conflicts,
+            vec![std::any::type_name::<R>().to_string()]
+        );
+        assert!(graph.to_dot().contains(&format!(
+            "\"s{}\" -> \"s{}\" [dir=none, style=dotted, color=red, label=\"{}\"];",
+            ambiguity.system_a,
+            ambiguity.system_b,
+            std::any::type_name::<R>()
+        )));
+    }
+
+    #[test]
+    fn v3_stage_graph_ambiguities() {
+        use crate::schedule_v3::IntoSystemConfig;
+
+        let mut world = World::new();
+        world.insert_resource(R);
+        let mut schedule = schedule_v3::Schedule::new();
+        schedule
+            .add_system(writes_a)
+            .add_system(writes_b)
+            .add_system(exclusive.after(writes_a).after(writes_b));
+        let graph = schedule.stage_graph(&mut world);
+
+        let a = system_index(&graph, "writes_a");
+        let b = system_index(&graph, "writes_b");
+        assert_eq!(
+            graph.ambiguities,
+            vec![AmbiguityGraphEdge {
+                system_a: a.min(b),
+                system_b: a.max(b),
+                conflicts: vec![std::any::type_name::<R>().to_string()],
+            }]
+        );
+    }
+
+    #[test]
+    fn schedule_graph() {
+        use crate::schedule_v3::IntoSystemConfig;
+
+        let mut world = World::new();
+        let mut v3_schedule = schedule_v3::Schedule::new();
+        v3_schedule.add_systems((first, writes_a.run_if(yes)).chain());
+        let mut schedule = Schedule::default()
+            .with_stage(TestStage::A, SystemStage::single(first))
+            .with_stage(
+                TestStage::B,
+                Schedule::default().with_stage(TestStage::C, SystemStage::single(writes_b)),
+            )
+            .with_stage(TestStage::D, v3_schedule);
+        let graph = schedule.graph(&mut world);
+
+        let labels: Vec<_> = graph
+            .stages
+            .iter()
+            .map(|(label, _)| label.as_str())
+            .collect();
+        assert_eq!(
+            labels,
+            vec!["TestStage::A", "TestStage::B/TestStage::C", "TestStage::D"]
+        );
+        assert_eq!(
+            graph
+                .get("TestStage::B/TestStage::C")
+                .unwrap()
+                .systems
+                .len(),
+            1
+        );
+
+        let stage = graph.get("TestStage::D").unwrap();
+        let first = system_index(stage, "first");
+        let writes_a = system_index(stage, "writes_a");
+        assert_eq!(stage.systems[writes_a].dependencies, vec![first]);
+        assert_eq!(stage.systems[writes_a].kind, SystemGraphKind::Parallel);
+        let criteria = &stage.run_criteria[stage.systems[writes_a].run_criteria[0]];
+        assert!(criteria.name.ends_with("yes"));
+        assert!(stage.systems[first].run_criteria.is_empty());
+
+        let json = graph.to_json();
+        assert!(json.starts_with(r#"{"stages":[{"label":"TestStage::A","graph":{"systems":[{"#));
+    }
+
+    #[test]
+    fn escape() {
+        assert_eq!(quote("a \"b\"\n\\"), r#""a \"b\"\n\\""#);
+    }
+}
@@ -3,8 +3,10 @@
 //! When using Bevy ECS, systems are usually not run directly, but are inserted into a
 //!  [`Stage`], which then lives within a [`Schedule`].
 
+mod ambiguity;
 mod executor;
 mod executor_parallel;
+mod graph_export;
 pub mod graph_utils;
 mod label;
 mod run_criteria;
@@ -14,8 +16,10 @@ mod system_container;
 mod system_descriptor;
 mod system_set;
 
+pub use ambiguity::*;
 pub use executor::*;
 pub use executor_parallel::*;
+pub use graph_export::*;
 pub use graph_utils::GraphNode;
 pub use label::*;
 pub use run_criteria::*;
@@ -225,6 +229,7 @@ impl Schedule {
     ///
     /// ```
     /// # use bevy_ecs::prelude::*;
+    /// # use bevy_ecs::schedule::SystemSet;
     /// #
     /// # fn my_system() {}
     /// # let mut schedule = Schedule::default();
@@ -344,6 +349,36 @@ impl Schedule {
         }
     }
 
+    /// Returns the resolved dependency graphs of all of the schedule's stages, in execution order.
+    ///
+    /// The stages of nested [`Schedule`]s are included, prefixed with the label of the stage
+    /// containing them. Stages that are neither a [`SystemStage`], a [`Schedule`] nor a
+    /// [`schedule_v3::Schedule`](crate::schedule_v3::Schedule) are listed with an empty graph.
+    ///
+    /// Systems that were added since a stage last ran are initialized first, see
+    /// [`SystemStage::graph`].
+    pub fn graph(&mut self, world: &mut World) -> ScheduleGraph {
+        let mut graph = ScheduleGraph::default();
+        self.add_stage_graphs(world, "", &mut graph);
+        graph
+    }
+
+    fn add_stage_graphs(&mut self, world: &mut World, prefix: &str, graph: &mut ScheduleGraph) {
+        for label in &self.stage_order {
+            let name = format!("{prefix}{:?}", label);
+            let stage = self.stages.get_mut(label).unwrap();
+            if let Some(stage) = stage.downcast_mut::<SystemStage>() {
+                graph.stages.push((name, stage.graph(world)));
+            } else if let Some(schedule) = stage.downcast_mut::<Schedule>() {
+                schedule.add_stage_graphs(world, &format!("{name}/"), graph);
+            } else if let Some(schedule) = stage.downcast_mut::<crate::schedule_v3::Schedule>() {
+                graph.stages.push((name, schedule.stage_graph(world)));
+            } else {
+                graph.stages.push((name, StageGraph::default()));
+            }
+        }
+    }
+
     /// Iterates over all of schedule's stages and their labels, in execution order.
     pub fn iter_stages(&self) -> impl Iterator<Item = (StageLabelId, &dyn Stage)> {
         self.stage_order
@@ -1,23 +1,20 @@
 use crate::{
-    self as bevy_ecs,
     change_detection::CHECK_TICK_THRESHOLD,
     component::ComponentId,
     prelude::IntoSystem,
     schedule::{
         graph_utils::{self, DependencyGraphError},
-        BoxedRunCriteria, DuplicateLabelStrategy, ExclusiveSystemContainer, GraphNode,
-        InsertionPoint, ParallelExecutor, ParallelSystemContainer, ParallelSystemExecutor,
-        RunCriteriaContainer, RunCriteriaDescriptor, RunCriteriaDescriptorOrLabel,
-        RunCriteriaInner, RunCriteriaLabelId, ShouldRun, SingleThreadedExecutor, SystemContainer,
-        SystemDescriptor, SystemLabelId, SystemSet,
+        AmbiguityGraphEdge, AmbiguityReport, AmbiguityReportLevel, BoxedRunCriteria,
+        DuplicateLabelStrategy, ExclusiveSystemContainer, GraphNode, InsertionPoint,
+        ParallelExecutor, ParallelSystemContainer, ParallelSystemExecutor,
+        ReportExecutionOrderAmbiguities, RunCriteriaContainer, RunCriteriaDescriptor,
+        RunCriteriaDescriptorOrLabel, RunCriteriaGraphNode, RunCriteriaInner, RunCriteriaLabelId,
+        ShouldRun, SingleThreadedExecutor, StageGraph, SystemContainer, SystemDescriptor,
+        SystemGraphKind, SystemGraphNode, SystemLabelId, SystemSet,
     },
     world::{World, WorldId},
 };
-use bevy_ecs_macros::Resource;
-use bevy_utils::{
-    tracing::{info, warn},
-    HashMap, HashSet,
-};
+use bevy_utils::{tracing::warn, HashMap, HashSet};
 use downcast_rs::{impl_downcast, Downcast};
 use fixedbitset::FixedBitSet;
 use std::fmt::Debug;
@@ -33,28 +30,6 @@ pub trait Stage: Downcast + Send + Sync {
 
 impl_downcast!(Stage);
 
-/// When this resource is present in the `App`'s `Resources`,
-/// each `SystemStage` will log a report containing
-/// pairs of systems with ambiguous execution order.
-///
-/// Systems that access the same Component or Resource within the same stage
-/// risk an ambiguous order that could result in logic bugs, unless they have an
-/// explicit execution ordering constraint between them.
-///
-/// This occurs because, in the absence of explicit constraints, systems are executed in
-/// an unstable, arbitrary order within each stage that may vary between runs and frames.
-///
-/// Some ambiguities reported by the ambiguity checker may be warranted (to allow two systems to run
-/// without blocking each other) or spurious, as the exact combination of archetypes used may
-/// prevent them from ever conflicting during actual gameplay. You can resolve the warnings produced
-/// by the ambiguity checker by adding `.before` or `.after` to one of the conflicting systems
-/// referencing the other system to force a specific ordering.
-///
-/// The checker may report a system more times than the amount of constraints it would actually need
-/// to have unambiguous order with regards to a group of already-constrained systems.
-#[derive(Resource, Default)]
-pub struct ReportExecutionOrderAmbiguities;
-
 /// Stores and executes systems. Execution order is not defined unless explicitly specified;
 /// see `SystemDescriptor` documentation.
 pub struct SystemStage {
@@ -434,6 +409,82 @@ impl SystemStage {
         }
     }
 
+    /// Initializes new systems and run criteria, and rebuilds the orders of all systems.
+    fn rebuild(&mut self, world: &mut World) {
+        self.initialize_systems(world);
+        self.rebuild_orders_and_dependencies();
+        self.systems_modified = false;
+        self.executor.rebuild_cached_data(&self.parallel);
+        self.executor_modified = false;
+        if let Some(settings) = world.get_resource::<ReportExecutionOrderAmbiguities>() {
+            self.report_ambiguities(settings, world);
+        }
+        if let Some(resource_id) = self.must_read_resource {
+            self.check_uses_resource(resource_id, world);
+        }
+    }
+
+    /// Returns the resolved dependency graph of this stage: its systems in execution order,
+    /// their labels and ordering constraints, their run criteria and the pairs of systems with an
+    /// ambiguous execution order.
+    ///
+    /// Systems that were added since the stage last ran are initialized first, so this can be
+    /// called before the stage ever runs.
+    ///
+    /// ```
+    /// # use bevy_ecs::{
+    /// #     schedule::{ParallelSystemDescriptorCoercion, SystemStage},
+    /// #     world::World,
+    /// # };
+    /// fn a() {}
+    /// fn b() {}
+    ///
+    /// let mut world = World::new();
+    /// let mut stage = SystemStage::parallel().with_system(a).with_system(b.after(a));
+    /// let graph = stage.graph(&mut world);
+    /// assert_eq!(graph.systems[1].dependencies, vec![0]);
+    /// std::fs::write("stage.dot", graph.to_dot())?;
+    /// # std::fs::remove_file("stage.dot")?;
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    pub fn graph(&mut self, world: &mut World) -> StageGraph {
+        if self.systems_modified {
+            self.rebuild(world);
+        }
+
+        let mut graph = StageGraph {
+            run_criteria: self
+                .run_criteria
+                .iter()
+                .map(|criteria| RunCriteriaGraphNode {
+                    name: criteria.name().into_owned(),
+                    label: criteria.label.map(|label| format!("{:?}", label)),
+                })
+                .collect(),
+            ..Default::default()
+        };
+        add_graph_systems(
+            &mut graph,
+            &self.exclusive_at_start,
+            SystemGraphKind::ExclusiveAtStart,
+            world,
+        );
+        add_graph_systems(&mut graph, &self.parallel, SystemGraphKind::Parallel, world);
+        add_graph_systems(
+            &mut graph,
+            &self.exclusive_before_commands,
+            SystemGraphKind::ExclusiveBeforeCommands,
+            world,
+        );
+        add_graph_systems(
+            &mut graph,
+            &self.exclusive_at_end,
+            SystemGraphKind::ExclusiveAtEnd,
+            world,
+        );
+        graph
+    }
+
     /// Rearranges all systems in topological orders. Systems must be initialized.
     fn rebuild_orders_and_dependencies(&mut self) {
         // This assertion exists to document that the number of systems in a stage is limited
@@ -505,73 +556,83 @@ impl SystemStage {
         );
     }
 
-    /// Logs execution order ambiguities between systems. System orders must be fresh.
-    fn report_ambiguities(&self, world: &World) {
-        debug_assert!(!self.systems_modified);
-        use std::fmt::Write;
-        fn write_display_names_of_pairs(
-            string: &mut String,
-            systems: &[impl SystemContainer],
-            mut ambiguities: Vec<(usize, usize, Vec<ComponentId>)>,
-            world: &World,
-        ) {
-            for (index_a, index_b, conflicts) in ambiguities.drain(..) {
-                writeln!(
-                    string,
-                    " -- {:?} and {:?}",
-                    systems[index_a].name(),
-                    systems[index_b].name()
-                )
-                .unwrap();
-                if !conflicts.is_empty() {
-                    let names = conflicts
-                        .iter()
-                        .map(|id| world.components().get_info(*id).unwrap().name())
-                        .collect::<Vec<_>>();
-                    writeln!(string, "    conflicts: {:?}", names).unwrap();
-                }
-            }
+    /// Returns the pairs of systems of this stage with an ambiguous execution order, along with
+    /// the components and resources they conflict on.
+    ///
+    /// Ambiguities between systems that share an ambiguity set are skipped, see
+    /// [`in_ambiguity_set`](crate::schedule::ParallelSystemDescriptorCoercion::in_ambiguity_set),
+    /// as well as those allowed by the [`ReportExecutionOrderAmbiguities`] resource, if it is
+    /// present. Systems that were added since the stage last ran are initialized first, which reports
+    /// ambiguities according to the [`ReportExecutionOrderAmbiguities`] resource, if present.
+    ///
+    /// ```
+    /// # use bevy_ecs::prelude::*;
+    /// #[derive(Component)]
+    /// struct Health(f32);
+    ///
+    /// fn heal(mut query: Query<&mut Health>) {}
+    /// fn poison(mut query: Query<&mut Health>) {}
+    ///
+    /// let mut world = World::new();
+    /// let mut stage = SystemStage::parallel().with_system(heal).with_system(poison);
+    /// let report = stage.ambiguity_report(&mut world);
+    /// assert_eq!(report.ambiguities.len(), 1);
+    /// assert_eq!(
+    ///     report.ambiguities[0].conflicts,
+    ///     vec![world.component_id::<Health>().unwrap()]
+    /// );
+    /// ```
+    pub fn ambiguity_report(&mut self, world: &mut World) -> AmbiguityReport {
+        if self.systems_modified {
+            self.rebuild(world);
         }
-        let parallel = find_ambiguities(&self.parallel);
-        let at_start = find_ambiguities(&self.exclusive_at_start);
-        let before_commands = find_ambiguities(&self.exclusive_before_commands);
-        let at_end = find_ambiguities(&self.exclusive_at_end);
-        if !(parallel.is_empty()
-            && at_start.is_empty()
-            && before_commands.is_empty()
-            && at_end.is_empty())
-        {
-            let mut string = "Execution order ambiguities detected, you might want to \
-                    add an explicit dependency relation between some of these systems:\n"
-                .to_owned();
-            if !parallel.is_empty() {
-                writeln!(string, " * Parallel systems:").unwrap();
-                write_display_names_of_pairs(&mut string, &self.parallel, parallel, world);
-            }
-            if !at_start.is_empty() {
-                writeln!(string, " * Exclusive systems at start of stage:").unwrap();
-                write_display_names_of_pairs(
-                    &mut string,
-                    &self.exclusive_at_start,
-                    at_start,
-                    world,
-                );
-            }
-            if !before_commands.is_empty() {
-                writeln!(string, " * Exclusive systems before commands of stage:").unwrap();
-                write_display_names_of_pairs(
-                    &mut string,
-                    &self.exclusive_before_commands,
-                    before_commands,
-                    world,
-                );
-            }
-            if !at_end.is_empty() {
-                writeln!(string, " * Exclusive systems at end of stage:").unwrap();
-                write_display_names_of_pairs(&mut string, &self.exclusive_at_end, at_end, world);
-            }
-            info!("{}", string);
+        let settings = world
+            .get_resource::<ReportExecutionOrderAmbiguities>()
+            .cloned()
+            .unwrap_or_default();
+        self.find_ambiguities(&settings)
+    }
+
+    /// Finds execution order ambiguities between systems that are not allowed by `settings`.
+    /// System orders must be fresh.
+    fn find_ambiguities(&self, settings: &ReportExecutionOrderAmbiguities) -> AmbiguityReport {
+        debug_assert!(!self.systems_modified);
+        let mut report = AmbiguityReport::default();
+        report.extend(
+            &self.exclusive_at_start,
+            SystemGraphKind::ExclusiveAtStart,
+            find_ambiguities(&self.exclusive_at_start),
+            settings,
+        );
+        report.extend(
+            &self.parallel,
+            SystemGraphKind::Parallel,
+            find_ambiguities(&self.parallel),
+            settings,
+        );
+        report.extend(
+            &self.exclusive_before_commands,
+            SystemGraphKind::ExclusiveBeforeCommands,
+            find_ambiguities(&self.exclusive_before_commands),
+            settings,
+        );
+        report.extend(
+            &self.exclusive_at_end,
+            SystemGraphKind::ExclusiveAtEnd,
+            find_ambiguities(&self.exclusive_at_end),
+            settings,
+        );
+        report
+    }
+
+    /// Reports execution order ambiguities between systems according to `settings`.
+    /// System orders must be fresh.
+    fn report_ambiguities(&self, settings: &ReportExecutionOrderAmbiguities, world: &World) {
+        if settings.level == AmbiguityReportLevel::Ignore {
+            return;
         }
+        self.find_ambiguities(settings)
+            .report(settings.level, world);
     }
 
     fn check_uses_resource(&self, resource_id: ComponentId, world: &World) {
@@ -709,6 +770,49 @@ fn process_systems(
     Ok(())
 }
 
+/// Appends the given topologically sorted systems to `graph`, along with their ambiguities.
+fn add_graph_systems(
+    graph: &mut StageGraph,
+    systems: &[impl SystemContainer],
+    kind: SystemGraphKind,
+    world: &World,
+) {
+    fn label_names(labels: &[SystemLabelId]) -> Vec<String> {
+        labels.iter().map(|label| format!("{:?}", label)).collect()
+    }
+
+    let offset = graph.systems.len();
+    graph.systems.extend(systems.iter().map(|container| {
+        SystemGraphNode {
+            name: container.name().into_owned(),
+            kind,
+            labels: label_names(container.labels()),
+            before: label_names(container.before()),
+            after: label_names(container.after()),
+            run_criteria: container.run_criteria().into_iter().collect(),
+            dependencies: container
+                .dependencies()
+                .iter()
+                .map(|index| index + offset)
+                .collect(),
+        }
+    }));
+    graph
+        .ambiguities
+        .extend(
+            find_ambiguities(systems)
+                .into_iter()
+                .map(|(index_a, index_b, conflicts)| AmbiguityGraphEdge {
+                    system_a: index_a + offset,
+                    system_b: index_b + offset,
+                    conflicts: conflicts
+                        .iter()
+                        .map(|id| world.components().get_info(*id).unwrap().name().to_string())
+                        .collect(),
+                }),
+        );
+}
+
 /// Returns vector containing all pairs of indices of systems with ambiguous execution order,
 /// along with specific components that have triggered the warning.
 /// Systems must be topologically sorted beforehand.
@@ -797,17 +901,7 @@ impl Stage for SystemStage {
         }
 
         if self.systems_modified {
-            self.initialize_systems(world);
-            self.rebuild_orders_and_dependencies();
-            self.systems_modified = false;
-            self.executor.rebuild_cached_data(&self.parallel);
-            self.executor_modified = false;
-            if world.contains_resource::<ReportExecutionOrderAmbiguities>() {
-                self.report_ambiguities(world);
-            }
-            if let Some(resource_id) = self.must_read_resource {
-                self.check_uses_resource(resource_id, world);
-            }
+            self.rebuild(world);
         } else if self.executor_modified {
             self.executor.rebuild_cached_data(&self.parallel);
             self.executor_modified = false;
@@ -974,9 +1068,10 @@ impl Stage for SystemStage {
 mod tests {
     use crate::{
         schedule::{
-            ExclusiveSystemDescriptorCoercion, ParallelSystemDescriptorCoercion, RunCriteria,
-            RunCriteriaDescriptorCoercion, ShouldRun, SingleThreadedExecutor, Stage, SystemLabel,
-            SystemLabelId, SystemSet, SystemStage,
+            AmbiguityReportLevel, ExclusiveSystemDescriptorCoercion,
+            ParallelSystemDescriptorCoercion, ReportExecutionOrderAmbiguities, RunCriteria,
+            RunCriteriaDescriptorCoercion, ShouldRun, SingleThreadedExecutor, Stage,
+            SystemGraphKind, SystemLabel, SystemLabelId, SystemSet, SystemStage,
         },
         system::{In, IntoExclusiveSystem, Local, Query, ResMut},
         world::World,
@@ -1995,6 +2090,80 @@ mod tests {
         assert_eq!(ambiguities.len(), 0);
     }
 
+    #[test]
+    fn ambiguity_report() {
+        fn resource(_: ResMut<R>) {}
+        fn component(_: Query<&mut W<f32>>) {}
+
+        let mut world = World::new();
+        world.insert_resource(R(0));
+        let mut stage = SystemStage::parallel()
+            .with_system(resource.label("0"))
+            .with_system(resource.label("1"))
+            .with_system(component.label("2"))
+            .with_system(component.label("3").after("2"));
+        let report = stage.ambiguity_report(&mut world);
+        assert_eq!(report.ambiguities.len(), 1);
+        let ambiguity = &report.ambiguities[0];
+        assert_eq!(ambiguity.kind, SystemGraphKind::Parallel);
+        assert!(ambiguity.system_a.ends_with("resource"));
+        assert!(ambiguity.system_b.ends_with("resource"));
+        assert_eq!(
+            ambiguity.conflicts,
+            vec![world
+                .components()
+                .get_resource_id(std::any::TypeId::of::<R>())
+                .unwrap()]
+        );
+        assert!(report.describe(&world).contains("::R"));
+
+        world.insert_resource(ReportExecutionOrderAmbiguities::new(
+            AmbiguityReportLevel::Error,
+        ));
+        let mut stage = SystemStage::parallel()
+            .with_system(resource.label("0").in_ambiguity_set("a"))
+            .with_system(resource.label("1").in_ambiguity_set("a"));
+        stage.run(&mut world);
+        assert!(stage.ambiguity_report(&mut world).is_empty());
+
+        let mut stage = SystemStage::parallel()
+            .with_system(resource.label("0"))
+            .with_system(resource.label("1"));
+        world.insert_resource(
+            ReportExecutionOrderAmbiguities::new(AmbiguityReportLevel::Error).ambiguous_with("1"),
+        );
+        stage.run(&mut world);
+        assert!(stage.ambiguity_report(&mut world).is_empty());
+
+        world.insert_resource(
+            ReportExecutionOrderAmbiguities::new(AmbiguityReportLevel::Error)
+                .ambiguous_with_crate("bevy_ecs"),
+        );
+        assert!(stage.ambiguity_report(&mut world).is_empty());
+
+        world.insert_resource(
+            ReportExecutionOrderAmbiguities::new(AmbiguityReportLevel::Error)
+                .ambiguous_with_crate("bevy"),
+        );
+        assert_eq!(stage.ambiguity_report(&mut world).ambiguities.len(), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "Execution order ambiguities detected")]
+    fn ambiguity_report_error_level() {
+        fn resource(_: ResMut<R>) {}
+
+        let mut world = World::new();
+        world.insert_resource(R(0));
+        world.insert_resource(ReportExecutionOrderAmbiguities::new(
+            AmbiguityReportLevel::Error,
+        ));
+        let mut stage = SystemStage::parallel()
+            .with_system(resource)
+            .with_system(resource);
+        stage.run(&mut world);
+    }
+
     #[test]
     #[should_panic]
     fn multiple_worlds_same_stage() {
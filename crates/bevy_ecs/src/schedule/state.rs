@@ -476,7 +476,8 @@ fn state_cleaner<T: StateData>(
 #[cfg(test)]
 mod test {
     use super::*;
-    use crate::prelude::*;
+    use crate::prelude::{Stage, SystemStage, World};
+    use crate::schedule::ParallelSystemDescriptorCoercion;
 
     #[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
     enum MyState {
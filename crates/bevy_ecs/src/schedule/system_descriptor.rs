@@ -25,7 +25,8 @@ use crate::{
 ///
 /// # Example
 /// ```
-/// # use bevy_ecs::prelude::*;
+/// # use bevy_ecs::prelude::{ExclusiveSystemDescriptorCoercion, IntoExclusiveSystem, SystemLabel, SystemStage};
+/// # use bevy_ecs::schedule::ParallelSystemDescriptorCoercion;
 /// # fn do_something() {}
 /// # fn do_the_other_thing() {}
 /// # fn do_something_else() {}
@@ -0,0 +1,274 @@
+use std::{borrow::Cow, marker::PhantomData};
+
+use crate::{
+    archetype::ArchetypeComponentId,
+    component::ComponentId,
+    query::Access,
+    system::{BoxedSystem, IntoSystem, System},
+    world::World,
+};
+
+/// A type-erased run condition stored in a [`Schedule`](super::Schedule).
+pub type BoxedCondition = BoxedSystem<(), bool>;
+
+/// A system that determines if one or more scheduled systems should run.
+///
+/// Implemented for functions and closures that convert into a [`System`] with no input that
+/// returns a `bool`. Conditions are evaluated right before the systems they guard, possibly in
+/// parallel with other systems, so they should only read data: any
+/// [`Commands`](crate::system::Commands) they issue are never applied.
+pub trait Condition<Params>: IntoSystem<(), bool, Params> {
+    /// Returns a condition that is `true` if both `self` and `and_then` are `true`.
+    ///
+    /// `and_then` is only evaluated if `self` is `true`.
+    ///
+    /// ```
+    /// # use bevy_ecs::prelude::*;
+    /// #[derive(Resource, PartialEq)]
+    /// struct Paused(bool);
+    ///
+    /// # fn tick() {}
+    /// # let mut schedule = bevy_ecs::schedule_v3::Schedule::new();
+    /// schedule.add_system(tick.run_if(resource_exists::<Paused>().and_then(resource_equals(Paused(false)))));
+    /// ```
+    fn and_then<P, C: Condition<P>>(self, and_then: C) -> AndThen<Self::System, C::System> {
+        CombinatorSystem::new(
+            IntoSystem::into_system(self),
+            IntoSystem::into_system(and_then),
+        )
+    }
+
+    /// Returns a condition that is `true` if either `self` or `or_else` is `true`.
+    ///
+    /// `or_else` is only evaluated if `self` is `false`.
+    fn or_else<P, C: Condition<P>>(self, or_else: C) -> OrElse<Self::System, C::System> {
+        CombinatorSystem::new(
+            IntoSystem::into_system(self),
+            IntoSystem::into_system(or_else),
+        )
+    }
+}
+
+impl<Params, F> Condition<Params> for F where F: IntoSystem<(), bool, Params> {}
+
+/// Combines the results of two conditions.
+#[doc(hidden)]
+pub trait Combine: Send + Sync + 'static {
+    const OPERATOR: &'static str;
+
+    fn combine(a: impl FnOnce() -> bool, b: impl FnOnce() -> bool) -> bool;
+}
+
+#[doc(hidden)]
+pub struct AndThenMarker;
+
+impl Combine for AndThenMarker {
+    const OPERATOR: &'static str = "&&";
+
+    fn combine(a: impl FnOnce() -> bool, b: impl FnOnce() -> bool) -> bool {
+        a() && b()
+    }
+}
+
+#[doc(hidden)]
+pub struct OrElseMarker;
+
+impl Combine for OrElseMarker {
+    const OPERATOR: &'static str = "||";
+
+    fn combine(a: impl FnOnce() -> bool, b: impl FnOnce() -> bool) -> bool {
+        a() || b()
+    }
+}
+
+/// A condition combining two conditions, see [`Condition::and_then`] and [`Condition::or_else`].
+pub struct CombinatorSystem<Func, A, B> {
+    a: A,
+    b: B,
+    name: Cow<'static, str>,
+    component_access: Access<ComponentId>,
+    archetype_component_access: Access<ArchetypeComponentId>,
+    // NOTE: PhantomData<fn()-> T> gives this safe Send/Sync impls
+    marker: PhantomData<fn() -> Func>,
+}
+
+/// A condition that is `true` if both of its conditions are, see [`Condition::and_then`].
+pub type AndThen<A, B> = CombinatorSystem<AndThenMarker, A, B>;
+
+/// A condition that is `true` if either of its conditions is, see [`Condition::or_else`].
+pub type OrElse<A, B> = CombinatorSystem<OrElseMarker, A, B>;
+
+impl<Func: Combine, A: System<In = (), Out = bool>, B: System<In = (), Out = bool>>
+    CombinatorSystem<Func, A, B>
+{
+    fn new(a: A, b: B) -> Self {
+        Self {
+            name: format!("{} {} {}", a.name(), Func::OPERATOR, b.name()).into(),
+            a,
+            b,
+            component_access: Access::default(),
+            archetype_component_access: Access::default(),
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<Func, A, B> System for CombinatorSystem<Func, A, B>
+where
+    Func: Combine,
+    A: System<In = (), Out = bool>,
+    B: System<In = (), Out = bool>,
+{
+    type In = ();
+    type Out = bool;
+
+    fn name(&self) -> Cow<'static, str> {
+        self.name.clone()
+    }
+
+    fn component_access(&self) -> &Access<ComponentId> {
+        &self.component_access
+    }
+
+    fn archetype_component_access(&self) -> &Access<ArchetypeComponentId> {
+        &self.archetype_component_access
+    }
+
+    fn is_send(&self) -> bool {
+        self.a.is_send() && self.b.is_send()
+    }
+
+    fn has_deferred(&self) -> bool {
+        self.a.has_deferred() || self.b.has_deferred()
+    }
+
+    unsafe fn run_unsafe(&mut self, _input: (), world: &World) -> bool {
+        Func::combine(
+            || self.a.run_unsafe((), world),
+            || self.b.run_unsafe((), world),
+        )
+    }
+
+    fn apply_buffers(&mut self, world: &mut World) {
+        self.a.apply_buffers(world);
+        self.b.apply_buffers(world);
+    }
+
+    fn initialize(&mut self, world: &mut World) {
+        self.a.initialize(world);
+        self.b.initialize(world);
+        self.component_access.extend(self.a.component_access());
+        self.component_access.extend(self.b.component_access());
+    }
+
+    fn update_archetype_component_access(&mut self, world: &World) {
+        self.a.update_archetype_component_access(world);
+        self.b.update_archetype_component_access(world);
+
+        self.archetype_component_access
+            .extend(self.a.archetype_component_access());
+        self.archetype_component_access
+            .extend(self.b.archetype_component_access());
+    }
+
+    fn check_change_tick(&mut self, change_tick: u32) {
+        self.a.check_change_tick(change_tick);
+        self.b.check_change_tick(change_tick);
+    }
+}
+
+/// Commonly used run conditions.
+pub mod common_conditions {
+    use super::Condition;
+    use crate::{
+        schedule_v3::{State, States},
+        system::{AlreadyWasSystem, In, IntoChainSystem, Res, Resource},
+    };
+
+    /// Returns a condition that is `true` the first time it is evaluated, and `false` after.
+    pub fn run_once() -> impl FnMut() -> bool + Clone {
+        let mut has_run = false;
+        move || {
+            if !has_run {
+                has_run = true;
+                true
+            } else {
+                false
+            }
+        }
+    }
+
+    /// Returns a condition that is `true` if the resource `T` exists.
+    pub fn resource_exists<T: Resource>() -> impl FnMut(Option<Res<T>>) -> bool + Clone {
+        move |res: Option<Res<T>>| res.is_some()
+    }
+
+    /// Returns a condition that is `true` if the resource `T` is equal to `value`.
+    ///
+    /// # Panics
+    ///
+    /// The condition panics if the resource does not exist.
+    pub fn resource_equals<T: Resource + PartialEq>(value: T) -> impl FnMut(Res<T>) -> bool {
+        move |res: Res<T>| *res == value
+    }
+
+    /// Returns a condition that is `true` if the resource `T` exists and is equal to `value`.
+    pub fn resource_exists_and_equals<T: Resource + PartialEq>(
+        value: T,
+    ) -> impl FnMut(Option<Res<T>>) -> bool {
+        move |res: Option<Res<T>>| match res {
+            Some(res) => *res == value,
+            None => false,
+        }
+    }
+
+    /// Returns a condition that is `true` if the resource `T` has been added or mutated since
+    /// the condition was last evaluated.
+    ///
+    /// # Panics
+    ///
+    /// The condition panics if the resource does not exist.
+    pub fn resource_changed<T: Resource>() -> impl FnMut(Res<T>) -> bool + Clone {
+        move |res: Res<T>| res.is_changed()
+    }
+
+    /// Returns a condition that is `true` if the [`State<S>`] resource exists.
+    pub fn state_exists<S: States>() -> impl FnMut(Option<Res<State<S>>>) -> bool + Clone {
+        move |current_state: Option<Res<State<S>>>| current_state.is_some()
+    }
+
+    /// Returns a condition that is `true` if the current state of the [`State<S>`] resource is
+    /// equal to `state`.
+    ///
+    /// # Panics
+    ///
+    /// The condition panics if the resource does not exist.
+    pub fn in_state<S: States>(state: S) -> impl FnMut(Res<State<S>>) -> bool + Clone {
+        move |current_state: Res<State<S>>| current_state.0 == state
+    }
+
+    /// Returns a condition that is `true` if the [`State<S>`] resource has changed since the
+    /// condition was last evaluated.
+    ///
+    /// # Panics
+    ///
+    /// The condition panics if the resource does not exist.
+    pub fn state_changed<S: States>() -> impl FnMut(Res<State<S>>) -> bool + Clone {
+        move |current_state: Res<State<S>>| current_state.is_changed()
+    }
+
+    /// Returns a condition that inverts the result of `condition`.
+    ///
+    /// ```
+    /// # use bevy_ecs::prelude::*;
+    /// #[derive(Resource)]
+    /// struct Paused;
+    ///
+    /// # fn tick() {}
+    /// # let mut schedule = bevy_ecs::schedule_v3::Schedule::new();
+    /// schedule.add_system(tick.run_if(not(resource_exists::<Paused>())));
+    /// ```
+    pub fn not<Params>(condition: impl Condition<Params>) -> impl Condition<AlreadyWasSystem> {
+        condition.chain(|In(result): In<bool>| !result)
+    }
+}
@@ -0,0 +1,550 @@
+use bevy_ecs_macros::all_tuples;
+
+use crate::{
+    error::BevyError,
+    schedule_v3::{
+        condition::{BoxedCondition, Condition},
+        graph_utils::{Ambiguity, Dependency, DependencyKind, GraphInfo},
+        set::{BoxedSystemSet, IntoSystemSet, IsExclusiveFunctionSystem, SystemSet},
+    },
+    system::{
+        AlreadyWasSystem, BoxedSystem, ExclusiveFunctionSystem, IntoFallibleSystem, IntoSystem,
+        IsFallibleSystem, IsFunctionSystem, System, SystemParam, SystemParamFunction,
+    },
+    world::World,
+};
+
+/// A [`SystemSet`] with scheduling metadata.
+pub struct SystemSetConfig {
+    pub(super) set: BoxedSystemSet,
+    pub(super) graph_info: GraphInfo,
+    pub(super) conditions: Vec<BoxedCondition>,
+}
+
+impl SystemSetConfig {
+    fn new(set: BoxedSystemSet) -> Self {
+        // system type sets are automatically populated
+        // to avoid unintentionally broad changes, they cannot be configured
+        assert!(
+            !set.is_system_type(),
+            "configuring system type sets is not allowed"
+        );
+
+        Self {
+            set,
+            graph_info: GraphInfo::default(),
+            conditions: Vec::new(),
+        }
+    }
+}
+
+/// A [`System`] with scheduling metadata.
+pub struct SystemConfig {
+    pub(super) system: BoxedSystem,
+    pub(super) graph_info: GraphInfo,
+    pub(super) conditions: Vec<BoxedCondition>,
+}
+
+impl SystemConfig {
+    fn new(system: BoxedSystem) -> Self {
+        // include system in its default sets
+        let sets = system.default_system_sets();
+        Self {
+            system,
+            graph_info: GraphInfo {
+                sets,
+                ..Default::default()
+            },
+            conditions: Vec::new(),
+        }
+    }
+}
+
+fn new_condition<P>(condition: impl Condition<P>) -> BoxedCondition {
+    Box::new(IntoSystem::into_system(condition))
+}
+
+fn ambiguous_with(graph_info: &mut GraphInfo, set: BoxedSystemSet) {
+    match &mut graph_info.ambiguous_with {
+        detection @ Ambiguity::Check => {
+            *detection = Ambiguity::IgnoreWithSet(vec![set]);
+        }
+        Ambiguity::IgnoreWithSet(ambiguous_with) => {
+            ambiguous_with.push(set);
+        }
+        Ambiguity::IgnoreAll => (),
+    }
+}
+
+/// Types that can be converted into a [`SystemSetConfig`].
+///
+/// This has been implemented for all types that implement [`SystemSet`] and boxed trait objects.
+pub trait IntoSystemSetConfig: Sized {
+    /// Convert into a [`SystemSetConfig`].
+    #[doc(hidden)]
+    fn into_config(self) -> SystemSetConfig;
+    /// Add to the provided `set`.
+    fn in_set(self, set: impl SystemSet) -> SystemSetConfig {
+        self.into_config().in_set(set)
+    }
+    /// Run before all systems in `set`.
+    fn before<M>(self, set: impl IntoSystemSet<M>) -> SystemSetConfig {
+        self.into_config().before(set)
+    }
+    /// Run after all systems in `set`.
+    fn after<M>(self, set: impl IntoSystemSet<M>) -> SystemSetConfig {
+        self.into_config().after(set)
+    }
+    /// Don't report execution order ambiguities between the systems in this set and the systems in `set`.
+    fn ambiguous_with<M>(self, set: impl IntoSystemSet<M>) -> SystemSetConfig {
+        self.into_config().ambiguous_with(set)
+    }
+    /// Don't report execution order ambiguities between the systems in this set and any other system.
+    fn ambiguous_with_all(self) -> SystemSetConfig {
+        self.into_config().ambiguous_with_all()
+    }
+    /// Run the systems in this set only if the [`Condition`] is `true`.
+    ///
+    /// The `Condition` will be evaluated at most once (per schedule run),
+    /// the first time a system in this set prepares to run.
+    fn run_if<P>(self, condition: impl Condition<P>) -> SystemSetConfig {
+        self.into_config().run_if(condition)
+    }
+}
+
+impl<S: SystemSet> IntoSystemSetConfig for S {
+    fn into_config(self) -> SystemSetConfig {
+        SystemSetConfig::new(Box::new(self))
+    }
+}
+
+impl IntoSystemSetConfig for BoxedSystemSet {
+    fn into_config(self) -> SystemSetConfig {
+        SystemSetConfig::new(self)
+    }
+}
+
+impl IntoSystemSetConfig for SystemSetConfig {
+    fn into_config(self) -> Self {
+        self
+    }
+
+    fn in_set(mut self, set: impl SystemSet) -> Self {
+        assert!(
+            set.system_type().is_none(),
+            "adding arbitrary system sets to a system type set is not allowed"
+        );
+        self.graph_info.sets.push(Box::new(set));
+        self
+    }
+
+    fn before<M>(mut self, set: impl IntoSystemSet<M>) -> Self {
+        self.graph_info.dependencies.push(Dependency::new(
+            DependencyKind::Before,
+            Box::new(set.into_set()),
+        ));
+        self
+    }
+
+    fn after<M>(mut self, set: impl IntoSystemSet<M>) -> Self {
+        self.graph_info.dependencies.push(Dependency::new(
+            DependencyKind::After,
+            Box::new(set.into_set()),
+        ));
+        self
+    }
+
+    fn ambiguous_with<M>(mut self, set: impl IntoSystemSet<M>) -> Self {
+        ambiguous_with(&mut self.graph_info, Box::new(set.into_set()));
+        self
+    }
+
+    fn ambiguous_with_all(mut self) -> Self {
+        self.graph_info.ambiguous_with = Ambiguity::IgnoreAll;
+        self
+    }
+
+    fn run_if<P>(mut self, condition: impl Condition<P>) -> Self {
+        self.conditions.push(new_condition(condition));
+        self
+    }
+}
+
+/// Types that can be converted into a [`SystemConfig`].
+///
+/// This has been implemented for boxed [`System<In=(), Out=()>`](crate::system::System)
+/// trait objects and all functions that turn into such, as well as for systems returning
+/// `Result<(), E>`, whose errors are passed to the
+/// [`SystemErrorHandler`](crate::error::SystemErrorHandler).
+pub trait IntoSystemConfig<Marker>: Sized {
+    /// Convert into a [`SystemConfig`].
+    #[doc(hidden)]
+    fn into_config(self) -> SystemConfig;
+    /// Add to `set` membership.
+    fn in_set(self, set: impl SystemSet) -> SystemConfig {
+        self.into_config().in_set(set)
+    }
+    /// Run before all systems in `set`.
+    fn before<M>(self, set: impl IntoSystemSet<M>) -> SystemConfig {
+        self.into_config().before(set)
+    }
+    /// Run after all systems in `set`.
+    fn after<M>(self, set: impl IntoSystemSet<M>) -> SystemConfig {
+        self.into_config().after(set)
+    }
+    /// Don't report execution order ambiguities between this system and the systems in `set`.
+    fn ambiguous_with<M>(self, set: impl IntoSystemSet<M>) -> SystemConfig {
+        self.into_config().ambiguous_with(set)
+    }
+    /// Don't report execution order ambiguities between this system and any other system.
+    fn ambiguous_with_all(self) -> SystemConfig {
+        self.into_config().ambiguous_with_all()
+    }
+    /// Run only if the [`Condition`] is `true`.
+    ///
+    /// The `Condition` will be evaluated at most once (per schedule run),
+    /// when the system prepares to run.
+    fn run_if<P>(self, condition: impl Condition<P>) -> SystemConfig {
+        self.into_config().run_if(condition)
+    }
+}
+
+// functions
+impl<Param, Marker, F> IntoSystemConfig<(IsFunctionSystem, Param, Marker)> for F
+where
+    Param: SystemParam + 'static,
+    Marker: 'static,
+    F: SystemParamFunction<(), (), Param, Marker>,
+{
+    fn into_config(self) -> SystemConfig {
+        SystemConfig::new(Box::new(IntoSystem::into_system(self)))
+    }
+}
+
+// exclusive functions
+impl<F> IntoSystemConfig<IsExclusiveFunctionSystem> for F
+where
+    F: FnMut(&mut World) + Send + Sync + 'static,
+{
+    fn into_config(self) -> SystemConfig {
+        SystemConfig::new(Box::new(ExclusiveFunctionSystem::new(self)))
+    }
+}
+
+// systems
+impl<S> IntoSystemConfig<AlreadyWasSystem> for S
+where
+    S: System<In = (), Out = ()>,
+{
+    fn into_config(self) -> SystemConfig {
+        SystemConfig::new(Box::new(self))
+    }
+}
+
+// systems returning a `Result`, with errors routed to the `SystemErrorHandler`
+impl<S, E, Params> IntoSystemConfig<(IsFallibleSystem, E, Params)> for S
+where
+    S: IntoSystem<(), Result<(), E>, Params>,
+    E: Into<BevyError> + 'static,
+{
+    fn into_config(self) -> SystemConfig {
+        SystemConfig::new(Box::new(self.fallible()))
+    }
+}
+
+impl IntoSystemConfig<()> for BoxedSystem<(), ()> {
+    fn into_config(self) -> SystemConfig {
+        SystemConfig::new(self)
+    }
+}
+
+impl IntoSystemConfig<()> for SystemConfig {
+    fn into_config(self) -> Self {
+        self
+    }
+
+    fn in_set(mut self, set: impl SystemSet) -> Self {
+        assert!(
+            set.system_type().is_none(),
+            "adding arbitrary systems to a system type set is not allowed"
+        );
+        self.graph_info.sets.push(Box::new(set));
+        self
+    }
+
+    fn before<M>(mut self, set: impl IntoSystemSet<M>) -> Self {
+        self.graph_info.dependencies.push(Dependency::new(
+            DependencyKind::Before,
+            Box::new(set.into_set()),
+        ));
+        self
+    }
+
+    fn after<M>(mut self, set: impl IntoSystemSet<M>) -> Self {
+        self.graph_info.dependencies.push(Dependency::new(
+            DependencyKind::After,
+            Box::new(set.into_set()),
+        ));
+        self
+    }
+
+    fn ambiguous_with<M>(mut self, set: impl IntoSystemSet<M>) -> Self {
+        ambiguous_with(&mut self.graph_info, Box::new(set.into_set()));
+        self
+    }
+
+    fn ambiguous_with_all(mut self) -> Self {
+        self.graph_info.ambiguous_with = Ambiguity::IgnoreAll;
+        self
+    }
+
+    fn run_if<P>(mut self, condition: impl Condition<P>) -> Self {
+        self.conditions.push(new_condition(condition));
+        self
+    }
+}
+
+/// A collection of [`SystemConfig`].
+pub struct SystemConfigs {
+    pub(super) systems: Vec<SystemConfig>,
+    /// If `true`, adds `before -> after` ordering constraints between the successive elements.
+    pub(super) chained: bool,
+}
+
+/// Types that can convert into a [`SystemConfigs`].
+pub trait IntoSystemConfigs<Marker>: Sized {
+    /// Convert into a [`SystemConfigs`].
+    #[doc(hidden)]
+    fn into_configs(self) -> SystemConfigs;
+
+    /// Add these systems to the provided `set`.
+    fn in_set(self, set: impl SystemSet) -> SystemConfigs {
+        self.into_configs().in_set(set)
+    }
+
+    /// Run before all systems in `set`.
+    fn before<M>(self, set: impl IntoSystemSet<M>) -> SystemConfigs {
+        self.into_configs().before(set)
+    }
+
+    /// Run after all systems in `set`.
+    fn after<M>(self, set: impl IntoSystemSet<M>) -> SystemConfigs {
+        self.into_configs().after(set)
+    }
+
+    /// Don't report execution order ambiguities between these systems and the systems in `set`.
+    fn ambiguous_with<M>(self, set: impl IntoSystemSet<M>) -> SystemConfigs {
+        self.into_configs().ambiguous_with(set)
+    }
+    /// Don't report execution order ambiguities between these systems and any other system.
+    fn ambiguous_with_all(self) -> SystemConfigs {
+        self.into_configs().ambiguous_with_all()
+    }
+
+    /// Treat this collection as a sequence of systems.
+    ///
+    /// Ordering constraints will be applied between the successive elements.
+    fn chain(self) -> SystemConfigs {
+        self.into_configs().chain()
+    }
+}
+
+impl IntoSystemConfigs<()> for SystemConfigs {
+    fn into_configs(self) -> Self {
+        self
+    }
+
+    fn in_set(mut self, set: impl SystemSet) -> Self {
+        assert!(
+            set.system_type().is_none(),
+            "adding arbitrary systems to a system type set is not allowed"
+        );
+        for config in &mut self.systems {
+            config.graph_info.sets.push(set.dyn_clone());
+        }
+        self
+    }
+
+    fn before<M>(mut self, set: impl IntoSystemSet<M>) -> Self {
+        let set = set.into_set();
+        for config in &mut self.systems {
+            config
+                .graph_info
+                .dependencies
+                .push(Dependency::new(DependencyKind::Before, set.dyn_clone()));
+        }
+        self
+    }
+
+    fn after<M>(mut self, set: impl IntoSystemSet<M>) -> Self {
+        let set = set.into_set();
+        for config in &mut self.systems {
+            config
+                .graph_info
+                .dependencies
+                .push(Dependency::new(DependencyKind::After, set.dyn_clone()));
+        }
+        self
+    }
+
+    fn ambiguous_with<M>(mut self, set: impl IntoSystemSet<M>) -> Self {
+        let set = set.into_set();
+        for config in &mut self.systems {
+            ambiguous_with(&mut config.graph_info, set.dyn_clone());
+        }
+        self
+    }
+
+    fn ambiguous_with_all(mut self) -> Self {
+        for config in &mut self.systems {
+            config.graph_info.ambiguous_with = Ambiguity::IgnoreAll;
+        }
+        self
+    }
+
+    fn chain(mut self) -> Self {
+        self.chained = true;
+        self
+    }
+}
+
+/// A collection of [`SystemSetConfig`].
+pub struct SystemSetConfigs {
+    pub(super) sets: Vec<SystemSetConfig>,
+    /// If `true`, adds `before -> after` ordering constraints between the successive elements.
+    pub(super) chained: bool,
+}
+
+/// Types that can convert into a [`SystemSetConfigs`].
+pub trait IntoSystemSetConfigs: Sized {
+    /// Convert into a [`SystemSetConfigs`].
+    #[doc(hidden)]
+    fn into_configs(self) -> SystemSetConfigs;
+
+    /// Add these system sets to the provided `set`.
+    fn in_set(self, set: impl SystemSet) -> SystemSetConfigs {
+        self.into_configs().in_set(set)
+    }
+
+    /// Run before all systems in `set`.
+    fn before<M>(self, set: impl IntoSystemSet<M>) -> SystemSetConfigs {
+        self.into_configs().before(set)
+    }
+
+    /// Run after all systems in `set`.
+    fn after<M>(self, set: impl IntoSystemSet<M>) -> SystemSetConfigs {
+        self.into_configs().after(set)
+    }
+
+    /// Don't report execution order ambiguities between the systems in these sets and the systems in `set`.
+    fn ambiguous_with<M>(self, set: impl IntoSystemSet<M>) -> SystemSetConfigs {
+        self.into_configs().ambiguous_with(set)
+    }
+    /// Don't report execution order ambiguities between the systems in these sets and any other system.
+    fn ambiguous_with_all(self) -> SystemSetConfigs {
+        self.into_configs().ambiguous_with_all()
+    }
+
+    /// Treat this collection as a sequence of system sets.
+    ///
+    /// Ordering constraints will be applied between the successive elements.
+    fn chain(self) -> SystemSetConfigs {
+        self.into_configs().chain()
+    }
+}
+
+impl IntoSystemSetConfigs for SystemSetConfigs {
+    fn into_configs(self) -> Self {
+        self
+    }
+
+    fn in_set(mut self, set: impl SystemSet) -> Self {
+        assert!(
+            set.system_type().is_none(),
+            "adding arbitrary system sets to a system type set is not allowed"
+        );
+        for config in &mut self.sets {
+            config.graph_info.sets.push(set.dyn_clone());
+        }
+        self
+    }
+
+    fn before<M>(mut self, set: impl IntoSystemSet<M>) -> Self {
+        let set = set.into_set();
+        for config in &mut self.sets {
+            config
+                .graph_info
+                .dependencies
+                .push(Dependency::new(DependencyKind::Before, set.dyn_clone()));
+        }
+        self
+    }
+
+    fn after<M>(mut self, set: impl IntoSystemSet<M>) -> Self {
+        let set = set.into_set();
+        for config in &mut self.sets {
+            config
+                .graph_info
+                .dependencies
+                .push(Dependency::new(DependencyKind::After, set.dyn_clone()));
+        }
+        self
+    }
+
+    fn ambiguous_with<M>(mut self, set: impl IntoSystemSet<M>) -> Self {
+        let set = set.into_set();
+        for config in &mut self.sets {
+            ambiguous_with(&mut config.graph_info, set.dyn_clone());
+        }
+        self
+    }
+
+    fn ambiguous_with_all(mut self) -> Self {
+        for config in &mut self.sets {
+            config.graph_info.ambiguous_with = Ambiguity::IgnoreAll;
+        }
+        self
+    }
+
+    fn chain(mut self) -> Self {
+        self.chained = true;
+        self
+    }
+}
+
+macro_rules! impl_system_collection {
+    ($(($param: ident, $sys: ident)),*) => {
+        impl<$($param, $sys),*> IntoSystemConfigs<($($param,)*)> for ($($sys,)*)
+        where
+            $($sys: IntoSystemConfig<$param>),*
+        {
+            #[allow(non_snake_case)]
+            fn into_configs(self) -> SystemConfigs {
+                let ($($sys,)*) = self;
+                SystemConfigs {
+                    systems: vec![$($sys.into_config(),)*],
+                    chained: false,
+                }
+            }
+        }
+    }
+}
+
+macro_rules! impl_system_set_collection {
+    ($($set: ident),*) => {
+        impl<$($set: IntoSystemSetConfig),*> IntoSystemSetConfigs for ($($set,)*)
+        {
+            #[allow(non_snake_case)]
+            fn into_configs(self) -> SystemSetConfigs {
+                let ($($set,)*) = self;
+                SystemSetConfigs {
+                    sets: vec![$($set.into_config(),)*],
+                    chained: false,
+                }
+            }
+        }
+    }
+}
+
+all_tuples!(impl_system_collection, 0, 15, P, S);
+all_tuples!(impl_system_set_collection, 0, 15, S);
@@ -0,0 +1,121 @@
+mod multi_threaded;
+mod single_threaded;
+
+pub use self::{multi_threaded::MultiThreadedExecutor, single_threaded::SingleThreadedExecutor};
+
+use fixedbitset::FixedBitSet;
+
+use crate::{
+    schedule_v3::{BoxedCondition, NodeId},
+    system::BoxedSystem,
+    world::World,
+};
+
+/// Types that can run a [`SystemSchedule`] on a [`World`].
+pub(super) trait SystemExecutor: Send + Sync {
+    fn kind(&self) -> ExecutorKind;
+    fn init(&mut self, schedule: &SystemSchedule);
+    fn run(&mut self, schedule: &mut SystemSchedule, world: &mut World);
+}
+
+/// Specifies how a [`Schedule`](super::Schedule) will be run.
+///
+/// The default depends on the target platform:
+///  - [`SingleThreaded`](ExecutorKind::SingleThreaded) on WASM.
+///  - [`MultiThreaded`](ExecutorKind::MultiThreaded) everywhere else.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub enum ExecutorKind {
+    /// Runs the schedule using a single thread.
+    ///
+    /// Useful if you're dealing with a single-threaded environment, saving your threads for
+    /// other things, or just trying minimize overhead.
+    SingleThreaded,
+    /// Runs the schedule using a thread pool. Non-conflicting systems can run in parallel.
+    MultiThreaded,
+}
+
+impl Default for ExecutorKind {
+    fn default() -> Self {
+        if cfg!(target_arch = "wasm32") {
+            ExecutorKind::SingleThreaded
+        } else {
+            ExecutorKind::MultiThreaded
+        }
+    }
+}
+
+/// Holds systems and conditions of a [`Schedule`](super::Schedule) sorted in topological order
+/// (along with dependency information for multi-threaded execution).
+///
+/// Since the arrays are sorted in the same order, elements are referenced by their index.
+/// `FixedBitSet` is used as a smaller, more efficient substitute of `HashSet<usize>`.
+#[derive(Default)]
+pub(super) struct SystemSchedule {
+    pub(super) systems: Vec<BoxedSystem>,
+    pub(super) system_conditions: Vec<Vec<BoxedCondition>>,
+    pub(super) set_conditions: Vec<Vec<BoxedCondition>>,
+    pub(super) system_ids: Vec<NodeId>,
+    pub(super) set_ids: Vec<NodeId>,
+    pub(super) system_dependencies: Vec<usize>,
+    pub(super) system_dependents: Vec<Vec<usize>>,
+    pub(super) sets_with_conditions_of_systems: Vec<FixedBitSet>,
+    pub(super) systems_in_sets_with_conditions: Vec<FixedBitSet>,
+}
+
+impl SystemSchedule {
+    pub const fn new() -> Self {
+        Self {
+            systems: Vec::new(),
+            system_conditions: Vec::new(),
+            set_conditions: Vec::new(),
+            system_ids: Vec::new(),
+            set_ids: Vec::new(),
+            system_dependencies: Vec::new(),
+            system_dependents: Vec::new(),
+            sets_with_conditions_of_systems: Vec::new(),
+            systems_in_sets_with_conditions: Vec::new(),
+        }
+    }
+}
+
+/// Instructs the executor to call [`apply_buffers`](crate::system::System::apply_buffers)
+/// on the systems that have run but not applied their buffers.
+///
+/// Unless disabled with
+/// [`ScheduleBuildSettings::auto_insert_apply_system_buffers`](super::ScheduleBuildSettings::auto_insert_apply_system_buffers),
+/// the schedule inserts this system automatically between systems with buffers and the systems
+/// ordered after them.
+///
+/// **Notes**
+/// - This function (currently) does nothing if it's called manually or wrapped inside a
+///   [`ChainSystem`](crate::system::ChainSystem).
+/// - Modifying a [`Schedule`](super::Schedule) may change the order buffers are applied.
+#[allow(unused_variables)]
+pub fn apply_system_buffers(world: &mut World) {}
+
+/// Returns `true` if the [`System`](crate::system::System) is an instance of
+/// [`apply_system_buffers`].
+pub(super) fn is_apply_system_buffers(system: &BoxedSystem) -> bool {
+    fn type_name_of<T>(_: &T) -> &'static str {
+        std::any::type_name::<T>()
+    }
+
+    system.is_exclusive() && system.name() == type_name_of(&apply_system_buffers)
+}
+
+/// Applies the buffers of the systems in `unapplied_systems`, in schedule order, and clears it.
+pub(super) fn apply_unapplied_buffers(
+    unapplied_systems: &mut FixedBitSet,
+    systems: &mut [BoxedSystem],
+    world: &mut World,
+) {
+    for system_index in unapplied_systems.ones() {
+        let system = &mut systems[system_index];
+        #[cfg(feature = "trace")]
+        let _apply_buffers_span =
+            bevy_utils::tracing::info_span!("apply_buffers", name = &*system.name()).entered();
+        system.apply_buffers(world);
+    }
+
+    unapplied_systems.clear();
+}
@@ -0,0 +1,500 @@
+use async_channel::{Receiver, Sender};
+use bevy_tasks::{ComputeTaskPool, TaskPool};
+#[cfg(feature = "trace")]
+use bevy_utils::tracing::Instrument;
+use fixedbitset::FixedBitSet;
+
+use crate::{
+    archetype::ArchetypeComponentId,
+    query::Access,
+    schedule_v3::{
+        executor::{apply_unapplied_buffers, is_apply_system_buffers},
+        BoxedCondition, ExecutorKind, SystemExecutor, SystemSchedule,
+    },
+    world::World,
+};
+
+/// Per-system data used by the [`MultiThreadedExecutor`].
+struct SystemTaskMetadata {
+    /// Used to tell the system's task whether to run the system (`true`) or to exit (`false`).
+    start_sender: Sender<bool>,
+    /// Receives the signal sent through `start_sender`.
+    start_receiver: Receiver<bool>,
+    /// Indices of the systems that directly depend on the system.
+    dependents: Vec<usize>,
+    /// The `ArchetypeComponentId` access of the system.
+    archetype_component_access: Access<ArchetypeComponentId>,
+    /// Is `true` if the system does not access `!Send` data.
+    is_send: bool,
+    /// Is `true` if the system is exclusive.
+    is_exclusive: bool,
+}
+
+/// The run conditions of a [`SystemSchedule`], borrowed separately from its systems.
+struct Conditions<'a> {
+    system_conditions: &'a mut [Vec<BoxedCondition>],
+    set_conditions: &'a mut [Vec<BoxedCondition>],
+    sets_with_conditions_of_systems: &'a [FixedBitSet],
+    systems_in_sets_with_conditions: &'a [FixedBitSet],
+}
+
+/// Runs the schedule using a thread pool. Non-conflicting systems can run in parallel.
+///
+/// The schedule runs in phases. In between phases, ready exclusive systems run one after the
+/// other with mutable access to the [`World`]. During a phase, every other ready system is started
+/// on the task pool as soon as it does not conflict with the systems that are running, until no
+/// more systems can make progress without an exclusive system.
+pub struct MultiThreadedExecutor {
+    /// Sends system completion events.
+    sender: Sender<usize>,
+    /// Receives system completion events.
+    receiver: Receiver<usize>,
+    /// Metadata for scheduling and running system tasks.
+    system_task_metadata: Vec<SystemTaskMetadata>,
+    /// The number of dependencies each system has that have not completed.
+    num_dependencies_remaining: Vec<usize>,
+    /// Union of the accesses of all currently running systems.
+    active_access: Access<ArchetypeComponentId>,
+    /// Returns `true` if a system with non-`Send` access is running.
+    local_thread_running: bool,
+    /// System sets whose conditions have been evaluated.
+    evaluated_sets: FixedBitSet,
+    /// Systems that have no remaining dependencies and are waiting to run.
+    ready_systems: FixedBitSet,
+    /// Copy of `ready_systems`, iterated over while `ready_systems` changes.
+    ready_systems_copy: FixedBitSet,
+    /// Systems that are running.
+    running_systems: FixedBitSet,
+    /// Systems that got skipped by a condition of one of their sets.
+    skipped_systems: FixedBitSet,
+    /// Systems that have run or been skipped.
+    completed_systems: FixedBitSet,
+    /// Systems that have run but have not had their buffers applied.
+    unapplied_systems: FixedBitSet,
+}
+
+impl Default for MultiThreadedExecutor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SystemExecutor for MultiThreadedExecutor {
+    fn kind(&self) -> ExecutorKind {
+        ExecutorKind::MultiThreaded
+    }
+
+    fn init(&mut self, schedule: &SystemSchedule) {
+        // pre-allocate space
+        let sys_count = schedule.system_ids.len();
+        let set_count = schedule.set_ids.len();
+
+        self.evaluated_sets = FixedBitSet::with_capacity(set_count);
+        self.ready_systems = FixedBitSet::with_capacity(sys_count);
+        self.ready_systems_copy = FixedBitSet::with_capacity(sys_count);
+        self.running_systems = FixedBitSet::with_capacity(sys_count);
+        self.skipped_systems = FixedBitSet::with_capacity(sys_count);
+        self.completed_systems = FixedBitSet::with_capacity(sys_count);
+        self.unapplied_systems = FixedBitSet::with_capacity(sys_count);
+
+        self.system_task_metadata = Vec::with_capacity(sys_count);
+        for index in 0..sys_count {
+            let (start_sender, start_receiver) = async_channel::bounded(1);
+            let system = &schedule.systems[index];
+            self.system_task_metadata.push(SystemTaskMetadata {
+                start_sender,
+                start_receiver,
+                dependents: schedule.system_dependents[index].clone(),
+                archetype_component_access: Access::default(),
+                is_send: system.is_send(),
+                is_exclusive: system.is_exclusive(),
+            });
+        }
+
+        self.num_dependencies_remaining = Vec::with_capacity(sys_count);
+    }
+
+    fn run(&mut self, schedule: &mut SystemSchedule, world: &mut World) {
+        // reset counts
+        let num_systems = schedule.systems.len();
+        self.num_dependencies_remaining.clear();
+        self.num_dependencies_remaining
+            .extend_from_slice(&schedule.system_dependencies);
+
+        for (system_index, dependencies) in self.num_dependencies_remaining.iter().enumerate() {
+            if *dependencies == 0 {
+                self.ready_systems.insert(system_index);
+            }
+        }
+
+        while self.completed_systems.count_ones(..) != num_systems {
+            self.run_exclusive_systems(schedule, world);
+
+            let has_ready_parallel_systems = self
+                .ready_systems
+                .ones()
+                .any(|index| !self.system_task_metadata[index].is_exclusive);
+            if has_ready_parallel_systems {
+                self.run_parallel_systems(schedule, world);
+            }
+        }
+
+        apply_unapplied_buffers(&mut self.unapplied_systems, &mut schedule.systems, world);
+        debug_assert!(self.ready_systems.is_clear());
+        debug_assert!(self.running_systems.is_clear());
+        self.evaluated_sets.clear();
+        self.skipped_systems.clear();
+        self.completed_systems.clear();
+    }
+}
+
+impl MultiThreadedExecutor {
+    pub fn new() -> Self {
+        let (sender, receiver) = async_channel::unbounded();
+        Self {
+            sender,
+            receiver,
+            system_task_metadata: Vec::new(),
+            num_dependencies_remaining: Vec::new(),
+            active_access: Access::default(),
+            local_thread_running: false,
+            evaluated_sets: FixedBitSet::new(),
+            ready_systems: FixedBitSet::new(),
+            ready_systems_copy: FixedBitSet::new(),
+            running_systems: FixedBitSet::new(),
+            skipped_systems: FixedBitSet::new(),
+            completed_systems: FixedBitSet::new(),
+            unapplied_systems: FixedBitSet::new(),
+        }
+    }
+
+    /// Runs the ready exclusive systems one after the other, and completes the ready systems that
+    /// were skipped by their sets.
+    fn run_exclusive_systems(&mut self, schedule: &mut SystemSchedule, world: &mut World) {
+        while let Some(system_index) = self.ready_systems.ones().find(|&index| {
+            self.system_task_metadata[index].is_exclusive || self.skipped_systems.contains(index)
+        }) {
+            self.ready_systems.set(system_index, false);
+
+            let mut conditions = Conditions {
+                system_conditions: &mut schedule.system_conditions,
+                set_conditions: &mut schedule.set_conditions,
+                sets_with_conditions_of_systems: &schedule.sets_with_conditions_of_systems,
+                systems_in_sets_with_conditions: &schedule.systems_in_sets_with_conditions,
+            };
+            // SAFETY: no system is running
+            let should_run = unsafe { self.should_run(system_index, &mut conditions, world) };
+
+            if should_run {
+                let system = &mut schedule.systems[system_index];
+                if is_apply_system_buffers(system) {
+                    apply_unapplied_buffers(
+                        &mut self.unapplied_systems,
+                        &mut schedule.systems,
+                        world,
+                    );
+                } else {
+                    #[cfg(feature = "trace")]
+                    let _system_span =
+                        bevy_utils::tracing::info_span!("system", name = &*system.name()).entered();
+                    system.run((), world);
+                }
+            }
+
+            self.finish_system(system_index, false);
+        }
+    }
+
+    /// Runs ready systems on the task pool until only exclusive systems can make progress.
+    fn run_parallel_systems(&mut self, schedule: &mut SystemSchedule, world: &mut World) {
+        let world = &*world;
+
+        {
+            #[cfg(feature = "trace")]
+            let _span = bevy_utils::tracing::info_span!("update_archetypes").entered();
+            // the world cannot change structurally until the phase ends
+            for (system_index, system) in schedule.systems.iter_mut().enumerate() {
+                let meta = &mut self.system_task_metadata[system_index];
+                if meta.is_exclusive || self.completed_systems.contains(system_index) {
+                    continue;
+                }
+                system.update_archetype_component_access(world);
+                meta.archetype_component_access
+                    .extend(system.archetype_component_access());
+            }
+            for condition in schedule
+                .system_conditions
+                .iter_mut()
+                .chain(schedule.set_conditions.iter_mut())
+                .flatten()
+            {
+                condition.update_archetype_component_access(world);
+            }
+        }
+
+        let SystemSchedule {
+            systems,
+            system_conditions,
+            set_conditions,
+            sets_with_conditions_of_systems,
+            systems_in_sets_with_conditions,
+            ..
+        } = schedule;
+        let mut conditions = Conditions {
+            system_conditions,
+            set_conditions,
+            sets_with_conditions_of_systems,
+            systems_in_sets_with_conditions,
+        };
+
+        ComputeTaskPool::init(TaskPool::default).scope(|scope| {
+            // spawn a task for every system that may run in this phase
+            for (system_index, system) in systems.iter_mut().enumerate() {
+                let meta = &self.system_task_metadata[system_index];
+                if meta.is_exclusive || self.completed_systems.contains(system_index) {
+                    continue;
+                }
+
+                let start_receiver = meta.start_receiver.clone();
+                let finish_sender = self.sender.clone();
+                #[cfg(feature = "trace")] // NB: outside the task to get the TLS current span
+                let system_span = bevy_utils::tracing::info_span!("system", name = &*system.name());
+                #[cfg(feature = "trace")]
+                let overhead_span =
+                    bevy_utils::tracing::info_span!("system overhead", name = &*system.name());
+                let task = async move {
+                    if start_receiver.recv().await.unwrap_or(false) {
+                        #[cfg(feature = "trace")]
+                        let system_guard = system_span.enter();
+                        // SAFETY: the executor prevents two systems with conflicting access from
+                        // running simultaneously.
+                        unsafe { system.run_unsafe((), world) };
+                        #[cfg(feature = "trace")]
+                        drop(system_guard);
+                        finish_sender
+                            .send(system_index)
+                            .await
+                            .unwrap_or_else(|error| unreachable!("{}", error));
+                    }
+                };
+
+                #[cfg(feature = "trace")]
+                let task = task.instrument(overhead_span);
+                if meta.is_send {
+                    scope.spawn(task);
+                } else {
+                    scope.spawn_local(task);
+                }
+            }
+
+            // conditions can access `!Send` data, so the executor runs on the local thread
+            let executor = async {
+                loop {
+                    self.start_ready_systems(&mut conditions, world);
+                    if self.running_systems.is_clear() {
+                        break;
+                    }
+
+                    // wait until at least one system has finished
+                    let index = self
+                        .receiver
+                        .recv()
+                        .await
+                        .unwrap_or_else(|error| unreachable!("{}", error));
+                    self.finish_system(index, true);
+                    // gather other systems than may have finished
+                    while let Ok(index) = self.receiver.try_recv() {
+                        self.finish_system(index, true);
+                    }
+                    self.rebuild_active_access();
+                }
+
+                // end the tasks of the systems that did not get to run in this phase
+                for (system_index, meta) in self.system_task_metadata.iter().enumerate() {
+                    if !meta.is_exclusive && !self.completed_systems.contains(system_index) {
+                        meta.start_sender
+                            .try_send(false)
+                            .unwrap_or_else(|error| unreachable!("{}", error));
+                    }
+                }
+            };
+
+            #[cfg(feature = "trace")]
+            let executor_span = bevy_utils::tracing::info_span!("multithreaded executor");
+            #[cfg(feature = "trace")]
+            let executor = executor.instrument(executor_span);
+            scope.spawn_local(executor);
+        });
+    }
+
+    /// Starts every ready system that can run next to the running systems, and completes the
+    /// ones that should not run.
+    fn start_ready_systems(&mut self, conditions: &mut Conditions, world: &World) {
+        loop {
+            let mut completed_any = false;
+            // iterate a copy, so that the loop can update `ready_systems`
+            let mut ready_systems = std::mem::take(&mut self.ready_systems_copy);
+            ready_systems.clone_from(&self.ready_systems);
+            for system_index in ready_systems.ones() {
+                if self.system_task_metadata[system_index].is_exclusive
+                    || !self.can_run(system_index, conditions)
+                {
+                    continue;
+                }
+
+                self.ready_systems.set(system_index, false);
+
+                // SAFETY: `can_run` checked that the conditions of the system and of its sets do
+                // not conflict with the running systems
+                if !unsafe { self.should_run(system_index, conditions, world) } {
+                    self.system_task_metadata[system_index]
+                        .start_sender
+                        .try_send(false)
+                        .unwrap_or_else(|error| unreachable!("{}", error));
+                    self.finish_system(system_index, false);
+                    completed_any = true;
+                    continue;
+                }
+
+                let meta = &self.system_task_metadata[system_index];
+                self.running_systems.insert(system_index);
+                self.active_access.extend(&meta.archetype_component_access);
+                if !meta.is_send {
+                    self.local_thread_running = true;
+                }
+                meta.start_sender
+                    .try_send(true)
+                    .unwrap_or_else(|error| unreachable!("{}", error));
+            }
+            self.ready_systems_copy = ready_systems;
+
+            // skipped systems may have made other systems ready
+            if !completed_any {
+                break;
+            }
+        }
+    }
+
+    /// Returns `true` if the system and the conditions that will be evaluated before it do not
+    /// conflict with the running systems.
+    fn can_run(&self, system_index: usize, conditions: &Conditions) -> bool {
+        let meta = &self.system_task_metadata[system_index];
+        if !meta.is_send && self.local_thread_running {
+            return false;
+        }
+
+        let set_conditions = conditions.sets_with_conditions_of_systems[system_index]
+            .ones()
+            .filter(|set_idx| !self.evaluated_sets.contains(*set_idx))
+            .flat_map(|set_idx| conditions.set_conditions[set_idx].iter());
+        for condition in set_conditions.chain(&conditions.system_conditions[system_index]) {
+            if !condition
+                .archetype_component_access()
+                .is_compatible(&self.active_access)
+            {
+                return false;
+            }
+        }
+
+        self.skipped_systems.contains(system_index)
+            || meta
+                .archetype_component_access
+                .is_compatible(&self.active_access)
+    }
+
+    /// Evaluates the conditions of the system's sets that have not been evaluated yet, then the
+    /// conditions of the system, and returns `true` if the system should run.
+    ///
+    /// # Safety
+    ///
+    /// The conditions must not conflict with the running systems.
+    unsafe fn should_run(
+        &mut self,
+        system_index: usize,
+        conditions: &mut Conditions,
+        world: &World,
+    ) -> bool {
+        if self.skipped_systems.contains(system_index) {
+            return false;
+        }
+
+        let mut should_run = true;
+        for set_idx in conditions.sets_with_conditions_of_systems[system_index].ones() {
+            if self.evaluated_sets.contains(set_idx) {
+                continue;
+            }
+
+            // evaluate system set's conditions
+            let set_conditions_met =
+                evaluate_and_fold_conditions(&mut conditions.set_conditions[set_idx], world);
+
+            if !set_conditions_met {
+                self.skipped_systems
+                    .union_with(&conditions.systems_in_sets_with_conditions[set_idx]);
+            }
+
+            should_run &= set_conditions_met;
+            self.evaluated_sets.insert(set_idx);
+        }
+
+        if should_run {
+            // evaluate system's conditions
+            should_run = evaluate_and_fold_conditions(
+                &mut conditions.system_conditions[system_index],
+                world,
+            );
+        }
+
+        should_run
+    }
+
+    /// Marks the system as completed and readies the dependents that have no remaining
+    /// dependencies.
+    fn finish_system(&mut self, system_index: usize, ran_in_task: bool) {
+        if ran_in_task {
+            self.running_systems.set(system_index, false);
+            self.unapplied_systems.insert(system_index);
+            if !self.system_task_metadata[system_index].is_send {
+                self.local_thread_running = false;
+            }
+        }
+        self.completed_systems.insert(system_index);
+
+        for &dependent in &self.system_task_metadata[system_index].dependents {
+            self.num_dependencies_remaining[dependent] -= 1;
+            if self.num_dependencies_remaining[dependent] == 0 {
+                self.ready_systems.insert(dependent);
+            }
+        }
+    }
+
+    fn rebuild_active_access(&mut self) {
+        self.active_access.clear();
+        for index in self.running_systems.ones() {
+            let system_meta = &self.system_task_metadata[index];
+            self.active_access
+                .extend(&system_meta.archetype_component_access);
+        }
+    }
+}
+
+/// Evaluates every condition, so that each one is run exactly once, and returns `true` if they
+/// all are `true`.
+///
+/// # Safety
+///
+/// The conditions must not conflict with any system running at the same time.
+unsafe fn evaluate_and_fold_conditions(conditions: &mut [BoxedCondition], world: &World) -> bool {
+    #[allow(clippy::unnecessary_fold)]
+    conditions
+        .iter_mut()
+        .map(|condition| {
+            #[cfg(feature = "trace")]
+            let _condition_span =
+                bevy_utils::tracing::info_span!("condition", name = &*condition.name()).entered();
+            condition.update_archetype_component_access(world);
+            // SAFETY: caller ensures the condition does not conflict with running systems
+            condition.run_unsafe((), world)
+        })
+        .fold(true, |acc, res| acc && res)
+}
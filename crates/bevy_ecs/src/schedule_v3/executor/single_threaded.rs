@@ -0,0 +1,112 @@
+use fixedbitset::FixedBitSet;
+
+use crate::{
+    schedule_v3::{
+        executor::{apply_unapplied_buffers, is_apply_system_buffers},
+        BoxedCondition, ExecutorKind, SystemExecutor, SystemSchedule,
+    },
+    world::World,
+};
+
+/// Runs the schedule using a single thread.
+///
+/// Useful if you're dealing with a single-threaded environment, saving your threads for
+/// other things, or just trying minimize overhead.
+#[derive(Default)]
+pub struct SingleThreadedExecutor {
+    /// System sets whose conditions have been evaluated.
+    evaluated_sets: FixedBitSet,
+    /// Systems that have run or been skipped.
+    completed_systems: FixedBitSet,
+    /// Systems that have run but have not had their buffers applied.
+    unapplied_systems: FixedBitSet,
+}
+
+impl SystemExecutor for SingleThreadedExecutor {
+    fn kind(&self) -> ExecutorKind {
+        ExecutorKind::SingleThreaded
+    }
+
+    fn init(&mut self, schedule: &SystemSchedule) {
+        // pre-allocate space
+        let sys_count = schedule.system_ids.len();
+        let set_count = schedule.set_ids.len();
+        self.evaluated_sets = FixedBitSet::with_capacity(set_count);
+        self.completed_systems = FixedBitSet::with_capacity(sys_count);
+        self.unapplied_systems = FixedBitSet::with_capacity(sys_count);
+    }
+
+    fn run(&mut self, schedule: &mut SystemSchedule, world: &mut World) {
+        for system_index in 0..schedule.systems.len() {
+            // the system was skipped by a condition of one of its sets
+            if self.completed_systems.contains(system_index) {
+                continue;
+            }
+
+            let mut should_run = true;
+            for set_idx in schedule.sets_with_conditions_of_systems[system_index].ones() {
+                if self.evaluated_sets.contains(set_idx) {
+                    continue;
+                }
+
+                // evaluate system set's conditions
+                let set_conditions_met =
+                    evaluate_and_fold_conditions(&mut schedule.set_conditions[set_idx], world);
+
+                if !set_conditions_met {
+                    self.completed_systems
+                        .union_with(&schedule.systems_in_sets_with_conditions[set_idx]);
+                }
+
+                should_run &= set_conditions_met;
+                self.evaluated_sets.insert(set_idx);
+            }
+
+            if should_run {
+                // evaluate system's conditions
+                should_run = evaluate_and_fold_conditions(
+                    &mut schedule.system_conditions[system_index],
+                    world,
+                );
+            }
+
+            // system has either been skipped or will run
+            self.completed_systems.insert(system_index);
+
+            if !should_run {
+                continue;
+            }
+
+            let system = &mut schedule.systems[system_index];
+            if is_apply_system_buffers(system) {
+                apply_unapplied_buffers(&mut self.unapplied_systems, &mut schedule.systems, world);
+                continue;
+            }
+
+            #[cfg(feature = "trace")]
+            let _system_span =
+                bevy_utils::tracing::info_span!("system", name = &*system.name()).entered();
+            system.run((), world);
+            self.unapplied_systems.insert(system_index);
+        }
+
+        apply_unapplied_buffers(&mut self.unapplied_systems, &mut schedule.systems, world);
+        self.evaluated_sets.clear();
+        self.completed_systems.clear();
+    }
+}
+
+/// Evaluates every condition, so that each one is run exactly once, and returns `true` if they
+/// all are `true`.
+fn evaluate_and_fold_conditions(conditions: &mut [BoxedCondition], world: &mut World) -> bool {
+    #[allow(clippy::unnecessary_fold)]
+    conditions
+        .iter_mut()
+        .map(|condition| {
+            #[cfg(feature = "trace")]
+            let _condition_span =
+                bevy_utils::tracing::info_span!("condition", name = &*condition.name()).entered();
+            condition.run((), world)
+        })
+        .fold(true, |acc, res| acc && res)
+}
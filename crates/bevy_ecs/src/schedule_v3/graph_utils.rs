@@ -0,0 +1,232 @@
+use std::collections::VecDeque;
+
+use bevy_utils::{HashMap, HashSet};
+
+use crate::schedule_v3::set::BoxedSystemSet;
+
+/// Unique identifier for a system or system set in a [`ScheduleGraph`](super::ScheduleGraph).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum NodeId {
+    System(usize),
+    Set(usize),
+}
+
+impl NodeId {
+    /// Returns the internal integer value.
+    pub fn index(&self) -> usize {
+        match self {
+            NodeId::System(index) | NodeId::Set(index) => *index,
+        }
+    }
+
+    /// Returns `true` if the identified node is a system.
+    pub const fn is_system(&self) -> bool {
+        matches!(self, NodeId::System(_))
+    }
+
+    /// Returns `true` if the identified node is a system set.
+    pub const fn is_set(&self) -> bool {
+        matches!(self, NodeId::Set(_))
+    }
+}
+
+/// Specifies what kind of edge should be added to the dependency graph.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) enum DependencyKind {
+    /// A node that should be preceded.
+    Before,
+    /// A node that should be succeeded.
+    After,
+}
+
+/// An edge to be added to the dependency graph.
+#[derive(Clone)]
+pub(crate) struct Dependency {
+    pub(crate) kind: DependencyKind,
+    pub(crate) set: BoxedSystemSet,
+}
+
+impl Dependency {
+    pub fn new(kind: DependencyKind, set: BoxedSystemSet) -> Self {
+        Self { kind, set }
+    }
+}
+
+/// Configures how execution order ambiguities of a node are reported.
+#[derive(Clone, Default)]
+pub(crate) enum Ambiguity {
+    #[default]
+    Check,
+    /// Ignore the ambiguities with the systems in any of these sets.
+    IgnoreWithSet(Vec<BoxedSystemSet>),
+    /// Ignore all ambiguities.
+    IgnoreAll,
+}
+
+/// Metadata about how a node fits in the schedule graph.
+#[derive(Clone, Default)]
+pub(crate) struct GraphInfo {
+    pub(crate) sets: Vec<BoxedSystemSet>,
+    pub(crate) dependencies: Vec<Dependency>,
+    pub(crate) ambiguous_with: Ambiguity,
+}
+
+/// A directed graph whose nodes and edges are kept in insertion order, so that everything derived
+/// from it, like its topological order, is deterministic.
+#[derive(Default)]
+pub(crate) struct DiGraph {
+    nodes: Vec<NodeId>,
+    successors: HashMap<NodeId, Vec<NodeId>>,
+    predecessors: HashMap<NodeId, Vec<NodeId>>,
+}
+
+impl DiGraph {
+    pub fn add_node(&mut self, node: NodeId) {
+        if !self.successors.contains_key(&node) {
+            self.nodes.push(node);
+            self.successors.insert(node, Vec::new());
+            self.predecessors.insert(node, Vec::new());
+        }
+    }
+
+    /// Adds an edge from `a` to `b`, adding both nodes if they are missing.
+    pub fn add_edge(&mut self, a: NodeId, b: NodeId) {
+        self.add_node(a);
+        self.add_node(b);
+        let successors = self.successors.get_mut(&a).unwrap();
+        if !successors.contains(&b) {
+            successors.push(b);
+            self.predecessors.get_mut(&b).unwrap().push(a);
+        }
+    }
+
+    pub fn nodes(&self) -> impl Iterator<Item = NodeId> + '_ {
+        self.nodes.iter().copied()
+    }
+
+    pub fn edges(&self) -> impl Iterator<Item = (NodeId, NodeId)> + '_ {
+        self.nodes
+            .iter()
+            .flat_map(|a| self.successors[a].iter().map(move |b| (*a, *b)))
+    }
+
+    pub fn successors(&self, node: NodeId) -> &[NodeId] {
+        self.successors.get(&node).map_or(&[], Vec::as_slice)
+    }
+
+    pub fn predecessors(&self, node: NodeId) -> &[NodeId] {
+        self.predecessors.get(&node).map_or(&[], Vec::as_slice)
+    }
+
+    /// Returns the nodes in topological order, or a cycle if there is none.
+    ///
+    /// Nodes that are not ordered relative to each other keep their insertion order.
+    pub fn toposort(&self) -> Result<Vec<NodeId>, Vec<NodeId>> {
+        let mut in_degrees: HashMap<NodeId, usize> = self
+            .nodes
+            .iter()
+            .map(|node| (*node, self.predecessors[node].len()))
+            .collect();
+        let mut queue: VecDeque<NodeId> = self
+            .nodes
+            .iter()
+            .copied()
+            .filter(|node| in_degrees[node] == 0)
+            .collect();
+
+        let mut sorted = Vec::with_capacity(self.nodes.len());
+        while let Some(node) = queue.pop_front() {
+            sorted.push(node);
+            for successor in &self.successors[&node] {
+                let in_degree = in_degrees.get_mut(successor).unwrap();
+                *in_degree -= 1;
+                if *in_degree == 0 {
+                    queue.push_back(*successor);
+                }
+            }
+        }
+
+        if sorted.len() == self.nodes.len() {
+            Ok(sorted)
+        } else {
+            let remaining: HashSet<NodeId> = in_degrees
+                .into_iter()
+                .filter(|(_, in_degree)| *in_degree > 0)
+                .map(|(node, _)| node)
+                .collect();
+            Err(self.find_cycle(&remaining))
+        }
+    }
+
+    /// Finds a cycle among `remaining`, the nodes left over by a topological sort.
+    ///
+    /// Every remaining node has a remaining predecessor, so walking predecessors must eventually
+    /// revisit a node.
+    fn find_cycle(&self, remaining: &HashSet<NodeId>) -> Vec<NodeId> {
+        let start = self
+            .nodes
+            .iter()
+            .copied()
+            .find(|node| remaining.contains(node))
+            .unwrap();
+        let mut path = vec![start];
+        let mut visited = HashMap::default();
+        visited.insert(start, 0);
+        let mut current = start;
+        loop {
+            current = self.predecessors[&current]
+                .iter()
+                .copied()
+                .find(|node| remaining.contains(node))
+                .unwrap();
+            if let Some(&index) = visited.get(&current) {
+                // the path follows predecessors, so reverse it and start from `current`
+                let mut cycle = path.split_off(index);
+                cycle.reverse();
+                cycle.rotate_right(1);
+                return cycle;
+            }
+            visited.insert(current, path.len());
+            path.push(current);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DiGraph, NodeId};
+
+    #[test]
+    fn toposort_keeps_insertion_order() {
+        let mut graph = DiGraph::default();
+        for index in 0..4 {
+            graph.add_node(NodeId::System(index));
+        }
+        graph.add_edge(NodeId::System(3), NodeId::System(1));
+
+        assert_eq!(
+            graph.toposort(),
+            Ok(vec![
+                NodeId::System(0),
+                NodeId::System(2),
+                NodeId::System(3),
+                NodeId::System(1)
+            ])
+        );
+    }
+
+    #[test]
+    fn toposort_finds_cycle() {
+        let mut graph = DiGraph::default();
+        graph.add_edge(NodeId::System(0), NodeId::System(1));
+        graph.add_edge(NodeId::System(1), NodeId::System(2));
+        graph.add_edge(NodeId::System(2), NodeId::System(3));
+        graph.add_edge(NodeId::System(3), NodeId::System(1));
+
+        let cycle = graph.toposort().unwrap_err();
+        assert_eq!(
+            cycle,
+            vec![NodeId::System(1), NodeId::System(2), NodeId::System(3)]
+        );
+    }
+}
@@ -0,0 +1,519 @@
+//! Tools for controlling system execution without stages.
+//!
+//! A [`Schedule`] holds every system in a single dependency graph. Systems can be grouped into
+//! hierarchical [`SystemSet`]s, and both systems and sets can be ordered with `before`/`after`
+//! and gated by boolean run conditions, which compose with [`Condition::and_then`],
+//! [`Condition::or_else`] and [`common_conditions::not`].
+//!
+//! Commands and other deferred changes are applied by [`apply_system_buffers`], which can be
+//! added explicitly or is inserted automatically between a system with buffers and the systems
+//! ordered after it (see [`ScheduleBuildSettings`]).
+mod condition;
+mod config;
+mod executor;
+mod graph_utils;
+mod schedule;
+mod set;
+mod state;
+
+pub use self::condition::*;
+pub use self::config::*;
+pub use self::executor::{
+    apply_system_buffers, ExecutorKind, MultiThreadedExecutor, SingleThreadedExecutor,
+};
+pub use self::graph_utils::NodeId;
+pub use self::schedule::*;
+pub use self::set::*;
+pub use self::state::*;
+
+use self::executor::{SystemExecutor, SystemSchedule};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate as bevy_ecs;
+    use crate::schedule::{AmbiguityReportLevel, ReportExecutionOrderAmbiguities};
+    use crate::system::{Commands, Res, ResMut, Resource};
+    use crate::world::World;
+
+    #[derive(Resource, Default)]
+    struct SystemOrder(Vec<u32>);
+
+    #[derive(Resource, Default)]
+    struct Counter(u32);
+
+    #[derive(Resource)]
+    struct Flag;
+
+    #[derive(SystemSet, Clone, Debug, PartialEq, Eq, Hash)]
+    enum TestSet {
+        A,
+        B,
+        C,
+    }
+
+    fn make_function_system(tag: u32) -> impl FnMut(ResMut<SystemOrder>) {
+        move |mut order: ResMut<SystemOrder>| order.0.push(tag)
+    }
+
+    fn make_exclusive_system(tag: u32) -> impl FnMut(&mut World) {
+        move |world: &mut World| world.resource_mut::<SystemOrder>().0.push(tag)
+    }
+
+    fn run_with_both_executors(mut make_schedule: impl FnMut() -> Schedule) -> [Vec<u32>; 2] {
+        [ExecutorKind::SingleThreaded, ExecutorKind::MultiThreaded].map(|kind| {
+            let mut world = World::new();
+            world.init_resource::<SystemOrder>();
+            let mut schedule = make_schedule();
+            schedule.set_executor_kind(kind);
+            schedule.run(&mut world);
+            world.remove_resource::<SystemOrder>().unwrap().0
+        })
+    }
+
+    #[test]
+    fn add_systems_correct_order() {
+        for order in run_with_both_executors(|| {
+            let mut schedule = Schedule::new();
+            schedule.add_systems(
+                (
+                    make_function_system(0),
+                    make_exclusive_system(1),
+                    make_function_system(2),
+                    make_exclusive_system(3),
+                )
+                    .chain(),
+            );
+            schedule
+        }) {
+            assert_eq!(order, vec![0, 1, 2, 3]);
+        }
+    }
+
+    #[test]
+    fn order_systems_with_before_and_after() {
+        fn first(mut order: ResMut<SystemOrder>) {
+            order.0.push(0);
+        }
+        fn second(mut order: ResMut<SystemOrder>) {
+            order.0.push(1);
+        }
+        fn third(mut order: ResMut<SystemOrder>) {
+            order.0.push(2);
+        }
+
+        for order in run_with_both_executors(|| {
+            let mut schedule = Schedule::new();
+            schedule
+                .add_system(third.after(second))
+                .add_system(second)
+                .add_system(first.before(second));
+            schedule
+        }) {
+            assert_eq!(order, vec![0, 1, 2]);
+        }
+    }
+
+    #[test]
+    fn order_system_sets() {
+        for order in run_with_both_executors(|| {
+            let mut schedule = Schedule::new();
+            schedule
+                .configure_sets((TestSet::A, TestSet::B, TestSet::C).chain())
+                .add_system(make_function_system(2).in_set(TestSet::C))
+                .add_system(make_exclusive_system(1).in_set(TestSet::B))
+                .add_system(make_function_system(0).in_set(TestSet::A));
+            schedule
+        }) {
+            assert_eq!(order, vec![0, 1, 2]);
+        }
+    }
+
+    #[test]
+    fn nested_sets_inherit_order() {
+        #[derive(SystemSet, Clone, Debug, PartialEq, Eq, Hash)]
+        struct Inner;
+
+        for order in run_with_both_executors(|| {
+            let mut schedule = Schedule::new();
+            schedule
+                .configure_set(Inner.in_set(TestSet::B))
+                .configure_set(TestSet::A.before(TestSet::B))
+                .add_system(make_function_system(1).in_set(Inner))
+                .add_system(make_function_system(0).in_set(TestSet::A));
+            schedule
+        }) {
+            assert_eq!(order, vec![0, 1]);
+        }
+    }
+
+    #[test]
+    fn system_run_conditions() {
+        for order in run_with_both_executors(|| {
+            let mut schedule = Schedule::new();
+            schedule.add_systems(
+                (
+                    make_function_system(0).run_if(|| false),
+                    make_function_system(1).run_if(|| true),
+                    make_exclusive_system(2).run_if(|| false),
+                    make_exclusive_system(3).run_if(|| true),
+                )
+                    .chain(),
+            );
+            schedule
+        }) {
+            assert_eq!(order, vec![1, 3]);
+        }
+    }
+
+    #[test]
+    fn system_set_run_conditions() {
+        for order in run_with_both_executors(|| {
+            let mut schedule = Schedule::new();
+            schedule
+                .configure_set(TestSet::A.run_if(|| false))
+                .configure_set(TestSet::B.run_if(|| true).after(TestSet::A))
+                .add_system(make_function_system(0).in_set(TestSet::A))
+                .add_system(make_exclusive_system(1).in_set(TestSet::A))
+                .add_system(make_function_system(2).in_set(TestSet::B))
+                .add_system(make_exclusive_system(3).in_set(TestSet::B).run_if(|| false));
+            schedule
+        }) {
+            assert_eq!(order, vec![2]);
+        }
+    }
+
+    #[test]
+    fn conditions_are_evaluated_once_per_run() {
+        fn count(mut counter: ResMut<Counter>) -> bool {
+            counter.0 += 1;
+            true
+        }
+
+        let mut world = World::new();
+        world.init_resource::<Counter>();
+        let mut schedule = Schedule::new();
+        schedule
+            .configure_set(TestSet::A.run_if(count))
+            .add_systems((|| {}, || {}).in_set(TestSet::A));
+        schedule.run(&mut world);
+        schedule.run(&mut world);
+        assert_eq!(world.resource::<Counter>().0, 2);
+    }
+
+    #[test]
+    fn combined_conditions() {
+        use common_conditions::*;
+
+        let mut world = World::new();
+        world.init_resource::<SystemOrder>();
+        let mut schedule = Schedule::new();
+        schedule.add_systems(
+            (
+                make_function_system(0).run_if(not(resource_exists::<Flag>())),
+                make_function_system(1).run_if(resource_exists::<Flag>()),
+                make_function_system(2).run_if(resource_exists::<Flag>().and_then(|| true)),
+                make_function_system(3).run_if(resource_exists::<Flag>().or_else(|| true)),
+                make_function_system(4).run_if(run_once()),
+            )
+                .chain(),
+        );
+
+        schedule.run(&mut world);
+        assert_eq!(world.resource::<SystemOrder>().0, vec![0, 3, 4]);
+
+        world.resource_mut::<SystemOrder>().0.clear();
+        world.insert_resource(Flag);
+        schedule.run(&mut world);
+        assert_eq!(world.resource::<SystemOrder>().0, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn auto_insert_apply_system_buffers() {
+        fn insert_flag(mut commands: Commands) {
+            commands.insert_resource(Flag);
+        }
+        fn check_flag(flag: Option<Res<Flag>>, mut counter: ResMut<Counter>) {
+            if flag.is_some() {
+                counter.0 += 1;
+            }
+        }
+
+        let mut world = World::new();
+        world.init_resource::<Counter>();
+        let mut schedule = Schedule::new();
+        schedule.add_systems((insert_flag, check_flag).chain());
+        schedule.run(&mut world);
+        assert_eq!(world.resource::<Counter>().0, 1);
+
+        let mut world = World::new();
+        world.init_resource::<Counter>();
+        let mut schedule = Schedule::new();
+        schedule
+            .set_build_settings(ScheduleBuildSettings {
+                auto_insert_apply_system_buffers: false,
+            })
+            .add_systems((insert_flag, check_flag).chain());
+        schedule.run(&mut world);
+        assert_eq!(world.resource::<Counter>().0, 0);
+        // buffers are still applied at the end of the schedule
+        assert!(world.contains_resource::<Flag>());
+    }
+
+    #[test]
+    fn explicit_apply_system_buffers() {
+        fn insert_flag(mut commands: Commands) {
+            commands.insert_resource(Flag);
+        }
+        fn check_flag(flag: Option<Res<Flag>>, mut counter: ResMut<Counter>) {
+            if flag.is_some() {
+                counter.0 += 1;
+            }
+        }
+
+        let mut world = World::new();
+        world.init_resource::<Counter>();
+        let mut schedule = Schedule::new();
+        schedule
+            .set_build_settings(ScheduleBuildSettings {
+                auto_insert_apply_system_buffers: false,
+            })
+            .add_systems((insert_flag, apply_system_buffers, check_flag).chain());
+        schedule.run(&mut world);
+        assert_eq!(world.resource::<Counter>().0, 1);
+    }
+
+    #[test]
+    fn dependency_cycle() {
+        fn foo() {}
+        fn bar() {}
+
+        let mut world = World::new();
+        let mut schedule = Schedule::new();
+        schedule
+            .add_system(foo.after(bar))
+            .add_system(bar.after(foo));
+        assert!(matches!(
+            schedule.initialize(&mut world),
+            Err(ScheduleBuildError::DependencyCycle(_))
+        ));
+
+        let mut schedule = Schedule::new();
+        schedule.configure_sets((TestSet::A, TestSet::B, TestSet::A).chain());
+        assert!(matches!(
+            schedule.initialize(&mut world),
+            Err(ScheduleBuildError::DependencyCycle(_))
+        ));
+    }
+
+    #[test]
+    fn hierarchy_cycle() {
+        let mut world = World::new();
+        let mut schedule = Schedule::new();
+        schedule
+            .configure_set(TestSet::A.in_set(TestSet::B))
+            .configure_set(TestSet::B.in_set(TestSet::A));
+        assert!(matches!(
+            schedule.initialize(&mut world),
+            Err(ScheduleBuildError::HierarchyCycle(_))
+        ));
+    }
+
+    #[test]
+    fn cross_dependency() {
+        fn foo() {}
+
+        let mut world = World::new();
+        let mut schedule = Schedule::new();
+        schedule.add_system(foo.in_set(TestSet::A).after(TestSet::A));
+        assert!(matches!(
+            schedule.initialize(&mut world),
+            Err(ScheduleBuildError::CrossDependency(_, _))
+        ));
+    }
+
+    #[test]
+    fn sets_have_order_but_intersect() {
+        fn foo() {}
+
+        let mut world = World::new();
+        let mut schedule = Schedule::new();
+        schedule
+            .add_system(foo.in_set(TestSet::A).in_set(TestSet::B))
+            .configure_set(TestSet::A.before(TestSet::B));
+        assert!(matches!(
+            schedule.initialize(&mut world),
+            Err(ScheduleBuildError::SetsHaveOrderButIntersect(_, _))
+        ));
+    }
+
+    #[test]
+    fn ambiguous_system_type_set() {
+        fn foo() {}
+        fn bar() {}
+
+        let mut world = World::new();
+        let mut schedule = Schedule::new();
+        schedule.add_systems((foo, foo, bar.after(foo)));
+        assert!(matches!(
+            schedule.initialize(&mut world),
+            Err(ScheduleBuildError::SystemTypeSetAmbiguity(_))
+        ));
+    }
+
+    #[test]
+    #[should_panic]
+    fn in_system_type_set() {
+        fn foo() {}
+        fn bar() {}
+
+        let mut schedule = Schedule::new();
+        schedule.add_system(foo.in_set(bar.into_set()));
+    }
+
+    #[test]
+    fn state_transitions() {
+        #[derive(States, Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+        enum AppState {
+            #[default]
+            Menu,
+            InGame,
+        }
+
+        let mut world = World::new();
+        world.init_resource::<SystemOrder>();
+        world.init_resource::<State<AppState>>();
+        world.init_resource::<NextState<AppState>>();
+
+        let mut on_exit = Schedule::new();
+        on_exit.add_system(make_function_system(0));
+        world.add_schedule(OnExit(AppState::Menu), on_exit);
+        let mut on_enter = Schedule::new();
+        on_enter.add_system(make_function_system(1));
+        world.add_schedule(OnEnter(AppState::InGame), on_enter);
+
+        let mut schedule = Schedule::new();
+        schedule
+            .add_system(apply_state_transition::<AppState>)
+            .add_system(
+                make_function_system(2)
+                    .run_if(common_conditions::in_state(AppState::InGame))
+                    .after(apply_state_transition::<AppState>),
+            );
+
+        schedule.run(&mut world);
+        assert!(world.resource::<SystemOrder>().0.is_empty());
+
+        world
+            .resource_mut::<NextState<AppState>>()
+            .set(AppState::InGame);
+        schedule.run(&mut world);
+        assert_eq!(world.resource::<State<AppState>>().0, AppState::InGame);
+        assert_eq!(world.resource::<SystemOrder>().0, vec![0, 1, 2]);
+        assert!(world.resource::<NextState<AppState>>().0.is_none());
+    }
+
+    #[test]
+    fn ambiguities() {
+        fn write_a(_: ResMut<Counter>) {}
+        fn write_b(_: ResMut<Counter>) {}
+        fn write_c(_: ResMut<Counter>) {}
+        fn read(_: Res<Counter>) {}
+
+        let mut world = World::new();
+        world.init_resource::<Counter>();
+        let mut schedule = Schedule::new();
+        schedule
+            .add_system(write_a.in_set(TestSet::A))
+            .add_system(write_b)
+            .add_system(write_c.after(write_b))
+            .add_system(read.ambiguous_with(TestSet::A));
+
+        let report = schedule.ambiguity_report(&mut world);
+        let mut pairs = report
+            .ambiguities
+            .iter()
+            .map(|ambiguity| {
+                let name = |name: &str| name.rsplit("::").next().unwrap().to_owned();
+                let (a, b) = (name(&ambiguity.system_a), name(&ambiguity.system_b));
+                (a.clone().min(b.clone()), a.max(b))
+            })
+            .collect::<Vec<_>>();
+        pairs.sort();
+        assert_eq!(
+            pairs,
+            [
+                ("read", "write_b"),
+                ("read", "write_c"),
+                ("write_a", "write_b"),
+                ("write_a", "write_c"),
+            ]
+            .map(|(a, b)| (a.to_owned(), b.to_owned()))
+        );
+        let counter_id = world
+            .components()
+            .get_resource_id(std::any::TypeId::of::<Counter>())
+            .unwrap();
+        assert!(report
+            .ambiguities
+            .iter()
+            .all(|ambiguity| ambiguity.conflicts == vec![counter_id]));
+
+        let mut schedule = Schedule::new();
+        schedule
+            .add_system(write_a.ambiguous_with_all())
+            .add_system(write_b)
+            .add_system(make_exclusive_system(0).after(write_b));
+        let report = schedule.ambiguity_report(&mut world);
+        assert_eq!(report.ambiguities.len(), 0);
+    }
+
+    #[test]
+    fn ambiguities_allow_list() {
+        fn write_a(_: ResMut<Counter>) {}
+        fn write_b(_: ResMut<Counter>) {}
+
+        let mut world = World::new();
+        world.init_resource::<Counter>();
+        world.insert_resource(
+            ReportExecutionOrderAmbiguities::new(AmbiguityReportLevel::Error)
+                .ambiguous_with_set(TestSet::A),
+        );
+        let mut schedule = Schedule::new();
+        schedule
+            .configure_set(TestSet::B.in_set(TestSet::A))
+            .add_system(write_a.in_set(TestSet::B))
+            .add_system(write_b);
+        schedule.run(&mut world);
+        assert!(schedule.ambiguity_report(&mut world).is_empty());
+
+        world.insert_resource(
+            ReportExecutionOrderAmbiguities::new(AmbiguityReportLevel::Error)
+                .ambiguous_with_crate("bevy_ecs"),
+        );
+        assert!(schedule.ambiguity_report(&mut world).is_empty());
+
+        world.insert_resource(
+            ReportExecutionOrderAmbiguities::new(AmbiguityReportLevel::Error)
+                .ambiguous_with_set(TestSet::C)
+                .ambiguous_with_crate("bevy"),
+        );
+        assert_eq!(schedule.ambiguity_report(&mut world).ambiguities.len(), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "Execution order ambiguities detected")]
+    fn ambiguities_error_level() {
+        fn write_a(_: ResMut<Counter>) {}
+        fn write_b(_: ResMut<Counter>) {}
+
+        let mut world = World::new();
+        world.init_resource::<Counter>();
+        world.insert_resource(ReportExecutionOrderAmbiguities::new(
+            AmbiguityReportLevel::Error,
+        ));
+        let mut schedule = Schedule::new();
+        schedule.add_system(write_a).add_system(write_b);
+        schedule.run(&mut world);
+    }
+}
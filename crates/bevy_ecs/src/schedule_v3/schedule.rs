@@ -0,0 +1,1330 @@
+use std::fmt;
+
+use bevy_utils::{tracing::warn, HashMap, HashSet};
+use fixedbitset::FixedBitSet;
+
+use crate::{
+    self as bevy_ecs,
+    change_detection::CHECK_TICK_THRESHOLD,
+    component::ComponentId,
+    schedule::{
+        AmbiguityGraphEdge, AmbiguityReport, AmbiguityReportLevel, ReportExecutionOrderAmbiguities,
+        RunCriteriaGraphNode, Stage, StageGraph, SystemGraphKind, SystemGraphNode,
+        SystemOrderAmbiguity,
+    },
+    schedule_v3::{
+        executor::{is_apply_system_buffers, SystemExecutor, SystemSchedule},
+        graph_utils::{Ambiguity, Dependency, DependencyKind, DiGraph, GraphInfo},
+        *,
+    },
+    system::{BoxedSystem, ExclusiveFunctionSystem, Resource},
+    world::World,
+};
+
+/// Resource that stores [`Schedule`]s mapped to [`ScheduleLabel`]s.
+#[derive(Default, Resource)]
+pub struct Schedules {
+    inner: HashMap<BoxedScheduleLabel, Schedule>,
+}
+
+impl Schedules {
+    /// Constructs an empty `Schedules` with zero initial capacity.
+    pub fn new() -> Self {
+        Self {
+            inner: HashMap::new(),
+        }
+    }
+
+    /// Inserts a labeled schedule into the map.
+    ///
+    /// If the map already had an entry for `label`, `schedule` is inserted,
+    /// and the old schedule is returned. Otherwise, `None` is returned.
+    pub fn insert(&mut self, label: impl ScheduleLabel, schedule: Schedule) -> Option<Schedule> {
+        let label: BoxedScheduleLabel = Box::new(label);
+        if self.inner.contains_key(&label) {
+            warn!("schedule with label {:?} already exists", label);
+        }
+        self.inner.insert(label, schedule)
+    }
+
+    /// Removes the schedule corresponding to the `label` from the map, returning it if it existed.
+    pub fn remove(&mut self, label: &dyn ScheduleLabel) -> Option<Schedule> {
+        self.inner.remove(label)
+    }
+
+    /// Returns `true` if the map contains a schedule for `label`.
+    pub fn contains(&self, label: &dyn ScheduleLabel) -> bool {
+        self.inner.contains_key(label)
+    }
+
+    /// Returns a reference to the schedule associated with `label`, if it exists.
+    pub fn get(&self, label: &dyn ScheduleLabel) -> Option<&Schedule> {
+        self.inner.get(label)
+    }
+
+    /// Returns a mutable reference to the schedule associated with `label`, if it exists.
+    pub fn get_mut(&mut self, label: &dyn ScheduleLabel) -> Option<&mut Schedule> {
+        self.inner.get_mut(label)
+    }
+
+    /// Returns an iterator over all schedules. Iteration order is undefined.
+    pub fn iter(&self) -> impl Iterator<Item = (&dyn ScheduleLabel, &Schedule)> {
+        self.inner
+            .iter()
+            .map(|(label, schedule)| (&**label, schedule))
+    }
+
+    /// Returns an iterator over mutable references to all schedules. Iteration order is
+    /// undefined.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (&dyn ScheduleLabel, &mut Schedule)> {
+        self.inner
+            .iter_mut()
+            .map(|(label, schedule)| (&**label, schedule))
+    }
+
+    /// Iterates the change ticks of all systems in all stored schedules and clamps any older than
+    /// [`MAX_CHANGE_AGE`](crate::change_detection::MAX_CHANGE_AGE).
+    /// This prevents overflow and thus prevents false positives.
+    pub(crate) fn check_change_ticks(&mut self, change_tick: u32) {
+        for schedule in self.inner.values_mut() {
+            schedule.check_change_ticks(change_tick);
+        }
+    }
+}
+
+/// A collection of systems, and the metadata and executor needed to run them
+/// in a certain order under certain conditions.
+///
+/// ```
+/// # use bevy_ecs::prelude::*;
+/// # use bevy_ecs::schedule_v3::Schedule;
+/// #[derive(SystemSet, Clone, PartialEq, Eq, Hash, Debug)]
+/// struct Physics;
+///
+/// #[derive(Resource)]
+/// struct Paused;
+///
+/// fn apply_gravity() {}
+/// fn move_bodies() {}
+/// fn render() {}
+///
+/// let mut schedule = Schedule::new();
+/// schedule
+///     .configure_set(Physics.run_if(not(resource_exists::<Paused>())))
+///     .add_systems((apply_gravity, move_bodies).chain().in_set(Physics))
+///     .add_system(render.after(Physics));
+///
+/// let mut world = World::new();
+/// schedule.run(&mut world);
+/// ```
+pub struct Schedule {
+    graph: ScheduleGraph,
+    executable: SystemSchedule,
+    executor: Box<dyn SystemExecutor>,
+    executor_initialized: bool,
+    last_tick_check: u32,
+}
+
+impl Default for Schedule {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Schedule {
+    /// Constructs an empty `Schedule`.
+    pub fn new() -> Self {
+        Self {
+            graph: ScheduleGraph::new(),
+            executable: SystemSchedule::new(),
+            executor: make_executor(ExecutorKind::default()),
+            executor_initialized: false,
+            last_tick_check: 0,
+        }
+    }
+
+    /// Add a system to the schedule.
+    pub fn add_system<P>(&mut self, system: impl IntoSystemConfig<P>) -> &mut Self {
+        self.graph.add_system(system);
+        self
+    }
+
+    /// Add a collection of systems to the schedule.
+    pub fn add_systems<P>(&mut self, systems: impl IntoSystemConfigs<P>) -> &mut Self {
+        self.graph.add_systems(systems);
+        self
+    }
+
+    /// Configure a system set in this schedule.
+    pub fn configure_set(&mut self, set: impl IntoSystemSetConfig) -> &mut Self {
+        self.graph.configure_set(set);
+        self
+    }
+
+    /// Configure a collection of system sets in this schedule.
+    pub fn configure_sets(&mut self, sets: impl IntoSystemSetConfigs) -> &mut Self {
+        self.graph.configure_sets(sets);
+        self
+    }
+
+    /// Changes the settings used when building the schedule.
+    pub fn set_build_settings(&mut self, settings: ScheduleBuildSettings) -> &mut Self {
+        self.graph.settings = settings;
+        self.graph.changed = true;
+        self
+    }
+
+    /// Returns the schedule's current execution strategy.
+    pub fn get_executor_kind(&self) -> ExecutorKind {
+        self.executor.kind()
+    }
+
+    /// Sets the schedule's execution strategy.
+    pub fn set_executor_kind(&mut self, executor: ExecutorKind) -> &mut Self {
+        if executor != self.executor.kind() {
+            self.executor = make_executor(executor);
+            self.executor_initialized = false;
+        }
+        self
+    }
+
+    /// Runs all systems in this schedule on the `world`, using its current execution strategy.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the schedule cannot be built, see [`ScheduleBuildError`].
+    pub fn run(&mut self, world: &mut World) {
+        self.initialize(world)
+            .unwrap_or_else(|e| panic!("Error when initializing schedule: {}", e));
+        self.executor.run(&mut self.executable, world);
+
+        let change_tick = world.change_tick();
+        if change_tick.wrapping_sub(self.last_tick_check) >= CHECK_TICK_THRESHOLD {
+            self.check_change_ticks(change_tick);
+            world.check_change_ticks();
+            self.last_tick_check = change_tick;
+        }
+    }
+
+    /// Initializes any newly-added systems and conditions, rebuilds the executable schedule,
+    /// and re-initializes the executor.
+    ///
+    /// Moves all systems and run conditions out of the [`ScheduleGraph`]. When the schedule is
+    /// rebuilt, its execution order ambiguities are reported according to the
+    /// [`ReportExecutionOrderAmbiguities`] resource, if present.
+    pub fn initialize(&mut self, world: &mut World) -> Result<(), ScheduleBuildError> {
+        if self.graph.changed {
+            self.graph.initialize(world);
+            self.graph.update_schedule(&mut self.executable, world)?;
+            self.graph.changed = false;
+            self.executor_initialized = false;
+            if let Some(settings) = world.get_resource::<ReportExecutionOrderAmbiguities>() {
+                if settings.level != AmbiguityReportLevel::Ignore {
+                    self.find_ambiguities(settings)
+                        .report(settings.level, world);
+                }
+            }
+        }
+
+        if !self.executor_initialized {
+            self.executor.init(&self.executable);
+            self.executor_initialized = true;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the [`ScheduleGraph`].
+    pub fn graph(&self) -> &ScheduleGraph {
+        &self.graph
+    }
+
+    /// Returns the pairs of systems of this schedule with an ambiguous execution order, along
+    /// with the components and resources they conflict on.
+    ///
+    /// Ambiguities allowed with [`ambiguous_with`](IntoSystemConfig::ambiguous_with) and
+    /// [`ambiguous_with_all`](IntoSystemConfig::ambiguous_with_all) are skipped, as well as those
+    /// allowed by the [`ReportExecutionOrderAmbiguities`] resource, if it is present.
+    ///
+    /// ```
+    /// # use bevy_ecs::prelude::*;
+    /// # use bevy_ecs::schedule_v3::Schedule;
+    /// #[derive(Component)]
+    /// struct Health(f32);
+    ///
+    /// fn heal(mut query: Query<&mut Health>) {}
+    /// fn poison(mut query: Query<&mut Health>) {}
+    /// fn regenerate(mut query: Query<&mut Health>) {}
+    ///
+    /// let mut world = World::new();
+    /// let mut schedule = Schedule::new();
+    /// schedule
+    ///     .add_system(heal)
+    ///     .add_system(poison)
+    ///     .add_system(regenerate.ambiguous_with(heal));
+    /// let report = schedule.ambiguity_report(&mut world);
+    /// assert_eq!(report.ambiguities.len(), 2);
+    /// assert_eq!(
+    ///     report.ambiguities[0].conflicts,
+    ///     vec![world.component_id::<Health>().unwrap()]
+    /// );
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the schedule cannot be built, see [`ScheduleBuildError`].
+    pub fn ambiguity_report(&mut self, world: &mut World) -> AmbiguityReport {
+        self.initialize(world)
+            .unwrap_or_else(|e| panic!("Error when initializing schedule: {}", e));
+        let settings = world
+            .get_resource::<ReportExecutionOrderAmbiguities>()
+            .cloned()
+            .unwrap_or_default();
+        self.find_ambiguities(&settings)
+    }
+
+    /// Names the conflicting systems found when the schedule was last built, skipping those
+    /// allowed by `settings`.
+    fn find_ambiguities(&self, settings: &ReportExecutionOrderAmbiguities) -> AmbiguityReport {
+        let systems: HashMap<NodeId, &BoxedSystem> = self
+            .executable
+            .system_ids
+            .iter()
+            .copied()
+            .zip(&self.executable.systems)
+            .collect();
+        let allowed_systems = self.graph.systems_in_sets(&settings.ambiguous_with_sets);
+        let allows = |id: &NodeId| {
+            allowed_systems.contains(id) || settings.allows_crate_of(&systems[id].name())
+        };
+        let ambiguities = self
+            .graph
+            .conflicting_systems
+            .iter()
+            .filter(|(a, b, _)| !allows(a) && !allows(b))
+            .map(|(a, b, conflicts)| {
+                let (system_a, system_b) = (systems[a], systems[b]);
+                SystemOrderAmbiguity {
+                    kind: if system_a.is_exclusive() || system_b.is_exclusive() {
+                        SystemGraphKind::Exclusive
+                    } else {
+                        SystemGraphKind::Parallel
+                    },
+                    system_a: system_a.name().into_owned(),
+                    system_b: system_b.name().into_owned(),
+                    conflicts: conflicts.clone(),
+                }
+            })
+            .collect();
+        AmbiguityReport { ambiguities }
+    }
+
+    /// Returns the resolved dependency graph of this schedule, in the format used to export
+    /// [`SystemStage`](crate::schedule::SystemStage) graphs.
+    ///
+    /// The labels of each system are the sets it directly belongs to, and each of its run
+    /// criteria is either one of its own run conditions, or a run condition of a set containing
+    /// it (labelled with the name of that set). The ambiguities are the ones found by the
+    /// ambiguity checker, see [`Schedule::ambiguity_report`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the schedule cannot be built, see [`ScheduleBuildError`].
+    pub fn stage_graph(&mut self, world: &mut World) -> StageGraph {
+        self.initialize(world)
+            .unwrap_or_else(|e| panic!("Error when initializing schedule: {}", e));
+
+        let executable = &self.executable;
+        let system_indices: HashMap<NodeId, usize> = executable
+            .system_ids
+            .iter()
+            .enumerate()
+            .map(|(index, id)| (*id, index))
+            .collect();
+        let node_name = |id: &NodeId| match system_indices.get(id) {
+            Some(&index) => executable.systems[index].name().into_owned(),
+            None => self.graph.node_name(*id),
+        };
+
+        let mut graph = StageGraph::default();
+        for (set_id, conditions) in executable.set_ids.iter().zip(&executable.set_conditions) {
+            for condition in conditions {
+                graph.run_criteria.push(RunCriteriaGraphNode {
+                    name: condition.name().into_owned(),
+                    label: Some(node_name(set_id)),
+                });
+            }
+        }
+        // the first run criteria of each set with conditions
+        let mut set_criteria = Vec::with_capacity(executable.set_conditions.len());
+        let mut start = 0;
+        for conditions in &executable.set_conditions {
+            set_criteria.push(start..start + conditions.len());
+            start += conditions.len();
+        }
+
+        let mut dependencies = vec![Vec::new(); executable.systems.len()];
+        for (index, dependents) in executable.system_dependents.iter().enumerate() {
+            for &dependent in dependents {
+                dependencies[dependent].push(index);
+            }
+        }
+
+        for (index, (system, id)) in executable
+            .systems
+            .iter()
+            .zip(&executable.system_ids)
+            .enumerate()
+        {
+            let mut run_criteria: Vec<usize> = executable.sets_with_conditions_of_systems[index]
+                .ones()
+                .flat_map(|set| set_criteria[set].clone())
+                .collect();
+            for condition in &executable.system_conditions[index] {
+                run_criteria.push(graph.run_criteria.len());
+                graph.run_criteria.push(RunCriteriaGraphNode {
+                    name: condition.name().into_owned(),
+                    label: None,
+                });
+            }
+
+            graph.systems.push(SystemGraphNode {
+                name: system.name().into_owned(),
+                kind: if system.is_exclusive() {
+                    SystemGraphKind::Exclusive
+                } else {
+                    SystemGraphKind::Parallel
+                },
+                labels: self
+                    .graph
+                    .hierarchy
+                    .predecessors(*id)
+                    .iter()
+                    .map(node_name)
+                    .collect(),
+                before: self
+                    .graph
+                    .dependency
+                    .successors(*id)
+                    .iter()
+                    .map(node_name)
+                    .collect(),
+                after: self
+                    .graph
+                    .dependency
+                    .predecessors(*id)
+                    .iter()
+                    .map(node_name)
+                    .collect(),
+                run_criteria,
+                dependencies: std::mem::take(&mut dependencies[index]),
+            });
+        }
+
+        graph.ambiguities = self
+            .graph
+            .conflicting_systems
+            .iter()
+            .map(|(a, b, conflicts)| AmbiguityGraphEdge {
+                system_a: system_indices[a],
+                system_b: system_indices[b],
+                conflicts: conflicts
+                    .iter()
+                    .map(|id| world.components().get_info(*id).unwrap().name().to_string())
+                    .collect(),
+            })
+            .collect();
+
+        graph
+    }
+
+    /// Iterates the change ticks of all systems in the schedule and clamps any older than
+    /// [`MAX_CHANGE_AGE`](crate::change_detection::MAX_CHANGE_AGE).
+    /// This prevents overflow and thus prevents false positives.
+    pub(crate) fn check_change_ticks(&mut self, change_tick: u32) {
+        for system in &mut self.executable.systems {
+            system.check_change_tick(change_tick);
+        }
+
+        for conditions in &mut self.executable.system_conditions {
+            for condition in conditions {
+                condition.check_change_tick(change_tick);
+            }
+        }
+
+        for conditions in &mut self.executable.set_conditions {
+            for condition in conditions {
+                condition.check_change_tick(change_tick);
+            }
+        }
+    }
+}
+
+impl Stage for Schedule {
+    fn run(&mut self, world: &mut World) {
+        Schedule::run(self, world);
+    }
+}
+
+impl World {
+    /// Adds a [`Schedule`] to the [`Schedules`] resource under `label`, creating the resource if
+    /// needed.
+    pub fn add_schedule(&mut self, label: impl ScheduleLabel, schedule: Schedule) {
+        self.get_resource_or_insert_with(Schedules::new)
+            .insert(label, schedule);
+    }
+
+    /// Runs the [`Schedule`] associated with `label` a single time.
+    ///
+    /// The schedule is removed from the [`Schedules`] resource while it runs, so it can add
+    /// other schedules or run them with [`World::run_schedule`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if no schedule is associated with `label`.
+    pub fn run_schedule(&mut self, label: impl ScheduleLabel) {
+        if let Err(error) = self.try_run_schedule(label) {
+            panic!("{}", error);
+        }
+    }
+
+    /// Runs the [`Schedule`] associated with `label` a single time, or returns an error if no
+    /// such schedule exists. See [`World::run_schedule`].
+    pub fn try_run_schedule(
+        &mut self,
+        label: impl ScheduleLabel,
+    ) -> Result<(), TryRunScheduleError> {
+        let label: BoxedScheduleLabel = Box::new(label);
+        let mut schedule = self
+            .get_resource_mut::<Schedules>()
+            .and_then(|mut schedules| schedules.remove(&*label))
+            .ok_or_else(|| TryRunScheduleError(label.clone()))?;
+
+        schedule.run(self);
+        self.get_resource_or_insert_with(Schedules::new)
+            .inner
+            .insert(label, schedule);
+        Ok(())
+    }
+}
+
+/// The error returned by [`World::try_run_schedule`] when no [`Schedule`] is associated with the
+/// label.
+#[derive(Debug)]
+pub struct TryRunScheduleError(pub BoxedScheduleLabel);
+
+impl std::error::Error for TryRunScheduleError {}
+
+impl fmt::Display for TryRunScheduleError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "The schedule with the label {:?} was not found.", self.0)
+    }
+}
+
+fn make_executor(kind: ExecutorKind) -> Box<dyn SystemExecutor> {
+    match kind {
+        ExecutorKind::SingleThreaded => Box::new(SingleThreadedExecutor::default()),
+        ExecutorKind::MultiThreaded => Box::new(MultiThreadedExecutor::new()),
+    }
+}
+
+/// Settings that influence how a [`Schedule`] is built.
+#[derive(Clone, Debug)]
+pub struct ScheduleBuildSettings {
+    /// If `true`, an [`apply_system_buffers`] is automatically inserted between a system that has
+    /// buffers to apply, such as [`Commands`](crate::system::Commands), and the systems ordered
+    /// after it, so that they see its changes.
+    ///
+    /// Defaults to `true`.
+    pub auto_insert_apply_system_buffers: bool,
+}
+
+impl Default for ScheduleBuildSettings {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ScheduleBuildSettings {
+    pub const fn new() -> Self {
+        Self {
+            auto_insert_apply_system_buffers: true,
+        }
+    }
+}
+
+/// A system in a [`ScheduleGraph`]. It is moved into the [`Schedule`]'s executable form while
+/// the schedule is built.
+struct SystemNode {
+    inner: Option<BoxedSystem>,
+}
+
+impl SystemNode {
+    fn get(&self) -> &BoxedSystem {
+        self.inner
+            .as_ref()
+            .expect("systems are only moved out of the graph while it is built")
+    }
+}
+
+/// Metadata for a [`Schedule`].
+///
+/// The graph has two kinds of nodes, systems and system sets, and two kinds of edges: the
+/// *hierarchy* edges go from a system set to each of its members, and the *dependency* edges from
+/// a system or system set to the ones that must run after it.
+pub struct ScheduleGraph {
+    systems: Vec<SystemNode>,
+    system_conditions: Vec<Option<Vec<BoxedCondition>>>,
+    system_sets: Vec<BoxedSystemSet>,
+    system_set_conditions: Vec<Option<Vec<BoxedCondition>>>,
+    system_set_ids: HashMap<BoxedSystemSet, NodeId>,
+    /// Nodes whose systems or conditions, starting at the given index, are not initialized.
+    uninit: Vec<(NodeId, usize)>,
+    hierarchy: DiGraph,
+    dependency: DiGraph,
+    /// Edges between nodes whose execution order ambiguities are not reported. Their direction
+    /// doesn't matter.
+    ambiguous_with: DiGraph,
+    ambiguous_with_all: HashSet<NodeId>,
+    /// The systems with conflicting access and an ambiguous execution order, found when the
+    /// schedule was last built.
+    conflicting_systems: Vec<(NodeId, NodeId, Vec<ComponentId>)>,
+    /// The `apply_system_buffers` systems inserted automatically, by the number of sync points
+    /// that run before them.
+    auto_sync_node_ids: HashMap<u32, NodeId>,
+    changed: bool,
+    settings: ScheduleBuildSettings,
+}
+
+impl ScheduleGraph {
+    fn new() -> Self {
+        Self {
+            systems: Vec::new(),
+            system_conditions: Vec::new(),
+            system_sets: Vec::new(),
+            system_set_conditions: Vec::new(),
+            system_set_ids: HashMap::new(),
+            uninit: Vec::new(),
+            hierarchy: DiGraph::default(),
+            dependency: DiGraph::default(),
+            ambiguous_with: DiGraph::default(),
+            ambiguous_with_all: HashSet::new(),
+            conflicting_systems: Vec::new(),
+            auto_sync_node_ids: HashMap::new(),
+            changed: false,
+            settings: ScheduleBuildSettings::default(),
+        }
+    }
+
+    /// Returns the system at the given [`NodeId`], if it exists and is not currently moved into
+    /// the executable schedule.
+    pub fn get_system_at(&self, id: NodeId) -> Option<&BoxedSystem> {
+        if !id.is_system() {
+            return None;
+        }
+        self.systems
+            .get(id.index())
+            .and_then(|system| system.inner.as_ref())
+    }
+
+    /// Returns the system set at the given [`NodeId`], if it exists.
+    pub fn get_set_at(&self, id: NodeId) -> Option<&dyn SystemSet> {
+        if !id.is_set() {
+            return None;
+        }
+        self.system_sets.get(id.index()).map(|set| &**set)
+    }
+
+    /// Returns the systems in any of the given `sets`, directly or through nested sets.
+    fn systems_in_sets(&self, sets: &[BoxedSystemSet]) -> HashSet<NodeId> {
+        let mut systems = HashSet::new();
+        let mut stack: Vec<NodeId> = sets
+            .iter()
+            .filter_map(|set| self.system_set_ids.get(set).copied())
+            .collect();
+        let mut visited = HashSet::new();
+        while let Some(id) = stack.pop() {
+            if !visited.insert(id) {
+                continue;
+            }
+            if id.is_system() {
+                systems.insert(id);
+            } else {
+                stack.extend_from_slice(self.hierarchy.successors(id));
+            }
+        }
+        systems
+    }
+
+    /// Returns the name of the system or system set at the given [`NodeId`].
+    pub fn node_name(&self, id: NodeId) -> String {
+        match id {
+            NodeId::System(index) => self.systems[index].inner.as_ref().map_or_else(
+                || format!("System({})", index),
+                |system| system.name().into(),
+            ),
+            NodeId::Set(index) => format!("{:?}", self.system_sets[index]),
+        }
+    }
+
+    fn add_systems<P>(&mut self, systems: impl IntoSystemConfigs<P>) {
+        let SystemConfigs { systems, chained } = systems.into_configs();
+        let mut system_iter = systems.into_iter();
+        if chained {
+            let Some(prev) = system_iter.next() else {
+                return;
+            };
+            let mut prev_id = self.add_system_inner(prev);
+            for next in system_iter {
+                let next_id = self.add_system_inner(next);
+                self.dependency.add_edge(prev_id, next_id);
+                prev_id = next_id;
+            }
+        } else {
+            for system in system_iter {
+                self.add_system_inner(system);
+            }
+        }
+    }
+
+    fn add_system<P>(&mut self, system: impl IntoSystemConfig<P>) {
+        self.add_system_inner(system.into_config());
+    }
+
+    fn add_system_inner(&mut self, config: SystemConfig) -> NodeId {
+        let SystemConfig {
+            system,
+            graph_info,
+            conditions,
+        } = config;
+
+        let id = NodeId::System(self.systems.len());
+
+        // graph updates are immediate
+        self.update_graphs(id, graph_info);
+
+        // system init has to be deferred (need `&mut World`)
+        self.uninit.push((id, 0));
+        self.systems.push(SystemNode {
+            inner: Some(system),
+        });
+        self.system_conditions.push(Some(conditions));
+
+        id
+    }
+
+    fn configure_sets(&mut self, sets: impl IntoSystemSetConfigs) {
+        let SystemSetConfigs { sets, chained } = sets.into_configs();
+        let mut set_iter = sets.into_iter();
+        if chained {
+            let Some(prev) = set_iter.next() else { return };
+            let mut prev_id = self.configure_set_inner(prev);
+            for next in set_iter {
+                let next_id = self.configure_set_inner(next);
+                self.dependency.add_edge(prev_id, next_id);
+                prev_id = next_id;
+            }
+        } else {
+            for set in set_iter {
+                self.configure_set_inner(set);
+            }
+        }
+    }
+
+    fn configure_set(&mut self, set: impl IntoSystemSetConfig) {
+        self.configure_set_inner(set.into_config());
+    }
+
+    fn configure_set_inner(&mut self, set: SystemSetConfig) -> NodeId {
+        let SystemSetConfig {
+            set,
+            graph_info,
+            mut conditions,
+        } = set;
+
+        let id = match self.system_set_ids.get(&set) {
+            Some(&id) => id,
+            None => self.add_set(set),
+        };
+
+        // graph updates are immediate
+        self.update_graphs(id, graph_info);
+
+        // system init has to be deferred (need `&mut World`)
+        let system_set_conditions = self.system_set_conditions[id.index()]
+            .as_mut()
+            .expect("set conditions are only moved out of the graph while it is built");
+        self.uninit.push((id, system_set_conditions.len()));
+        system_set_conditions.append(&mut conditions);
+
+        id
+    }
+
+    fn add_set(&mut self, set: BoxedSystemSet) -> NodeId {
+        let id = NodeId::Set(self.system_sets.len());
+        self.system_sets.push(set.clone());
+        self.system_set_conditions.push(Some(Vec::new()));
+        self.system_set_ids.insert(set, id);
+        self.hierarchy.add_node(id);
+        self.dependency.add_node(id);
+        id
+    }
+
+    fn get_or_add_set(&mut self, set: BoxedSystemSet) -> NodeId {
+        match self.system_set_ids.get(&set) {
+            Some(&id) => id,
+            None => self.add_set(set),
+        }
+    }
+
+    /// Adds the hierarchy and dependency edges described by `graph_info` to the node `id`.
+    fn update_graphs(&mut self, id: NodeId, graph_info: GraphInfo) {
+        self.changed = true;
+
+        let GraphInfo {
+            sets,
+            dependencies,
+            ambiguous_with,
+        } = graph_info;
+
+        self.hierarchy.add_node(id);
+        for set in sets {
+            let set_id = self.get_or_add_set(set);
+            self.hierarchy.add_edge(set_id, id);
+        }
+
+        self.dependency.add_node(id);
+        for Dependency { kind, set } in dependencies {
+            let set_id = self.get_or_add_set(set);
+            let (lhs, rhs) = match kind {
+                DependencyKind::Before => (id, set_id),
+                DependencyKind::After => (set_id, id),
+            };
+            self.dependency.add_edge(lhs, rhs);
+        }
+
+        match ambiguous_with {
+            Ambiguity::Check => {}
+            Ambiguity::IgnoreWithSet(sets) => {
+                for set in sets {
+                    let set_id = self.get_or_add_set(set);
+                    self.ambiguous_with.add_edge(id, set_id);
+                }
+            }
+            Ambiguity::IgnoreAll => {
+                self.ambiguous_with_all.insert(id);
+            }
+        }
+    }
+
+    /// Initializes any newly-added systems and conditions by calling
+    /// [`System::initialize`](crate::system::System).
+    fn initialize(&mut self, world: &mut World) {
+        for (id, i) in self.uninit.drain(..) {
+            match id {
+                NodeId::System(index) => {
+                    self.systems[index]
+                        .inner
+                        .as_mut()
+                        .unwrap()
+                        .initialize(world);
+                    for condition in self.system_conditions[index].as_mut().unwrap() {
+                        condition.initialize(world);
+                    }
+                }
+                NodeId::Set(index) => {
+                    for condition in self.system_set_conditions[index]
+                        .as_mut()
+                        .unwrap()
+                        .iter_mut()
+                        .skip(i)
+                    {
+                        condition.initialize(world);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Builds an execution-optimized [`SystemSchedule`] from the current state of the graph.
+    ///
+    /// All systems and conditions must be in the graph.
+    fn build_schedule(&mut self, world: &mut World) -> Result<SystemSchedule, ScheduleBuildError> {
+        // check hierarchy for cycles
+        let hier_topsort = self
+            .hierarchy
+            .toposort()
+            .map_err(|cycle| ScheduleBuildError::HierarchyCycle(self.describe_cycle(&cycle)))?;
+
+        let SetMembership {
+            set_systems,
+            set_system_bitsets,
+            set_descendants,
+        } = self.map_sets_to_systems(&hier_topsort);
+
+        // check dependencies for cycles
+        self.dependency
+            .toposort()
+            .map_err(|cycle| ScheduleBuildError::DependencyCycle(self.describe_cycle(&cycle)))?;
+
+        self.check_dependencies(&set_systems, &set_system_bitsets, &set_descendants)?;
+
+        // flatten: combine `in_set` with `before` and `after` information
+        // have to do it like this to preserve transitivity
+        let mut dependency_flattened = DiGraph::default();
+        for index in 0..self.systems.len() {
+            dependency_flattened.add_node(NodeId::System(index));
+        }
+        for (lhs, rhs) in self.dependency.edges() {
+            let lhs_systems = node_systems(lhs, &set_systems);
+            let rhs_systems = node_systems(rhs, &set_systems);
+            for &lhs_system in &lhs_systems {
+                for &rhs_system in &rhs_systems {
+                    dependency_flattened
+                        .add_edge(NodeId::System(lhs_system), NodeId::System(rhs_system));
+                }
+            }
+        }
+
+        if self.settings.auto_insert_apply_system_buffers {
+            dependency_flattened = self.auto_insert_apply_system_buffers(&dependency_flattened)?;
+            // the inserted systems have no state to initialize besides their change tick
+            for (id, _) in self.uninit.drain(..) {
+                self.systems[id.index()]
+                    .inner
+                    .as_mut()
+                    .unwrap()
+                    .initialize(world);
+            }
+        }
+
+        // topsort
+        let dg_system_ids = dependency_flattened
+            .toposort()
+            .map_err(|cycle| ScheduleBuildError::DependencyCycle(self.describe_cycle(&cycle)))?;
+        let dg_system_idx_map: HashMap<NodeId, usize> = dg_system_ids
+            .iter()
+            .enumerate()
+            .map(|(i, id)| (*id, i))
+            .collect();
+
+        self.conflicting_systems = self.find_conflicting_systems(
+            &dg_system_ids,
+            &dg_system_idx_map,
+            &dependency_flattened,
+            &set_systems,
+        );
+
+        // get the number of dependencies and the immediate dependents of each system
+        // (needed by multi-threaded executor to run systems in the correct order)
+        let mut system_dependencies = Vec::with_capacity(dg_system_ids.len());
+        let mut system_dependents = Vec::with_capacity(dg_system_ids.len());
+        for &sys_id in &dg_system_ids {
+            system_dependencies.push(dependency_flattened.predecessors(sys_id).len());
+            system_dependents.push(
+                dependency_flattened
+                    .successors(sys_id)
+                    .iter()
+                    .map(|dep_id| dg_system_idx_map[dep_id])
+                    .collect::<Vec<_>>(),
+            );
+        }
+
+        // get the sets with conditions, outermost first
+        let hg_set_ids: Vec<NodeId> = hier_topsort
+            .iter()
+            .copied()
+            .filter(|id| {
+                id.is_set()
+                    && !self.system_set_conditions[id.index()]
+                        .as_ref()
+                        .unwrap()
+                        .is_empty()
+            })
+            .collect();
+
+        // get the rows and columns of the hierarchy graph's reachability matrix
+        // (needed to we can evaluate conditions in the correct order)
+        let sys_count = dg_system_ids.len();
+        let set_with_conditions_count = hg_set_ids.len();
+        let mut sets_with_conditions_of_systems =
+            vec![FixedBitSet::with_capacity(set_with_conditions_count); sys_count];
+        let mut systems_in_sets_with_conditions =
+            vec![FixedBitSet::with_capacity(sys_count); set_with_conditions_count];
+        for (i, set_id) in hg_set_ids.iter().enumerate() {
+            for &system_index in &set_systems[set_id] {
+                let row = dg_system_idx_map[&NodeId::System(system_index)];
+                sets_with_conditions_of_systems[row].insert(i);
+                systems_in_sets_with_conditions[i].insert(row);
+            }
+        }
+
+        Ok(SystemSchedule {
+            systems: Vec::with_capacity(sys_count),
+            system_conditions: Vec::with_capacity(sys_count),
+            set_conditions: Vec::with_capacity(set_with_conditions_count),
+            system_ids: dg_system_ids,
+            set_ids: hg_set_ids,
+            system_dependencies,
+            system_dependents,
+            sets_with_conditions_of_systems,
+            systems_in_sets_with_conditions,
+        })
+    }
+
+    /// Collects the systems each set contains, directly or through nested sets.
+    fn map_sets_to_systems(&self, hier_topsort: &[NodeId]) -> SetMembership {
+        let mut set_systems: HashMap<NodeId, Vec<usize>> =
+            HashMap::with_capacity_and_hasher(self.system_sets.len(), Default::default());
+        let mut set_system_bitsets =
+            HashMap::with_capacity_and_hasher(self.system_sets.len(), Default::default());
+        let mut set_descendants: HashMap<NodeId, HashSet<NodeId>> =
+            HashMap::with_capacity_and_hasher(self.system_sets.len(), Default::default());
+        for &id in hier_topsort.iter().rev() {
+            if id.is_system() {
+                continue;
+            }
+
+            let mut systems = Vec::new();
+            let mut system_bitset = FixedBitSet::with_capacity(self.systems.len());
+            let mut descendants = HashSet::default();
+
+            for &child in self.hierarchy.successors(id) {
+                descendants.insert(child);
+                match child {
+                    NodeId::System(index) => {
+                        if !system_bitset.contains(index) {
+                            systems.push(index);
+                            system_bitset.insert(index);
+                        }
+                    }
+                    NodeId::Set(_) => {
+                        for &index in &set_systems[&child] {
+                            if !system_bitset.contains(index) {
+                                systems.push(index);
+                                system_bitset.insert(index);
+                            }
+                        }
+                        descendants.extend(set_descendants[&child].iter().copied());
+                    }
+                }
+            }
+
+            set_systems.insert(id, systems);
+            set_system_bitsets.insert(id, system_bitset);
+            set_descendants.insert(id, descendants);
+        }
+
+        SetMembership {
+            set_systems,
+            set_system_bitsets,
+            set_descendants,
+        }
+    }
+
+    /// Checks that the dependencies can be satisfied given the hierarchy.
+    fn check_dependencies(
+        &self,
+        set_systems: &HashMap<NodeId, Vec<usize>>,
+        set_system_bitsets: &HashMap<NodeId, FixedBitSet>,
+        set_descendants: &HashMap<NodeId, HashSet<NodeId>>,
+    ) -> Result<(), ScheduleBuildError> {
+        for (a, b) in self.dependency.edges() {
+            // a node cannot be ordered relative to a set it belongs to
+            let a_contains_b = a.is_set() && set_descendants[&a].contains(&b);
+            let b_contains_a = b.is_set() && set_descendants[&b].contains(&a);
+            if a_contains_b || b_contains_a {
+                return Err(ScheduleBuildError::CrossDependency(
+                    self.node_name(a),
+                    self.node_name(b),
+                ));
+            }
+
+            // sets that share systems cannot be ordered relative to each other
+            if a.is_set() && b.is_set() {
+                let a_systems = &set_system_bitsets[&a];
+                let b_systems = &set_system_bitsets[&b];
+                if !a_systems.is_disjoint(b_systems) {
+                    return Err(ScheduleBuildError::SetsHaveOrderButIntersect(
+                        self.node_name(a),
+                        self.node_name(b),
+                    ));
+                }
+            }
+        }
+
+        // ordering relative to a system type set is ambiguous if it has several instances
+        for (&id, systems) in set_systems {
+            let set = &self.system_sets[id.index()];
+            if set.is_system_type()
+                && systems.len() > 1
+                && (!self.dependency.predecessors(id).is_empty()
+                    || !self.dependency.successors(id).is_empty())
+            {
+                return Err(ScheduleBuildError::SystemTypeSetAmbiguity(
+                    self.node_name(id),
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the pairs of systems that are not ordered relative to each other and have
+    /// conflicting access, skipping those whose ambiguities are not reported.
+    ///
+    /// Exclusive systems conflict with every other system.
+    fn find_conflicting_systems(
+        &self,
+        topsort: &[NodeId],
+        topsort_indices: &HashMap<NodeId, usize>,
+        dependency_flattened: &DiGraph,
+        set_systems: &HashMap<NodeId, Vec<usize>>,
+    ) -> Vec<(NodeId, NodeId, Vec<ComponentId>)> {
+        // the systems that run after each system, by position in `topsort`
+        let mut successors = vec![FixedBitSet::with_capacity(topsort.len()); topsort.len()];
+        for (i, &id) in topsort.iter().enumerate().rev() {
+            for successor in dependency_flattened.successors(id) {
+                let j = topsort_indices[successor];
+                let (before, after) = successors.split_at_mut(j);
+                before[i].union_with(&after[0]);
+                before[i].insert(j);
+            }
+        }
+
+        let mut ignore_all = FixedBitSet::with_capacity(self.systems.len());
+        for &id in &self.ambiguous_with_all {
+            ignore_all.extend(node_systems(id, set_systems));
+        }
+        let mut ignored_pairs = HashSet::new();
+        for (a, b) in self.ambiguous_with.edges() {
+            for system_a in node_systems(a, set_systems) {
+                for system_b in node_systems(b, set_systems) {
+                    ignored_pairs.insert((system_a.min(system_b), system_a.max(system_b)));
+                }
+            }
+        }
+
+        let mut conflicting_systems = Vec::new();
+        for (i, &a) in topsort.iter().enumerate() {
+            for (j, &b) in topsort.iter().enumerate().skip(i + 1) {
+                let (index_a, index_b) = (a.index(), b.index());
+                if successors[i].contains(j)
+                    || ignore_all.contains(index_a)
+                    || ignore_all.contains(index_b)
+                    || ignored_pairs.contains(&(index_a.min(index_b), index_a.max(index_b)))
+                {
+                    continue;
+                }
+
+                let system_a = self.systems[index_a].get();
+                let system_b = self.systems[index_b].get();
+                if system_a.is_exclusive() || system_b.is_exclusive() {
+                    conflicting_systems.push((a, b, Vec::new()));
+                } else {
+                    let conflicts = system_a
+                        .component_access()
+                        .get_conflicts(system_b.component_access());
+                    if !conflicts.is_empty() {
+                        conflicting_systems.push((a, b, conflicts));
+                    }
+                }
+            }
+        }
+        conflicting_systems
+    }
+
+    /// Returns a copy of `dependency_flattened` where an [`apply_system_buffers`] runs between each
+    /// system with buffers and the systems that depend on it.
+    ///
+    /// Sync points are shared: every system is assigned a distance, the number of sync points
+    /// needed before it, and edges leading to the same distance use the same sync point.
+    fn auto_insert_apply_system_buffers(
+        &mut self,
+        dependency_flattened: &DiGraph,
+    ) -> Result<DiGraph, ScheduleBuildError> {
+        let mut sync_point_graph = DiGraph::default();
+        for node in dependency_flattened.nodes() {
+            sync_point_graph.add_node(node);
+        }
+
+        let topo = dependency_flattened
+            .toposort()
+            .map_err(|cycle| ScheduleBuildError::DependencyCycle(self.describe_cycle(&cycle)))?;
+
+        // calculate the number of sync points each system is from the beginning of the graph
+        let mut distances: HashMap<NodeId, u32> = HashMap::with_capacity(topo.len());
+        for node in topo {
+            let node_distance = distances.get(&node).copied().unwrap_or(0);
+            let add_sync_after = self.systems[node.index()].get().has_deferred();
+
+            for &target in dependency_flattened.successors(node) {
+                let add_sync_on_edge =
+                    add_sync_after && !is_apply_system_buffers(self.systems[target.index()].get());
+                let weight = if add_sync_on_edge { 1 } else { 0 };
+                let distance = distances.entry(target).or_insert(0);
+                *distance = (*distance).max(node_distance + weight);
+                let distance = *distance;
+
+                if add_sync_on_edge {
+                    let sync_point = self.get_sync_point(distance);
+                    sync_point_graph.add_edge(node, sync_point);
+                    sync_point_graph.add_edge(sync_point, target);
+                } else {
+                    sync_point_graph.add_edge(node, target);
+                }
+            }
+        }
+
+        Ok(sync_point_graph)
+    }
+
+    /// Returns the `apply_system_buffers` for the given distance, adding it if needed.
+    fn get_sync_point(&mut self, distance: u32) -> NodeId {
+        if let Some(&id) = self.auto_sync_node_ids.get(&distance) {
+            return id;
+        }
+
+        let id = NodeId::System(self.systems.len());
+        self.systems.push(SystemNode {
+            inner: Some(Box::new(ExclusiveFunctionSystem::new(apply_system_buffers))),
+        });
+        self.system_conditions.push(Some(Vec::new()));
+        self.hierarchy.add_node(id);
+        self.dependency.add_node(id);
+        // sync points run the buffers of the systems ordered before them, whatever they access
+        self.ambiguous_with_all.insert(id);
+        self.uninit.push((id, 0));
+        self.auto_sync_node_ids.insert(distance, id);
+        id
+    }
+
+    /// Updates the `SystemSchedule` from the `ScheduleGraph`.
+    fn update_schedule(
+        &mut self,
+        schedule: &mut SystemSchedule,
+        world: &mut World,
+    ) -> Result<(), ScheduleBuildError> {
+        if !self.uninit.is_empty() {
+            return Err(ScheduleBuildError::Uninitialized);
+        }
+
+        // move systems out of old schedule
+        for ((id, system), conditions) in schedule
+            .system_ids
+            .drain(..)
+            .zip(schedule.systems.drain(..))
+            .zip(schedule.system_conditions.drain(..))
+        {
+            self.systems[id.index()].inner = Some(system);
+            self.system_conditions[id.index()] = Some(conditions);
+        }
+
+        for (id, conditions) in schedule
+            .set_ids
+            .drain(..)
+            .zip(schedule.set_conditions.drain(..))
+        {
+            self.system_set_conditions[id.index()] = Some(conditions);
+        }
+
+        *schedule = self.build_schedule(world)?;
+
+        // move systems into new schedule
+        for &id in &schedule.system_ids {
+            let system = self.systems[id.index()].inner.take().unwrap();
+            let conditions = self.system_conditions[id.index()].take().unwrap();
+            schedule.systems.push(system);
+            schedule.system_conditions.push(conditions);
+        }
+
+        for &id in &schedule.set_ids {
+            let conditions = self.system_set_conditions[id.index()].take().unwrap();
+            schedule.set_conditions.push(conditions);
+        }
+
+        Ok(())
+    }
+
+    fn describe_cycle(&self, cycle: &[NodeId]) -> String {
+        let mut description = String::new();
+        for id in cycle.iter().chain(cycle.first()) {
+            if !description.is_empty() {
+                description.push_str(" -> ");
+            }
+            description.push_str(&self.node_name(*id));
+        }
+        description
+    }
+}
+
+/// The systems each set contains, directly or through nested sets.
+struct SetMembership {
+    set_systems: HashMap<NodeId, Vec<usize>>,
+    set_system_bitsets: HashMap<NodeId, FixedBitSet>,
+    /// The systems and sets each set contains.
+    set_descendants: HashMap<NodeId, HashSet<NodeId>>,
+}
+
+/// Returns the indices of the systems a node stands for in the flattened dependency graph.
+fn node_systems(id: NodeId, set_systems: &HashMap<NodeId, Vec<usize>>) -> Vec<usize> {
+    match id {
+        NodeId::System(index) => vec![index],
+        NodeId::Set(_) => set_systems[&id].clone(),
+    }
+}
+
+/// Category of errors encountered during schedule construction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScheduleBuildError {
+    /// A system set contains itself, directly or through nested sets.
+    HierarchyCycle(String),
+    /// The systems and system sets cannot be ordered, because of a cycle of `before`/`after`
+    /// constraints.
+    DependencyCycle(String),
+    /// Tried to order a system or system set relative to a system set it belongs to.
+    CrossDependency(String, String),
+    /// Tried to order system sets that share systems.
+    SetsHaveOrderButIntersect(String, String),
+    /// Tried to order a system or system set relative to a system function added several times.
+    SystemTypeSetAmbiguity(String),
+    /// Tried to run a schedule before all of its systems have been initialized.
+    Uninitialized,
+}
+
+impl std::error::Error for ScheduleBuildError {}
+
+impl fmt::Display for ScheduleBuildError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ScheduleBuildError::HierarchyCycle(cycle) => {
+                write!(f, "System set hierarchy contains a cycle: {}", cycle)
+            }
+            ScheduleBuildError::DependencyCycle(cycle) => {
+                write!(f, "System dependencies contain a cycle: {}", cycle)
+            }
+            ScheduleBuildError::CrossDependency(a, b) => write!(
+                f,
+                "{} and {} have both `in_set` and `before`-`after` relationships (these might be \
+                transitive). This combination is unsolvable as a system cannot run before or \
+                after a set it belongs to.",
+                a, b
+            ),
+            ScheduleBuildError::SetsHaveOrderButIntersect(a, b) => write!(
+                f,
+                "{} and {} have a `before`-`after` relationship (which may be transitive) but \
+                share systems.",
+                a, b
+            ),
+            ScheduleBuildError::SystemTypeSetAmbiguity(set) => write!(
+                f,
+                "Tried to order against {} in a schedule that has more than one instance of it. \
+                Use a named system set instead.",
+                set
+            ),
+            ScheduleBuildError::Uninitialized => {
+                write!(f, "Systems in schedule have not been initialized.")
+            }
+        }
+    }
+}
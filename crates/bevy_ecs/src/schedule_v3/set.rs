@@ -0,0 +1,184 @@
+use std::{
+    any::TypeId,
+    fmt::Debug,
+    hash::{Hash, Hasher},
+    marker::PhantomData,
+};
+
+pub use bevy_ecs_macros::{ScheduleLabel, SystemSet};
+use bevy_utils::{define_boxed_label, label::DynEq};
+
+use crate::{
+    system::{IsFunctionSystem, SystemParam, SystemParamFunction},
+    world::World,
+};
+
+define_boxed_label!(
+    /// A strongly-typed class of labels used to identify a [`Schedule`](super::Schedule).
+    ScheduleLabel
+);
+
+/// A shorthand for `Box<dyn ScheduleLabel>`.
+pub type BoxedScheduleLabel = Box<dyn ScheduleLabel>;
+/// A shorthand for `Box<dyn SystemSet>`.
+pub type BoxedSystemSet = Box<dyn SystemSet>;
+
+/// Types that identify logical groups of systems.
+///
+/// Systems can be added to any number of sets with
+/// [`IntoSystemConfig::in_set`](super::IntoSystemConfig::in_set), and sets can themselves be
+/// nested. Ordering constraints and run conditions configured on a set apply to every system it
+/// contains.
+pub trait SystemSet: 'static + Send + Sync + Debug {
+    /// Returns `Some` if this system set is a [`SystemTypeSet`].
+    fn system_type(&self) -> Option<TypeId> {
+        None
+    }
+
+    /// Returns a boxed clone of this set.
+    fn dyn_clone(&self) -> Box<dyn SystemSet>;
+
+    /// Casts this value to a form where it can be compared with other type-erased values.
+    fn as_dyn_eq(&self) -> &dyn DynEq;
+
+    /// Feeds this value into the given [`Hasher`].
+    fn dyn_hash(&self, state: &mut dyn Hasher);
+}
+
+impl dyn SystemSet {
+    /// Returns `true` if this system set is a [`SystemTypeSet`].
+    pub fn is_system_type(&self) -> bool {
+        self.system_type().is_some()
+    }
+}
+
+impl PartialEq for dyn SystemSet {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_dyn_eq().dyn_eq(other.as_dyn_eq())
+    }
+}
+
+impl Eq for dyn SystemSet {}
+
+impl Hash for dyn SystemSet {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.dyn_hash(state);
+    }
+}
+
+impl Clone for Box<dyn SystemSet> {
+    fn clone(&self) -> Self {
+        self.dyn_clone()
+    }
+}
+
+/// A [`SystemSet`] grouping instances of the same function.
+///
+/// Every system built from a function is automatically added to the [`SystemTypeSet`] of that
+/// function, which is what allows using the function itself in
+/// [`before`](super::IntoSystemConfig::before) and [`after`](super::IntoSystemConfig::after).
+pub struct SystemTypeSet<T>(PhantomData<fn() -> T>);
+
+impl<T> SystemTypeSet<T> {
+    pub(crate) fn new() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<T> Debug for SystemTypeSet<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("SystemTypeSet")
+            .field(&std::any::type_name::<T>())
+            .finish()
+    }
+}
+
+impl<T> Hash for SystemTypeSet<T> {
+    fn hash<H: Hasher>(&self, _state: &mut H) {
+        // all instances of this type are equal
+    }
+}
+
+impl<T> Clone for SystemTypeSet<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for SystemTypeSet<T> {}
+
+impl<T> PartialEq for SystemTypeSet<T> {
+    #[inline]
+    fn eq(&self, _other: &Self) -> bool {
+        // all instances of this type are equal
+        true
+    }
+}
+
+impl<T> Eq for SystemTypeSet<T> {}
+
+impl<T: 'static> SystemSet for SystemTypeSet<T> {
+    fn system_type(&self) -> Option<TypeId> {
+        Some(TypeId::of::<T>())
+    }
+
+    fn dyn_clone(&self) -> Box<dyn SystemSet> {
+        Box::new(*self)
+    }
+
+    fn as_dyn_eq(&self) -> &dyn DynEq {
+        self
+    }
+
+    fn dyn_hash(&self, mut state: &mut dyn Hasher) {
+        TypeId::of::<Self>().hash(&mut state);
+        self.hash(&mut state);
+    }
+}
+
+/// Types that can be converted into a [`SystemSet`].
+pub trait IntoSystemSet<Marker>: Sized {
+    type Set: SystemSet;
+
+    fn into_set(self) -> Self::Set;
+}
+
+// systems sets
+impl<S: SystemSet> IntoSystemSet<()> for S {
+    type Set = Self;
+
+    #[inline]
+    fn into_set(self) -> Self::Set {
+        self
+    }
+}
+
+// systems
+impl<In, Out, Param, Marker, F> IntoSystemSet<(IsFunctionSystem, In, Out, Param, Marker)> for F
+where
+    Param: SystemParam,
+    F: SystemParamFunction<In, Out, Param, Marker>,
+{
+    type Set = SystemTypeSet<Self>;
+
+    #[inline]
+    fn into_set(self) -> Self::Set {
+        SystemTypeSet::new()
+    }
+}
+
+/// Marker for exclusive functions taking `&mut World`.
+pub struct IsExclusiveFunctionSystem;
+
+// exclusive systems
+impl<F> IntoSystemSet<IsExclusiveFunctionSystem> for F
+where
+    F: FnMut(&mut World) + Send + Sync + 'static,
+{
+    type Set = SystemTypeSet<Self>;
+
+    #[inline]
+    fn into_set(self) -> Self::Set {
+        SystemTypeSet::new()
+    }
+}
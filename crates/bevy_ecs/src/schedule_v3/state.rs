@@ -0,0 +1,92 @@
+use std::fmt::Debug;
+use std::hash::Hash;
+use std::mem;
+
+use crate as bevy_ecs;
+use crate::schedule_v3::ScheduleLabel;
+use crate::system::Resource;
+use crate::world::World;
+
+pub use bevy_ecs_macros::States;
+
+/// Types that can define world-wide states in a finite-state machine.
+///
+/// The [`Default`] trait defines the starting state.
+/// Multiple states can be defined for the same world,
+/// allowing you to classify the state of the world across orthogonal dimensions.
+/// You can access the current state of type `T` with the [`State<T>`] resource,
+/// and the queued state with the [`NextState<T>`] resource.
+///
+/// State transitions typically occur in the [`OnEnter<T>`] and [`OnExit<T>`] schedules,
+/// which are run by the [`apply_state_transition::<T>`] system.
+///
+/// # Example
+///
+/// ```rust
+/// use bevy_ecs::prelude::States;
+///
+/// #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default, States)]
+/// enum GameState {
+///     #[default]
+///     MainMenu,
+///     SettingsMenu,
+///     InGame,
+/// }
+/// ```
+pub trait States: 'static + Send + Sync + Clone + PartialEq + Eq + Hash + Debug + Default {}
+
+/// The label of a [`Schedule`](super::Schedule) that runs whenever [`State<S>`]
+/// enters this state.
+#[derive(ScheduleLabel, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct OnEnter<S: States>(pub S);
+
+/// The label of a [`Schedule`](super::Schedule) that runs whenever [`State<S>`]
+/// exits this state.
+#[derive(ScheduleLabel, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct OnExit<S: States>(pub S);
+
+/// A finite-state machine whose transitions have associated schedules
+/// ([`OnEnter(state)`](OnEnter) and [`OnExit(state)`](OnExit)).
+///
+/// The current state value can be accessed through this resource. To *change* the state,
+/// queue a transition in the [`NextState<S>`] resource, and it will be applied by the next
+/// [`apply_state_transition::<S>`] system.
+#[derive(Resource, Default, Debug)]
+pub struct State<S: States>(pub S);
+
+/// The next state of [`State<S>`].
+///
+/// To queue a transition, just set the contained value to `Some(next_state)`.
+/// Note that these transitions can be overridden by other systems:
+/// only the actual value of this resource at the time of [`apply_state_transition`] matters.
+#[derive(Resource, Default, Debug)]
+pub struct NextState<S: States>(pub Option<S>);
+
+impl<S: States> NextState<S> {
+    /// Tentatively set a planned state transition to `Some(state)`.
+    pub fn set(&mut self, state: S) {
+        self.0 = Some(state);
+    }
+}
+
+/// Run the enter schedule for the current state
+pub fn run_enter_schedule<S: States>(world: &mut World) {
+    let state = world.resource::<State<S>>().0.clone();
+    world.try_run_schedule(OnEnter(state)).ok();
+}
+
+/// If a new state is queued in [`NextState<S>`], this system:
+/// - Takes the new state value from [`NextState<S>`] and updates [`State<S>`].
+/// - Runs the [`OnExit(exited_state)`](OnExit) schedule.
+/// - Runs the [`OnEnter(entered_state)`](OnEnter) schedule.
+pub fn apply_state_transition<S: States>(world: &mut World) {
+    if let Some(entered) = world.resource_mut::<NextState<S>>().0.take() {
+        let mut state = world.resource_mut::<State<S>>();
+        if state.0 != entered {
+            let exited = mem::replace(&mut state.0, entered.clone());
+            // Try to run the schedules if they exist.
+            world.try_run_schedule(OnExit(exited)).ok();
+            world.try_run_schedule(OnEnter(entered)).ok();
+        }
+    }
+}
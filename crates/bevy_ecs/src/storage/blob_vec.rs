@@ -52,7 +52,7 @@ impl BlobVec {
         if item_layout.size() == 0 {
             BlobVec {
                 swap_scratch: NonNull::dangling(),
-                data: NonNull::dangling(),
+                data: dangling_with_align(item_layout.align()),
                 capacity: usize::MAX,
                 len: 0,
                 item_layout,
@@ -63,7 +63,7 @@ impl BlobVec {
                 .unwrap_or_else(|| std::alloc::handle_alloc_error(item_layout));
             let mut blob_vec = BlobVec {
                 swap_scratch,
-                data: NonNull::dangling(),
+                data: dangling_with_align(item_layout.align()),
                 capacity: 0,
                 len: 0,
                 item_layout,
@@ -104,6 +104,36 @@ impl BlobVec {
         }
     }
 
+    /// Shrinks the capacity of the [`BlobVec`] to its length, freeing the allocation entirely if
+    /// it is empty.
+    pub fn shrink_to_fit(&mut self) {
+        if self.item_layout.size() == 0 || self.capacity == self.len {
+            return;
+        }
+        let old_layout =
+            array_layout(&self.item_layout, self.capacity).expect("array layout should be valid");
+        if self.len == 0 {
+            // SAFETY:
+            // - ptr was be allocated via this allocator
+            // - the layout of the ptr was `array_layout(self.item_layout, self.capacity)`
+            unsafe { std::alloc::dealloc(self.get_ptr_mut().as_ptr(), old_layout) };
+            self.data = dangling_with_align(self.item_layout.align());
+        } else {
+            let new_layout =
+                array_layout(&self.item_layout, self.len).expect("array layout should be valid");
+            // SAFETY:
+            // - ptr was be allocated via this allocator
+            // - the layout of the ptr was `array_layout(self.item_layout, self.capacity)`
+            // - `item_layout.size() > 0` and `self.len > 0`, so the layout size is non-zero
+            // - the new size is smaller than the old one, so it cannot overflow
+            let new_data = unsafe {
+                std::alloc::realloc(self.get_ptr_mut().as_ptr(), old_layout, new_layout.size())
+            };
+            self.data = NonNull::new(new_data).unwrap_or_else(|| handle_alloc_error(new_layout));
+        }
+        self.capacity = self.len;
+    }
+
     // SAFETY: must not be called for a ZST item layout
     #[warn(unsafe_op_in_unsafe_fn)] // to allow unsafe blocks in unsafe fn
     unsafe fn grow_exact(&mut self, increment: NonZeroUsize) {
@@ -349,6 +379,14 @@ impl Drop for BlobVec {
     }
 }
 
+/// Returns a dangling pointer that is well-aligned for items with the given alignment, so that
+/// empty slices over an unallocated [`BlobVec`] remain valid.
+fn dangling_with_align(align: usize) -> NonNull<u8> {
+    debug_assert!(align.is_power_of_two());
+    // SAFETY: a power of two is never zero
+    unsafe { NonNull::new_unchecked(align as *mut u8) }
+}
+
 /// From <https://doc.rust-lang.org/beta/src/core/alloc/layout.rs.html>
 fn array_layout(layout: &Layout, n: usize) -> Option<Layout> {
     let (array_layout, offset) = repeat_layout(layout, n)?;
@@ -458,6 +496,36 @@ mod tests {
         assert_eq!(blob_vec.capacity(), 1_000);
     }
 
+    #[test]
+    fn shrink_to_fit() {
+        let item_layout = Layout::new::<usize>();
+        // SAFETY: `drop` fn is `None`, usize doesn't need dropping
+        let mut blob_vec = unsafe { BlobVec::new(item_layout, None, 64) };
+        // SAFETY: `i` is a usize, i.e. the type corresponding to `item_layout`
+        unsafe {
+            for i in 0..10 {
+                push(&mut blob_vec, i as usize);
+            }
+        }
+
+        blob_vec.shrink_to_fit();
+        assert_eq!(blob_vec.capacity(), 10);
+        // SAFETY: all values pushed are usize
+        unsafe {
+            for i in 0..10 {
+                assert_eq!(*get_mut::<usize>(&mut blob_vec, i), i);
+            }
+            push(&mut blob_vec, 10_usize);
+        }
+        assert_eq!(blob_vec.len(), 11);
+
+        blob_vec.clear();
+        blob_vec.shrink_to_fit();
+        assert_eq!(blob_vec.capacity(), 0);
+        // SAFETY: the vec is empty, so the slice has no items to read
+        assert!(unsafe { blob_vec.get_slice::<usize>() }.is_empty());
+    }
+
     #[derive(Debug, Eq, PartialEq, Clone)]
     struct Foo {
         a: u8,
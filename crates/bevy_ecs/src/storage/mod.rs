@@ -7,6 +7,18 @@ mod table;
 pub use sparse_set::*;
 pub use table::*;
 
+/// Which allocations [`World::shrink_storage_with`](crate::world::World::shrink_storage_with)
+/// releases.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ShrinkPolicy {
+    /// Shrinks every allocation to fit its contents.
+    #[default]
+    All,
+    /// Only frees the allocations of empty tables, sparse sets and archetypes, leaving the
+    /// storage of live entities untouched.
+    EmptyOnly,
+}
+
 /// The raw data stores of a [World](crate::world::World)
 #[derive(Default)]
 pub struct Storages {
@@ -1,10 +1,10 @@
 use crate::{
     component::{ComponentId, ComponentInfo, ComponentTicks},
     entity::Entity,
-    storage::Column,
+    storage::{Column, ShrinkPolicy},
 };
 use bevy_ptr::{OwningPtr, Ptr};
-use std::{cell::UnsafeCell, hash::Hash, marker::PhantomData};
+use std::{cell::UnsafeCell, hash::Hash, marker::PhantomData, mem::size_of};
 
 type EntityId = u32;
 
@@ -30,6 +30,14 @@ impl<I, V> SparseArray<I, V> {
     }
 }
 
+impl<I, V> SparseArray<I, V> {
+    /// The bytes allocated for the sparse values, including unused capacity.
+    #[inline]
+    pub fn bytes_allocated(&self) -> usize {
+        self.values.capacity() * size_of::<Option<V>>()
+    }
+}
+
 impl<I: SparseSetIndex, V> SparseArray<I, V> {
     #[inline]
     pub fn insert(&mut self, index: I, value: V) {
@@ -70,6 +78,32 @@ impl<I: SparseSetIndex, V> SparseArray<I, V> {
     pub fn clear(&mut self) {
         self.values.clear();
     }
+
+    /// Drops trailing empty slots and shrinks the capacity to fit the remaining ones.
+    pub fn shrink_to_fit(&mut self) {
+        let len = self
+            .values
+            .iter()
+            .rposition(Option::is_some)
+            .map_or(0, |index| index + 1);
+        self.values.truncate(len);
+        self.values.shrink_to_fit();
+    }
+}
+
+/// Memory usage of a [`ComponentSparseSet`], as reported by [`SparseSets::stats`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SparseSetStats {
+    /// The id of the component stored in the set.
+    pub component_id: ComponentId,
+    /// The number of components stored in the set.
+    pub len: usize,
+    /// The number of components the set can hold without reallocating.
+    pub capacity: usize,
+    /// The bytes occupied by the stored components, their change ticks and owning entities.
+    pub bytes_used: usize,
+    /// The bytes allocated by the set, including unused capacity and the sparse index.
+    pub bytes_allocated: usize,
 }
 
 /// A sparse data structure of [Components](crate::component::Component)
@@ -234,6 +268,12 @@ impl ComponentSparseSet {
         }
     }
 
+    pub(crate) fn shrink_to_fit(&mut self) {
+        self.dense.shrink_to_fit();
+        self.entities.shrink_to_fit();
+        self.sparse.shrink_to_fit();
+    }
+
     pub(crate) fn check_change_ticks(&mut self, change_tick: u32) {
         self.dense.check_change_ticks(change_tick);
     }
@@ -425,6 +465,39 @@ impl SparseSets {
         }
     }
 
+    /// Returns the memory used by each component sparse set.
+    pub fn stats(&self) -> Vec<SparseSetStats> {
+        self.sets
+            .iter()
+            .map(|(component_id, set)| {
+                let dense = set.dense.stats();
+                // the full entity is only stored in debug builds, see `ComponentSparseSet::entities`
+                let entity_size = if cfg!(debug_assertions) {
+                    size_of::<Entity>()
+                } else {
+                    size_of::<EntityId>()
+                };
+                SparseSetStats {
+                    component_id: *component_id,
+                    len: dense.len,
+                    capacity: dense.capacity,
+                    bytes_used: dense.bytes_used + set.entities.len() * entity_size,
+                    bytes_allocated: dense.bytes_allocated
+                        + set.entities.capacity() * entity_size
+                        + set.sparse.bytes_allocated(),
+                }
+            })
+            .collect()
+    }
+
+    pub(crate) fn shrink_to_fit(&mut self, policy: ShrinkPolicy) {
+        for set in self.sets.values_mut() {
+            if policy == ShrinkPolicy::All || set.is_empty() {
+                set.shrink_to_fit();
+            }
+        }
+    }
+
     pub(crate) fn check_change_ticks(&mut self, change_tick: u32) {
         for set in self.sets.values_mut() {
             set.check_change_ticks(change_tick);
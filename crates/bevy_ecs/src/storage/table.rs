@@ -2,13 +2,14 @@ use crate::{
     component::{ComponentId, ComponentInfo, ComponentTicks, Components},
     entity::Entity,
     query::debug_checked_unreachable,
-    storage::{blob_vec::BlobVec, SparseSet},
+    storage::{blob_vec::BlobVec, ShrinkPolicy, SparseSet},
 };
 use bevy_ptr::{OwningPtr, Ptr, PtrMut};
 use bevy_utils::HashMap;
 use std::alloc::Layout;
 use std::{
     cell::UnsafeCell,
+    mem::size_of,
     ops::{Index, IndexMut},
 };
 
@@ -32,6 +33,21 @@ impl TableId {
     }
 }
 
+/// Memory usage of a [`Column`], as reported by [`Column::stats`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ColumnStats {
+    /// The number of components stored in the column.
+    pub len: usize,
+    /// The number of components the column can hold without reallocating.
+    pub capacity: usize,
+    /// The size in bytes of a single component, excluding its change ticks.
+    pub item_size: usize,
+    /// The bytes occupied by the stored components and their change ticks.
+    pub bytes_used: usize,
+    /// The bytes allocated for components and their change ticks, including unused capacity.
+    pub bytes_allocated: usize,
+}
+
 #[derive(Debug)]
 pub struct Column {
     data: BlobVec,
@@ -160,6 +176,12 @@ impl Column {
         self.ticks.reserve_exact(additional);
     }
 
+    /// Shrinks the capacity of the column to its length.
+    pub fn shrink_to_fit(&mut self) {
+        self.data.shrink_to_fit();
+        self.ticks.shrink_to_fit();
+    }
+
     #[inline]
     pub fn get_data_ptr(&self) -> Ptr<'_> {
         self.data.get_ptr()
@@ -207,6 +229,31 @@ impl Column {
         self.ticks.clear();
     }
 
+    /// The number of components this column can hold without reallocating.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        // zero-sized components report an unbounded data capacity, the ticks are always allocated
+        self.ticks.capacity()
+    }
+
+    /// Returns the memory used by this column.
+    pub fn stats(&self) -> ColumnStats {
+        let item_size = self.data.layout().size();
+        let ticks_size = size_of::<UnsafeCell<ComponentTicks>>();
+        let data_allocated = if item_size == 0 {
+            0
+        } else {
+            self.data.capacity() * item_size
+        };
+        ColumnStats {
+            len: self.len(),
+            capacity: self.capacity(),
+            item_size,
+            bytes_used: self.len() * (item_size + ticks_size),
+            bytes_allocated: data_allocated + self.ticks.capacity() * ticks_size,
+        }
+    }
+
     #[inline]
     pub(crate) fn check_change_ticks(&mut self, change_tick: u32) {
         for component_ticks in &mut self.ticks {
@@ -215,6 +262,23 @@ impl Column {
     }
 }
 
+/// Memory usage of a [`Table`], as reported by [`Tables::stats`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TableStats {
+    /// The id of the table.
+    pub id: TableId,
+    /// The number of entities stored in the table.
+    pub entity_count: usize,
+    /// The number of entities the table can hold without reallocating.
+    pub entity_capacity: usize,
+    /// The memory used by each column of the table.
+    pub columns: Vec<(ComponentId, ColumnStats)>,
+    /// The bytes occupied by the stored entities and components.
+    pub bytes_used: usize,
+    /// The bytes allocated for entities and components, including unused capacity.
+    pub bytes_allocated: usize,
+}
+
 pub struct Table {
     columns: SparseSet<ComponentId, Column>,
     entities: Vec<Entity>,
@@ -367,6 +431,18 @@ impl Table {
         self.columns.contains(component_id)
     }
 
+    /// Shrinks the capacity of the table and all its columns as much as possible.
+    pub(crate) fn shrink_to_fit(&mut self) {
+        self.entities.shrink_to_fit();
+
+        // columns must be able to hold as many rows as the entities vector, see `reserve`
+        let capacity = self.entities.capacity();
+        for column in self.columns.values_mut() {
+            column.shrink_to_fit();
+            column.reserve_exact(capacity - column.len());
+        }
+    }
+
     pub(crate) fn reserve(&mut self, additional: usize) {
         if self.entities.capacity() - self.entities.len() < additional {
             self.entities.reserve(additional);
@@ -506,12 +582,49 @@ impl Tables {
         self.tables.iter()
     }
 
+    /// Returns the memory used by each table.
+    pub fn stats(&self) -> Vec<TableStats> {
+        self.tables
+            .iter()
+            .enumerate()
+            .map(|(index, table)| {
+                let columns: Vec<_> = table
+                    .columns
+                    .iter()
+                    .map(|(id, column)| (*id, column.stats()))
+                    .collect();
+                let entity_size = size_of::<Entity>();
+                TableStats {
+                    id: TableId::new(index),
+                    entity_count: table.len(),
+                    entity_capacity: table.capacity(),
+                    bytes_used: table.len() * entity_size
+                        + columns.iter().map(|(_, c)| c.bytes_used).sum::<usize>(),
+                    bytes_allocated: table.capacity() * entity_size
+                        + columns
+                            .iter()
+                            .map(|(_, c)| c.bytes_allocated)
+                            .sum::<usize>(),
+                    columns,
+                }
+            })
+            .collect()
+    }
+
     pub(crate) fn clear(&mut self) {
         for table in &mut self.tables {
             table.clear();
         }
     }
 
+    pub(crate) fn shrink_to_fit(&mut self, policy: ShrinkPolicy) {
+        for table in &mut self.tables {
+            if policy == ShrinkPolicy::All || table.is_empty() {
+                table.shrink_to_fit();
+            }
+        }
+    }
+
     pub(crate) fn check_change_ticks(&mut self, change_tick: u32) {
         for table in &mut self.tables {
             table.check_change_ticks(change_tick);
@@ -0,0 +1,340 @@
+use std::any::Any;
+
+use async_channel::{Receiver, Sender};
+use bevy_utils::tracing::warn;
+
+use super::{Command, CommandQueue, Despawn, InsertBatch, Remove};
+use crate as bevy_ecs;
+use crate::{bundle::Bundle, component::Component, entity::Entity, system::Resource, world::World};
+
+/// A buffer of [`Command`]s that is not tied to a system.
+///
+/// Unlike [`Commands`](super::Commands), a [`CommandBuffer`] does not borrow the [`World`], so it
+/// can be recorded anywhere, for example in a task running on the
+/// [`AsyncComputeTaskPool`](bevy_tasks::AsyncComputeTaskPool). Once recorded, it is either
+/// applied directly with [`CommandBuffer::apply`], or submitted to the world through a
+/// [`CommandBufferSender`] and applied by [`apply_command_buffers`].
+///
+/// Since the buffer cannot allocate entities without the [`World`], the entities it spawns must
+/// be reserved beforehand with [`CommandBuffer::reserve_entities`]. This lets later commands of
+/// the buffer refer to the entities it spawns.
+///
+/// Consecutive spawns or inserts of the same bundle type are recorded as a single batch, so that
+/// the affected entities are moved between archetypes together. Other command queues, such as the
+/// ones of [`Commands`](super::Commands) and [`ParallelCommands`](super::ParallelCommands), apply
+/// their commands one at a time.
+///
+/// ```
+/// use bevy_ecs::{prelude::*, system::{apply_command_buffers, CommandBuffer, CommandBuffers}};
+///
+/// #[derive(Component)]
+/// struct Health(u32);
+///
+/// #[derive(Component)]
+/// struct Target(Entity);
+///
+/// let mut world = World::new();
+/// world.init_resource::<CommandBuffers>();
+/// let sender = world.resource::<CommandBuffers>().sender();
+/// let mut buffer = CommandBuffer::new();
+/// buffer.reserve_entities(&world, 100);
+///
+/// std::thread::spawn(move || {
+///     let boss = buffer.spawn((Health(1000),));
+///     for _ in 0..99 {
+///         let minion = buffer.spawn((Health(100),));
+///         buffer.insert(minion, Target(boss));
+///     }
+///     sender.submit(buffer);
+/// })
+/// .join()
+/// .unwrap();
+///
+/// apply_command_buffers(&mut world);
+/// assert_eq!(world.query::<&Health>().iter(&world).count(), 100);
+/// assert_eq!(world.query::<&Target>().iter(&world).count(), 99);
+/// ```
+#[derive(Default)]
+pub struct CommandBuffer {
+    queue: CommandQueue,
+    pending: Option<Box<dyn PendingBatch>>,
+    /// Entities reserved for [`CommandBuffer::spawn`], in reverse order.
+    reserved: Vec<Entity>,
+}
+
+impl CommandBuffer {
+    /// Creates an empty [`CommandBuffer`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Pushes a generic [`Command`] to the buffer.
+    pub fn add<C: Command>(&mut self, command: C) {
+        self.flush_pending();
+        self.queue.push(command);
+    }
+
+    /// Reserves `count` entities in the `world`, to be spawned with [`CommandBuffer::spawn`].
+    ///
+    /// Reserved entities that are not spawned are despawned when the buffer is applied.
+    pub fn reserve_entities(&mut self, world: &World, count: u32) {
+        let reserved = world.entities().reserve_entities(count).collect::<Vec<_>>();
+        self.reserved.splice(0..0, reserved.into_iter().rev());
+    }
+
+    /// Spawns a new entity with the given [`Bundle`], returning it so that other commands can
+    /// refer to it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if all the entities reserved with [`CommandBuffer::reserve_entities`] were already
+    /// spawned.
+    pub fn spawn<B: Bundle>(&mut self, bundle: B) -> Entity {
+        let entity = self.reserved.pop().expect(
+            "CommandBuffer::spawn requires an entity reserved with CommandBuffer::reserve_entities",
+        );
+        self.insert_bundle(entity, bundle);
+        entity
+    }
+
+    /// Inserts a [`Bundle`] into an existing `entity`.
+    ///
+    /// # Panics
+    ///
+    /// Applying the buffer panics if the entity does not exist.
+    pub fn insert_bundle<B: Bundle>(&mut self, entity: Entity, bundle: B) {
+        self.batch::<PendingInserts<B>>().0.push((entity, bundle));
+    }
+
+    /// Inserts a [`Component`] into an existing `entity`.
+    ///
+    /// # Panics
+    ///
+    /// Applying the buffer panics if the entity does not exist.
+    pub fn insert<C: Component>(&mut self, entity: Entity, component: C) {
+        self.insert_bundle(entity, (component,));
+    }
+
+    /// Removes a [`Component`] from `entity`, if both exist.
+    pub fn remove<C: Component>(&mut self, entity: Entity) {
+        self.add(Remove::<C> {
+            entity,
+            phantom: Default::default(),
+        });
+    }
+
+    /// Despawns `entity`.
+    pub fn despawn(&mut self, entity: Entity) {
+        self.add(Despawn { entity });
+    }
+
+    /// Returns `true` if no commands were recorded and no entities are reserved.
+    pub fn is_empty(&self) -> bool {
+        self.queue.is_empty() && self.pending.is_none() && self.reserved.is_empty()
+    }
+
+    /// Applies the recorded commands to the `world`, in the order they were recorded, then
+    /// despawns the reserved entities that were not spawned. This clears the buffer.
+    pub fn apply(&mut self, world: &mut World) {
+        self.flush_pending();
+        for entity in self.reserved.drain(..) {
+            self.queue.push(Despawn { entity });
+        }
+        self.queue.apply(world);
+    }
+
+    /// Returns the batch currently being recorded, starting a new one if it is of another kind.
+    fn batch<P: PendingBatch + Default>(&mut self) -> &mut P {
+        let is_current = self
+            .pending
+            .as_mut()
+            .is_some_and(|pending| pending.as_any_mut().is::<P>());
+        if !is_current {
+            self.flush_pending();
+        }
+        self.pending
+            .get_or_insert_with(|| Box::new(P::default()))
+            .as_any_mut()
+            .downcast_mut::<P>()
+            .unwrap()
+    }
+
+    fn flush_pending(&mut self) {
+        if let Some(pending) = self.pending.take() {
+            pending.push_to(&mut self.queue);
+        }
+    }
+}
+
+/// Commands of a single kind and bundle type, recorded consecutively into a [`CommandBuffer`].
+trait PendingBatch: Send + Sync + 'static {
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+    fn push_to(self: Box<Self>, queue: &mut CommandQueue);
+}
+
+struct PendingInserts<B>(Vec<(Entity, B)>);
+
+impl<B> Default for PendingInserts<B> {
+    fn default() -> Self {
+        Self(Vec::new())
+    }
+}
+
+impl<B: Bundle> PendingBatch for PendingInserts<B> {
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn push_to(self: Box<Self>, queue: &mut CommandQueue) {
+        queue.push(InsertBatch {
+            bundles_iter: self.0,
+        });
+    }
+}
+
+/// A channel through which [`CommandBuffer`]s recorded outside of systems are submitted to the
+/// [`World`].
+///
+/// Submitted buffers are applied, in submission order, by [`apply_command_buffers`]. The `App` of
+/// `bevy_app` adds this resource and applies the submitted buffers at the start of each update.
+#[derive(Resource)]
+pub struct CommandBuffers {
+    sender: Sender<CommandBuffer>,
+    receiver: Receiver<CommandBuffer>,
+}
+
+impl Default for CommandBuffers {
+    fn default() -> Self {
+        let (sender, receiver) = async_channel::unbounded();
+        Self { sender, receiver }
+    }
+}
+
+impl CommandBuffers {
+    /// Returns a new [`CommandBufferSender`] submitting to this channel.
+    pub fn sender(&self) -> CommandBufferSender {
+        CommandBufferSender(self.sender.clone())
+    }
+}
+
+/// The sending half of [`CommandBuffers`]. Can be cloned and moved to other threads or tasks.
+#[derive(Clone)]
+pub struct CommandBufferSender(Sender<CommandBuffer>);
+
+impl CommandBufferSender {
+    /// Submits a recorded `buffer`, to be applied the next time [`apply_command_buffers`] runs.
+    pub fn submit(&self, buffer: CommandBuffer) {
+        if buffer.is_empty() {
+            return;
+        }
+        if self.0.try_send(buffer).is_err() {
+            warn!("Dropped a submitted CommandBuffer because the CommandBuffers resource was removed.");
+        }
+    }
+}
+
+/// Applies all [`CommandBuffer`]s submitted through the [`CommandBuffers`] resource so far.
+///
+/// Does nothing if the resource does not exist.
+pub fn apply_command_buffers(world: &mut World) {
+    let receiver = match world.get_resource::<CommandBuffers>() {
+        Some(buffers) => buffers.receiver.clone(),
+        None => return,
+    };
+    while let Ok(mut buffer) = receiver.try_recv() {
+        buffer.apply(world);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::component::Component;
+
+    #[derive(Component, Debug, PartialEq)]
+    struct A(u32);
+
+    #[derive(Component, Debug, PartialEq)]
+    struct B(u32);
+
+    #[test]
+    fn command_buffer_batches_in_order() {
+        let mut world = World::new();
+        let entities: Vec<_> = (0..10).map(|_| world.spawn().id()).collect();
+
+        let mut buffer = CommandBuffer::new();
+        for (i, entity) in entities.iter().enumerate() {
+            buffer.insert(*entity, A(i as u32));
+        }
+        buffer.despawn(entities[0]);
+        for entity in &entities[1..] {
+            buffer.insert(*entity, B(0));
+        }
+        buffer.remove::<A>(entities[1]);
+        buffer.reserve_entities(&world, 3);
+        let spawned = buffer.spawn((A(100), B(100)));
+        buffer.insert(spawned, A(101));
+        let unused = buffer.spawn((A(102), B(102)));
+        buffer.despawn(unused);
+        buffer.apply(&mut world);
+        assert!(buffer.is_empty());
+
+        assert!(world.get_entity(entities[0]).is_none());
+        assert!(world.get::<A>(entities[1]).is_none());
+        assert_eq!(world.get::<A>(entities[2]), Some(&A(2)));
+        assert_eq!(world.get::<B>(entities[2]), Some(&B(0)));
+        assert_eq!(world.get::<A>(spawned), Some(&A(101)));
+        assert_eq!(world.query::<(&A, &B)>().iter(&world).count(), 9);
+        // the entity that was reserved but not spawned is freed
+        assert_eq!(world.entities().len(), 10);
+    }
+
+    #[test]
+    fn command_buffers_submitted_from_other_threads() {
+        let mut world = World::new();
+        world.init_resource::<CommandBuffers>();
+        let entity = world.spawn().id();
+
+        let handles: Vec<_> = (0..4)
+            .map(|i| {
+                let sender = world.resource::<CommandBuffers>().sender();
+                let mut buffer = CommandBuffer::new();
+                buffer.reserve_entities(&world, 1);
+                std::thread::spawn(move || {
+                    buffer.spawn((A(i),));
+                    buffer.insert(entity, B(i));
+                    sender.submit(buffer);
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        apply_command_buffers(&mut world);
+        assert_eq!(world.query::<&A>().iter(&world).count(), 4);
+        assert!(world.get::<B>(entity).is_some());
+
+        // buffers are only applied once
+        apply_command_buffers(&mut world);
+        assert_eq!(world.query::<&A>().iter(&world).count(), 4);
+    }
+
+    #[test]
+    #[should_panic(expected = "requires an entity reserved")]
+    fn command_buffer_spawn_without_reservation() {
+        CommandBuffer::new().spawn((A(0),));
+    }
+
+    #[test]
+    #[should_panic]
+    fn command_buffer_insert_missing_entity() {
+        let mut world = World::new();
+        let entity = world.spawn().id();
+        world.despawn(entity);
+
+        let mut buffer = CommandBuffer::new();
+        buffer.insert(entity, A(0));
+        buffer.apply(&mut world);
+    }
+}
@@ -76,8 +76,18 @@ impl CommandQueue {
         }
     }
 
-    /// Execute the queued [`Command`]s in the world.
+    /// Returns `true` if there are no queued [`Command`]s.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.metas.is_empty()
+    }
+
+    /// Execute the queued [`Command`]s in the world, one at a time.
     /// This clears the queue.
+    ///
+    /// Commands are not batched, use [`Commands::insert_batch`](super::Commands::insert_batch) or
+    /// a [`CommandBuffer`](super::CommandBuffer) to move many entities between archetypes
+    /// together.
     #[inline]
     pub fn apply(&mut self, world: &mut World) {
         // flush the previously queued entities
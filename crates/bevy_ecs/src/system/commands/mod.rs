@@ -1,3 +1,4 @@
+mod command_buffer;
 mod command_queue;
 mod parallel_scope;
 
@@ -5,14 +6,18 @@ use crate::{
     bundle::Bundle,
     component::Component,
     entity::{Entities, Entity},
+    event::Event,
+    observer::{self, BoxedObserverSystem, Traversal, Trigger},
+    relation::RelationKind,
     world::{FromWorld, World},
 };
 use bevy_utils::tracing::{error, info, warn};
+pub use command_buffer::*;
 pub use command_queue::CommandQueue;
 pub use parallel_scope::*;
 use std::marker::PhantomData;
 
-use super::Resource;
+use super::{IntoSystem, Resource, RunSystem, SystemId};
 
 /// A [`World`] mutation.
 ///
@@ -314,6 +319,23 @@ impl<'w, 's> Commands<'w, 's> {
         self.queue.push(InsertOrSpawnBatch { bundles_iter });
     }
 
+    /// Inserts a [`Bundle`] into each of the given existing entities.
+    ///
+    /// This is faster than inserting the bundles one-by-one, as entities sharing an archetype
+    /// are moved to their new archetype together.
+    ///
+    /// # Panics
+    ///
+    /// The command will panic when applied if any of the entities does not exist.
+    pub fn insert_batch<I, B>(&mut self, bundles_iter: I)
+    where
+        I: IntoIterator + Send + Sync + 'static,
+        I::IntoIter: Iterator<Item = (Entity, B)>,
+        B: Bundle,
+    {
+        self.queue.push(InsertBatch { bundles_iter });
+    }
+
     /// Inserts a resource with standard starting values to the [`World`].
     ///
     /// If the resource already exists, nothing happens.
@@ -403,6 +425,45 @@ impl<'w, 's> Commands<'w, 's> {
         });
     }
 
+    /// Runs the system registered with the given [`SystemId`].
+    ///
+    /// See [`World::run_system`] for more details.
+    pub fn run_system(&mut self, system_id: SystemId) {
+        self.queue.push(RunSystem { system_id });
+    }
+
+    /// Adds a global observer system for events of type `E`.
+    ///
+    /// See [`World::observe`] for more details.
+    pub fn observe<E: Event, Params>(&mut self, system: impl IntoSystem<Trigger<E>, (), Params>) {
+        self.queue.push(Observe {
+            entity: None,
+            system: Box::new(IntoSystem::into_system(system)),
+        });
+    }
+
+    /// Runs the observers of `entity` for `event`, then the global observers.
+    ///
+    /// See [`World::trigger`] for more details.
+    pub fn trigger<E: Event>(&mut self, event: E, entity: Entity) {
+        self.queue.push(TriggerEvent::<E, ()> {
+            event,
+            entity,
+            phantom: PhantomData,
+        });
+    }
+
+    /// Runs the observers of `entity` for `event`, bubbling up through the [`Traversal`] `T`.
+    ///
+    /// See [`World::trigger_bubbling`] for more details.
+    pub fn trigger_bubbling<T: Traversal, E: Event>(&mut self, event: E, entity: Entity) {
+        self.queue.push(TriggerEvent::<E, T> {
+            event,
+            entity,
+            phantom: PhantomData,
+        });
+    }
+
     /// Adds a command directly to the command queue.
     ///
     /// `command` can be a built-in command, custom struct that implements [`Command`] or a closure
@@ -605,6 +666,61 @@ impl<'w, 's, 'a> EntityCommands<'w, 's, 'a> {
         self
     }
 
+    /// Adds an edge of the [`RelationKind`] `K` from the entity to `target`.
+    ///
+    /// See [`World::relate`] for more details.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use bevy_ecs::prelude::*;
+    /// #
+    /// # #[derive(Resource)]
+    /// # struct PlayerEntity { entity: Entity }
+    /// #
+    /// struct Targeting;
+    /// impl RelationKind for Targeting {}
+    ///
+    /// fn spawn_turret_system(mut commands: Commands, player: Res<PlayerEntity>) {
+    ///     commands.spawn().relate::<Targeting>(player.entity);
+    /// }
+    /// # bevy_ecs::system::assert_is_system(spawn_turret_system);
+    /// ```
+    pub fn relate<K: RelationKind>(&mut self, target: Entity) -> &mut Self {
+        self.commands.add(Relate::<K> {
+            source: self.entity,
+            target,
+            phantom: PhantomData,
+        });
+        self
+    }
+
+    /// Removes the edge of the [`RelationKind`] `K` from the entity to `target`, if it exists.
+    ///
+    /// See [`World::unrelate`] for more details.
+    pub fn unrelate<K: RelationKind>(&mut self, target: Entity) -> &mut Self {
+        self.commands.add(Unrelate::<K> {
+            source: self.entity,
+            target,
+            phantom: PhantomData,
+        });
+        self
+    }
+
+    /// Adds an observer system for events of type `E` triggered on the entity.
+    ///
+    /// See [`World::trigger`] for more details.
+    pub fn observe<E: Event, Params>(
+        &mut self,
+        system: impl IntoSystem<Trigger<E>, (), Params>,
+    ) -> &mut Self {
+        self.commands.add(Observe {
+            entity: Some(self.entity),
+            system: Box::new(IntoSystem::into_system(system)),
+        });
+        self
+    }
+
     /// Despawns the entity.
     ///
     /// See [`World::despawn`] for more details.
@@ -722,6 +838,34 @@ where
     }
 }
 
+pub struct InsertBatch<I, B>
+where
+    I: IntoIterator + Send + Sync + 'static,
+    B: Bundle,
+    I::IntoIter: Iterator<Item = (Entity, B)>,
+{
+    pub bundles_iter: I,
+}
+
+impl<I, B> Command for InsertBatch<I, B>
+where
+    I: IntoIterator + Send + Sync + 'static,
+    B: Bundle,
+    I::IntoIter: Iterator<Item = (Entity, B)>,
+{
+    fn write(self, world: &mut World) {
+        let bundles: Vec<_> = self.bundles_iter.into_iter().collect();
+        if let Some((entity, _)) = bundles
+            .iter()
+            .find(|(entity, _)| !world.entities().contains(*entity))
+        {
+            panic!("error[B0003]: Could not insert a bundle (of type `{}`) for entity {:?} because it doesn't exist in this World.", std::any::type_name::<B>(), entity);
+        }
+        // all entities exist, so this only inserts
+        let _ = world.insert_or_spawn_batch(bundles);
+    }
+}
+
 #[derive(Debug)]
 pub struct Despawn {
     pub entity: Entity,
@@ -808,6 +952,73 @@ where
     }
 }
 
+#[derive(Debug)]
+pub struct Relate<K> {
+    pub source: Entity,
+    pub target: Entity,
+    pub phantom: PhantomData<K>,
+}
+
+impl<K> Command for Relate<K>
+where
+    K: RelationKind,
+{
+    fn write(self, world: &mut World) {
+        if world.get_entity(self.source).is_none() || world.get_entity(self.target).is_none() {
+            panic!("error[B0003]: Could not relate entity {:?} to {:?} (with `{}`) because one of them doesn't exist in this World.", self.source, self.target, std::any::type_name::<K>());
+        }
+        world.relate::<K>(self.source, self.target);
+    }
+}
+
+#[derive(Debug)]
+pub struct Unrelate<K> {
+    pub source: Entity,
+    pub target: Entity,
+    pub phantom: PhantomData<K>,
+}
+
+impl<K> Command for Unrelate<K>
+where
+    K: RelationKind,
+{
+    fn write(self, world: &mut World) {
+        world.unrelate::<K>(self.source, self.target);
+    }
+}
+
+pub struct Observe<E: Event> {
+    pub entity: Option<Entity>,
+    pub system: BoxedObserverSystem<E>,
+}
+
+impl<E: Event> Command for Observe<E> {
+    fn write(self, world: &mut World) {
+        if let Some(entity) = self.entity {
+            if world.get_entity(entity).is_none() {
+                panic!("error[B0003]: Could not add an observer of `{}` to entity {:?} because it doesn't exist in this World.", std::any::type_name::<E>(), entity);
+            }
+        }
+        observer::observe(world, self.entity, self.system);
+    }
+}
+
+pub struct TriggerEvent<E, T = ()> {
+    pub event: E,
+    pub entity: Entity,
+    pub phantom: PhantomData<T>,
+}
+
+impl<E, T> Command for TriggerEvent<E, T>
+where
+    E: Event,
+    T: Traversal,
+{
+    fn write(self, world: &mut World) {
+        observer::trigger::<T, E>(world, self.event, self.entity);
+    }
+}
+
 pub struct InitResource<R: Resource + FromWorld> {
     _phantom: PhantomData<R>,
 }
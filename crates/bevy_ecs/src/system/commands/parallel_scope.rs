@@ -21,6 +21,10 @@ pub struct ParallelCommandsState {
 ///
 /// Note: Because command application order will depend on how many threads are ran, non-commutative commands may result in non-deterministic results.
 ///
+/// The commands of each thread are applied one at a time, like those of [`Commands`]. To spawn or
+/// insert into many entities from other threads, a [`CommandBuffer`](super::CommandBuffer) can
+/// batch them instead.
+///
 /// Example:
 /// ```
 /// # use bevy_ecs::prelude::*;
@@ -70,7 +74,8 @@ impl<'w, 's> SystemParamFetch<'w, 's> for ParallelCommandsState {
 
 // SAFETY: no component or resource access to report
 unsafe impl SystemParamState for ParallelCommandsState {
-    fn init(_: &mut World, _: &mut crate::system::SystemMeta) -> Self {
+    fn init(_: &mut World, system_meta: &mut crate::system::SystemMeta) -> Self {
+        system_meta.set_has_deferred();
         Self::default()
     }
 
@@ -1,6 +1,10 @@
 use crate::{
+    archetype::ArchetypeComponentId,
     change_detection::MAX_CHANGE_AGE,
-    system::{check_system_change_tick, BoxedSystem, IntoSystem},
+    component::ComponentId,
+    query::Access,
+    schedule_v3::{SystemSet, SystemTypeSet},
+    system::{check_system_change_tick, BoxedSystem, IntoSystem, System},
     world::World,
 };
 use std::borrow::Cow;
@@ -70,6 +74,89 @@ where
     }
 }
 
+/// An exclusive function wrapped as a [`System`], so that it can be added to a
+/// [`schedule_v3::Schedule`](crate::schedule_v3::Schedule).
+///
+/// The executor runs it with [`System::run`] while no other system is running.
+pub struct ExclusiveFunctionSystem<F> {
+    inner: ExclusiveSystemFn<F>,
+    component_access: Access<ComponentId>,
+    archetype_component_access: Access<ArchetypeComponentId>,
+}
+
+impl<F> ExclusiveFunctionSystem<F>
+where
+    F: FnMut(&mut World) + Send + Sync + 'static,
+{
+    pub fn new(func: F) -> Self {
+        Self {
+            inner: func.exclusive_system(),
+            component_access: Access::default(),
+            archetype_component_access: Access::default(),
+        }
+    }
+}
+
+impl<F> System for ExclusiveFunctionSystem<F>
+where
+    F: FnMut(&mut World) + Send + Sync + 'static,
+{
+    type In = ();
+    type Out = ();
+
+    fn name(&self) -> Cow<'static, str> {
+        self.inner.name.clone()
+    }
+
+    fn component_access(&self) -> &Access<ComponentId> {
+        &self.component_access
+    }
+
+    fn archetype_component_access(&self) -> &Access<ArchetypeComponentId> {
+        &self.archetype_component_access
+    }
+
+    fn is_send(&self) -> bool {
+        // exclusive systems run on the thread that owns the world
+        false
+    }
+
+    fn is_exclusive(&self) -> bool {
+        true
+    }
+
+    fn has_deferred(&self) -> bool {
+        false
+    }
+
+    unsafe fn run_unsafe(&mut self, _input: (), _world: &World) {
+        panic!(
+            "Cannot run exclusive system {} with a shared World reference",
+            self.name()
+        );
+    }
+
+    fn run(&mut self, _input: (), world: &mut World) {
+        ExclusiveSystem::run(&mut self.inner, world);
+    }
+
+    fn apply_buffers(&mut self, _world: &mut World) {}
+
+    fn initialize(&mut self, world: &mut World) {
+        ExclusiveSystem::initialize(&mut self.inner, world);
+    }
+
+    fn update_archetype_component_access(&mut self, _world: &World) {}
+
+    fn check_change_tick(&mut self, change_tick: u32) {
+        ExclusiveSystem::check_change_tick(&mut self.inner, change_tick);
+    }
+
+    fn default_system_sets(&self) -> Vec<Box<dyn SystemSet>> {
+        vec![Box::new(SystemTypeSet::<F>::new())]
+    }
+}
+
 pub struct ExclusiveSystemCoerced {
     system: BoxedSystem<(), ()>,
 }
@@ -0,0 +1,237 @@
+use crate::{
+    archetype::ArchetypeComponentId,
+    component::ComponentId,
+    error::{BevyError, SystemErrorContext, SystemErrorHandler},
+    query::Access,
+    schedule::SystemLabelId,
+    schedule_v3::SystemSet,
+    system::{IntoSystem, System},
+    world::World,
+};
+use std::{borrow::Cow, marker::PhantomData};
+
+/// A [`System`] that wraps a system returning `Result<(), E>` and passes any error to the
+/// [`SystemErrorHandler`] resource, along with the name of the system.
+///
+/// Systems returning such a `Result` are wrapped automatically when added to a
+/// [`schedule_v3::Schedule`](crate::schedule_v3::Schedule). For a
+/// [`SystemStage`](crate::schedule::SystemStage), wrap them with
+/// [`fallible`](IntoFallibleSystem::fallible) first.
+///
+/// # Examples
+///
+/// ```
+/// use bevy_ecs::{error::{BevyError, SystemErrorHandler}, prelude::*, system::IntoFallibleSystem};
+///
+/// #[derive(Resource)]
+/// struct Message(String);
+///
+/// fn parse_message_system(message: Res<Message>) -> Result<(), BevyError> {
+///     let _value = message.0.parse::<usize>()?;
+///     Ok(())
+/// }
+///
+/// fn count_errors(_error: BevyError, _context: bevy_ecs::error::SystemErrorContext) {
+///     // report the error somewhere
+/// }
+///
+/// let mut world = World::default();
+/// world.insert_resource(Message("forty-two".to_string()));
+/// world.insert_resource(SystemErrorHandler(count_errors));
+///
+/// let mut system = parse_message_system.fallible();
+/// system.initialize(&mut world);
+/// system.run((), &mut world);
+/// ```
+pub struct FallibleSystem<S, E> {
+    system: S,
+    component_access: Access<ComponentId>,
+    archetype_component_access: Access<ArchetypeComponentId>,
+    handler_archetype_component_id: Option<ArchetypeComponentId>,
+    marker: PhantomData<fn() -> E>,
+}
+
+impl<S, E> FallibleSystem<S, E> {
+    fn handle(&self, result: Result<(), E>, world: &World, name: Cow<'static, str>)
+    where
+        E: Into<BevyError>,
+    {
+        if let Err(error) = result {
+            let handler = world
+                .get_resource::<SystemErrorHandler>()
+                .copied()
+                .unwrap_or_default();
+            (handler.0)(error.into(), SystemErrorContext { name });
+        }
+    }
+}
+
+impl<S, E> System for FallibleSystem<S, E>
+where
+    S: System<In = (), Out = Result<(), E>>,
+    E: Into<BevyError> + 'static,
+{
+    type In = ();
+    type Out = ();
+
+    fn name(&self) -> Cow<'static, str> {
+        self.system.name()
+    }
+
+    fn component_access(&self) -> &Access<ComponentId> {
+        &self.component_access
+    }
+
+    fn archetype_component_access(&self) -> &Access<ArchetypeComponentId> {
+        &self.archetype_component_access
+    }
+
+    fn is_send(&self) -> bool {
+        self.system.is_send()
+    }
+
+    fn is_exclusive(&self) -> bool {
+        self.system.is_exclusive()
+    }
+
+    fn has_deferred(&self) -> bool {
+        self.system.has_deferred()
+    }
+
+    unsafe fn run_unsafe(&mut self, input: Self::In, world: &World) {
+        let result = self.system.run_unsafe(input, world);
+        self.handle(result, world, self.system.name());
+    }
+
+    fn run(&mut self, input: Self::In, world: &mut World) {
+        self.update_archetype_component_access(world);
+        let result = self.system.run(input, world);
+        self.handle(result, world, self.system.name());
+    }
+
+    fn apply_buffers(&mut self, world: &mut World) {
+        self.system.apply_buffers(world);
+    }
+
+    fn initialize(&mut self, world: &mut World) {
+        self.system.initialize(world);
+        self.component_access.extend(self.system.component_access());
+
+        // The handler is read whenever the wrapped system fails.
+        let component_id = world.initialize_resource::<SystemErrorHandler>();
+        self.component_access.add_read(component_id);
+        self.handler_archetype_component_id = world
+            .archetypes
+            .resource()
+            .get_archetype_component_id(component_id);
+    }
+
+    fn update_archetype_component_access(&mut self, world: &World) {
+        self.system.update_archetype_component_access(world);
+        self.archetype_component_access
+            .extend(self.system.archetype_component_access());
+        if let Some(id) = self.handler_archetype_component_id {
+            self.archetype_component_access.add_read(id);
+        }
+    }
+
+    fn check_change_tick(&mut self, change_tick: u32) {
+        self.system.check_change_tick(change_tick);
+    }
+
+    fn default_labels(&self) -> Vec<SystemLabelId> {
+        self.system.default_labels()
+    }
+
+    fn default_system_sets(&self) -> Vec<Box<dyn SystemSet>> {
+        self.system.default_system_sets()
+    }
+}
+
+/// An extension trait providing the [`IntoFallibleSystem::fallible`] method, which routes the
+/// errors of a system returning `Result<(), E>` to the [`SystemErrorHandler`].
+///
+/// This is done automatically when adding such a system to a
+/// [`schedule_v3::Schedule`](crate::schedule_v3::Schedule).
+///
+/// See [`FallibleSystem`].
+pub trait IntoFallibleSystem<E, Params>: IntoSystem<(), Result<(), E>, Params> + Sized
+where
+    E: Into<BevyError> + 'static,
+{
+    /// Wraps this system so that its errors are passed to the [`SystemErrorHandler`].
+    fn fallible(self) -> FallibleSystem<Self::System, E>;
+}
+
+impl<S, E, Params> IntoFallibleSystem<E, Params> for S
+where
+    S: IntoSystem<(), Result<(), E>, Params>,
+    E: Into<BevyError> + 'static,
+{
+    fn fallible(self) -> FallibleSystem<S::System, E> {
+        FallibleSystem {
+            system: IntoSystem::into_system(self),
+            component_access: Default::default(),
+            archetype_component_access: Default::default(),
+            handler_archetype_component_id: None,
+            marker: PhantomData,
+        }
+    }
+}
+
+/// Marker type for systems returning `Result<(), E>`, used to distinguish their
+/// schedule integration from that of systems returning `()`.
+#[doc(hidden)]
+pub struct IsFallibleSystem;
+
+#[cfg(test)]
+mod tests {
+    use crate as bevy_ecs;
+    use crate::{
+        error::{BevyError, SystemErrorContext, SystemErrorHandler},
+        prelude::*,
+        schedule_v3::Schedule,
+    };
+    use std::sync::Mutex;
+
+    #[derive(Component)]
+    struct A;
+
+    fn single_a(query: Query<&A>) -> Result<(), BevyError> {
+        query.get_single()?;
+        Ok(())
+    }
+
+    #[test]
+    fn errors_are_routed_to_handler() {
+        static FAILED: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+        fn record(_error: BevyError, context: SystemErrorContext) {
+            FAILED.lock().unwrap().push(context.name.into_owned());
+        }
+
+        let mut world = World::new();
+        world.insert_resource(SystemErrorHandler(record));
+        let mut schedule = Schedule::new();
+        schedule.add_system(single_a);
+
+        world.spawn().insert(A);
+        schedule.run(&mut world);
+        assert!(FAILED.lock().unwrap().is_empty());
+
+        world.spawn().insert(A);
+        schedule.run(&mut world);
+        let failed = FAILED.lock().unwrap();
+        assert_eq!(failed.len(), 1);
+        assert!(failed[0].ends_with("single_a"));
+    }
+
+    #[test]
+    #[should_panic(expected = "single_a")]
+    fn errors_panic_by_default() {
+        let mut world = World::new();
+        let mut schedule = Schedule::new();
+        schedule.add_system(single_a);
+        schedule.run(&mut world);
+    }
+}
@@ -5,6 +5,7 @@ use crate::{
     prelude::FromWorld,
     query::{Access, FilteredAccessSet},
     schedule::{SystemLabel, SystemLabelId},
+    schedule_v3::{SystemSet, SystemTypeSet},
     system::{
         check_system_change_tick, ReadOnlySystemParamFetch, System, SystemParam, SystemParamFetch,
         SystemParamItem, SystemParamState,
@@ -23,6 +24,7 @@ pub struct SystemMeta {
     // NOTE: this must be kept private. making a SystemMeta non-send is irreversible to prevent
     // SystemParams from overriding each other
     is_send: bool,
+    has_deferred: bool,
     pub(crate) last_change_tick: u32,
 }
 
@@ -33,6 +35,7 @@ impl SystemMeta {
             archetype_component_access: Access::default(),
             component_access_set: FilteredAccessSet::default(),
             is_send: true,
+            has_deferred: false,
             last_change_tick: 0,
         }
     }
@@ -50,6 +53,20 @@ impl SystemMeta {
     pub fn set_non_send(&mut self) {
         self.is_send = false;
     }
+
+    /// Returns true if the system has buffers to apply with [`System::apply_buffers`].
+    #[inline]
+    pub fn has_deferred(&self) -> bool {
+        self.has_deferred
+    }
+
+    /// Marks the system as having buffers to apply, such as [`Commands`](crate::system::Commands).
+    ///
+    /// This is irreversible.
+    #[inline]
+    pub fn set_has_deferred(&mut self) {
+        self.has_deferred = true;
+    }
 }
 
 // TODO: Actually use this in FunctionSystem. We should probably only do this once Systems are constructed using a World reference
@@ -387,6 +404,11 @@ where
         self.system_meta.is_send
     }
 
+    #[inline]
+    fn has_deferred(&self) -> bool {
+        self.system_meta.has_deferred
+    }
+
     #[inline]
     unsafe fn run_unsafe(&mut self, input: Self::In, world: &World) -> Self::Out {
         let change_tick = world.increment_change_tick();
@@ -448,6 +470,10 @@ where
     fn default_labels(&self) -> Vec<SystemLabelId> {
         vec![self.func.as_system_label().as_label()]
     }
+
+    fn default_system_sets(&self) -> Vec<Box<dyn SystemSet>> {
+        vec![Box::new(SystemTypeSet::<F>::new())]
+    }
 }
 
 /// A [`SystemLabel`] that was automatically generated for a system on the basis of its `TypeId`.
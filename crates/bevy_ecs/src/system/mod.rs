@@ -70,20 +70,24 @@
 
 mod commands;
 mod exclusive_system;
+mod fallible_system;
 mod function_system;
 mod query;
 #[allow(clippy::module_inception)]
 mod system;
 mod system_chaining;
 mod system_param;
+mod system_registry;
 
 pub use commands::*;
 pub use exclusive_system::*;
+pub use fallible_system::*;
 pub use function_system::*;
 pub use query::*;
 pub use system::*;
 pub use system_chaining::*;
 pub use system_param::*;
+pub use system_registry::*;
 
 /// Ensure that a given function is a system
 ///
@@ -1,5 +1,5 @@
 use crate::{
-    component::Component,
+    component::{Component, Tick},
     entity::Entity,
     query::{
         QueryCombinationIter, QueryEntityError, QueryItem, QueryIter, QueryManyIter,
@@ -296,6 +296,47 @@ impl<'w, 's, Q: WorldQuery, F: WorldQuery> Query<'w, 's, Q, F> {
         }
     }
 
+    /// Returns a query whose change detection considers changes made since `tick`, instead of
+    /// since the last time the system ran.
+    ///
+    /// This affects the [`Changed`](crate::query::Changed) and [`Added`](crate::query::Added)
+    /// filters as well as [`ChangeTrackers`](crate::query::ChangeTrackers), and is useful for
+    /// systems that need to keep track of changes at a different rate than they run, such as
+    /// replication sending each client the changes since the last tick it received.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use bevy_ecs::prelude::*;
+    /// # use bevy_ecs::component::Tick;
+    /// # use bevy_ecs::system::SystemChangeTick;
+    /// #
+    /// # #[derive(Component)]
+    /// # struct Position(f32);
+    /// #
+    /// #[derive(Resource)]
+    /// struct Client {
+    ///     last_sent: Tick,
+    /// }
+    ///
+    /// fn replicate_system(
+    ///     mut query: Query<(Entity, &Position), Changed<Position>>,
+    ///     mut client: ResMut<Client>,
+    ///     ticks: SystemChangeTick,
+    /// ) {
+    ///     for (entity, position) in &query.since(client.last_sent) {
+    ///         println!("Sending {:?} at {}", entity, position.0);
+    ///     }
+    ///     client.last_sent = ticks.this_run();
+    /// }
+    /// # bevy_ecs::system::assert_is_system(replicate_system);
+    /// ```
+    #[inline]
+    pub fn since(&mut self, tick: Tick) -> Query<'_, 's, Q, F> {
+        // SAFETY: the returned query borrows `self` mutably, so it has the same access
+        unsafe { Query::new(self.world, self.state, tick.get(), self.change_tick) }
+    }
+
     /// Returns an [`Iterator`] over the query results.
     ///
     /// This can only return immutable data (mutable data will be cast to an immutable form).
@@ -520,6 +561,126 @@ impl<'w, 's, Q: WorldQuery, F: WorldQuery> Query<'w, 's, Q, F> {
         }
     }
 
+    /// Returns an [`Iterator`] over the query results, sorted by the key extracted by `key`.
+    ///
+    /// Unlike [`Self::iter`], whose order depends on the history of archetype creation and table
+    /// moves, the order of this iterator only depends on the keys. For the order to be fully
+    /// deterministic, the keys must be unique, e.g. by including the [`Entity`] of each item.
+    ///
+    /// This can only return immutable data, see [`Self::iter_sorted_by_key_mut`] for mutable
+    /// access.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use bevy_ecs::prelude::*;
+    /// #
+    /// # #[derive(Component)]
+    /// # struct Player { score: u32 }
+    /// #
+    /// fn print_ranking_system(query: Query<(Entity, &Player)>) {
+    ///     let ranking = query.iter_sorted_by_key(|(entity, player)| (u32::MAX - player.score, *entity));
+    ///     for (rank, (_, player)) in ranking.enumerate() {
+    ///         println!("#{}: {} points", rank + 1, player.score);
+    ///     }
+    /// }
+    /// # bevy_ecs::system::assert_is_system(print_ranking_system);
+    /// ```
+    #[inline]
+    pub fn iter_sorted_by_key<'a, K: Ord>(
+        &'a self,
+        key: impl FnMut(&ROQueryItem<'a, Q>) -> K,
+    ) -> std::vec::IntoIter<ROQueryItem<'a, Q>> {
+        let mut items: Vec<_> = self.iter().collect();
+        items.sort_by_key(key);
+        items.into_iter()
+    }
+
+    /// Returns an [`Iterator`] over the query results, sorted by the key extracted by `key`.
+    ///
+    /// See [`Self::iter_sorted_by_key`].
+    #[inline]
+    pub fn iter_sorted_by_key_mut<'a, K: Ord>(
+        &'a mut self,
+        key: impl FnMut(&QueryItem<'a, Q>) -> K,
+    ) -> std::vec::IntoIter<QueryItem<'a, Q>> {
+        let mut items: Vec<_> = self.iter_mut().collect();
+        items.sort_by_key(key);
+        items.into_iter()
+    }
+
+    /// Returns an [`Iterator`] over the query results of a list of [`Entity`]'s, visiting the
+    /// entities grouped by the table and archetype they are stored in instead of in the given order.
+    ///
+    /// Entities that do not exist or do not match the query are skipped. Fetching entities that are
+    /// scattered across the world this way is more cache-friendly than calling [`Self::get`] for each.
+    ///
+    /// # Examples
+    /// ```
+    /// # use bevy_ecs::prelude::*;
+    /// #[derive(Component)]
+    /// struct Mass(f32);
+    ///
+    /// #[derive(Component)]
+    /// struct Neighbours(Vec<Entity>);
+    ///
+    /// fn system(neighbours_query: Query<&Neighbours>, mass_query: Query<&Mass>) {
+    ///     for neighbours in &neighbours_query {
+    ///         let total_mass: f32 = mass_query
+    ///             .iter_many_sorted(&neighbours.0)
+    ///             .map(|mass| mass.0)
+    ///             .sum();
+    ///         println!("Neighbouring mass: {}", total_mass);
+    ///     }
+    /// }
+    /// # bevy_ecs::system::assert_is_system(system);
+    /// ```
+    #[inline]
+    pub fn iter_many_sorted<EntityList: IntoIterator>(
+        &self,
+        entities: EntityList,
+    ) -> QueryManyIter<'_, '_, Q::ReadOnly, F::ReadOnly, std::vec::IntoIter<Entity>>
+    where
+        EntityList::Item: Borrow<Entity>,
+    {
+        let entities = self.state.sort_by_storage(self.world, entities);
+        // SAFETY: system runs without conflicts with other systems.
+        // same-system queries have runtime borrow checks when they conflict
+        unsafe {
+            self.state.as_readonly().iter_many_unchecked_manual(
+                entities,
+                self.world,
+                self.last_change_tick,
+                self.change_tick,
+            )
+        }
+    }
+
+    /// Returns an iterator over the query results of a list of [`Entity`]'s, visiting the
+    /// entities grouped by the table and archetype they are stored in instead of in the given order.
+    ///
+    /// See [`Self::iter_many_sorted`].
+    #[inline]
+    pub fn iter_many_sorted_mut<EntityList: IntoIterator>(
+        &mut self,
+        entities: EntityList,
+    ) -> QueryManyIter<'_, '_, Q, F, std::vec::IntoIter<Entity>>
+    where
+        EntityList::Item: Borrow<Entity>,
+    {
+        let entities = self.state.sort_by_storage(self.world, entities);
+        // SAFETY: system runs without conflicts with other systems.
+        // same-system queries have runtime borrow checks when they conflict
+        unsafe {
+            self.state.iter_many_unchecked_manual(
+                entities,
+                self.world,
+                self.last_change_tick,
+                self.change_tick,
+            )
+        }
+    }
+
     /// Returns an [`Iterator`] over the query results.
     ///
     /// # Safety
@@ -719,6 +880,70 @@ impl<'w, 's, Q: WorldQuery, F: WorldQuery> Query<'w, 's, Q, F> {
         };
     }
 
+    /// Runs `f` on each query result in parallel using the [`World`]'s [`ComputeTaskPool`], in
+    /// batches of entities sorted by [`Entity`] ID.
+    ///
+    /// Unlike with [`Self::par_for_each`], which items end up in a batch, and the order of the
+    /// items within it, only depends on the IDs of the matching entities. Combined with
+    /// [`EntityOrder::Deterministic`](crate::entity::EntityOrder::Deterministic), this makes the
+    /// batches reproducible across runs of the same simulation.
+    ///
+    /// This can only be called for immutable data, see [`Self::par_for_each_sorted_mut`] for
+    /// mutable access.
+    ///
+    /// # Panics
+    /// The [`ComputeTaskPool`] is not initialized. If using this from a query that is being
+    /// initialized and run from the ECS scheduler, this should never panic.
+    ///
+    /// [`ComputeTaskPool`]: bevy_tasks::prelude::ComputeTaskPool
+    #[inline]
+    pub fn par_for_each_sorted<'this>(
+        &'this self,
+        batch_size: usize,
+        f: impl Fn(ROQueryItem<'this, Q>) + Send + Sync + Clone,
+    ) {
+        // SAFETY: system runs without conflicts with other systems. same-system queries have runtime
+        // borrow checks when they conflict
+        unsafe {
+            self.state
+                .as_readonly()
+                .par_for_each_sorted_unchecked_manual(
+                    self.world,
+                    batch_size,
+                    f,
+                    self.last_change_tick,
+                    self.change_tick,
+                );
+        };
+    }
+
+    /// Runs `f` on each query result in parallel using the [`World`]'s [`ComputeTaskPool`], in
+    /// batches of entities sorted by [`Entity`] ID. See [`Self::par_for_each_sorted`].
+    ///
+    /// # Panics
+    /// The [`ComputeTaskPool`] is not initialized. If using this from a query that is being
+    /// initialized and run from the ECS scheduler, this should never panic.
+    ///
+    /// [`ComputeTaskPool`]: bevy_tasks::prelude::ComputeTaskPool
+    #[inline]
+    pub fn par_for_each_sorted_mut<'a, FN: Fn(QueryItem<'a, Q>) + Send + Sync + Clone>(
+        &'a mut self,
+        batch_size: usize,
+        f: FN,
+    ) {
+        // SAFETY: system runs without conflicts with other systems. same-system queries have runtime
+        // borrow checks when they conflict
+        unsafe {
+            self.state.par_for_each_sorted_unchecked_manual(
+                self.world,
+                batch_size,
+                f,
+                self.last_change_tick,
+                self.change_tick,
+            );
+        };
+    }
+
     /// Returns the query result for the given [`Entity`].
     ///
     /// In case of a nonexisting entity or mismatched component, a [`QueryEntityError`] is
@@ -789,6 +1014,30 @@ impl<'w, 's, Q: WorldQuery, F: WorldQuery> Query<'w, 's, Q, F> {
         }
     }
 
+    /// Returns the read-only query results for the given slice of [`Entity`], in the same order.
+    ///
+    /// The entities are fetched grouped by the table and archetype they are stored in, which is
+    /// more cache-friendly than calling [`Query::get`] for each of them. Each entity that does not
+    /// exist or does not match the query gets its own [`QueryEntityError`].
+    ///
+    /// See [`Query::get_many`] for a fixed number of entities.
+    #[inline]
+    pub fn get_many_dynamic(
+        &self,
+        entities: &[Entity],
+    ) -> Vec<Result<ROQueryItem<'_, Q>, QueryEntityError>> {
+        // SAFETY: system runs without conflicts with other systems.
+        // same-system queries have runtime borrow checks when they conflict
+        unsafe {
+            self.state.as_readonly().get_many_dynamic_unchecked_manual(
+                self.world,
+                entities,
+                self.last_change_tick,
+                self.change_tick,
+            )
+        }
+    }
+
     /// Returns the read-only query items for the provided array of [`Entity`]
     ///
     /// See [`Query::get_many`] for the [`Result`]-returning equivalent.
@@ -2,7 +2,7 @@ use bevy_utils::tracing::warn;
 
 use crate::{
     archetype::ArchetypeComponentId, change_detection::MAX_CHANGE_AGE, component::ComponentId,
-    query::Access, schedule::SystemLabelId, world::World,
+    query::Access, schedule::SystemLabelId, schedule_v3::SystemSet, world::World,
 };
 use std::borrow::Cow;
 
@@ -31,6 +31,15 @@ pub trait System: Send + Sync + 'static {
     fn archetype_component_access(&self) -> &Access<ArchetypeComponentId>;
     /// Returns true if the system is [`Send`].
     fn is_send(&self) -> bool;
+    /// Returns true if the system must be run exclusively, with a mutable reference to the
+    /// [`World`].
+    fn is_exclusive(&self) -> bool {
+        false
+    }
+    /// Returns true if the system may have buffers to apply with [`System::apply_buffers`].
+    fn has_deferred(&self) -> bool {
+        true
+    }
     /// Runs the system with the given input in the world. Unlike [`System::run`], this function
     /// takes a shared reference to [`World`] and may therefore break Rust's aliasing rules, making
     /// it unsafe to call.
@@ -59,6 +68,11 @@ pub trait System: Send + Sync + 'static {
     fn default_labels(&self) -> Vec<SystemLabelId> {
         Vec::new()
     }
+    /// The default [`SystemSet`]s the system belongs to in a
+    /// [`schedule_v3::Schedule`](crate::schedule_v3::Schedule).
+    fn default_system_sets(&self) -> Vec<Box<dyn SystemSet>> {
+        Vec::new()
+    }
 }
 
 /// A convenience type alias for a boxed [`System`] trait object.
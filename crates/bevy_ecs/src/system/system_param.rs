@@ -3,7 +3,7 @@ use crate::{
     archetype::{Archetype, Archetypes},
     bundle::Bundles,
     change_detection::Ticks,
-    component::{Component, ComponentId, ComponentTicks, Components},
+    component::{Component, ComponentId, ComponentTicks, Components, Tick},
     entity::{Entities, Entity},
     query::{
         Access, FilteredAccess, FilteredAccessSet, QueryState, ReadOnlyWorldQuery, WorldQuery,
@@ -232,8 +232,7 @@ impl_param_set!();
 /// ```
 /// # let mut world = World::default();
 /// # let mut schedule = Schedule::default();
-/// # schedule.add_stage("update", SystemStage::parallel());
-/// # use bevy_ecs::prelude::*;
+/// # use bevy_ecs::{prelude::*, schedule_v3::Schedule};
 /// #[derive(Resource)]
 /// struct MyResource { value: u32 }
 ///
@@ -248,9 +247,8 @@ impl_param_set!();
 ///     resource.value = 0;
 ///     assert_eq!(resource.value, 0);
 /// }
-/// # schedule.add_system_to_stage("update", read_resource_system.label("first"));
-/// # schedule.add_system_to_stage("update", write_resource_system.after("first"));
-/// # schedule.run_once(&mut world);
+/// # schedule.add_systems((read_resource_system, write_resource_system).chain());
+/// # schedule.run(&mut world);
 /// ```
 pub trait Resource: Send + Sync + 'static {}
 
@@ -556,7 +554,8 @@ unsafe impl ReadOnlySystemParamFetch for CommandQueue {}
 
 // SAFETY: only local state is accessed
 unsafe impl SystemParamState for CommandQueue {
-    fn init(_world: &mut World, _system_meta: &mut SystemMeta) -> Self {
+    fn init(_world: &mut World, system_meta: &mut SystemMeta) -> Self {
+        system_meta.set_has_deferred();
         Default::default()
     }
 
@@ -1269,6 +1268,19 @@ impl SystemChangeTick {
     pub fn last_change_tick(&self) -> u32 {
         self.last_change_tick
     }
+
+    /// Returns the current [`World`] change tick seen by the system, as a [`Tick`].
+    #[inline]
+    pub fn this_run(&self) -> Tick {
+        Tick::new(self.change_tick)
+    }
+
+    /// Returns the [`World`] change tick seen by the system the previous time it ran, as a
+    /// [`Tick`].
+    #[inline]
+    pub fn last_run(&self) -> Tick {
+        Tick::new(self.last_change_tick)
+    }
 }
 
 // SAFETY: Only reads internal system state
@@ -0,0 +1,275 @@
+use crate::{
+    component::{Component, TableStorage},
+    entity::{Disabled, Entity},
+    system::{BoxedSystem, Command, IntoSystem},
+    world::World,
+};
+use bevy_utils::tracing::warn;
+use std::fmt;
+
+/// An identifier for a system registered with [`World::register_system`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct SystemId(Entity);
+
+/// A system registered with [`World::register_system`], stored on the entity of its [`SystemId`].
+pub(crate) struct RegisteredSystem {
+    initialized: bool,
+    /// `None` while the system is running.
+    system: Option<BoxedSystem>,
+}
+
+impl Component for RegisteredSystem {
+    type Storage = TableStorage;
+}
+
+impl World {
+    /// Registers a system and returns a [`SystemId`] that can be used to run it on demand with
+    /// [`World::run_system`] or [`Commands::run_system`](crate::system::Commands::run_system).
+    ///
+    /// The system is only initialized the first time it runs, and its state, such as
+    /// [`Local`](crate::system::Local)s and change detection ticks, is kept between runs.
+    ///
+    /// The system is stored on its own entity, which is marked [`Disabled`] so that it doesn't
+    /// show up in queries. Only [`World::remove_system`] should be used to get rid of it.
+    ///
+    /// ```
+    /// use bevy_ecs::prelude::*;
+    ///
+    /// #[derive(Resource, Default)]
+    /// struct Counter(u32);
+    ///
+    /// fn increment(mut counter: ResMut<Counter>, mut runs: Local<u32>) {
+    ///     *runs += 1;
+    ///     counter.0 = *runs;
+    /// }
+    ///
+    /// let mut world = World::new();
+    /// world.init_resource::<Counter>();
+    /// let id = world.register_system(increment);
+    ///
+    /// world.run_system(id).unwrap();
+    /// world.run_system(id).unwrap();
+    /// assert_eq!(world.resource::<Counter>().0, 2);
+    /// ```
+    pub fn register_system<Params>(&mut self, system: impl IntoSystem<(), (), Params>) -> SystemId {
+        let entity = self
+            .spawn()
+            .insert_bundle((
+                RegisteredSystem {
+                    initialized: false,
+                    system: Some(Box::new(IntoSystem::into_system(system))),
+                },
+                Disabled,
+            ))
+            .id();
+        SystemId(entity)
+    }
+
+    /// Removes a system registered with [`World::register_system`] and returns it.
+    ///
+    /// Fails if the system is not registered, or if it is currently running.
+    pub fn remove_system(&mut self, id: SystemId) -> Result<BoxedSystem, RegisteredSystemError> {
+        match self.get_entity_mut(id.0) {
+            Some(mut entity) => {
+                let registered_system = entity
+                    .remove::<RegisteredSystem>()
+                    .ok_or(RegisteredSystemError::SystemIdNotRegistered(id))?;
+                entity.despawn();
+                registered_system
+                    .system
+                    .ok_or(RegisteredSystemError::Recursive(id))
+            }
+            None => Err(RegisteredSystemError::SystemIdNotRegistered(id)),
+        }
+    }
+
+    /// Runs a system registered with [`World::register_system`], then applies its
+    /// [`Commands`](crate::system::Commands).
+    ///
+    /// Fails if the system is not registered, or if it is already running, for example if it
+    /// tries to run itself through [`Commands::run_system`](crate::system::Commands::run_system).
+    pub fn run_system(&mut self, id: SystemId) -> Result<(), RegisteredSystemError> {
+        let mut registered_system = self
+            .get_mut::<RegisteredSystem>(id.0)
+            .ok_or(RegisteredSystemError::SystemIdNotRegistered(id))?;
+        let initialized = registered_system.initialized;
+        let mut system = registered_system
+            .system
+            .take()
+            .ok_or(RegisteredSystemError::Recursive(id))?;
+
+        if !initialized {
+            system.initialize(self);
+        }
+        system.run((), self);
+        system.apply_buffers(self);
+
+        // The system may have removed itself while running, in which case it is dropped.
+        if let Some(mut registered_system) = self.get_mut::<RegisteredSystem>(id.0) {
+            registered_system.initialized = true;
+            registered_system.system = Some(system);
+        }
+        Ok(())
+    }
+}
+
+/// Runs the system with the given [`SystemId`]. See [`World::run_system`].
+#[derive(Debug, Copy, Clone)]
+pub struct RunSystem {
+    pub system_id: SystemId,
+}
+
+impl Command for RunSystem {
+    fn write(self, world: &mut World) {
+        if let Err(error) = world.run_system(self.system_id) {
+            warn!("Could not run system: {}", error);
+        }
+    }
+}
+
+/// An error that occurs when running or removing a system registered with
+/// [`World::register_system`].
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum RegisteredSystemError {
+    /// No system is registered with this [`SystemId`].
+    SystemIdNotRegistered(SystemId),
+    /// The system is currently running.
+    Recursive(SystemId),
+}
+
+impl std::error::Error for RegisteredSystemError {}
+
+impl fmt::Display for RegisteredSystemError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RegisteredSystemError::SystemIdNotRegistered(id) => {
+                write!(f, "System {:?} was not registered.", id)
+            }
+            RegisteredSystemError::Recursive(id) => {
+                write!(f, "System {:?} tried to run itself recursively.", id)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RegisteredSystemError;
+    use crate as bevy_ecs;
+    use crate::{prelude::*, system::SystemId};
+
+    #[derive(Resource, Default, PartialEq, Debug)]
+    struct Counter(u32);
+
+    #[test]
+    fn registered_systems_are_hidden_from_queries() {
+        fn noop() {}
+
+        let mut world = World::new();
+        let entity = world.spawn().id();
+        let id = world.register_system(noop);
+
+        let entities = world.query::<Entity>().iter(&world).collect::<Vec<_>>();
+        assert_eq!(entities, vec![entity]);
+
+        world.remove_system(id).unwrap();
+        assert_eq!(world.entities().len(), 1);
+    }
+
+    #[test]
+    fn local_state_persists() {
+        fn count(mut counter: ResMut<Counter>, mut runs: Local<u32>) {
+            *runs += 1;
+            counter.0 = *runs;
+        }
+
+        let mut world = World::new();
+        world.init_resource::<Counter>();
+        let id = world.register_system(count);
+        for _ in 0..3 {
+            world.run_system(id).unwrap();
+        }
+        assert_eq!(*world.resource::<Counter>(), Counter(3));
+
+        // Each registration has its own state
+        let other = world.register_system(count);
+        world.run_system(other).unwrap();
+        assert_eq!(*world.resource::<Counter>(), Counter(1));
+    }
+
+    #[test]
+    fn change_detection_persists() {
+        #[derive(Resource)]
+        struct Value(u32);
+
+        fn count_changes(value: Res<Value>, mut counter: ResMut<Counter>) {
+            if value.is_changed() {
+                counter.0 += 1;
+            }
+        }
+
+        let mut world = World::new();
+        world.init_resource::<Counter>();
+        world.insert_resource(Value(0));
+        let id = world.register_system(count_changes);
+
+        world.run_system(id).unwrap();
+        world.run_system(id).unwrap();
+        assert_eq!(*world.resource::<Counter>(), Counter(1));
+
+        world.resource_mut::<Value>().0 = 1;
+        world.run_system(id).unwrap();
+        assert_eq!(*world.resource::<Counter>(), Counter(2));
+    }
+
+    #[test]
+    fn run_system_from_commands() {
+        #[derive(Resource)]
+        struct Callback(SystemId);
+
+        fn increment(mut counter: ResMut<Counter>) {
+            counter.0 += 1;
+        }
+
+        fn run_callback(mut commands: Commands, callback: Res<Callback>) {
+            commands.run_system(callback.0);
+            commands.run_system(callback.0);
+        }
+
+        let mut world = World::new();
+        world.init_resource::<Counter>();
+        let callback = world.register_system(increment);
+        world.insert_resource(Callback(callback));
+        let id = world.register_system(run_callback);
+
+        world.run_system(id).unwrap();
+        assert_eq!(*world.resource::<Counter>(), Counter(2));
+    }
+
+    #[test]
+    fn recursive_and_removed_systems() {
+        #[derive(Resource)]
+        struct Own(SystemId);
+
+        fn run_self(own: Res<Own>, mut counter: ResMut<Counter>, mut commands: Commands) {
+            counter.0 += 1;
+            commands.run_system(own.0);
+        }
+
+        let mut world = World::new();
+        world.init_resource::<Counter>();
+        let id = world.register_system(run_self);
+        world.insert_resource(Own(id));
+
+        // The nested run is rejected instead of recursing forever
+        world.run_system(id).unwrap();
+        assert_eq!(*world.resource::<Counter>(), Counter(1));
+
+        assert!(world.remove_system(id).is_ok());
+        assert_eq!(
+            world.run_system(id),
+            Err(RegisteredSystemError::SystemIdNotRegistered(id))
+        );
+        assert!(world.remove_system(id).is_err());
+    }
+}
@@ -0,0 +1,83 @@
+use crate::{
+    change_detection::MutUntyped,
+    component::{Component, ComponentId},
+    entity::Entity,
+    event::Event,
+    system::{Commands, Resource},
+    world::{Mut, World},
+};
+use std::ops::Deref;
+
+/// A [`World`] reference that can read and mutate existing data, but cannot change the structure
+/// of the world (spawning or despawning entities, adding or removing components).
+///
+/// Structural changes are queued through [`DeferredWorld::commands`] instead, and applied by the
+/// world once the operation that handed out this reference completes. This is what
+/// [`ComponentHooks`](crate::component::ComponentHooks) receive.
+pub struct DeferredWorld<'w> {
+    world: &'w mut World,
+}
+
+impl<'w> Deref for DeferredWorld<'w> {
+    type Target = World;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        self.world
+    }
+}
+
+impl<'w> DeferredWorld<'w> {
+    #[inline]
+    pub(crate) fn new(world: &'w mut World) -> Self {
+        Self { world }
+    }
+
+    /// Returns [`Commands`] whose commands are applied to the world once the current structural
+    /// operation completes.
+    #[inline]
+    pub fn commands(&mut self) -> Commands<'_, '_> {
+        Commands::new_from_entities(&mut self.world.command_queue, &self.world.entities)
+    }
+
+    /// Retrieves a mutable reference to the given `entity`'s [`Component`] of the given type.
+    /// Returns [`None`] if the `entity` does not have a [`Component`] of the given type.
+    #[inline]
+    pub fn get_mut<T: Component>(&mut self, entity: Entity) -> Option<Mut<'_, T>> {
+        self.world.get_mut(entity)
+    }
+
+    /// Retrieves a [`MutUntyped`] of the given `entity`'s component with the given
+    /// [`ComponentId`]. See [`World::get_mut_by_id`].
+    #[inline]
+    pub fn get_mut_by_id(
+        &mut self,
+        entity: Entity,
+        component_id: ComponentId,
+    ) -> Option<MutUntyped<'_>> {
+        self.world.get_mut_by_id(entity, component_id)
+    }
+
+    /// Gets a mutable reference to the resource of the given type.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the resource does not exist. Use [`get_resource_mut`](Self::get_resource_mut)
+    /// instead if you want to handle this case.
+    #[inline]
+    pub fn resource_mut<R: Resource>(&mut self) -> Mut<'_, R> {
+        self.world.resource_mut()
+    }
+
+    /// Gets a mutable reference to the resource of the given type if it exists.
+    #[inline]
+    pub fn get_resource_mut<R: Resource>(&mut self) -> Option<Mut<'_, R>> {
+        self.world.get_resource_mut()
+    }
+
+    /// Sends an [`Event`]. See [`World::send_event`].
+    #[inline]
+    pub fn send_event<E: Event>(&mut self, event: E) {
+        self.world.send_event(event);
+    }
+}
@@ -1,10 +1,14 @@
 use crate::{
     archetype::{Archetype, ArchetypeId, Archetypes},
-    bundle::{Bundle, BundleInfo},
+    bundle::{Bundle, BundleId, BundleInfo, DynamicBundleComponents},
     change_detection::{MutUntyped, Ticks},
     component::{Component, ComponentId, ComponentTicks, Components, StorageType},
     entity::{Entities, Entity, EntityLocation},
+    event::Event,
+    observer::{self, Trigger},
+    relation::{self, RelationKind},
     storage::{SparseSet, Storages},
+    system::IntoSystem,
     world::{Mut, World},
 };
 use bevy_ptr::{OwningPtr, Ptr, UnsafeCellDeref};
@@ -233,16 +237,18 @@ impl<'w> EntityMut<'w> {
 
     pub fn insert_bundle<T: Bundle>(&mut self, bundle: T) -> &mut Self {
         let change_tick = self.world.change_tick();
+        let old_archetype_id = self.location.archetype_id;
         let bundle_info = self
             .world
             .bundles
             .init_info::<T>(&mut self.world.components, &mut self.world.storages);
+        let bundle_id = bundle_info.id();
         let mut bundle_inserter = bundle_info.get_bundle_inserter(
             &mut self.world.entities,
             &mut self.world.archetypes,
             &mut self.world.components,
             &mut self.world.storages,
-            self.location.archetype_id,
+            old_archetype_id,
             change_tick,
         );
         // SAFETY: location matches current entity. `T` matches `bundle_info`
@@ -250,26 +256,108 @@ impl<'w> EntityMut<'w> {
             self.location = bundle_inserter.insert(self.entity, self.location.index, bundle);
         }
 
+        self.world
+            .trigger_insert_hooks(old_archetype_id, bundle_id, self.entity);
+        self.flush_commands();
+        self
+    }
+
+    /// Inserts the component with the given [`ComponentId`] into the entity, from a pointer to
+    /// its value. If the entity already has the component, it is replaced.
+    ///
+    /// **You should prefer to use the typed API [`EntityMut::insert`] where possible and only
+    /// use this in cases where the actual component types are not known at compile time,
+    /// e.g. for components defined by a scripting runtime with a
+    /// [`ComponentDescriptor`](crate::component::ComponentDescriptor).**
+    ///
+    /// # Panics
+    ///
+    /// Panics if `component_id` is not a valid [`ComponentId`].
+    ///
+    /// # Safety
+    ///
+    /// - `component_id` must be from the same [`World`] as this entity.
+    /// - `component` must point to a valid value of the component's type, which is moved into the
+    ///   entity.
+    pub unsafe fn insert_by_id(
+        &mut self,
+        component_id: ComponentId,
+        component: OwningPtr<'_>,
+    ) -> &mut Self {
+        self.insert_by_ids([(component_id, component)])
+    }
+
+    /// Inserts the components with the given [`ComponentId`]s into the entity, from pointers to
+    /// their values. Components the entity already has are replaced.
+    ///
+    /// This is the type-erased equivalent of [`EntityMut::insert_bundle`], see
+    /// [`EntityMut::insert_by_id`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if any of the [`ComponentId`]s is not valid, or if they contain duplicates.
+    ///
+    /// # Safety
+    ///
+    /// - The [`ComponentId`]s must be from the same [`World`] as this entity.
+    /// - Each pointer must point to a valid value of the type of its component, which is moved
+    ///   into the entity.
+    pub unsafe fn insert_by_ids<'a>(
+        &mut self,
+        components: impl IntoIterator<Item = (ComponentId, OwningPtr<'a>)>,
+    ) -> &mut Self {
+        let (component_ids, values): (Vec<_>, Vec<_>) = components.into_iter().unzip();
+        let change_tick = self.world.change_tick();
+        let old_archetype_id = self.location.archetype_id;
+        let bundle_info = self
+            .world
+            .bundles
+            .init_dynamic_info(&mut self.world.components, &component_ids);
+        let bundle_id = bundle_info.id();
+        let mut bundle_inserter = bundle_info.get_bundle_inserter(
+            &mut self.world.entities,
+            &mut self.world.archetypes,
+            &mut self.world.components,
+            &mut self.world.storages,
+            old_archetype_id,
+            change_tick,
+        );
+        // SAFETY: location matches current entity. `values` match `bundle_info` as ensured by the
+        // caller
+        self.location = bundle_inserter.insert(
+            self.entity,
+            self.location.index,
+            DynamicBundleComponents(values),
+        );
+
+        self.world
+            .trigger_insert_hooks(old_archetype_id, bundle_id, self.entity);
+        self.flush_commands();
         self
     }
 
+    /// Applies the commands queued by component hooks, which may move this entity.
+    fn flush_commands(&mut self) {
+        if !self.world.command_queue.is_empty() {
+            self.world.flush_commands();
+            self.update_location();
+        }
+    }
+
     // TODO: move to BundleInfo
     pub fn remove_bundle<T: Bundle>(&mut self) -> Option<T> {
-        let archetypes = &mut self.world.archetypes;
-        let storages = &mut self.world.storages;
-        let components = &mut self.world.components;
-        let entities = &mut self.world.entities;
-        let removed_components = &mut self.world.removed_components;
-
-        let bundle_info = self.world.bundles.init_info::<T>(components, storages);
+        let world = &mut *self.world;
+        let bundle_info = world
+            .bundles
+            .init_info::<T>(&mut world.components, &mut world.storages);
         let old_location = self.location;
         // SAFETY: `archetype_id` exists because it is referenced in the old `EntityLocation` which is valid,
         // components exist in `bundle_info` because `Bundles::init_info` initializes a `BundleInfo` containing all components of the bundle type `T`
         let new_archetype_id = unsafe {
             remove_bundle_from_archetype(
-                archetypes,
-                storages,
-                components,
+                &mut world.archetypes,
+                &mut world.storages,
+                &mut world.components,
                 old_location.archetype_id,
                 bundle_info,
                 false,
@@ -280,6 +368,20 @@ impl<'w> EntityMut<'w> {
             return None;
         }
 
+        let bundle_id = bundle_info.id();
+        if world.archetypes[old_location.archetype_id].has_remove_hook() {
+            // hooks can't change the structure of the world, so `old_location` stays valid
+            let bundle_components = bundle_info.component_ids.clone();
+            world.trigger_remove_hooks(old_location.archetype_id, self.entity, bundle_components);
+        }
+
+        let archetypes = &mut world.archetypes;
+        let storages = &mut world.storages;
+        let components = &mut world.components;
+        let entities = &mut world.entities;
+        let removed_components = &mut world.removed_components;
+        let bundle_info = world.bundles.get(bundle_id).unwrap();
+
         let old_archetype = &mut archetypes[old_location.archetype_id];
         let mut bundle_components = bundle_info.component_ids.iter().cloned();
         let entity = self.entity;
@@ -315,6 +417,7 @@ impl<'w> EntityMut<'w> {
             );
         }
 
+        self.flush_commands();
         Some(result)
     }
 
@@ -380,22 +483,47 @@ impl<'w> EntityMut<'w> {
     // TODO: move to BundleInfo
     /// Remove any components in the bundle that the entity has.
     pub fn remove_bundle_intersection<T: Bundle>(&mut self) {
-        let archetypes = &mut self.world.archetypes;
-        let storages = &mut self.world.storages;
-        let components = &mut self.world.components;
-        let entities = &mut self.world.entities;
-        let removed_components = &mut self.world.removed_components;
+        let world = &mut *self.world;
+        let bundle_id = world
+            .bundles
+            .init_info::<T>(&mut world.components, &mut world.storages)
+            .id();
+        self.remove_bundle_intersection_by_id(bundle_id);
+    }
 
-        let bundle_info = self.world.bundles.init_info::<T>(components, storages);
+    /// Removes the component with the given [`ComponentId`] from the entity, if it has it.
+    /// The component is dropped.
+    ///
+    /// This is the type-erased equivalent of [`EntityMut::remove`], see
+    /// [`EntityMut::insert_by_id`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `component_id` is not a valid [`ComponentId`].
+    pub fn remove_by_id(&mut self, component_id: ComponentId) -> &mut Self {
+        let world = &mut *self.world;
+        let bundle_id = world
+            .bundles
+            .init_dynamic_info(&mut world.components, &[component_id])
+            .id();
+        self.remove_bundle_intersection_by_id(bundle_id);
+        self
+    }
+
+    /// Removes and drops any components of the bundle with the given [`BundleId`] that the entity
+    /// has.
+    fn remove_bundle_intersection_by_id(&mut self, bundle_id: BundleId) {
+        let world = &mut *self.world;
+        let bundle_info = world.bundles.get(bundle_id).unwrap();
         let old_location = self.location;
 
         // SAFETY: `archetype_id` exists because it is referenced in the old `EntityLocation` which is valid,
         // components exist in `bundle_info` because `Bundles::init_info` initializes a `BundleInfo` containing all components of the bundle type `T`
         let new_archetype_id = unsafe {
             remove_bundle_from_archetype(
-                archetypes,
-                storages,
-                components,
+                &mut world.archetypes,
+                &mut world.storages,
+                &mut world.components,
                 old_location.archetype_id,
                 bundle_info,
                 true,
@@ -407,6 +535,18 @@ impl<'w> EntityMut<'w> {
             return;
         }
 
+        if world.archetypes[old_location.archetype_id].has_remove_hook() {
+            // hooks can't change the structure of the world, so `old_location` stays valid
+            let bundle_components = bundle_info.component_ids.clone();
+            world.trigger_remove_hooks(old_location.archetype_id, self.entity, bundle_components);
+        }
+
+        let archetypes = &mut world.archetypes;
+        let storages = &mut world.storages;
+        let entities = &mut world.entities;
+        let removed_components = &mut world.removed_components;
+        let bundle_info = world.bundles.get(bundle_id).unwrap();
+
         let old_archetype = &mut archetypes[old_location.archetype_id];
         let entity = self.entity;
         for component_id in bundle_info.component_ids.iter().cloned() {
@@ -440,6 +580,8 @@ impl<'w> EntityMut<'w> {
                 new_archetype_id,
             );
         }
+
+        self.flush_commands();
     }
 
     pub fn insert<T: Component>(&mut self, value: T) -> &mut Self {
@@ -450,44 +592,64 @@ impl<'w> EntityMut<'w> {
         self.remove_bundle::<(T,)>().map(|v| v.0)
     }
 
+    /// Adds an edge of the [`RelationKind`] `K` from this entity to `target`.
+    ///
+    /// See [`World::relate`] for more details.
+    pub fn relate<K: RelationKind>(&mut self, target: Entity) -> &mut Self {
+        relation::relate::<K>(self.world, self.entity, target);
+        self.update_location();
+        self
+    }
+
+    /// Removes the edge of the [`RelationKind`] `K` from this entity to `target`, returning
+    /// `false` if there was none.
+    ///
+    /// See [`World::unrelate`] for more details.
+    pub fn unrelate<K: RelationKind>(&mut self, target: Entity) -> bool {
+        let removed = relation::unrelate::<K>(self.world, self.entity, target);
+        self.update_location();
+        removed
+    }
+
+    /// Adds an observer system for events of type `E` triggered on this entity.
+    ///
+    /// See [`World::trigger`] for more details.
+    pub fn observe<E: Event, Params>(
+        &mut self,
+        system: impl IntoSystem<Trigger<E>, (), Params>,
+    ) -> &mut Self {
+        observer::observe(
+            self.world,
+            Some(self.entity),
+            Box::new(IntoSystem::into_system(system)),
+        );
+        self.update_location();
+        self
+    }
+
     pub fn despawn(self) {
         let world = self.world;
         world.flush();
-        let location = world
-            .entities
-            .free(self.entity)
-            .expect("entity should exist at this point.");
-        let table_row;
-        let moved_entity;
-        {
-            let archetype = &mut world.archetypes[location.archetype_id];
-            for component_id in archetype.components() {
-                let removed_components = world
-                    .removed_components
-                    .get_or_insert_with(component_id, Vec::new);
-                removed_components.push(self.entity);
-            }
-            let remove_result = archetype.swap_remove(location.index);
-            if let Some(swapped_entity) = remove_result.swapped_entity {
-                world.entities.meta[swapped_entity.id as usize].location = location;
+        if !world.relations.is_empty() {
+            let despawn_handlers: Vec<_> = world.archetypes[self.location.archetype_id]
+                .components()
+                .filter_map(|component_id| world.relations.despawn_handler(component_id))
+                .collect();
+            // Relation cleanup can move this entity, or despawn it through a cycle of edges
+            for despawn_handler in despawn_handlers {
+                despawn_handler(world, self.entity);
             }
-            table_row = remove_result.table_row;
-
-            for component_id in archetype.sparse_set_components() {
-                let sparse_set = world.storages.sparse_sets.get_mut(*component_id).unwrap();
-                sparse_set.remove(self.entity);
+            if !world.entities.contains(self.entity) {
+                return;
             }
-            // SAFETY: table rows stored in archetypes always exist
-            moved_entity = unsafe {
-                world.storages.tables[archetype.table_id()].swap_remove_unchecked(table_row)
-            };
-        };
-
-        if let Some(moved_entity) = moved_entity {
-            let moved_location = world.entities.get(moved_entity).unwrap();
-            world.archetypes[moved_location.archetype_id]
-                .set_entity_table_row(moved_location.index, table_row);
         }
+        let archetype_id = world.entities.get(self.entity).unwrap().archetype_id;
+        if world.archetypes[archetype_id].has_remove_hook() {
+            let components: Vec<_> = world.archetypes[archetype_id].components().collect();
+            world.trigger_remove_hooks(archetype_id, self.entity, components);
+        }
+        world.despawn_without_hooks(self.entity);
+        world.flush_commands();
     }
 
     #[inline]
@@ -825,6 +987,7 @@ unsafe fn remove_bundle_from_archetype(
         }
 
         let new_archetype_id = archetypes.get_id_or_insert(
+            components,
             next_table_id,
             next_table_components,
             next_sparse_set_components,
@@ -997,4 +1160,104 @@ mod tests {
         assert!(entity.get_by_id(invalid_component_id).is_none());
         assert!(entity.get_mut_by_id(invalid_component_id).is_none());
     }
+
+    #[derive(Component)]
+    #[component(storage = "SparseSet")]
+    struct SparseComponent(u32);
+
+    #[test]
+    fn entity_mut_insert_by_id() {
+        let mut world = World::new();
+        let test_component = world.init_component::<TestComponent>();
+        let sparse_component = world.init_component::<SparseComponent>();
+
+        let mut entity = world.spawn();
+        bevy_ptr::OwningPtr::make(TestComponent(1), |ptr| {
+            // SAFETY: `ptr` points to a `TestComponent`
+            unsafe { entity.insert_by_id(test_component, ptr) };
+        });
+        assert_eq!(entity.get::<TestComponent>().unwrap().0, 1);
+
+        bevy_ptr::OwningPtr::make(TestComponent(2), |a| {
+            bevy_ptr::OwningPtr::make(SparseComponent(3), |b| {
+                // SAFETY: the pointers match the types of their components
+                unsafe { entity.insert_by_ids([(test_component, a), (sparse_component, b)]) };
+            });
+        });
+        assert_eq!(entity.get::<TestComponent>().unwrap().0, 2);
+        assert_eq!(entity.get::<SparseComponent>().unwrap().0, 3);
+
+        entity
+            .remove_by_id(test_component)
+            .remove_by_id(sparse_component);
+        assert!(!entity.contains::<TestComponent>());
+        assert!(!entity.contains::<SparseComponent>());
+        // removing a component the entity does not have does nothing
+        entity.remove_by_id(test_component);
+    }
+
+    #[test]
+    fn dynamic_component() {
+        use crate::component::{ComponentDescriptor, StorageType};
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        static DROPPED: AtomicUsize = AtomicUsize::new(0);
+
+        // SAFETY: the component is always a `u64`
+        unsafe fn drop_u64(ptr: bevy_ptr::OwningPtr<'_>) {
+            DROPPED.fetch_add(ptr.read::<u64>() as usize, Ordering::SeqCst);
+        }
+
+        let mut world = World::new();
+        // SAFETY: `drop_u64` matches the layout
+        let descriptor = unsafe {
+            ComponentDescriptor::new_with_layout(
+                "Dynamic",
+                StorageType::Table,
+                std::alloc::Layout::new::<u64>(),
+                Some(drop_u64),
+            )
+        };
+        let dynamic = world.init_component_with_descriptor(descriptor);
+
+        let entity = bevy_ptr::OwningPtr::make(10u64, |ptr| {
+            // SAFETY: `ptr` points to a `u64`
+            unsafe { world.spawn_by_ids([(dynamic, ptr)]).id() }
+        });
+        let other = world.spawn().insert(TestComponent(0)).id();
+        bevy_ptr::OwningPtr::make(20u64, |ptr| {
+            // SAFETY: `ptr` points to a `u64`
+            unsafe { world.entity_mut(other).insert_by_id(dynamic, ptr) };
+        });
+
+        // SAFETY: the component is a `u64`
+        let value = unsafe {
+            *world
+                .entity(entity)
+                .get_by_id(dynamic)
+                .unwrap()
+                .deref::<u64>()
+        };
+        assert_eq!(value, 10);
+        assert_eq!(DROPPED.load(Ordering::SeqCst), 0);
+
+        world.entity_mut(other).remove_by_id(dynamic);
+        assert_eq!(DROPPED.load(Ordering::SeqCst), 20);
+        assert!(world.entity(other).contains::<TestComponent>());
+        world.despawn(entity);
+        assert_eq!(DROPPED.load(Ordering::SeqCst), 30);
+    }
+
+    #[test]
+    #[should_panic]
+    fn insert_by_ids_duplicate_components() {
+        let mut world = World::new();
+        let test_component = world.init_component::<TestComponent>();
+        bevy_ptr::OwningPtr::make(TestComponent(1), |a| {
+            bevy_ptr::OwningPtr::make(TestComponent(2), |b| {
+                // SAFETY: the pointers match the types of their components
+                unsafe { world.spawn_by_ids([(test_component, a), (test_component, b)]) };
+            });
+        });
+    }
 }
@@ -0,0 +1,141 @@
+use crate::{
+    archetype::{ArchetypeId, ArchetypeStats},
+    storage::{SparseSetStats, TableStats},
+    world::World,
+};
+
+/// A snapshot of the memory used by the archetypes and storages of a [`World`].
+///
+/// Created with [`World::memory_stats`].
+#[derive(Debug, Clone, Default)]
+pub struct MemoryStats {
+    /// The memory used by each archetype, by [`ArchetypeId`].
+    pub archetypes: Vec<ArchetypeStats>,
+    /// The memory used by each table, by [`TableId`](crate::storage::TableId).
+    pub tables: Vec<TableStats>,
+    /// The memory used by the sparse set of each component stored in one.
+    pub sparse_sets: Vec<SparseSetStats>,
+}
+
+impl MemoryStats {
+    /// The bytes occupied by entities and components across all archetypes and storages.
+    pub fn bytes_used(&self) -> usize {
+        self.archetypes.iter().map(|a| a.bytes_used).sum::<usize>()
+            + self.tables.iter().map(|t| t.bytes_used).sum::<usize>()
+            + self.sparse_sets.iter().map(|s| s.bytes_used).sum::<usize>()
+    }
+
+    /// The bytes allocated across all archetypes and storages, including unused capacity.
+    pub fn bytes_allocated(&self) -> usize {
+        self.archetypes
+            .iter()
+            .map(|a| a.bytes_allocated)
+            .sum::<usize>()
+            + self.tables.iter().map(|t| t.bytes_allocated).sum::<usize>()
+            + self
+                .sparse_sets
+                .iter()
+                .map(|s| s.bytes_allocated)
+                .sum::<usize>()
+    }
+
+    /// Returns the archetypes holding fewer than `min_entities` entities.
+    ///
+    /// An archetype with exactly `min_entities` entities is not considered fragmented.
+    ///
+    /// Many such archetypes usually mean that entities are spread over many combinations of
+    /// components, which hurts iteration performance. The built-in empty and resource
+    /// archetypes are never returned.
+    pub fn fragmented_archetypes(
+        &self,
+        min_entities: usize,
+    ) -> impl Iterator<Item = &ArchetypeStats> + '_ {
+        self.archetypes.iter().filter(move |archetype| {
+            archetype.id != ArchetypeId::EMPTY
+                && archetype.id != ArchetypeId::RESOURCE
+                && archetype.entity_count < min_entities
+        })
+    }
+}
+
+impl World {
+    /// Returns the memory used by the archetypes, tables and sparse sets of this world.
+    ///
+    /// ```
+    /// use bevy_ecs::prelude::*;
+    ///
+    /// #[derive(Component)]
+    /// struct Position(f32, f32);
+    ///
+    /// let mut world = World::new();
+    /// world.spawn().insert(Position(0.0, 0.0));
+    ///
+    /// let stats = world.memory_stats();
+    /// assert!(stats.bytes_used() <= stats.bytes_allocated());
+    /// assert_eq!(stats.fragmented_archetypes(2).count(), 1);
+    /// ```
+    pub fn memory_stats(&self) -> MemoryStats {
+        MemoryStats {
+            archetypes: self.archetypes.stats(),
+            tables: self.storages.tables.stats(),
+            sparse_sets: self.storages.sparse_sets.stats(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate as bevy_ecs;
+    use crate::{component::Component, world::World};
+
+    #[derive(Component)]
+    struct A {
+        _value: u64,
+    }
+
+    #[derive(Component)]
+    #[component(storage = "SparseSet")]
+    struct B {
+        _value: u32,
+    }
+
+    #[test]
+    fn memory_stats() {
+        let mut world = World::new();
+        let a = world.init_component::<A>();
+        let b = world.init_component::<B>();
+        for i in 0..10 {
+            world.spawn().insert(A { _value: i });
+        }
+        world
+            .spawn()
+            .insert_bundle((A { _value: 0 }, B { _value: 0 }));
+
+        let stats = world.memory_stats();
+        assert!(stats.bytes_used() <= stats.bytes_allocated());
+
+        let table = stats
+            .tables
+            .iter()
+            .find(|table| table.columns.iter().any(|(id, _)| *id == a))
+            .unwrap();
+        assert_eq!(table.entity_count, 11);
+        let column = table.columns[0].1;
+        assert_eq!(column.len, 11);
+        assert_eq!(column.item_size, 8);
+        assert!(column.capacity >= 11);
+
+        let sparse_set = stats
+            .sparse_sets
+            .iter()
+            .find(|set| set.component_id == b)
+            .unwrap();
+        assert_eq!(sparse_set.len, 1);
+
+        let fragmented: Vec<_> = stats.fragmented_archetypes(2).collect();
+        assert_eq!(fragmented.len(), 1);
+        assert_eq!(fragmented[0].entity_count, 1);
+        assert_eq!(fragmented[0].component_count, 2);
+        assert_eq!(stats.fragmented_archetypes(1).count(), 0);
+    }
+}
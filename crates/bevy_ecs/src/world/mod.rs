@@ -1,24 +1,33 @@
+mod deferred_world;
 mod entity_ref;
+mod memory_stats;
 mod spawn_batch;
 mod world_cell;
 
 pub use crate::change_detection::Mut;
+pub use deferred_world::*;
 pub use entity_ref::*;
+pub use memory_stats::*;
 pub use spawn_batch::*;
 pub use world_cell::*;
 
 use crate::{
-    archetype::{ArchetypeComponentId, ArchetypeComponentInfo, ArchetypeId, Archetypes},
-    bundle::{Bundle, BundleInserter, BundleSpawner, Bundles},
+    archetype::{
+        ArchetypeComponentId, ArchetypeComponentInfo, ArchetypeId, Archetypes, ComponentStatus,
+    },
+    bundle::{Bundle, BundleId, BundleInserter, BundleSpawner, Bundles},
     change_detection::{MutUntyped, Ticks},
     component::{
-        Component, ComponentDescriptor, ComponentId, ComponentInfo, ComponentTicks, Components,
-        StorageType,
+        Component, ComponentDescriptor, ComponentHooks, ComponentId, ComponentInfo, ComponentTicks,
+        Components, StorageType,
     },
-    entity::{AllocAtWithoutReplacement, Entities, Entity},
+    entity::{AllocAtWithoutReplacement, Entities, Entity, EntityOrder},
+    event::Event,
+    observer::{self, Traversal, Trigger},
     query::{QueryState, WorldQuery},
-    storage::{Column, SparseSet, Storages},
-    system::Resource,
+    relation::{self, RelationKind, Relations},
+    storage::{Column, ShrinkPolicy, SparseSet, Storages},
+    system::{CommandQueue, IntoSystem, Resource},
 };
 use bevy_ptr::{OwningPtr, Ptr, UnsafeCellDeref};
 use bevy_utils::tracing::debug;
@@ -58,6 +67,9 @@ pub struct World {
     pub(crate) storages: Storages,
     pub(crate) bundles: Bundles,
     pub(crate) removed_components: SparseSet<ComponentId, Vec<Entity>>,
+    pub(crate) relations: Relations,
+    /// Commands queued by [`ComponentHooks`] through a [`DeferredWorld`].
+    pub(crate) command_queue: CommandQueue,
     /// Access cache used by [WorldCell].
     pub(crate) archetype_component_access: ArchetypeComponentAccess,
     main_thread_validator: MainThreadValidator,
@@ -75,6 +87,8 @@ impl Default for World {
             storages: Default::default(),
             bundles: Default::default(),
             removed_components: Default::default(),
+            relations: Default::default(),
+            command_queue: Default::default(),
             archetype_component_access: Default::default(),
             main_thread_validator: Default::default(),
             // Default value is `1`, and `last_change_tick`s default to `0`, such that changes
@@ -119,6 +133,15 @@ impl World {
         &mut self.entities
     }
 
+    /// Sets the order in which the IDs of despawned entities are reused.
+    ///
+    /// Use [`EntityOrder::Deterministic`] when the same sequence of spawns and despawns must
+    /// produce the same entities on every run, for example in lockstep multiplayer.
+    pub fn set_entity_order(&mut self, order: EntityOrder) {
+        self.flush();
+        self.entities.set_order(order);
+    }
+
     /// Retrieves this world's [Archetypes] collection
     #[inline]
     pub fn archetypes(&self) -> &Archetypes {
@@ -143,6 +166,12 @@ impl World {
         &self.bundles
     }
 
+    /// Retrieves this world's [Relations] collection
+    #[inline]
+    pub fn relations(&self) -> &Relations {
+        &self.relations
+    }
+
     /// Retrieves a [`WorldCell`], which safely enables multiple mutable World accesses at the same
     /// time, provided those accesses do not conflict with each other.
     #[inline]
@@ -172,6 +201,43 @@ impl World {
             .init_component_with_descriptor(&mut self.storages, descriptor)
     }
 
+    /// Returns a mutable reference to the [`ComponentHooks`] of the [`Component`] type `T`,
+    /// initializing the component first if needed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `T` has already been added to an entity, as hooks must be registered before
+    /// the component is used.
+    pub fn register_component_hooks<T: Component>(&mut self) -> &mut ComponentHooks {
+        let component_id = self.init_component::<T>();
+        self.register_component_hooks_by_id(component_id).unwrap()
+    }
+
+    /// Returns a mutable reference to the [`ComponentHooks`] of the component with the given
+    /// [`ComponentId`], or [`None`] if there is no such component.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the component has already been added to an entity, as hooks must be registered
+    /// before the component is used.
+    pub fn register_component_hooks_by_id(
+        &mut self,
+        component_id: ComponentId,
+    ) -> Option<&mut ComponentHooks> {
+        let hooks = self.components.get_hooks_mut(component_id)?;
+        if self
+            .archetypes
+            .iter()
+            .any(|archetype| archetype.contains(component_id))
+        {
+            panic!(
+                "Component hooks for {:?} cannot be registered after the component was added to an entity.",
+                component_id
+            );
+        }
+        Some(hooks)
+    }
+
     /// Returns the [`ComponentId`] of the given [`Component`] type `T`.
     ///
     /// The returned `ComponentId` is specific to the `World` instance
@@ -406,12 +472,59 @@ impl World {
         EntityMut::new(self, entity, location)
     }
 
+    /// Spawns a new [`Entity`] with the components with the given [`ComponentId`]s, from
+    /// pointers to their values.
+    ///
+    /// This is the type-erased equivalent of [`World::spawn`] followed by
+    /// [`EntityMut::insert_bundle`], see [`EntityMut::insert_by_id`].
+    ///
+    /// ```
+    /// use bevy_ecs::{component::{ComponentDescriptor, StorageType}, ptr::OwningPtr, world::World};
+    /// use std::alloc::Layout;
+    ///
+    /// let mut world = World::new();
+    /// // SAFETY: `u64` needs no drop fn
+    /// let descriptor = unsafe {
+    ///     ComponentDescriptor::new_with_layout("Score", StorageType::Table, Layout::new::<u64>(), None)
+    /// };
+    /// let score = world.init_component_with_descriptor(descriptor);
+    ///
+    /// let entity = OwningPtr::make(42u64, |ptr| {
+    ///     // SAFETY: `ptr` points to a `u64`, which matches the layout of `score`
+    ///     unsafe { world.spawn_by_ids([(score, ptr)]).id() }
+    /// });
+    /// // SAFETY: `score` components are `u64`s
+    /// let value = unsafe { world.entity(entity).get_by_id(score).unwrap().deref::<u64>() };
+    /// assert_eq!(*value, 42);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if any of the [`ComponentId`]s is not valid, or if they contain duplicates.
+    ///
+    /// # Safety
+    ///
+    /// - The [`ComponentId`]s must be from this [`World`].
+    /// - Each pointer must point to a valid value of the type of its component, which is moved
+    ///   into the entity.
+    pub unsafe fn spawn_by_ids<'a>(
+        &mut self,
+        components: impl IntoIterator<Item = (ComponentId, OwningPtr<'a>)>,
+    ) -> EntityMut<'_> {
+        let mut entity = self.spawn();
+        entity.insert_by_ids(components);
+        entity
+    }
+
     /// Spawns a batch of entities with the same component [Bundle] type. Takes a given [Bundle]
     /// iterator and returns a corresponding [Entity] iterator.
     /// This is more efficient than spawning entities and adding components to them individually,
     /// but it is limited to spawning entities with the same [Bundle] type, whereas spawning
     /// individually is more flexible.
     ///
+    /// If the bundle has [`ComponentHooks`], the entities are spawned one at a time as the
+    /// returned iterator is advanced, and their hooks run right away.
+    ///
     /// ```
     /// use bevy_ecs::{component::Component, entity::Entity, world::World};
     ///
@@ -514,6 +627,134 @@ impl World {
             .unwrap_or(false)
     }
 
+    /// Despawns `entity` without running the `on_remove`
+    /// [component hooks](crate::component::ComponentHooks) or the
+    /// [`DespawnPolicy`](crate::relation::DespawnPolicy) of relations, returning `false` if it
+    /// does not exist. Its components still show up in
+    /// [`RemovedComponents`](crate::system::RemovedComponents).
+    pub(crate) fn despawn_without_hooks(&mut self, entity: Entity) -> bool {
+        self.flush();
+        let location = match self.entities.free(entity) {
+            Some(location) => location,
+            None => return false,
+        };
+        let table_row;
+        let moved_entity;
+        {
+            let archetype = &mut self.archetypes[location.archetype_id];
+            for component_id in archetype.components() {
+                let removed_components = self
+                    .removed_components
+                    .get_or_insert_with(component_id, Vec::new);
+                removed_components.push(entity);
+            }
+            let remove_result = archetype.swap_remove(location.index);
+            if let Some(swapped_entity) = remove_result.swapped_entity {
+                self.entities.meta[swapped_entity.id as usize].location = location;
+            }
+            table_row = remove_result.table_row;
+
+            for component_id in archetype.sparse_set_components() {
+                let sparse_set = self.storages.sparse_sets.get_mut(*component_id).unwrap();
+                sparse_set.remove(entity);
+            }
+            // SAFETY: table rows stored in archetypes always exist
+            moved_entity = unsafe {
+                self.storages.tables[archetype.table_id()].swap_remove_unchecked(table_row)
+            };
+        };
+
+        if let Some(moved_entity) = moved_entity {
+            let moved_location = self.entities.get(moved_entity).unwrap();
+            self.archetypes[moved_location.archetype_id]
+                .set_entity_table_row(moved_location.index, table_row);
+        }
+        true
+    }
+
+    /// Adds an edge of the [`RelationKind`] `K` from `source` to `target`, returning `false` if
+    /// the edge already existed.
+    ///
+    /// This inserts or updates a [`Relation<K>`](crate::relation::Relation) on `source` and a
+    /// [`RelationSources<K>`](crate::relation::RelationSources) on `target`. See the
+    /// [`relation`](crate::relation) module for more details.
+    ///
+    /// ```
+    /// use bevy_ecs::prelude::*;
+    ///
+    /// struct Targeting;
+    /// impl RelationKind for Targeting {}
+    ///
+    /// let mut world = World::new();
+    /// let enemy = world.spawn().id();
+    /// let turret = world.spawn().id();
+    /// assert!(world.relate::<Targeting>(turret, enemy));
+    ///
+    /// world.despawn(enemy);
+    /// assert!(world.get::<Relation<Targeting>>(turret).is_none());
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if either entity does not exist.
+    pub fn relate<K: RelationKind>(&mut self, source: Entity, target: Entity) -> bool {
+        relation::relate::<K>(self, source, target)
+    }
+
+    /// Removes the edge of the [`RelationKind`] `K` from `source` to `target`, returning `false`
+    /// if there was none.
+    ///
+    /// Relation components left without any edges are removed from both entities.
+    pub fn unrelate<K: RelationKind>(&mut self, source: Entity, target: Entity) -> bool {
+        relation::unrelate::<K>(self, source, target)
+    }
+
+    /// Adds a global observer system for events of type `E`, which runs every time an `E` is
+    /// triggered on any entity.
+    ///
+    /// See the [`observer`](crate::observer) module for more details.
+    pub fn observe<E: Event, Params>(
+        &mut self,
+        system: impl IntoSystem<Trigger<E>, (), Params>,
+    ) -> &mut Self {
+        observer::observe(self, None, Box::new(IntoSystem::into_system(system)));
+        self
+    }
+
+    /// Immediately runs the observers of `entity` for `event`, then the global observers.
+    ///
+    /// Commands issued by the observers have been applied by the time this returns.
+    ///
+    /// ```
+    /// use bevy_ecs::prelude::*;
+    ///
+    /// struct Clicked;
+    ///
+    /// #[derive(Component)]
+    /// struct Pressed;
+    ///
+    /// let mut world = World::new();
+    /// let button = world
+    ///     .spawn()
+    ///     .observe(|In(trigger): In<Trigger<Clicked>>, mut commands: Commands| {
+    ///         commands.entity(trigger.entity()).insert(Pressed);
+    ///     })
+    ///     .id();
+    ///
+    /// world.trigger(Clicked, button);
+    /// assert!(world.entity(button).contains::<Pressed>());
+    /// ```
+    pub fn trigger(&mut self, event: impl Event, entity: Entity) {
+        observer::trigger::<(), _>(self, event, entity);
+    }
+
+    /// Like [`World::trigger`], but the event then bubbles up to each entity returned by the
+    /// [`Traversal`] `T` in turn, running their observers, until an observer calls
+    /// [`Trigger::stop_propagation`].
+    pub fn trigger_bubbling<T: Traversal>(&mut self, event: impl Event, entity: Entity) {
+        observer::trigger::<T, _>(self, event, entity);
+    }
+
     /// Clears component tracker state
     pub fn clear_trackers(&mut self) {
         for entities in self.removed_components.values_mut() {
@@ -973,6 +1214,14 @@ impl World {
         let bundle_info = self
             .bundles
             .init_info::<B>(&mut self.components, &mut self.storages);
+        let bundle_id = bundle_info.id();
+        let has_hooks = bundle_info.component_ids.iter().any(|&component_id| {
+            // SAFETY: bundle components are always initialized
+            let hooks = unsafe { self.components.get_info_unchecked(component_id) }.hooks();
+            hooks.on_add.is_some() || hooks.on_insert.is_some()
+        });
+        // entities and the archetypes they were in before the bundle was inserted
+        let mut hooked_entities = Vec::new();
         enum SpawnOrInsert<'a, 'b> {
             Spawn(BundleSpawner<'a, 'b>),
             Insert(BundleInserter<'a, 'b>, ArchetypeId),
@@ -1001,6 +1250,9 @@ impl World {
                 .alloc_at_without_replacement(entity)
             {
                 AllocAtWithoutReplacement::Exists(location) => {
+                    if has_hooks {
+                        hooked_entities.push((entity, location.archetype_id));
+                    }
                     match spawn_or_insert {
                         SpawnOrInsert::Insert(ref mut inserter, archetype)
                             if location.archetype_id == archetype =>
@@ -1025,6 +1277,9 @@ impl World {
                     };
                 }
                 AllocAtWithoutReplacement::DidNotExist => {
+                    if has_hooks {
+                        hooked_entities.push((entity, ArchetypeId::EMPTY));
+                    }
                     if let SpawnOrInsert::Spawn(ref mut spawner) = spawn_or_insert {
                         // SAFETY: `entity` is allocated (but non existent), bundle matches inserter
                         unsafe { spawner.spawn_non_existent(entity, bundle) };
@@ -1047,6 +1302,11 @@ impl World {
             }
         }
 
+        for (entity, archetype_id) in hooked_entities {
+            self.trigger_insert_hooks(archetype_id, bundle_id, entity);
+        }
+        self.flush_commands();
+
         if invalid_entities.is_empty() {
             Ok(())
         } else {
@@ -1331,6 +1591,88 @@ impl World {
         );
     }
 
+    /// Applies the commands queued by [`ComponentHooks`] through a [`DeferredWorld`].
+    ///
+    /// This is done automatically at the end of every operation that runs hooks.
+    pub fn flush_commands(&mut self) {
+        if !self.command_queue.is_empty() {
+            let mut command_queue = std::mem::take(&mut self.command_queue);
+            command_queue.apply(self);
+        }
+    }
+
+    /// Runs the `on_add` and `on_insert` hooks for the components of `bundle_id` that were just
+    /// inserted into `entity`, which was previously stored in `archetype_id`.
+    pub(crate) fn trigger_insert_hooks(
+        &mut self,
+        archetype_id: ArchetypeId,
+        bundle_id: BundleId,
+        entity: Entity,
+    ) {
+        let add_bundle = self.archetypes[archetype_id]
+            .edges()
+            .get_add_bundle(bundle_id)
+            .unwrap();
+        let new_archetype = &self.archetypes[add_bundle.archetype_id];
+        if !new_archetype.has_add_hook() && !new_archetype.has_insert_hook() {
+            return;
+        }
+        let bundle_info = self.bundles.get(bundle_id).unwrap();
+        let components = &self.components;
+        let mut hooks = Vec::new();
+        for (&component_id, status) in bundle_info
+            .component_ids
+            .iter()
+            .zip(&add_bundle.bundle_status)
+        {
+            // SAFETY: bundle components are always initialized
+            let on_add = unsafe { components.get_info_unchecked(component_id) }
+                .hooks()
+                .on_add;
+            if let (ComponentStatus::Added, Some(on_add)) = (status, on_add) {
+                hooks.push((component_id, on_add));
+            }
+        }
+        for &component_id in &bundle_info.component_ids {
+            // SAFETY: bundle components are always initialized
+            let on_insert = unsafe { components.get_info_unchecked(component_id) }
+                .hooks()
+                .on_insert;
+            if let Some(on_insert) = on_insert {
+                hooks.push((component_id, on_insert));
+            }
+        }
+        for (component_id, hook) in hooks {
+            hook(DeferredWorld::new(self), entity, component_id);
+        }
+    }
+
+    /// Runs the `on_remove` hooks for `components` of `entity`, before they are removed from
+    /// `archetype_id`.
+    pub(crate) fn trigger_remove_hooks(
+        &mut self,
+        archetype_id: ArchetypeId,
+        entity: Entity,
+        components: impl IntoIterator<Item = ComponentId>,
+    ) {
+        let archetype = &self.archetypes[archetype_id];
+        if !archetype.has_remove_hook() {
+            return;
+        }
+        let hooks: Vec<_> = components
+            .into_iter()
+            .filter(|&component_id| archetype.contains(component_id))
+            .filter_map(|component_id| {
+                // SAFETY: components of an archetype are always initialized
+                let info = unsafe { self.components.get_info_unchecked(component_id) };
+                Some((component_id, info.hooks().on_remove?))
+            })
+            .collect();
+        for (component_id, hook) in hooks {
+            hook(DeferredWorld::new(self), entity, component_id);
+        }
+    }
+
     /// Empties queued entities and adds them to the empty [Archetype](crate::archetype::Archetype).
     /// This should be called before doing operations that might operate on queued entities,
     /// such as inserting a [Component].
@@ -1378,14 +1720,65 @@ impl World {
         for column in resource_archetype.unique_components.values_mut() {
             column.check_change_ticks(change_tick);
         }
+
+        if let Some(mut schedules) = self.get_resource_mut::<crate::schedule_v3::Schedules>() {
+            schedules.check_change_ticks(change_tick);
+        }
     }
 
+    /// Despawns all the entities of the world at once.
+    ///
+    /// Unlike [`World::despawn`], this does not run the `on_remove`
+    /// [component hooks](crate::component::ComponentHooks) or the
+    /// [`DespawnPolicy`](crate::relation::DespawnPolicy) of relations: every relation edge
+    /// disappears along with both of its endpoints, so none can be left dangling.
     pub fn clear_entities(&mut self) {
         self.storages.tables.clear();
         self.storages.sparse_sets.clear();
         self.archetypes.clear_entities();
         self.entities.clear();
     }
+
+    /// Shrinks the allocations of tables, sparse sets, archetypes and entity metadata to fit
+    /// their contents, releasing memory kept around after many entities were despawned.
+    ///
+    /// Archetypes and tables are never removed, so existing [`QueryState`]s stay valid.
+    /// Inserting entities afterwards reallocates as needed.
+    ///
+    /// ```
+    /// use bevy_ecs::prelude::*;
+    ///
+    /// #[derive(Component)]
+    /// struct Position(f32, f32);
+    ///
+    /// let mut world = World::new();
+    /// let entities: Vec<_> = (0..1000)
+    ///     .map(|_| world.spawn().insert(Position(0.0, 0.0)).id())
+    ///     .collect();
+    /// for entity in entities {
+    ///     world.despawn(entity);
+    /// }
+    ///
+    /// let before = world.memory_stats().bytes_allocated();
+    /// world.shrink_storage();
+    /// assert!(world.memory_stats().bytes_allocated() < before);
+    /// ```
+    pub fn shrink_storage(&mut self) {
+        self.shrink_storage_with(ShrinkPolicy::All);
+    }
+
+    /// Like [`World::shrink_storage`], but only releases the allocations selected by `policy`.
+    pub fn shrink_storage_with(&mut self, policy: ShrinkPolicy) {
+        self.storages.tables.shrink_to_fit(policy);
+        self.storages.sparse_sets.shrink_to_fit(policy);
+        self.archetypes.shrink_to_fit(policy);
+        if policy == ShrinkPolicy::All {
+            self.entities.shrink_to_fit();
+            for entities in self.removed_components.values_mut() {
+                entities.shrink_to_fit();
+            }
+        }
+    }
 }
 
 impl World {
@@ -1461,6 +1854,104 @@ impl World {
         Some(())
     }
 
+    /// Gets the [`ComponentTicks`] of the resource with the id [`ComponentId`] if it exists.
+    ///
+    /// This can be used to detect changes to resources whose types are not known at compile
+    /// time, see [`ComponentTicks::is_changed`].
+    #[inline]
+    pub fn get_resource_ticks_by_id(&self, component_id: ComponentId) -> Option<ComponentTicks> {
+        let column = self.get_populated_resource_column(component_id)?;
+        // SAFETY:
+        // - index is in-bounds because the column is initialized and non-empty
+        // - no mutable reference to the ticks can exist while the world is borrowed
+        Some(unsafe { column.get_ticks_unchecked(0).read() })
+    }
+
+    /// Iterates over all [`Send`] and [`Sync`] resources in the world, yielding the
+    /// [`ComponentInfo`], an untyped pointer to the value and the [`ComponentTicks`] of each
+    /// resource.
+    ///
+    /// Non-send resources are skipped, as they can only be accessed from the main thread. Their
+    /// ids can still be listed through [`Components::iter`], and their values retrieved with
+    /// [`World::get_resource_by_id`].
+    ///
+    /// The values can be turned into reflected references through the `ReflectResource` or
+    /// `ReflectFromPtr` type data registered for the [`ComponentInfo::type_id`].
+    ///
+    /// ```
+    /// # use bevy_ecs::prelude::*;
+    /// #[derive(Resource)]
+    /// struct Score(u32);
+    ///
+    /// let mut world = World::new();
+    /// world.insert_resource(Score(10));
+    ///
+    /// let names = world
+    ///     .iter_resources()
+    ///     .map(|(info, _, _)| info.name())
+    ///     .collect::<Vec<_>>();
+    /// assert_eq!(names, vec![std::any::type_name::<Score>()]);
+    /// ```
+    pub fn iter_resources(
+        &self,
+    ) -> impl Iterator<Item = (&ComponentInfo, Ptr<'_>, ComponentTicks)> + '_ {
+        self.archetypes
+            .resource()
+            .unique_components()
+            .iter()
+            .filter_map(|(&component_id, column)| {
+                let info = self.components.get_info(component_id)?;
+                if !info.is_send_and_sync() || column.is_empty() {
+                    return None;
+                }
+                // SAFETY:
+                // - index is in-bounds because the column is initialized and non-empty
+                // - no mutable reference to the ticks can exist while the world is borrowed
+                let ticks = unsafe { column.get_ticks_unchecked(0).read() };
+                Some((info, column.get_data_ptr(), ticks))
+            })
+    }
+
+    /// Iterates mutably over all [`Send`] and [`Sync`] resources in the world, yielding the
+    /// [`ComponentInfo`] and a change-detecting untyped pointer to the value of each resource.
+    ///
+    /// Non-send resources are skipped, see [`World::iter_resources`].
+    pub fn iter_resources_mut(
+        &mut self,
+    ) -> impl Iterator<Item = (&ComponentInfo, MutUntyped<'_>)> + '_ {
+        let last_change_tick = self.last_change_tick();
+        let change_tick = self.read_change_tick();
+        let components = &self.components;
+        self.archetypes
+            .resource()
+            .unique_components()
+            .iter()
+            .filter_map(move |(&component_id, column)| {
+                let info = components.get_info(component_id)?;
+                if !info.is_send_and_sync() || column.is_empty() {
+                    return None;
+                }
+                let ticks = Ticks {
+                    // SAFETY:
+                    // - index is in-bounds because the column is initialized and non-empty
+                    // - world access is unique and each resource is yielded once, so no other
+                    //   reference to the ticks of the same row can exist at the same time
+                    component_ticks: unsafe { column.get_ticks_unchecked(0).deref_mut() },
+                    last_change_tick,
+                    change_tick,
+                };
+                Some((
+                    info,
+                    MutUntyped {
+                        // SAFETY: world access is unique and each resource is yielded once, so no
+                        // other reference can exist at the same time
+                        value: unsafe { column.get_data_ptr().assert_unique() },
+                        ticks,
+                    },
+                ))
+            })
+    }
+
     /// Retrieves an immutable untyped reference to the given `entity`'s [Component] of the given [`ComponentId`].
     /// Returns [None] if the `entity` does not have a [Component] of the given type.
     ///
@@ -1566,6 +2057,7 @@ mod tests {
         change_detection::DetectChanges,
         component::{ComponentDescriptor, ComponentId, ComponentInfo, StorageType},
         ptr::OwningPtr,
+        storage::ShrinkPolicy,
         system::Resource,
     };
     use bevy_ecs_macros::Component;
@@ -1739,6 +2231,81 @@ mod tests {
         assert_eq!(resource.0, 43);
     }
 
+    #[test]
+    fn iter_resources() {
+        #[derive(Resource)]
+        struct OtherResource;
+        struct NonSendResource;
+
+        let mut world = World::new();
+        world.insert_resource(TestResource(42));
+        world.insert_resource(OtherResource);
+        world.insert_non_send_resource(NonSendResource);
+        world.remove_resource::<OtherResource>();
+        let component_id = world
+            .components()
+            .get_resource_id(std::any::TypeId::of::<TestResource>())
+            .unwrap();
+
+        let resources = world.iter_resources().collect::<Vec<_>>();
+        assert_eq!(resources.len(), 1);
+        let (info, resource, ticks) = &resources[0];
+        assert_eq!(info.id(), component_id);
+        // SAFETY: `TestResource` is the correct resource type
+        assert_eq!(unsafe { resource.deref::<TestResource>() }.0, 42);
+        assert!(ticks.is_added(world.last_change_tick(), world.read_change_tick()));
+
+        world.clear_trackers();
+        let ticks = world.get_resource_ticks_by_id(component_id).unwrap();
+        assert!(!ticks.is_changed(world.last_change_tick(), world.read_change_tick()));
+    }
+
+    #[test]
+    fn iter_resources_mut() {
+        let mut world = World::new();
+        world.insert_resource(TestResource(42));
+        world.clear_trackers();
+
+        for (_, mut resource) in world.iter_resources_mut() {
+            // SAFETY: `TestResource` is the only resource
+            unsafe { resource.as_mut().deref_mut::<TestResource>() }.0 = 43;
+        }
+
+        assert_eq!(world.resource::<TestResource>().0, 43);
+        assert!(world.is_resource_changed::<TestResource>());
+    }
+
+    #[cfg(feature = "bevy_reflect")]
+    #[test]
+    fn iter_resources_reflect() {
+        use crate::reflect::ReflectResource;
+        use bevy_reflect::{Reflect, TypeRegistry};
+
+        #[derive(Resource, Reflect, Default)]
+        #[reflect(Resource)]
+        struct Score(u32);
+
+        let mut registry = TypeRegistry::default();
+        registry.register::<Score>();
+
+        let mut world = World::new();
+        world.insert_resource(Score(10));
+        world.insert_resource(TestResource(42));
+
+        let reflected = world
+            .iter_resources()
+            .filter_map(|(info, _, _)| {
+                let registration = registry.get(info.type_id()?)?;
+                registration.data::<ReflectResource>()
+            })
+            .map(|reflect_resource| reflect_resource.reflect(&world).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(reflected.len(), 1);
+        assert!(reflected[0]
+            .reflect_partial_eq(&Score(10))
+            .unwrap_or_default());
+    }
+
     #[test]
     fn custom_resource_with_layout() {
         static DROP_COUNT: AtomicU32 = AtomicU32::new(0);
@@ -1898,4 +2465,63 @@ mod tests {
             [Some(baz_id)].into()
         );
     }
+
+    #[test]
+    fn shrink_storage_keeps_query_state_valid() {
+        #[derive(Component, PartialEq, Debug)]
+        struct Table(u32);
+        #[derive(Component, PartialEq, Debug)]
+        #[component(storage = "SparseSet")]
+        struct Sparse(u32);
+
+        let mut world = World::new();
+        let entities: Vec<_> = (0..100)
+            .map(|i| world.spawn().insert_bundle((Table(i), Sparse(i))).id())
+            .collect();
+        let mut query = world.query::<(&Table, &Sparse)>();
+        assert_eq!(query.iter(&world).count(), 100);
+
+        for entity in &entities[1..] {
+            world.despawn(*entity);
+        }
+        world.shrink_storage();
+
+        assert_eq!(
+            query.iter(&world).collect::<Vec<_>>(),
+            vec![(&Table(0), &Sparse(0))]
+        );
+        let table = world.memory_stats().tables.into_iter().last().unwrap();
+        assert_eq!(table.entity_capacity, 1);
+
+        let spawned = world.spawn().insert_bundle((Table(1), Sparse(1))).id();
+        assert_eq!(query.iter(&world).count(), 2);
+        assert_eq!(query.get(&world, spawned).unwrap(), (&Table(1), &Sparse(1)));
+    }
+
+    #[test]
+    fn shrink_storage_empty_only() {
+        #[derive(Component)]
+        struct A;
+        #[derive(Component)]
+        struct B;
+
+        let mut world = World::new();
+        for _ in 0..100 {
+            world.spawn().insert(A);
+        }
+        let b = world.spawn().insert(B).id();
+        world.despawn(b);
+
+        world.shrink_storage_with(ShrinkPolicy::EmptyOnly);
+        let stats = world.memory_stats();
+        let capacities: Vec<_> = stats
+            .tables
+            .iter()
+            .map(|table| (table.entity_count, table.entity_capacity))
+            .collect();
+        assert!(capacities.contains(&(0, 0)));
+        assert!(capacities
+            .iter()
+            .any(|&(count, capacity)| count == 100 && capacity >= 100));
+    }
 }
@@ -11,7 +11,15 @@ where
     I::Item: Bundle,
 {
     inner: I,
-    spawner: BundleSpawner<'w, 'w>,
+    spawner: BatchSpawner<'w>,
+}
+
+enum BatchSpawner<'w> {
+    /// Spawns all the entities straight into their archetype.
+    Batched(BundleSpawner<'w, 'w>),
+    /// Spawns the entities one at a time, so that the component hooks of the bundle run as each
+    /// entity is spawned.
+    Hooked(&'w mut World),
 }
 
 impl<'w, I> SpawnBatchIter<'w, I>
@@ -28,6 +36,26 @@ where
         let (lower, upper) = iter.size_hint();
         let length = upper.unwrap_or(lower);
 
+        let has_hooks = {
+            let bundle_info = world
+                .bundles
+                .init_info::<I::Item>(&mut world.components, &mut world.storages);
+            let spawner = bundle_info.get_bundle_spawner(
+                &mut world.entities,
+                &mut world.archetypes,
+                &mut world.components,
+                &mut world.storages,
+                *world.change_tick.get_mut(),
+            );
+            spawner.archetype.has_add_hook() || spawner.archetype.has_insert_hook()
+        };
+        if has_hooks {
+            return Self {
+                inner: iter,
+                spawner: BatchSpawner::Hooked(world),
+            };
+        }
+
         let bundle_info = world
             .bundles
             .init_info::<I::Item>(&mut world.components, &mut world.storages);
@@ -43,7 +71,7 @@ where
 
         Self {
             inner: iter,
-            spawner,
+            spawner: BatchSpawner::Batched(spawner),
         }
     }
 }
@@ -67,8 +95,11 @@ where
 
     fn next(&mut self) -> Option<Entity> {
         let bundle = self.inner.next()?;
-        // SAFETY: bundle matches spawner type
-        unsafe { Some(self.spawner.spawn(bundle)) }
+        match &mut self.spawner {
+            // SAFETY: bundle matches spawner type
+            BatchSpawner::Batched(spawner) => unsafe { Some(spawner.spawn(bundle)) },
+            BatchSpawner::Hooked(world) => Some(world.spawn().insert_bundle(bundle).id()),
+        }
     }
 
     fn size_hint(&self) -> (usize, Option<usize>) {
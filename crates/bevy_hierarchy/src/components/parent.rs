@@ -1,6 +1,7 @@
 use bevy_ecs::{
     component::Component,
     entity::{Entity, EntityMap, MapEntities, MapEntitiesError},
+    observer::Traversal,
     reflect::{ReflectComponent, ReflectMapEntities},
     world::{FromWorld, World},
 };
@@ -41,6 +42,13 @@ impl MapEntities for Parent {
     }
 }
 
+/// Bubbles events triggered with [`World::trigger_bubbling`] up to the parent of each entity.
+impl Traversal for Parent {
+    fn traverse(world: &World, entity: Entity) -> Option<Entity> {
+        world.get::<Parent>(entity).map(Parent::get)
+    }
+}
+
 impl Deref for Parent {
     type Target = Entity;
 
@@ -0,0 +1,209 @@
+use crate::components::Children;
+use bevy_ecs::{
+    entity::{Disabled, Entity},
+    system::{Command, EntityCommands},
+    world::{EntityMut, World},
+};
+use bevy_utils::tracing::debug;
+
+/// Disables the given entity and all its children recursively
+#[derive(Debug)]
+pub struct DisableRecursive {
+    /// Target entity
+    pub entity: Entity,
+}
+
+/// Enables the given entity and all its children recursively
+#[derive(Debug)]
+pub struct EnableRecursive {
+    /// Target entity
+    pub entity: Entity,
+}
+
+/// Function for disabling an entity and all its children, see [`Disabled`]
+pub fn disable_with_children_recursive(world: &mut World, entity: Entity) {
+    if let Some(mut entity_mut) = world.get_entity_mut(entity) {
+        entity_mut.insert(Disabled);
+    } else {
+        debug!("Failed to disable entity {:?}", entity);
+        return;
+    }
+
+    for child in children_of(world, entity) {
+        disable_with_children_recursive(world, child);
+    }
+}
+
+/// Function for enabling an entity and all its children, see [`Disabled`]
+pub fn enable_with_children_recursive(world: &mut World, entity: Entity) {
+    if let Some(mut entity_mut) = world.get_entity_mut(entity) {
+        entity_mut.remove::<Disabled>();
+    } else {
+        debug!("Failed to enable entity {:?}", entity);
+        return;
+    }
+
+    for child in children_of(world, entity) {
+        enable_with_children_recursive(world, child);
+    }
+}
+
+fn children_of(world: &World, entity: Entity) -> Vec<Entity> {
+    world
+        .get::<Children>(entity)
+        .map(|children| children.to_vec())
+        .unwrap_or_default()
+}
+
+impl Command for DisableRecursive {
+    fn write(self, world: &mut World) {
+        #[cfg(feature = "trace")]
+        let _span = bevy_utils::tracing::info_span!(
+            "command",
+            name = "DisableRecursive",
+            entity = bevy_utils::tracing::field::debug(self.entity)
+        )
+        .entered();
+        disable_with_children_recursive(world, self.entity);
+    }
+}
+
+impl Command for EnableRecursive {
+    fn write(self, world: &mut World) {
+        #[cfg(feature = "trace")]
+        let _span = bevy_utils::tracing::info_span!(
+            "command",
+            name = "EnableRecursive",
+            entity = bevy_utils::tracing::field::debug(self.entity)
+        )
+        .entered();
+        enable_with_children_recursive(world, self.entity);
+    }
+}
+
+/// Trait that holds functions for disabling and enabling entities recursively down the hierarchy
+pub trait DisableRecursiveExt {
+    /// Disables the provided entity alongside all descendants, by inserting [`Disabled`] on each
+    /// of them.
+    fn disable_recursive(&mut self) -> &mut Self;
+
+    /// Enables the provided entity alongside all descendants, by removing [`Disabled`] from each
+    /// of them.
+    fn enable_recursive(&mut self) -> &mut Self;
+}
+
+impl<'w, 's, 'a> DisableRecursiveExt for EntityCommands<'w, 's, 'a> {
+    fn disable_recursive(&mut self) -> &mut Self {
+        let entity = self.id();
+        self.commands().add(DisableRecursive { entity });
+        self
+    }
+
+    fn enable_recursive(&mut self) -> &mut Self {
+        let entity = self.id();
+        self.commands().add(EnableRecursive { entity });
+        self
+    }
+}
+
+impl<'w> DisableRecursiveExt for EntityMut<'w> {
+    fn disable_recursive(&mut self) -> &mut Self {
+        let entity = self.id();
+
+        // SAFETY: The location is updated.
+        unsafe {
+            disable_with_children_recursive(self.world_mut(), entity);
+            self.update_location();
+        }
+        self
+    }
+
+    fn enable_recursive(&mut self) -> &mut Self {
+        let entity = self.id();
+
+        // SAFETY: The location is updated.
+        unsafe {
+            enable_with_children_recursive(self.world_mut(), entity);
+            self.update_location();
+        }
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy_ecs::{
+        component::Component,
+        entity::Disabled,
+        query::IncludeDisabled,
+        system::{CommandQueue, Commands},
+        world::World,
+    };
+
+    use super::DisableRecursiveExt;
+    use crate::child_builder::{BuildChildren, BuildWorldChildren};
+
+    #[derive(Component, Clone, Copy, PartialEq, Eq, Ord, PartialOrd, Debug)]
+    struct Idx(u32);
+
+    #[test]
+    fn disable_and_enable_recursive() {
+        let mut world = World::default();
+        let mut queue = CommandQueue::default();
+        let parent;
+        {
+            let mut commands = Commands::new(&mut queue, &world);
+            parent = commands
+                .spawn_bundle((Idx(0),))
+                .with_children(|parent| {
+                    parent.spawn_bundle((Idx(1),)).with_children(|child| {
+                        child.spawn_bundle((Idx(2),));
+                    });
+                })
+                .id();
+            commands.spawn_bundle((Idx(3),));
+        }
+        queue.apply(&mut world);
+
+        let mut query = world.query::<&Idx>();
+        let mut all_query = world.query_filtered::<&Idx, IncludeDisabled>();
+        let mut disabled_query = world.query_filtered::<&Idx, bevy_ecs::query::With<Disabled>>();
+
+        {
+            let mut commands = Commands::new(&mut queue, &world);
+            commands.entity(parent).disable_recursive();
+        }
+        queue.apply(&mut world);
+
+        let mut enabled = query.iter(&world).copied().collect::<Vec<_>>();
+        enabled.sort();
+        assert_eq!(enabled, vec![Idx(3)]);
+        let mut disabled = disabled_query.iter(&world).copied().collect::<Vec<_>>();
+        disabled.sort();
+        assert_eq!(disabled, vec![Idx(0), Idx(1), Idx(2)]);
+        assert_eq!(all_query.iter(&world).count(), 4);
+
+        world.entity_mut(parent).enable_recursive();
+
+        assert_eq!(query.iter(&world).count(), 4);
+        assert_eq!(disabled_query.iter(&world).count(), 0);
+    }
+
+    #[test]
+    fn disable_recursive_with_world() {
+        let mut world = World::default();
+        let mut child = None;
+        let parent = world
+            .spawn()
+            .insert(Idx(0))
+            .with_children(|parent| {
+                child = Some(parent.spawn().insert(Idx(1)).id());
+            })
+            .disable_recursive()
+            .id();
+
+        assert!(world.get::<Disabled>(parent).is_some());
+        assert!(world.get::<Disabled>(child.unwrap()).is_some());
+        assert_eq!(world.query::<&Idx>().iter(&world).count(), 0);
+    }
+}
@@ -1,10 +1,11 @@
 use crate::components::{Children, Parent};
 use bevy_ecs::{
     entity::Entity,
+    query::IncludeDisabled,
     system::{Command, EntityCommands},
     world::{EntityMut, World},
 };
-use bevy_utils::tracing::debug;
+use bevy_utils::{tracing::debug, HashSet};
 
 /// Despawns the given entity and all its children recursively
 #[derive(Debug)]
@@ -80,6 +81,38 @@ impl Command for DespawnChildrenRecursive {
     }
 }
 
+/// Removes the `freed` entities from the [`Children`] of their parents, and the [`Parent`] of
+/// their children.
+///
+/// This is the cleanup registered by [`HierarchyPlugin`](crate::HierarchyPlugin) with
+/// [`RollbackRegistry::register_cleanup`](bevy_ecs::rollback::RollbackRegistry::register_cleanup),
+/// since rolling back frees entities without updating the hierarchy.
+pub fn remove_freed_from_hierarchy(world: &mut World, freed: &HashSet<Entity>) {
+    let mut childless = Vec::new();
+    let mut query = world.query_filtered::<(Entity, &mut Children), IncludeDisabled>();
+    for (entity, mut children) in query.iter_mut(world) {
+        if children.0.iter().any(|child| freed.contains(child)) {
+            children.0.retain(|child| !freed.contains(child));
+            if children.0.is_empty() {
+                childless.push(entity);
+            }
+        }
+    }
+    for entity in childless {
+        world.entity_mut(entity).remove::<Children>();
+    }
+
+    let orphans = world
+        .query_filtered::<(Entity, &Parent), IncludeDisabled>()
+        .iter(world)
+        .filter(|(_, parent)| freed.contains(&parent.get()))
+        .map(|(entity, _)| entity)
+        .collect::<Vec<_>>();
+    for entity in orphans {
+        world.entity_mut(entity).remove::<Parent>();
+    }
+}
+
 /// Trait that holds functions for despawning recursively down the transform hierarchy
 pub trait DespawnRecursiveExt {
     /// Despawns the provided entity alongside all descendants.
@@ -147,8 +180,11 @@ mod tests {
         world::World,
     };
 
-    use super::DespawnRecursiveExt;
-    use crate::{child_builder::BuildChildren, components::Children};
+    use super::{remove_freed_from_hierarchy, DespawnRecursiveExt};
+    use crate::{
+        child_builder::{BuildChildren, BuildWorldChildren},
+        components::{Children, Parent},
+    };
 
     #[derive(Component, Clone, Copy, PartialEq, Eq, Ord, PartialOrd, Debug)]
     struct Idx(u32);
@@ -232,4 +268,26 @@ mod tests {
             ]
         );
     }
+
+    #[test]
+    fn remove_freed() {
+        let mut world = World::default();
+        let parent = world.spawn().id();
+        let kept = world.spawn().id();
+        let freed_child = world.spawn().id();
+        world.entity_mut(parent).push_children(&[kept, freed_child]);
+        let freed_parent = world.spawn().id();
+        let orphan = world.spawn().id();
+        world.entity_mut(freed_parent).push_children(&[orphan]);
+
+        let freed = [freed_child, freed_parent].into_iter().collect();
+        remove_freed_from_hierarchy(&mut world, &freed);
+        assert_eq!(&**world.get::<Children>(parent).unwrap(), &[kept]);
+        assert_eq!(world.get::<Parent>(kept).unwrap().get(), parent);
+        assert!(world.get::<Parent>(orphan).is_none());
+
+        let freed = [kept].into_iter().collect();
+        remove_freed_from_hierarchy(&mut world, &freed);
+        assert!(world.get::<Children>(parent).is_none());
+    }
 }
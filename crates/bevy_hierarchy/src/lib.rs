@@ -10,6 +10,9 @@ pub use components::*;
 mod hierarchy;
 pub use hierarchy::*;
 
+mod disable;
+pub use disable::*;
+
 mod child_builder;
 pub use child_builder::*;
 
@@ -19,10 +22,11 @@ pub use events::*;
 #[doc(hidden)]
 pub mod prelude {
     #[doc(hidden)]
-    pub use crate::{child_builder::*, components::*, hierarchy::*, HierarchyPlugin};
+    pub use crate::{child_builder::*, components::*, disable::*, hierarchy::*, HierarchyPlugin};
 }
 
 use bevy_app::prelude::*;
+use bevy_ecs::rollback::RollbackRegistry;
 
 /// The base plugin for handling [`Parent`] and [`Children`] components
 #[derive(Default)]
@@ -32,6 +36,10 @@ impl Plugin for HierarchyPlugin {
     fn build(&self, app: &mut App) {
         app.register_type::<Children>()
             .register_type::<Parent>()
-            .add_event::<HierarchyEvent>();
+            .add_event::<HierarchyEvent>()
+            .init_resource::<RollbackRegistry>();
+        app.world
+            .resource_mut::<RollbackRegistry>()
+            .register_cleanup(remove_freed_from_hierarchy);
     }
 }
@@ -0,0 +1,57 @@
+use crate::{
+    keyboard::{KeyCode, KeyboardInput},
+    mouse::{MouseButton, MouseButtonInput},
+    ButtonState,
+};
+use bevy_app::ci_testing::{fail, CiTestingEvent};
+use bevy_ecs::world::World;
+use serde::de::DeserializeOwned;
+
+/// Sends the keyboard and mouse input scripted in the CI testing configuration.
+pub(crate) fn handle_input_events(world: &mut World, event: &CiTestingEvent) {
+    let result = match event {
+        CiTestingEvent::KeyPress(name) => send_keyboard_input(world, name, ButtonState::Pressed),
+        CiTestingEvent::KeyRelease(name) => send_keyboard_input(world, name, ButtonState::Released),
+        CiTestingEvent::MouseButtonPress(name) => {
+            send_mouse_button_input(world, name, ButtonState::Pressed)
+        }
+        CiTestingEvent::MouseButtonRelease(name) => {
+            send_mouse_button_input(world, name, ButtonState::Released)
+        }
+        _ => Ok(()),
+    };
+    if let Err(message) = result {
+        fail(world, message);
+    }
+}
+
+fn send_keyboard_input(world: &mut World, name: &str, state: ButtonState) -> Result<(), String> {
+    world.send_event(KeyboardInput {
+        scan_code: 0,
+        key_code: Some(parse::<KeyCode>(name)?),
+        state,
+    });
+    Ok(())
+}
+
+fn send_mouse_button_input(
+    world: &mut World,
+    name: &str,
+    state: ButtonState,
+) -> Result<(), String> {
+    world.send_event(MouseButtonInput {
+        button: parse::<MouseButton>(name)?,
+        state,
+    });
+    Ok(())
+}
+
+fn parse<T: DeserializeOwned>(name: &str) -> Result<T, String> {
+    ron::from_str(name).map_err(|_| {
+        format!(
+            "invalid {} in CI testing configuration: {}",
+            std::any::type_name::<T>(),
+            name
+        )
+    })
+}
@@ -1,4 +1,6 @@
 mod axis;
+#[cfg(feature = "bevy_ci_testing")]
+mod ci_testing;
 pub mod gamepad;
 mod input;
 pub mod keyboard;
@@ -7,6 +9,8 @@ pub mod touch;
 
 pub use axis::*;
 use bevy_ecs::schedule::{ParallelSystemDescriptorCoercion, SystemLabel};
+#[cfg(feature = "bevy_ci_testing")]
+use bevy_ecs::{schedule::ExclusiveSystemDescriptorCoercion, system::IntoExclusiveSystem};
 pub use input::*;
 
 pub mod prelude {
@@ -84,6 +88,14 @@ impl Plugin for InputPlugin {
                 CoreStage::PreUpdate,
                 touch_screen_input_system.label(InputSystem),
             );
+
+        #[cfg(feature = "bevy_ci_testing")]
+        app.add_system_to_stage(
+            CoreStage::PreUpdate,
+            bevy_app::ci_testing::ci_testing_event_handler(ci_testing::handle_input_events)
+                .exclusive_system()
+                .at_start(),
+        );
     }
 }
 
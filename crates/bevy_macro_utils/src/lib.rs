@@ -216,3 +216,42 @@ pub fn derive_label(
     })
     .into()
 }
+
+/// Derive a label trait defined with `define_boxed_label!`, or one with the same methods.
+///
+/// The type must implement `Clone`, `Eq`, `Hash` and `Debug`. Unlike [`derive_label`], the type
+/// may contain data.
+pub fn derive_boxed_label(input: syn::DeriveInput, trait_path: &syn::Path) -> TokenStream {
+    let bevy_utils_path = BevyManifest::default().get_path("bevy_utils");
+    let ident = input.ident.clone();
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let mut where_clause = where_clause.cloned().unwrap_or_else(|| syn::WhereClause {
+        where_token: Default::default(),
+        predicates: Default::default(),
+    });
+    where_clause.predicates.push(
+        syn::parse2(quote! {
+            Self: 'static + Send + Sync + Clone + Eq + ::std::fmt::Debug + ::std::hash::Hash
+        })
+        .unwrap(),
+    );
+
+    (quote! {
+        impl #impl_generics #trait_path for #ident #ty_generics #where_clause {
+            fn dyn_clone(&self) -> std::boxed::Box<dyn #trait_path> {
+                std::boxed::Box::new(std::clone::Clone::clone(self))
+            }
+
+            fn as_dyn_eq(&self) -> &dyn #bevy_utils_path::label::DynEq {
+                self
+            }
+
+            fn dyn_hash(&self, mut state: &mut dyn std::hash::Hasher) {
+                let ty_id = std::any::TypeId::of::<Self>();
+                std::hash::Hash::hash(&ty_id, &mut state);
+                std::hash::Hash::hash(self, &mut state);
+            }
+        }
+    })
+    .into()
+}
@@ -39,7 +39,7 @@ pub mod draw_3d_graph {
 
 use bevy_app::prelude::*;
 use bevy_asset::{load_internal_asset, Assets, Handle, HandleUntyped};
-use bevy_ecs::prelude::*;
+use bevy_ecs::{prelude::*, schedule::ParallelSystemDescriptorCoercion};
 use bevy_reflect::TypeUuid;
 use bevy_render::{
     camera::CameraUpdateSystem,
@@ -1,7 +1,7 @@
 use std::marker::PhantomData;
 
 use bevy_app::{App, CoreStage, Plugin, StartupStage};
-use bevy_ecs::{prelude::*, reflect::ReflectComponent};
+use bevy_ecs::{prelude::*, reflect::ReflectComponent, schedule::ParallelSystemDescriptorCoercion};
 use bevy_math::Mat4;
 use bevy_reflect::{
     std_traits::ReflectDefault, FromReflect, GetTypeRegistration, Reflect, ReflectDeserialize,
@@ -20,7 +20,7 @@ use crate::{
     RenderApp, RenderStage,
 };
 use bevy_app::{App, Plugin};
-use bevy_ecs::prelude::*;
+use bevy_ecs::{prelude::*, schedule::ParallelSystemDescriptorCoercion};
 use bevy_math::{Mat4, Vec3};
 use bevy_reflect::Reflect;
 use bevy_transform::components::GlobalTransform;
@@ -42,7 +42,10 @@ impl Plugin for ViewPlugin {
                 .add_system_to_stage(RenderStage::Prepare, prepare_view_uniforms)
                 .add_system_to_stage(
                     RenderStage::Prepare,
-                    prepare_view_targets.after(WindowSystem::Prepare),
+                    ParallelSystemDescriptorCoercion::after(
+                        prepare_view_targets,
+                        WindowSystem::Prepare,
+                    ),
                 );
         }
     }
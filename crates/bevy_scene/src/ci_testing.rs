@@ -0,0 +1,45 @@
+use crate::DynamicScene;
+use bevy_app::{
+    ci_testing::{fail, CiTestingEvent},
+    AppTypeRegistry,
+};
+use bevy_ecs::world::World;
+use bevy_utils::tracing::info;
+
+/// Dumps the world and compares it against golden files, as scripted in the CI testing
+/// configuration.
+pub(crate) fn handle_scene_events(world: &mut World, event: &CiTestingEvent) {
+    let result = match event {
+        CiTestingEvent::DumpScene(path) => serialize_world(world).and_then(|scene| {
+            std::fs::write(path, scene).map_err(|err| format!("cannot write {}: {}", path, err))
+        }),
+        CiTestingEvent::CompareScene(path) => serialize_world(world).and_then(|scene| {
+            let golden = std::fs::read_to_string(path)
+                .map_err(|err| format!("cannot read {}: {}", path, err))?;
+            if scene == golden {
+                info!("The world matches {}.", path);
+                Ok(())
+            } else {
+                Err(format!(
+                    "the world does not match {}, found:\n{}",
+                    path, scene
+                ))
+            }
+        }),
+        _ => Ok(()),
+    };
+    if let Err(message) = result {
+        fail(world, message);
+    }
+}
+
+/// Serializes the world as a [`DynamicScene`], with its entities sorted so that the result does
+/// not depend on the archetypes they are stored in.
+fn serialize_world(world: &World) -> Result<String, String> {
+    let type_registry = world.resource::<AppTypeRegistry>();
+    let mut scene = DynamicScene::from_world(world, type_registry);
+    scene.entities.sort_by_key(|entity| entity.entity);
+    scene
+        .serialize_ron(type_registry)
+        .map_err(|err| format!("cannot serialize the world: {}", err))
+}
@@ -1,4 +1,6 @@
 mod bundle;
+#[cfg(feature = "bevy_ci_testing")]
+mod ci_testing;
 mod dynamic_scene;
 mod scene;
 mod scene_loader;
@@ -35,5 +37,13 @@ impl Plugin for ScenePlugin {
             )
             // Systems `*_bundle_spawner` must run before `scene_spawner_system`
             .add_system_to_stage(CoreStage::PreUpdate, scene_spawner);
+
+        #[cfg(feature = "bevy_ci_testing")]
+        app.add_system_to_stage(
+            CoreStage::Last,
+            bevy_app::ci_testing::ci_testing_event_handler(ci_testing::handle_scene_events)
+                .exclusive_system()
+                .at_end(),
+        );
     }
 }
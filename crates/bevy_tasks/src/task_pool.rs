@@ -130,6 +130,15 @@ impl TaskPool {
         }
     }
 
+    /// Runs `f` with the local executor of the current thread, which runs the tasks spawned with
+    /// [`spawn_local`](Self::spawn_local) on this thread.
+    pub fn with_local_executor<F, R>(&self, f: F) -> R
+    where
+        F: FnOnce(&async_executor::LocalExecutor) -> R,
+    {
+        Self::LOCAL_EXECUTOR.with(f)
+    }
+
     /// Return the number of threads owned by the task pool
     pub fn thread_num(&self) -> usize {
         self.threads.len()
@@ -109,3 +109,24 @@ impl Deref for IoTaskPool {
         &self.0
     }
 }
+
+/// Runs the tasks spawned on the current thread with [`TaskPool::spawn_local`] by the global task
+/// pools, to make progress while the thread is waiting on them.
+///
+/// This is meant to be called in a loop on the main thread, which does not run these tasks on
+/// its own, unlike the worker threads of the pools.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn tick_global_task_pools_on_main_thread() {
+    let pools = [
+        COMPUTE_TASK_POOL.get().map(|pool| &pool.0),
+        ASYNC_COMPUTE_TASK_POOL.get().map(|pool| &pool.0),
+        IO_TASK_POOL.get().map(|pool| &pool.0),
+    ];
+    for pool in pools.into_iter().flatten() {
+        pool.with_local_executor(|local_executor| {
+            for _ in 0..100 {
+                local_executor.try_tick();
+            }
+        });
+    }
+}
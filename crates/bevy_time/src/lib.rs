@@ -1,11 +1,13 @@
 mod fixed_timestep;
 mod stopwatch;
+mod testing;
 #[allow(clippy::module_inception)]
 mod time;
 mod timer;
 
 pub use fixed_timestep::*;
 pub use stopwatch::*;
+pub use testing::*;
 pub use time::*;
 pub use timer::*;
 
@@ -0,0 +1,46 @@
+use crate::{create_time_channels, Time};
+use bevy_app::testing::AppTestHarness;
+
+/// Extends [`AppTestHarness`] to drive [`Time`] with the virtual frame time of the harness.
+pub trait VirtualTimeExt {
+    /// Sends the virtual time of each frame through a [`TimeSender`](crate::TimeSender), so that
+    /// [`Time`] advances by exactly [`AppTestHarness::frame_delta`] every frame instead of
+    /// following the wall clock.
+    ///
+    /// The app must have the [`TimePlugin`](crate::TimePlugin).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bevy_app::{prelude::*, testing::AppTestHarness};
+    /// # use bevy_time::{prelude::*, TimePlugin, VirtualTimeExt};
+    /// # use bevy_utils::Duration;
+    /// #
+    /// let mut app = App::new();
+    /// app.add_plugin(TimePlugin);
+    ///
+    /// let mut harness = AppTestHarness::new(app).with_frame_delta(Duration::from_millis(20));
+    /// harness.add_virtual_time();
+    /// harness.update_frames(4);
+    ///
+    /// let time = harness.world().resource::<Time>();
+    /// assert_eq!(time.delta(), Duration::from_millis(20));
+    /// assert_eq!(time.time_since_startup(), Duration::from_millis(60));
+    /// ```
+    fn add_virtual_time(&mut self) -> &mut Self;
+}
+
+impl VirtualTimeExt for AppTestHarness {
+    fn add_virtual_time(&mut self) -> &mut Self {
+        let world = self.world_mut();
+        world.init_resource::<Time>();
+        let startup = world.resource::<Time>().startup();
+        let (time_sender, time_receiver) = create_time_channels();
+        world.insert_resource(time_receiver);
+        self.add_clock(move |_, elapsed| {
+            time_sender.0.try_send(startup + elapsed).expect(
+                "The virtual time was not received. Add the TimePlugin to the app of the AppTestHarness.",
+            );
+        })
+    }
+}
@@ -8,7 +8,7 @@ pub use render_pass::*;
 use crate::{prelude::UiCameraConfig, CalculatedClip, Node, UiColor, UiImage};
 use bevy_app::prelude::*;
 use bevy_asset::{load_internal_asset, AssetEvent, Assets, Handle, HandleUntyped};
-use bevy_ecs::prelude::*;
+use bevy_ecs::{prelude::*, schedule::ParallelSystemDescriptorCoercion};
 use bevy_math::{Mat4, Vec2, Vec3, Vec4Swizzles};
 use bevy_reflect::TypeUuid;
 use bevy_render::{
@@ -84,7 +84,10 @@ pub fn build_ui_render(app: &mut App) {
         )
         .add_system_to_stage(
             RenderStage::Extract,
-            extract_text_uinodes.after(RenderUiSystem::ExtractNode),
+            ParallelSystemDescriptorCoercion::after(
+                extract_text_uinodes,
+                RenderUiSystem::ExtractNode,
+            ),
         )
         .add_system_to_stage(RenderStage::Prepare, prepare_uinodes)
         .add_system_to_stage(RenderStage::Queue, queue_uinodes)
@@ -47,6 +47,60 @@ where
     }
 }
 
+/// Macro to define a new label trait whose values can hold data and are stored in a [`Box`].
+///
+/// Unlike [`define_label`], labels defined this way are compared and hashed by value through
+/// [`DynEq`] and [`DynHash`], so they can be used as `Box<dyn LabelTrait>` keys.
+///
+/// # Example
+///
+/// ```
+/// # use bevy_utils::define_boxed_label;
+/// define_boxed_label!(
+///     /// A class of labels.
+///     MyNewBoxedLabelTrait
+/// );
+/// ```
+#[macro_export]
+macro_rules! define_boxed_label {
+    (
+        $(#[$label_attr:meta])*
+        $label_name:ident $(,)?
+    ) => {
+        $(#[$label_attr])*
+        pub trait $label_name: 'static + Send + Sync + ::std::fmt::Debug {
+            /// Returns a boxed clone of this label.
+            fn dyn_clone(&self) -> ::std::boxed::Box<dyn $label_name>;
+
+            /// Casts this value to a form where it can be compared with other type-erased values.
+            fn as_dyn_eq(&self) -> &dyn $crate::label::DynEq;
+
+            /// Feeds this value into the given [`Hasher`](::std::hash::Hasher).
+            fn dyn_hash(&self, state: &mut dyn ::std::hash::Hasher);
+        }
+
+        impl PartialEq for dyn $label_name {
+            fn eq(&self, other: &Self) -> bool {
+                self.as_dyn_eq().dyn_eq(other.as_dyn_eq())
+            }
+        }
+
+        impl Eq for dyn $label_name {}
+
+        impl ::std::hash::Hash for dyn $label_name {
+            fn hash<H: ::std::hash::Hasher>(&self, state: &mut H) {
+                self.dyn_hash(state);
+            }
+        }
+
+        impl ::std::clone::Clone for ::std::boxed::Box<dyn $label_name> {
+            fn clone(&self) -> Self {
+                self.dyn_clone()
+            }
+        }
+    };
+}
+
 /// Macro to define a new label trait
 ///
 /// # Example
@@ -11,6 +11,7 @@ use bevy_app::{App, AppExit, CoreStage, Plugin};
 use bevy_ecs::prelude::*;
 use bevy_ecs::{
     event::{Events, ManualEventReader},
+    schedule::ParallelSystemDescriptorCoercion,
     world::World,
 };
 use bevy_input::{
@@ -239,9 +239,10 @@ fn print_at_end_round(mut counter: Local<u32>) {
     println!();
 }
 
-#[derive(Debug, Hash, PartialEq, Eq, Clone, StageLabel)]
-enum MyStage {
+#[derive(Debug, Hash, PartialEq, Eq, Clone, SystemSet)]
+enum MySet {
     BeforeRound,
+    Round,
     AfterRound,
 }
 
@@ -277,64 +278,54 @@ fn main() {
         // we don't accidentally run the game for an extra round.
         //
         // Rather than splitting each of your systems into separate stages, you should force an
-        // explicit ordering between them by giving the relevant systems a label with
-        // `.label`, then using the `.before` or `.after` methods. Systems will not be
+        // explicit ordering between them using the `.before` or `.after` methods, either on
+        // systems directly or on the `SystemSet`s they belong to. Systems will not be
         // scheduled until all of the systems that they have an "ordering dependency" on have
         // completed.
         //
         // Doing that will, in just about all cases, lead to better performance compared to
         // splitting systems between stages, because it gives the scheduling algorithm more
         // opportunities to run systems in parallel.
-        // Stages are still necessary, however: end of a stage is a hard sync point
-        // (meaning, no systems are running) where `Commands` issued by systems are processed.
-        // This is required because commands can perform operations that are incompatible with
+        // `Commands` issued by systems are applied at sync points (meaning, no systems are
+        // running), because commands can perform operations that are incompatible with
         // having systems in flight, such as spawning or deleting entities,
-        // adding or removing resources, etc.
+        // adding or removing resources, etc. The schedule automatically inserts an
+        // `apply_system_buffers` sync point between ordered systems when it is needed.
         //
-        // add_system(system) adds systems to the UPDATE stage by default
-        // However we can manually specify the stage if we want to. The following is equivalent to
-        // add_system(score_system)
-        .add_system_to_stage(CoreStage::Update, score_system)
+        // add_system(system) adds systems to the `CoreSet::Update` set of the UPDATE stage by default
+        .add_system(score_system.in_set(MySet::Round))
         // There are other `CoreStages`, such as `Last` which runs at the very end of each run.
         .add_system_to_stage(CoreStage::Last, print_at_end_round)
-        // We can also create new stages. Here is what our games stage order will look like:
+        // We can also create new system sets, and order them relative to each other.
+        // Here is what our games execution order will look like:
         // "before_round": new_player_system, new_round_system
-        // "update": print_message_system, score_system
+        // "round": score_system
         // "after_round": score_check_system, game_over_system
-        .add_stage_before(
-            CoreStage::Update,
-            MyStage::BeforeRound,
-            SystemStage::parallel(),
+        // `print_message_system` is not in any of these sets, so it may run at any point.
+        .configure_sets((MySet::BeforeRound, MySet::Round, MySet::AfterRound).chain())
+        .add_system(new_round_system.in_set(MySet::BeforeRound))
+        .add_system(
+            new_player_system
+                .after(new_round_system)
+                .in_set(MySet::BeforeRound),
         )
-        .add_stage_after(
-            CoreStage::Update,
-            MyStage::AfterRound,
-            SystemStage::parallel(),
-        )
-        .add_system_to_stage(MyStage::BeforeRound, new_round_system)
-        .add_system_to_stage(
-            MyStage::BeforeRound,
-            new_player_system.after(new_round_system),
-        )
-        .add_system_to_stage(
-            MyStage::BeforeRound,
-            // Systems which take `&mut World` as an argument must call `.exclusive_system()`.
-            // The following will not compile.
-            //.add_system_to_stage(MyStage::BeforeRound, exclusive_player_system)
-            exclusive_player_system.exclusive_system(),
-        )
-        .add_system_to_stage(MyStage::AfterRound, score_check_system)
-        .add_system_to_stage(
+        // Systems which take `&mut World` as an argument can be added directly, and will
+        // run exclusively.
+        .add_system(exclusive_player_system.in_set(MySet::BeforeRound))
+        .add_system(score_check_system.in_set(MySet::AfterRound))
+        .add_system(
             // We can ensure that `game_over_system` runs after `score_check_system` using explicit ordering
             // To do this we use either `.before` or `.after` to describe the order we want the relationship
             // Since we are using `after`, `game_over_system` runs after `score_check_system`
-            MyStage::AfterRound,
-            game_over_system.after(score_check_system),
+            game_over_system
+                .after(score_check_system)
+                .in_set(MySet::AfterRound),
         )
         // We can check our systems for execution order ambiguities by examining the output produced
         // in the console by using the `LogPlugin` and adding the following Resource to our App :)
         // Be aware that not everything reported by this checker is a potential problem, you'll have
-        // to make that judgement yourself.
+        // to make that judgement yourself. Using `AmbiguityReportLevel::Error` instead makes the app
+        // panic when an ambiguity is found, which is useful to catch new ambiguities in CI.
         .add_plugin(LogPlugin::default())
         .init_resource::<ReportExecutionOrderAmbiguities>()
         // This call to run() starts the app we just built!
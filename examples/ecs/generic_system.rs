@@ -11,8 +11,9 @@
 
 use bevy::{ecs::component::Component, prelude::*};
 
-#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Hash, States)]
 enum AppState {
+    #[default]
     MainMenu,
     InGame,
 }
@@ -32,20 +33,17 @@ struct LevelUnload;
 fn main() {
     App::new()
         .add_plugins(DefaultPlugins)
-        .add_state(AppState::MainMenu)
+        .add_state::<AppState>()
         .add_startup_system(setup_system)
         .add_system(print_text_system)
-        .add_system_set(
-            SystemSet::on_update(AppState::MainMenu).with_system(transition_to_in_game_system),
-        )
+        .add_system(transition_to_in_game_system.run_if(in_state(AppState::MainMenu)))
         // add the cleanup systems
-        .add_system_set(
+        .add_system_to_schedule(
+            OnExit(AppState::MainMenu),
             // Pass in the types your system should operate on using the ::<T> (turbofish) syntax
-            SystemSet::on_exit(AppState::MainMenu).with_system(cleanup_system::<MenuClose>),
-        )
-        .add_system_set(
-            SystemSet::on_exit(AppState::InGame).with_system(cleanup_system::<LevelUnload>),
+            cleanup_system::<MenuClose>,
         )
+        .add_system_to_schedule(OnExit(AppState::InGame), cleanup_system::<LevelUnload>)
         .run();
 }
 
@@ -74,11 +72,11 @@ fn print_text_system(time: Res<Time>, mut query: Query<(&mut PrinterTick, &TextT
 }
 
 fn transition_to_in_game_system(
-    mut state: ResMut<State<AppState>>,
+    mut next_state: ResMut<NextState<AppState>>,
     keyboard_input: Res<Input<KeyCode>>,
 ) {
     if keyboard_input.pressed(KeyCode::Space) {
-        state.set(AppState::InGame).unwrap();
+        next_state.set(AppState::InGame);
     }
 }
 
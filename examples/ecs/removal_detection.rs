@@ -16,7 +16,7 @@ fn main() {
     App::new()
         .add_plugins(DefaultPlugins)
         .add_startup_system(setup)
-        .add_system_to_stage(CoreStage::Update, remove_component)
+        .add_system(remove_component)
         .add_system_to_stage(CoreStage::PostUpdate, react_on_removal)
         .run();
 }
@@ -6,22 +6,22 @@ use bevy::prelude::*;
 fn main() {
     App::new()
         .add_plugins(DefaultPlugins)
-        .add_state(AppState::Menu)
+        .add_state::<AppState>()
         .add_startup_system(setup)
-        .add_system_set(SystemSet::on_enter(AppState::Menu).with_system(setup_menu))
-        .add_system_set(SystemSet::on_update(AppState::Menu).with_system(menu))
-        .add_system_set(SystemSet::on_exit(AppState::Menu).with_system(cleanup_menu))
-        .add_system_set(SystemSet::on_enter(AppState::InGame).with_system(setup_game))
-        .add_system_set(
-            SystemSet::on_update(AppState::InGame)
-                .with_system(movement)
-                .with_system(change_color),
-        )
+        // This system runs when we enter `AppState::Menu`, during `CoreSet::StateTransitions`.
+        .add_system_to_schedule(OnEnter(AppState::Menu), setup_menu)
+        // By contrast, this system will run once every frame, as long as we are in `AppState::Menu`.
+        .add_system(menu.run_if(in_state(AppState::Menu)))
+        .add_system_to_schedule(OnExit(AppState::Menu), cleanup_menu)
+        .add_system_to_schedule(OnEnter(AppState::InGame), setup_game)
+        .add_system(movement.run_if(in_state(AppState::InGame)))
+        .add_system(change_color.run_if(in_state(AppState::InGame)))
         .run();
 }
 
-#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Hash, States)]
 enum AppState {
+    #[default]
     Menu,
     InGame,
 }
@@ -70,7 +70,7 @@ fn setup_menu(mut commands: Commands, asset_server: Res<AssetServer>) {
 }
 
 fn menu(
-    mut state: ResMut<State<AppState>>,
+    mut next_state: ResMut<NextState<AppState>>,
     mut interaction_query: Query<
         (&Interaction, &mut UiColor),
         (Changed<Interaction>, With<Button>),
@@ -80,7 +80,7 @@ fn menu(
         match *interaction {
             Interaction::Clicked => {
                 *color = PRESSED_BUTTON.into();
-                state.set(AppState::InGame).unwrap();
+                next_state.set(AppState::InGame);
             }
             Interaction::Hovered => {
                 *color = HOVERED_BUTTON.into();
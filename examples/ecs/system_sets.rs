@@ -1,38 +1,38 @@
 //! Shows how systems with a similar purpose can be grouped into sets.
 //!
 //! ```none
-//! Physics                     (Criteria: App has run < 1.0 seconds)
-//!     \--> update_velocity        (via label PhysicsSystem::UpdateVelocity)
-//!     \--> movement               (via label PhysicsSystem::Movement)
-//! PostPhysics                 (Criteria: Resource `done` is false)
+//! Physics                     (Condition: App has run < 1.0 seconds)
+//!     \--> update_velocity
+//!     \--> movement
+//! PostPhysics                 (Condition: Resource `done` is false)
 //!     \--> collision || sfx
-//! Exit                        (Criteria: Resource `done` is true)
+//! Exit                        (Condition: Resource `done` is true)
 //!     \--> exit
 //! ```
 //!
-//! The `Physics` label represents a [`SystemSet`] containing two systems.
-//! This set's criteria is to stop after a second has elapsed.
+//! `Physics` is a [`SystemSet`] containing two systems.
+//! This set's run condition is to stop after a second has elapsed.
 //! The two systems (`update_velocity`, `movement`) run in a specified order.
 //!
-//! Another label `PostPhysics` uses run criteria to only run after `Physics` has finished.
-//! This set's criteria is to run only when _not done_, as specified via a resource.
+//! Another set `PostPhysics` is ordered to only run after `Physics` has finished.
+//! This set's run condition is to run only when _not done_, as specified via a resource.
 //! The two systems here (collision, sfx) are not specified to run in any order, and the actual
 //! ordering can then change between invocations.
 //!
-//! Lastly a system with run criteria  _done_ is used to exit the app.
+//! Lastly a system with the run condition _done_ is used to exit the app.
 
-use bevy::{app::AppExit, ecs::schedule::ShouldRun, prelude::*};
+use bevy::{app::AppExit, prelude::*};
 
-/// A [`SystemLabel`] can be applied as a label to systems and system sets,
-/// which can then be referred to from other systems.
+/// A [`SystemSet`] can be used to group systems, which can then be referred to as a whole
+/// from other systems and sets.
 /// This is useful in case a user wants to e.g. run _before_ or _after_
-/// some label.
+/// some set, or configure a run condition shared by every system in it.
 /// `Clone`, `Hash`, `Debug`, `PartialEq`, `Eq`, are all required to derive
-/// [`SystemLabel`].
-#[derive(Clone, Hash, Debug, PartialEq, Eq, SystemLabel)]
+/// [`SystemSet`].
+#[derive(Clone, Hash, Debug, PartialEq, Eq, SystemSet)]
 struct Physics;
 
-#[derive(Clone, Hash, Debug, PartialEq, Eq, SystemLabel)]
+#[derive(Clone, Hash, Debug, PartialEq, Eq, SystemSet)]
 struct PostPhysics;
 
 /// Resource used to stop our example.
@@ -43,51 +43,39 @@ fn main() {
     App::new()
         .add_plugins(DefaultPlugins)
         .init_resource::<Done>()
-        // Note that the system sets added in this example set their run criteria explicitly.
-        // See the `ecs/state.rs` example for a pattern where run criteria are set implicitly for common
-        // use cases- typically state transitions.
-        // Also note that a system set has a single run criterion at most, which means using `.with_run_criteria(...)`
-        // after `SystemSet::on_update(...)` would override the state transition criterion.
-        .add_system_set(
-            SystemSet::new()
-                // This label is added to all systems in this set.
-                // The label can then be referred to elsewhere (other sets).
-                .label(Physics)
-                // This criteria ensures this whole system set only runs when this system's
-                // output says so (ShouldRun::Yes)
-                .with_run_criteria(run_for_a_second)
-                .with_system(update_velocity)
-                // Make movement run after update_velocity
-                .with_system(movement.after(update_velocity)),
+        // Note that the system sets configured in this example set their run conditions explicitly.
+        // See the `ecs/state.rs` example for a pattern where run conditions are used for common
+        // use cases- typically states.
+        // Unlike run criteria, any number of run conditions can be added to a set or a system,
+        // and all of them must be `true` for it to run.
+        .configure_set(
+            Physics
+                // This condition ensures every system in this set only runs when this
+                // condition returns `true`
+                .run_if(run_for_a_second),
         )
-        .add_system_set(
-            SystemSet::new()
-                .label(PostPhysics)
-                // This whole set runs after `Physics` (which in this case is a label for
-                // another set).
+        .configure_set(
+            PostPhysics
+                // This whole set runs after `Physics`.
                 // There is also `.before(..)`.
                 .after(Physics)
-                // This shows that we can modify existing run criteria results.
-                // Here we create a _not done_ criteria by piping the output of
-                // the `is_done` system and inverting the output.
-                // Notice a string literal also works as a label.
-                .with_run_criteria(RunCriteria::pipe("is_done_label", inverse))
-                // `collision` and `sfx` are not ordered with respect to
-                // each other, and may run in any order
-                .with_system(collision)
-                .with_system(sfx),
-        )
-        .add_system(
-            exit.after(PostPhysics)
-                // Label the run criteria such that the `PostPhysics` set can reference it
-                .with_run_criteria(is_done.label("is_done_label")),
+                // Conditions can be combined and inverted.
+                // Here we create a _not done_ condition by inverting the output of `is_done`.
+                .run_if(not(is_done)),
         )
+        .add_system(update_velocity.in_set(Physics))
+        // Make movement run after update_velocity
+        .add_system(movement.after(update_velocity).in_set(Physics))
+        // `collision` and `sfx` are not ordered with respect to
+        // each other, and may run in any order
+        .add_systems((collision, sfx).in_set(PostPhysics))
+        .add_system(exit.after(PostPhysics).run_if(is_done))
         .run();
 }
 
-/// Example of a run criteria.
+/// Example of a run condition.
 /// Here we only want to run for a second, then stop.
-fn run_for_a_second(time: Res<Time>, mut done: ResMut<Done>) -> ShouldRun {
+fn run_for_a_second(time: Res<Time>, mut done: ResMut<Done>) -> bool {
     let elapsed = time.seconds_since_startup();
     if elapsed < 1.0 {
         info!(
@@ -95,26 +83,16 @@ fn run_for_a_second(time: Res<Time>, mut done: ResMut<Done>) -> ShouldRun {
             elapsed,
             1.0 - elapsed
         );
-        ShouldRun::Yes
+        true
     } else {
         done.0 = true;
-        ShouldRun::No
+        false
     }
 }
 
-/// Another run criteria, simply using a resource.
-fn is_done(done: Res<Done>) -> ShouldRun {
-    done.0.into()
-}
-
-/// Used with [`RunCritera::pipe`], inverts the result of the
-/// passed system.
-fn inverse(input: In<ShouldRun>) -> ShouldRun {
-    match input.0 {
-        ShouldRun::No => ShouldRun::Yes,
-        ShouldRun::Yes => ShouldRun::No,
-        _ => unreachable!(),
-    }
+/// Another run condition, simply using a resource.
+fn is_done(done: Res<Done>) -> bool {
+    done.0
 }
 
 fn update_velocity() {
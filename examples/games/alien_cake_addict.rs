@@ -1,10 +1,11 @@
 //! Eat the cakes. Eat them all. An example 3D game.
 
-use bevy::{ecs::schedule::SystemSet, prelude::*, time::FixedTimestep};
+use bevy::{prelude::*, time::FixedTimestep};
 use rand::Rng;
 
-#[derive(Clone, Eq, PartialEq, Debug, Hash)]
+#[derive(Clone, Copy, Default, Eq, PartialEq, Debug, Hash, States)]
 enum GameState {
+    #[default]
     Playing,
     GameOver,
 }
@@ -13,22 +14,21 @@ fn main() {
     App::new()
         .init_resource::<Game>()
         .add_plugins(DefaultPlugins)
-        .add_state(GameState::Playing)
+        .add_state::<GameState>()
         .add_startup_system(setup_cameras)
-        .add_system_set(SystemSet::on_enter(GameState::Playing).with_system(setup))
-        .add_system_set(
-            SystemSet::on_update(GameState::Playing)
-                .with_system(move_player)
-                .with_system(focus_camera)
-                .with_system(rotate_bonus)
-                .with_system(scoreboard_system),
-        )
-        .add_system_set(SystemSet::on_exit(GameState::Playing).with_system(teardown))
-        .add_system_set(SystemSet::on_enter(GameState::GameOver).with_system(display_score))
-        .add_system_set(SystemSet::on_update(GameState::GameOver).with_system(gameover_keyboard))
-        .add_system_set(SystemSet::on_exit(GameState::GameOver).with_system(teardown))
-        .add_system_set(
-            SystemSet::new()
+        .add_system_to_schedule(OnEnter(GameState::Playing), setup)
+        .add_system(move_player.run_if(in_state(GameState::Playing)))
+        .add_system(focus_camera.run_if(in_state(GameState::Playing)))
+        .add_system(rotate_bonus.run_if(in_state(GameState::Playing)))
+        .add_system(scoreboard_system.run_if(in_state(GameState::Playing)))
+        .add_system_to_schedule(OnExit(GameState::Playing), teardown)
+        .add_system_to_schedule(OnEnter(GameState::GameOver), display_score)
+        .add_system(gameover_keyboard.run_if(in_state(GameState::GameOver)))
+        .add_system_to_schedule(OnExit(GameState::GameOver), teardown)
+        .add_stage_after(
+            CoreStage::Update,
+            FixedUpdateStage,
+            SystemStage::parallel()
                 .with_run_criteria(FixedTimestep::step(5.0))
                 .with_system(spawn_bonus),
         )
@@ -36,6 +36,9 @@ fn main() {
         .run();
 }
 
+#[derive(Debug, Hash, PartialEq, Eq, Clone, StageLabel)]
+struct FixedUpdateStage;
+
 struct Cell {
     height: f32,
 }
@@ -289,11 +292,12 @@ fn focus_camera(
 
 // despawn the bonus if there is one, then spawn a new one at a random location
 fn spawn_bonus(
-    mut state: ResMut<State<GameState>>,
+    state: Res<State<GameState>>,
+    mut next_state: ResMut<NextState<GameState>>,
     mut commands: Commands,
     mut game: ResMut<Game>,
 ) {
-    if *state.current() != GameState::Playing {
+    if state.0 != GameState::Playing {
         return;
     }
     if let Some(entity) = game.bonus.entity {
@@ -301,8 +305,7 @@ fn spawn_bonus(
         commands.entity(entity).despawn_recursive();
         game.bonus.entity = None;
         if game.score <= -5 {
-            // We don't particularly care if this operation fails
-            let _ = state.overwrite_set(GameState::GameOver);
+            next_state.set(GameState::GameOver);
             return;
         }
     }
@@ -361,9 +364,12 @@ fn scoreboard_system(game: Res<Game>, mut query: Query<&mut Text>) {
 }
 
 // restart the game when pressing spacebar
-fn gameover_keyboard(mut state: ResMut<State<GameState>>, keyboard_input: Res<Input<KeyCode>>) {
+fn gameover_keyboard(
+    mut next_state: ResMut<NextState<GameState>>,
+    keyboard_input: Res<Input<KeyCode>>,
+) {
     if keyboard_input.just_pressed(KeyCode::Space) {
-        state.set(GameState::Playing).unwrap();
+        next_state.set(GameState::Playing);
     }
 }
 
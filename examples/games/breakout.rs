@@ -1,6 +1,7 @@
 //! A simplified implementation of the classic game "Breakout".
 
 use bevy::{
+    ecs::schedule::ParallelSystemDescriptorCoercion,
     prelude::*,
     sprite::collide_aabb::{collide, Collision},
     sprite::MaterialMesh2dBundle,
@@ -58,19 +59,35 @@ fn main() {
         .insert_resource(ClearColor(BACKGROUND_COLOR))
         .add_startup_system(setup)
         .add_event::<CollisionEvent>()
-        .add_system_set(
-            SystemSet::new()
+        .add_stage_after(
+            CoreStage::Update,
+            FixedUpdateStage,
+            SystemStage::parallel()
                 .with_run_criteria(FixedTimestep::step(TIME_STEP as f64))
-                .with_system(check_for_collisions)
-                .with_system(move_paddle.before(check_for_collisions))
-                .with_system(apply_velocity.before(check_for_collisions))
-                .with_system(play_collision_sound.after(check_for_collisions)),
+                .with_system(
+                    check_for_collisions
+                        .label(PhysicsSystem::CheckForCollisions)
+                        .after(move_paddle)
+                        .after(apply_velocity)
+                        .before(play_collision_sound),
+                )
+                .with_system(move_paddle)
+                .with_system(apply_velocity)
+                .with_system(play_collision_sound),
         )
         .add_system(update_scoreboard)
         .add_system(bevy::window::close_on_esc)
         .run();
 }
 
+#[derive(Debug, Hash, PartialEq, Eq, Clone, StageLabel)]
+struct FixedUpdateStage;
+
+#[derive(Debug, Hash, PartialEq, Eq, Clone, SystemLabel)]
+enum PhysicsSystem {
+    CheckForCollisions,
+}
+
 #[derive(Component)]
 struct Paddle;
 
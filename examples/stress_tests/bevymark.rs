@@ -49,14 +49,19 @@ fn main() {
         .add_system(movement_system)
         .add_system(collision_system)
         .add_system(counter_system)
-        .add_system_set(
-            SystemSet::new()
+        .add_stage_after(
+            CoreStage::Update,
+            FixedUpdateStage,
+            SystemStage::parallel()
                 .with_run_criteria(FixedTimestep::step(0.2))
                 .with_system(scheduled_spawner),
         )
         .run();
 }
 
+#[derive(Debug, Hash, PartialEq, Eq, Clone, StageLabel)]
+struct FixedUpdateStage;
+
 #[derive(Resource)]
 struct BirdScheduled {
     wave: usize,